@@ -0,0 +1,9 @@
+-- Amount-type provenance per municipality-year. The fetchers fall back from
+-- audited actuals (AUDA) to the original (ORGB) and adjusted (ADJB) budgets
+-- when a year has no audited figures yet; this records which one the row's
+-- figures came from so budgeted numbers are never presented as audited.
+ALTER TABLE public.financial_data
+    ADD COLUMN amount_type text;
+
+COMMENT ON COLUMN public.financial_data.amount_type IS
+    'Least authoritative amount type among the row''s cube figures: AUDA | ORGB | ADJB. NULL = legacy row or no data.';
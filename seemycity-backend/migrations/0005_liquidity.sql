@@ -0,0 +1,10 @@
+-- Scoring v3: Liquidity pillar. Cash at year end is stored as a raw input so
+-- the healing pass can re-derive the pillar without upstream calls.
+ALTER TABLE public.financial_data
+    ADD COLUMN cash_balance numeric,
+    ADD COLUMN liquidity_score numeric(5,2);
+
+COMMENT ON COLUMN public.financial_data.cash_balance IS
+    'Cash and cash equivalents at year end (cflow_v2 item 4200). May be negative (overdraft). NULL = not reported.';
+COMMENT ON COLUMN public.financial_data.liquidity_score IS
+    'Liquidity pillar (0-100): cash coverage in months of operating spend. NULL = no cash data; the overall score then renormalizes over the core pillars.';
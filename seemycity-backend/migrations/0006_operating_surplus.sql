@@ -0,0 +1,11 @@
+-- Operating surplus (revenue - operational expenditure): derived from stored
+-- figures, kept as a column so listings and exports can read it directly.
+ALTER TABLE public.financial_data
+    ADD COLUMN operating_surplus numeric;
+
+UPDATE public.financial_data
+SET operating_surplus = revenue - operational_expenditure
+WHERE revenue IS NOT NULL AND operational_expenditure IS NOT NULL;
+
+COMMENT ON COLUMN public.financial_data.operating_surplus IS
+    'Revenue minus operational expenditure; negative = deficit. NULL when either figure is missing.';
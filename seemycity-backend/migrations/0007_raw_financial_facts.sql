@@ -0,0 +1,21 @@
+-- Raw Treasury cube cells behind each financial_data row, so the sums (and
+-- scores) can be re-derived after an item-code change without refetching
+-- every municipality. Replaced per (municipality, year, cube) on each fetch.
+CREATE TABLE public.raw_financial_facts (
+    municipality_id character varying NOT NULL,
+    year integer NOT NULL,
+    cube text NOT NULL,
+    item_code text NOT NULL,
+    item_label text NOT NULL,
+    amount_type text,
+    amount double precision,
+    fetched_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT raw_financial_facts_pkey PRIMARY KEY (municipality_id, year, cube, item_code),
+    CONSTRAINT raw_financial_facts_municipalities_fk FOREIGN KEY (municipality_id)
+        REFERENCES public.municipalities(id) ON DELETE CASCADE
+);
+COMMENT ON TABLE public.raw_financial_facts IS 'Aggregate cells fetched from the Municipal Money API, one row per item per cube per municipality-year.';
+COMMENT ON COLUMN public.raw_financial_facts.amount IS
+    'amount.sum exactly as returned (float), so re-summing reproduces the stored figures.';
+COMMENT ON COLUMN public.raw_financial_facts.amount_type IS
+    'Amount type the cell belongs to (AUDA/ORGB/ADJB). NULL for cubes without one (uifwexp).';
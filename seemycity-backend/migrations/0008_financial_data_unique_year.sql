@@ -0,0 +1,25 @@
+-- One financial_data row per municipality-year. 0001 declares this
+-- constraint, but databases restored from older dumps can lack it; without
+-- it every upsert fails (its ON CONFLICT target is gone) and rows inserted
+-- by hand can repeat a year. Idempotent: on a database that has the
+-- constraint this deletes nothing and adds nothing. The server checks for it
+-- at startup (db::financials::has_unique_year_constraint).
+
+-- Keep the most recently updated row of each duplicated municipality-year.
+DELETE FROM public.financial_data a
+USING public.financial_data b
+WHERE a.municipality_id = b.municipality_id
+  AND a.year = b.year
+  AND (a.updated_at, a.id) < (b.updated_at, b.id);
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conrelid = 'public.financial_data'::regclass
+          AND conname = 'financial_data_municipality_id_year_key'
+    ) THEN
+        ALTER TABLE public.financial_data
+            ADD CONSTRAINT financial_data_municipality_id_year_key UNIQUE (municipality_id, year);
+    END IF;
+END $$;
@@ -0,0 +1,9 @@
+-- When the demographics cube was last asked for a municipality's missing
+-- population. A lookup that finds nothing (or fails) is not retried until
+-- this is old enough, so a municipality the cube doesn't cover doesn't cost
+-- Treasury calls on every detail request.
+ALTER TABLE public.municipalities
+    ADD COLUMN population_checked_at timestamp with time zone;
+
+COMMENT ON COLUMN public.municipalities.population_checked_at IS
+    'Last demographics-cube lookup for a missing population; NULL = never looked up.';
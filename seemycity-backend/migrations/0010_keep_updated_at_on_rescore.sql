@@ -0,0 +1,17 @@
+-- Rescoring and recomputing rewrite a row from figures it already holds.
+-- They are not fetches, so they must not restamp updated_at, which drives the
+-- cache TTL and Last-Modified. Those statements set seemycity.keep_updated_at
+-- for their transaction (db::financials::keep_updated_at); every other update
+-- is stamped as before.
+CREATE OR REPLACE FUNCTION public.trigger_set_timestamp() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+  IF current_setting('seemycity.keep_updated_at', true) = 'on' THEN
+    NEW.updated_at = OLD.updated_at;
+  ELSE
+    NEW.updated_at = NOW();
+  END IF;
+  RETURN NEW;
+END;
+$$;
@@ -0,0 +1,6 @@
+-- GiST index on the boundaries as geography, so the nearest-municipality
+-- lookup can order candidates by geodesic `<->` distance through an index.
+-- The geometry index ranks by planar degrees, which stretch east-west
+-- relative to north-south and could pick the wrong candidates.
+CREATE INDEX IF NOT EXISTS municipal_geometries_geog_idx
+    ON public.municipal_geometries USING gist ((geom::geography));
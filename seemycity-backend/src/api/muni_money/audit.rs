@@ -1,28 +1,147 @@
 // src/api/muni_money/audit.rs
 
 use super::client::MunicipalMoneyClient;
-use super::types::{ApiClientError, AuditApiResponse, AuditOpinionFact}; 
+use super::types::{ApiClientError, AuditApiResponse, AuditOpinionFact};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
 /// Fetches the audit outcome for a specific municipality and year.
-/// 
-/// Returns the audit opinion string (e.g., "Unqualified", "Qualified") if available,
-/// otherwise returns None.
+///
+/// Returns the audit opinion label (e.g. "Unqualified - No findings"), or
+/// `None` when the cube has no opinion for that year. When several facts come
+/// back, [`select_audit_opinion`] picks one; conflicting labels are logged.
 pub async fn get_audit_outcome(
     client: &MunicipalMoneyClient,
     muni_code: &str,
     year: i32,
 ) -> Result<Option<String>, ApiClientError> {
-    // Fetch audit facts using the specific client method
-    let response: AuditApiResponse = client
-        .fetch_audit_opinion_facts(muni_code, year)
-        .await?;
-
-    // The API should return zero or one fact for a specific muni/year combo in this cube.
-    // We take the first fact if it exists.
-    let first_fact: Option<&AuditOpinionFact> = response.cells.first();
-
-    match first_fact {
-        Some(fact) => Ok(Some(fact.opinion_label.clone())),
-        None => Ok(None), // No data found for this muni/year
+    let response: AuditApiResponse = client.fetch_audit_opinion_facts(muni_code, year).await?;
+    let selected = select_audit_opinion(&response.data, muni_code, year);
+    if let Some(selected) = selected {
+        let conflicting = matching_audit_opinions(&response.data, muni_code, year)
+            .filter(|fact| fact.opinion_label != selected.opinion_label)
+            .count();
+        if conflicting > 0 {
+            log::warn!(
+                "{} audit opinion facts for {} in {} disagree with '{}', which is kept",
+                conflicting,
+                muni_code,
+                year,
+                selected.opinion_label
+            );
+        }
     }
-}
\ No newline at end of file
+    Ok(selected.map(|fact| fact.opinion_label.clone()))
+}
+
+/// The fact that carries a municipality's opinion for `year`, out of a facts
+/// response. Facts for another municipality or year, or with a blank label,
+/// are ignored. The request already cuts by municipality and year, and the
+/// cube holds one opinion for each, so the first fact left is taken; the
+/// response order is not relied on to tell a reissued opinion apart.
+pub fn select_audit_opinion<'a>(
+    facts: &'a [AuditOpinionFact],
+    muni_code: &str,
+    year: i32,
+) -> Option<&'a AuditOpinionFact> {
+    matching_audit_opinions(facts, muni_code, year).next()
+}
+
+// The facts `select_audit_opinion` chooses from, in response order.
+fn matching_audit_opinions<'a>(
+    facts: &'a [AuditOpinionFact],
+    muni_code: &str,
+    year: i32,
+) -> impl Iterator<Item = &'a AuditOpinionFact> {
+    facts
+        .iter()
+        .filter(move |fact| fact.demarcation_code.eq_ignore_ascii_case(muni_code))
+        .filter(move |fact| fact.year.is_none_or(|y| y == year))
+        .filter(|fact| !fact.opinion_label.trim().is_empty())
+}
+
+/// How many years `get_audit_outcome_history` fetches at once. The client's
+/// `MUNI_MONEY_MAX_CONCURRENCY` still caps the requests in flight.
+pub const AUDIT_HISTORY_CONCURRENCY: usize = 4;
+
+/// Fetches the audit outcome for every year in `start_year..=end_year`, oldest
+/// first, as `(year, opinion label)`. Years without an opinion are left out,
+/// so the result may be shorter than the range (or empty). One request per
+/// year, [`AUDIT_HISTORY_CONCURRENCY`] at a time; the first failing request
+/// aborts the whole history and the requests still pending.
+///
+/// Not part of the refresh pipeline: scores use the scored year's opinion
+/// only, since a trend derived from a fetched history could not be reproduced
+/// when stored rows are healed or rescored.
+pub async fn get_audit_outcome_history(
+    client: &MunicipalMoneyClient,
+    muni_code: &str,
+    start_year: i32,
+    end_year: i32,
+) -> Result<Vec<(i32, String)>, ApiClientError> {
+    if start_year > end_year {
+        return Err(ApiClientError::InvalidParameters(format!(
+            "audit history start year {start_year} is after end year {end_year}"
+        )));
+    }
+    let limiter = Arc::new(Semaphore::new(AUDIT_HISTORY_CONCURRENCY));
+    let mut tasks = JoinSet::new();
+    for year in start_year..=end_year {
+        let (client, limiter, muni_code) = (client.clone(), limiter.clone(), muni_code.to_string());
+        tasks.spawn(crate::request_id::inherit(async move {
+            let _permit = limiter.acquire_owned().await.expect("audit history limiter is never closed");
+            (year, get_audit_outcome(&client, &muni_code, year).await)
+        }));
+    }
+
+    let mut history = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        // Nothing aborts these tasks, so a failed join is a panic: re-raise it.
+        let (year, outcome) = joined.unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()));
+        if let Some(outcome) = outcome? {
+            history.push((year, outcome));
+        }
+    }
+    history.sort_by_key(|(year, _)| *year);
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fact(code: &str, year: Option<i32>, label: &str) -> AuditOpinionFact {
+        AuditOpinionFact {
+            demarcation_code: code.to_string(),
+            demarcation_label: format!("Municipality {code}"),
+            year,
+            opinion_label: label.to_string(),
+            opinion_code: label.to_lowercase(),
+        }
+    }
+
+    #[test]
+    fn the_first_matching_fact_with_a_label_is_selected() {
+        let facts = [
+            fact("BUF", Some(2023), "Adverse"),
+            fact("CPT", Some(2022), "Disclaimer"),
+            fact("CPT", Some(2023), " "),
+            fact("CPT", Some(2023), "Unqualified - Emphasis of Matter items"),
+            fact("CPT", Some(2023), "Qualified"),
+        ];
+        let selected = select_audit_opinion(&facts, "cpt", 2023).unwrap();
+        assert_eq!(selected.opinion_label, "Unqualified - Emphasis of Matter items");
+    }
+
+    #[test]
+    fn facts_without_a_year_still_count_and_empty_responses_select_nothing() {
+        assert_eq!(
+            select_audit_opinion(&[fact("CPT", None, "Unqualified - No findings")], "CPT", 2023)
+                .map(|f| f.opinion_label.as_str()),
+            Some("Unqualified - No findings")
+        );
+        assert!(select_audit_opinion(&[], "CPT", 2023).is_none());
+        assert!(select_audit_opinion(&[fact("CPT", Some(2021), "Qualified")], "CPT", 2023).is_none());
+    }
+}
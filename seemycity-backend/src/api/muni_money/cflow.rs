@@ -0,0 +1,28 @@
+// src/api/muni_money/cflow.rs
+
+use super::cubes::CFLOW_CUBE;
+use super::{client::MunicipalMoneyClient, query::CutBuilder, types::*};
+
+impl MunicipalMoneyClient {
+    /// Fetches all cash-flow statement items for a specific municipality and
+    /// year using the aggregate endpoint.
+    /// Several `amount_types` are ORed into one request.
+    pub async fn fetch_cflow_aggregate(
+        &self,
+        municipality_code: &str,
+        year: i32,
+        amount_types: &[AmountType],
+    ) -> Result<FactsApiResponse<FinancialItemFact>, ApiClientError> {
+        // amount_type.code labels each cell, so a batched `AUDA;ORGB;ADJB` cut
+        // can be split back into amount types.
+        const DRILLDOWNS: &[&str] =
+            &["demarcation.code", "demarcation.label", "item.code", "item.label", "amount_type.code"];
+
+        let cuts = CutBuilder::new()
+            .amount_types(amount_types)
+            .number("financial_period.period", year)
+            .quoted("demarcation.code", municipality_code);
+
+        self.fetch_aggregate(CFLOW_CUBE, DRILLDOWNS, &cuts).await
+    }
+}
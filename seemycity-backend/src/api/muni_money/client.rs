@@ -1,20 +1,67 @@
 // src/api/muni_money/client.rs
-use super::types::{ApiClientError, AuditApiResponse};
+use super::cubes::AUDIT_OPINION_CUBE;
+use super::item_codes::ItemCodeSets;
+use super::query::{CubeQuery, CutBuilder};
+use super::types::{ApiClientError, AuditApiResponse, FactsApiResponse, FactsEndpointResponse, FinancialItemFact};
+use crate::config::{
+    MuniMoneySettings, DEFAULT_METRIC_DEADLINE_SECONDS, DEFAULT_MUNI_MONEY_API_BASE_URL, DEFAULT_TIMEOUT_SECONDS,
+    DEFAULT_USER_AGENT,
+};
+use crate::metrics::metrics;
+use reqwest::header::{HeaderMap, HeaderValue, FROM};
 use reqwest::Client;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
 use std::env;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::Semaphore;
 
 const MUNI_MONEY_API_BASE_URL_ENV_VAR: &str = "MUNI_MONEY_API_BASE_URL";
-const DEFAULT_MUNI_MONEY_API_BASE_URL: &str = "https://municipaldata.treasury.gov.za/api";
-// The Treasury API normally answers in 1-3s; when it is degraded it hangs, so a
-// tight timeout keeps a cold cache miss from blocking the request for long.
-const DEFAULT_TIMEOUT_SECONDS: u64 = 10;
+/// Built-in per-cube request timeouts, for cubes that answer much faster than
+/// the rest: a small audit_opinions lookup taking seconds means it is hung,
+/// not busy. Capped at the client's default timeout, so lowering
+/// MUNI_MONEY_TIMEOUT_SECS lowers these too.
+pub const DEFAULT_CUBE_TIMEOUTS: &[(&str, Duration)] = &[(AUDIT_OPINION_CUBE, Duration::from_secs(5))];
+// The API returns transient 502/503s often enough that one retry round is
+// worth it; delays double per attempt (500ms, 1s, 2s) plus jitter.
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+// Upper bound on aggregate pages followed for one request. A single
+// municipality-year is a few hundred cells, far under one page; hitting this
+// means the upstream's count is wrong, not that the data is that large.
+const MAX_AGGREGATE_PAGES: u32 = 50;
+// Rows asked for per `/facts` page; the API's own maximum. One
+// municipality-year of incexp is a few thousand facts (items × functions).
+const FACTS_PAGE_SIZE: usize = 10_000;
 
 /// Client for interacting with the Municipal Money API.
 #[derive(Debug, Clone)]
 pub struct MunicipalMoneyClient {
     client: Client,
     base_url: String,
+    /// Timeout for one request to a cube without an entry in `cube_timeouts`.
+    request_timeout: Duration,
+    /// Per-cube request timeouts, by cube name; see `with_cube_timeout`.
+    cube_timeouts: Arc<HashMap<String, Duration>>,
+    /// Request all amount types (AUDA/ORGB/ADJB) in one call per cube instead
+    /// of one call per amount type. Off by default.
+    batch_amount_types: bool,
+    /// Retries after the first attempt for 5xx responses and timeouts.
+    max_retries: u32,
+    /// Delay before the first retry; doubles for each further retry.
+    retry_base_delay: Duration,
+    /// Caps requests in flight across every cube method. Behind an `Arc` so
+    /// all clones (handlers, the cache warmer) draw on the same permits.
+    /// `None` = unlimited.
+    concurrency_limit: Option<Arc<Semaphore>>,
+    /// Which item codes the fetchers sum into each metric.
+    item_codes: Arc<ItemCodeSets>,
+    /// How long a refresh waits on any one metric before giving up on it.
+    metric_deadline: Duration,
+    /// Replace negative revenue/expenditure/debt/capex totals with zero
+    /// instead of keeping them for the confidence layer. Off by default.
+    clamp_negative_totals: bool,
 }
 
 impl MunicipalMoneyClient {
@@ -24,81 +71,403 @@ impl MunicipalMoneyClient {
     pub fn new() -> Result<Self, ApiClientError> {
         let base_url = env::var(MUNI_MONEY_API_BASE_URL_ENV_VAR)
             .unwrap_or_else(|_| DEFAULT_MUNI_MONEY_API_BASE_URL.to_string());
+        Self::with_base_url(base_url)
+    }
+
+    /// Creates a client against an explicit base URL (e.g. a local mock server
+    /// in tests), ignoring the environment.
+    pub fn with_base_url(base_url: impl Into<String>) -> Result<Self, ApiClientError> {
+        Self::build(base_url.into(), Duration::from_secs(DEFAULT_TIMEOUT_SECONDS), DEFAULT_USER_AGENT, None)
+    }
+
+    /// Creates a client from the loaded settings: base URL, the `User-Agent`
+    /// and `From` headers, request timeouts (default and per cube),
+    /// amount-type batching, the shared concurrency cap, the per-metric
+    /// deadline and negative-total clamping.
+    pub fn from_settings(settings: &MuniMoneySettings) -> Result<Self, ApiClientError> {
+        let client = Self::build(
+            settings.base_url.clone(),
+            Duration::from_secs(settings.timeout_secs),
+            &settings.user_agent,
+            settings.contact.as_deref(),
+        )?;
+        let client = settings
+            .cube_timeouts
+            .iter()
+            .fold(client, |client, (cube, secs)| client.with_cube_timeout(cube, Duration::from_secs(*secs)));
+        Ok(client
+            .with_batched_amount_types(settings.batch_amount_types)
+            .with_max_concurrency(settings.max_concurrency)
+            .with_metric_deadline(Duration::from_secs(settings.metric_deadline_secs))
+            .with_clamped_negative_totals(settings.clamp_negative_totals))
+    }
 
-        log::info!("Initializing Municipal Money client with base URL: {}", base_url);
+    /// Builds the shared reqwest client. `user_agent` and the optional
+    /// `contact` (sent as `From`) are default headers, so every request made
+    /// through the client identifies us.
+    fn build(
+        base_url: String,
+        timeout: Duration,
+        user_agent: &str,
+        contact: Option<&str>,
+    ) -> Result<Self, ApiClientError> {
+        log::info!("Initializing Municipal Money client with base URL: {} as {}", base_url, user_agent);
 
+        let mut headers = HeaderMap::new();
+        if let Some(contact) = contact {
+            let contact = HeaderValue::from_str(contact)
+                .map_err(|e| ApiClientError::InvalidParameters(format!("contact header '{contact}': {e}")))?;
+            headers.insert(FROM, contact);
+        }
         let client = Client::builder()
-            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECONDS))
+            .timeout(timeout)
+            .user_agent(user_agent)
+            .default_headers(headers)
             .build()
             .map_err(|e| {
                 log::error!("Failed to build reqwest client: {}", e);
                 ApiClientError::RequestError(e)
             })?;
 
-        Ok(Self { client, base_url })
+        let cube_timeouts = DEFAULT_CUBE_TIMEOUTS
+            .iter()
+            .map(|(cube, cube_timeout)| (cube.to_string(), (*cube_timeout).min(timeout)))
+            .collect();
+
+        Ok(Self {
+            client,
+            base_url,
+            request_timeout: timeout,
+            cube_timeouts: Arc::new(cube_timeouts),
+            batch_amount_types: false,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            concurrency_limit: None,
+            item_codes: Arc::new(ItemCodeSets::default()),
+            metric_deadline: Duration::from_secs(DEFAULT_METRIC_DEADLINE_SECONDS),
+            clamp_negative_totals: false,
+        })
     }
 
-    /// Fetches audit opinion facts for a specific municipality and year.
-    ///
-    /// # Arguments
-    /// * `municipality_code` - The demarcation code of the municipality (e.g., "BUF").
-    /// * `year` - The financial year end year.
-    ///
-    /// # Returns
-    /// A `Result` containing the parsed `AuditApiResponse` or an `ApiClientError`.
-    /// NOTE: This currently expects the API to return the old `AuditApiResponse` structure.
-    /// This might need updating if the audit endpoint changes or if we want to standardize response parsing.
+    /// Fetches the audit opinion facts for a municipality and year from the
+    /// cube's `/facts` endpoint. The opinion is a label, not a measure: the
+    /// rows themselves are the answer and there is nothing to sum.
     pub async fn fetch_audit_opinion_facts(
         &self,
         municipality_code: &str,
         year: i32,
     ) -> Result<AuditApiResponse, ApiClientError> {
-        const AUDIT_OPINION_CUBE: &str = "audit_opinions";
-        // Corrected drilldowns - removed financial_year_end.year
-        const AUDIT_DRILLDOWNS: &str = "demarcation.code|demarcation.label|opinion.code|opinion.label";
-        // Removed AUDIT_AGGREGATES constant
+        const AUDIT_FIELDS: &[&str] = &[
+            "demarcation.code",
+            "demarcation.label",
+            "financial_year_end.year",
+            "opinion.code",
+            "opinion.label",
+        ];
+
+        // The code is quoted, as in every other cube's cut (this one used to
+        // send it bare). The cube's cut syntax reads `"CPT"` and `CPT` as the
+        // same member; only the quoted form keeps a code with `|`, `:` or `;`
+        // in it as one value. `audit_opinion_cut_quotes_the_code` in
+        // tests/muni_money_mock_test.rs pins the exact cut.
+        let cuts = CutBuilder::new()
+            .number("financial_year_end.year", year)
+            .quoted("demarcation.code", municipality_code);
+        let url = CubeQuery::facts(AUDIT_OPINION_CUBE).fields(AUDIT_FIELDS).cut(&cuts).url(&self.base_url)?;
+
+        let data: AuditApiResponse = self
+            .fetch_json(AUDIT_OPINION_CUBE, &url)
+            .await
+            .inspect_err(|e| metrics().record_upstream_error(AUDIT_OPINION_CUBE, e))?;
+        log::trace!("Received Audit Opinion API response data: {:?}", data);
+        Ok(data)
+    }
+
+    /// Enables or disables batched amount-type fetching: one request per cube
+    /// carrying every amount type in the fallback chain, rather than one
+    /// request per amount type tried. Cuts a no-audited-data year from up to
+    /// three calls per cube to one.
+    pub fn with_batched_amount_types(mut self, enabled: bool) -> Self {
+        self.batch_amount_types = enabled;
+        self
+    }
+
+    /// Whether amount types are fetched in one batched call per cube.
+    pub fn batches_amount_types(&self) -> bool {
+        self.batch_amount_types
+    }
 
-        // Corrected cuts order and removed quotes around muni code (URL encoding handles this)
-        let cuts = format!(
-            "financial_year_end.year:{}|demarcation.code:{}",
-            year, municipality_code 
-        );
+    /// Replaces the built-in item-code selection (ITEM_CODES_FILE). The caller
+    /// validates it; see `ItemCodeSets::from_file`.
+    pub fn with_item_codes(mut self, item_codes: ItemCodeSets) -> Self {
+        self.item_codes = Arc::new(item_codes);
+        self
+    }
 
-        // Construct URL - Removed aggregates parameter
-        let url = format!(
-            "{}/cubes/{}/aggregate?drilldown={}&cut={}",
-            self.base_url, AUDIT_OPINION_CUBE, AUDIT_DRILLDOWNS, cuts
-        );
+    /// The item-code selection figures are summed with.
+    pub fn item_codes(&self) -> &ItemCodeSets {
+        &self.item_codes
+    }
 
-        log::debug!("Fetching Audit Opinions URL: {}", url);
+    /// Sets how long a refresh waits on any one metric (all its requests,
+    /// retries and amount-type fallbacks) before treating it as failed.
+    pub fn with_metric_deadline(mut self, deadline: Duration) -> Self {
+        self.metric_deadline = deadline;
+        self
+    }
 
-        let response = self.client.get(&url).send().await?;
+    /// See `with_metric_deadline`.
+    pub fn metric_deadline(&self) -> Duration {
+        self.metric_deadline
+    }
+
+    /// Runs `fetch` (one metric's requests, retries and fallbacks), abandoning
+    /// it with `DeadlineExceeded` once the metric deadline passes. Nothing is
+    /// logged here: the caller logs the error once, naming the metric.
+    pub async fn within_metric_deadline<T>(
+        &self,
+        fetch: impl std::future::Future<Output = Result<T, ApiClientError>>,
+    ) -> Result<T, ApiClientError> {
+        tokio::time::timeout(self.metric_deadline, fetch)
+            .await
+            .unwrap_or(Err(ApiClientError::DeadlineExceeded(self.metric_deadline)))
+    }
+
+    /// Enables or disables clamping negative aggregate totals to zero (see
+    /// `financials::checked_total`). Negative totals are logged either way.
+    pub fn with_clamped_negative_totals(mut self, enabled: bool) -> Self {
+        self.clamp_negative_totals = enabled;
+        self
+    }
+
+    /// Whether negative aggregate totals are clamped to zero.
+    pub fn clamps_negative_totals(&self) -> bool {
+        self.clamp_negative_totals
+    }
+
+    /// Sets how often transient failures (5xx, timeouts, connection errors)
+    /// are retried, and the delay before the first retry. Each further retry
+    /// doubles the delay, plus up to 50% random jitter. 4xx is never retried.
+    pub fn with_retry_policy(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_base_delay = base_delay;
+        self
+    }
+
+    /// Sets the timeout for each request to `cube` (e.g. `"incexp_v2"`),
+    /// replacing the client default and any built-in one for that cube: short
+    /// for cheap lookups, generous for the big aggregates. Each retry gets the
+    /// full timeout again.
+    pub fn with_cube_timeout(mut self, cube: &str, timeout: Duration) -> Self {
+        Arc::make_mut(&mut self.cube_timeouts).insert(cube.to_string(), timeout);
+        self
+    }
+
+    /// The timeout one request to `cube` is sent with.
+    pub fn cube_timeout(&self, cube: &str) -> Duration {
+        self.cube_timeouts.get(cube).copied().unwrap_or(self.request_timeout)
+    }
+
+    /// Caps how many Treasury API requests this client (and every clone of it)
+    /// has in flight at once; further requests wait for a free slot. `0`
+    /// removes the cap. Backoff sleeps between retries don't hold a slot.
+    pub fn with_max_concurrency(mut self, max_in_flight: usize) -> Self {
+        self.concurrency_limit = (max_in_flight > 0).then(|| Arc::new(Semaphore::new(max_in_flight)));
+        self
+    }
+
+    /// GETs `url` on `cube` with that cube's timeout, retrying transient
+    /// failures per the client's retry policy. The final response is returned
+    /// as-is (callers still check the status); non-transient request errors
+    /// are returned immediately.
+    pub(super) async fn get_with_retry(&self, cube: &str, url: &str) -> Result<reqwest::Response, ApiClientError> {
+        let timeout = self.cube_timeout(cube);
+        let mut attempt = 0;
+        loop {
+            let permit = match &self.concurrency_limit {
+                // The semaphore is never closed, so acquire cannot fail.
+                Some(limit) => Some(limit.acquire().await.expect("concurrency semaphore closed")),
+                None => None,
+            };
+            let sent = self.client.get(url).timeout(timeout).send().await;
+            drop(permit);
+            let transient = match sent {
+                Ok(response) if response.status().is_server_error() && attempt < self.max_retries => {
+                    format!("status {}", response.status())
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if (e.is_timeout() || e.is_connect()) && attempt < self.max_retries => e.to_string(),
+                Err(e) => return Err(ApiClientError::RequestError(e)),
+            };
+            let delay = backoff_delay(self.retry_base_delay, attempt);
+            attempt += 1;
+            log::warn!(
+                "Transient Treasury API failure ({transient}); retry {attempt}/{} in {:?}: {url}",
+                self.max_retries, delay
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// GETs `{base_url}/cubes/{cube}/aggregate` summing `amount` over the given
+    /// drilldowns and cuts, and parses the facts. Every financial cube fetcher
+    /// delegates here; a non-2xx status becomes `ApiClientError::ApiError`
+    /// carrying the upstream body.
+    pub(super) async fn fetch_aggregate(
+        &self,
+        cube: &str,
+        drilldowns: &[&str],
+        cuts: &CutBuilder,
+    ) -> Result<FactsApiResponse<FinancialItemFact>, ApiClientError> {
+        self.fetch_aggregate_of(cube, drilldowns, cuts, &["amount.sum"]).await
+    }
+
+    /// `fetch_aggregate` for a cube whose measure is not `amount` (e.g. the
+    /// demographics cube's `total_population`): sums `aggregates` instead and
+    /// parses the cells as `T`. Paging and error counting are the same.
+    pub(super) async fn fetch_aggregate_of<T: DeserializeOwned + std::fmt::Debug>(
+        &self,
+        cube: &str,
+        drilldowns: &[&str],
+        cuts: &CutBuilder,
+        aggregates: &[&str],
+    ) -> Result<FactsApiResponse<T>, ApiClientError> {
+        // A cell is unique by its drilldown, so ordering by it gives every
+        // page the same total order.
+        let query =
+            CubeQuery::aggregate(cube).drilldown(drilldowns).cut(cuts).aggregates(aggregates).order(drilldowns);
+        let url = query.url(&self.base_url)?;
+
+        let mut data = self.fetch_aggregate_page(cube, &url).await?;
+
+        // The aggregate endpoint caps the cells it returns per response and
+        // reports the full count in `total_cell_count`; the first page's
+        // length is the server's page size, so keep asking for the next page
+        // at that size until every cell is in. An empty page ends the loop
+        // early rather than spinning on a miscounted total.
+        let page_size = data.cells.len();
+        let mut page = 1;
+        while data.cells.len() < data.total_cell_count as usize && page_size > 0 {
+            page += 1;
+            if page > MAX_AGGREGATE_PAGES {
+                log::warn!(
+                    "{} aggregate still short after {} pages ({} of {} cells); using what we have",
+                    cube, MAX_AGGREGATE_PAGES, data.cells.len(), data.total_cell_count
+                );
+                break;
+            }
+            let page_url = query.clone().param("page", page).param("pagesize", page_size).url(&self.base_url)?;
+            let next = self.fetch_aggregate_page(cube, &page_url).await?;
+            if next.cells.is_empty() {
+                log::warn!(
+                    "{} aggregate page {} came back empty at {} of {} cells",
+                    cube, page, data.cells.len(), data.total_cell_count
+                );
+                break;
+            }
+            data.cells.extend(next.cells);
+        }
+
+        log::trace!("Received {} aggregate API response data: {:?}", cube, data);
+
+        Ok(data)
+    }
+
+    /// GETs `{base_url}/cubes/{cube}/facts` with the given fields and cuts:
+    /// the individual facts behind an aggregate, unsummed. Pages are followed
+    /// until `total_fact_count` rows are in, with the same bounds as
+    /// `fetch_aggregate`, every page sorted by `order`. Rows come back as
+    /// `FinancialItemFact`s (`amount` is the fact's own amount), so callers
+    /// can sum them like aggregate cells.
+    pub(super) async fn fetch_facts(
+        &self,
+        cube: &str,
+        fields: &[&str],
+        order: &[&str],
+        cuts: &CutBuilder,
+    ) -> Result<FactsApiResponse<FinancialItemFact>, ApiClientError> {
+        let query =
+            CubeQuery::facts(cube).fields(fields).cut(cuts).order(order).param("pagesize", FACTS_PAGE_SIZE);
+        let first = self.fetch_facts_page(cube, &query.url(&self.base_url)?).await?;
+        let total = first.total_fact_count;
+        let mut rows = first.data;
+        let mut page = 1;
+        while rows.len() < total as usize {
+            page += 1;
+            if page > MAX_AGGREGATE_PAGES {
+                log::warn!(
+                    "{} facts still short after {} pages ({} of {} rows); using what we have",
+                    cube, MAX_AGGREGATE_PAGES, rows.len(), total
+                );
+                break;
+            }
+            let next = self.fetch_facts_page(cube, &query.clone().param("page", page).url(&self.base_url)?).await?;
+            if next.data.is_empty() {
+                log::warn!("{} facts page {} came back empty at {} of {} rows", cube, page, rows.len(), total);
+                break;
+            }
+            rows.extend(next.data);
+        }
+
+        let cells: Vec<FinancialItemFact> = rows.into_iter().map(FinancialItemFact::from).collect();
+        Ok(FactsApiResponse { total_cell_count: cells.len() as u32, cells })
+    }
+
+    /// GETs one page of `/facts`; failures are counted like aggregate pages.
+    async fn fetch_facts_page(&self, cube: &str, url: &str) -> Result<FactsEndpointResponse, ApiClientError> {
+        self.fetch_json(cube, url).await.inspect_err(|e| metrics().record_upstream_error(cube, e))
+    }
+
+    /// GETs one page of an aggregate response, turning a non-success status
+    /// into `ApiClientError::ApiError`. Every failure is counted in
+    /// `seemycity_upstream_errors_total` under `cube`.
+    async fn fetch_aggregate_page<T: DeserializeOwned>(
+        &self,
+        cube: &str,
+        url: &str,
+    ) -> Result<FactsApiResponse<T>, ApiClientError> {
+        self.fetch_json(cube, url)
+            .await
+            .inspect_err(|e| metrics().record_upstream_error(cube, e))
+    }
+
+    /// GETs `url` and parses the JSON body (one aggregate or facts page of
+    /// `cube`). Not counted in the metrics; the callers do that.
+    async fn fetch_json<T: DeserializeOwned>(&self, cube: &str, url: &str) -> Result<T, ApiClientError> {
+        log::debug!("Fetching {} URL: {}", cube, url);
+
+        let response = self.get_with_retry(cube, url).await?;
 
         if !response.status().is_success() {
-             let status = response.status();
-             let body = response
+            let status = response.status();
+            let body = response
                 .text()
                 .await
                 .unwrap_or_else(|_| "Failed to read error body".to_string());
-             log::error!(
-                "Audit Opinion API request failed with status {}: {}",
-                status,
-                body
-            );
-             return Err(ApiClientError::ApiError {
-                status: status.as_u16(),
-                body: Some(body),
-            });
+            let error = ApiClientError::from_error_body(status.as_u16(), body);
+            log::error!("{} {}", cube, error);
+            return Err(error);
         }
 
-        // Deserialize. IMPORTANT: Assumes AuditApiResponse structure matches the aggregate response format.
-        // This might need adjustment based on the actual API response for the audit cube aggregate.
-        // If the audit aggregate response is different, we might need a separate struct or parsing logic.
-        let data: AuditApiResponse = response.json().await.map_err(ApiClientError::RequestError)?;
-
-        log::trace!("Received Audit Opinion API response data: {:?}", data);
+        response.json().await.map_err(ApiClientError::RequestError)
+    }
 
-        Ok(data)
+    /// Cheap reachability probe for health checks: GETs `{base_url}/cubes`
+    /// once with its own `timeout`. Not retried and not counted against the
+    /// concurrency cap, so a busy client can't make the probe wait.
+    pub async fn ping(&self, timeout: Duration) -> Result<(), ApiClientError> {
+        let url = format!("{}/cubes", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(ApiClientError::RequestError)?;
+        if !response.status().is_success() {
+            return Err(ApiClientError::ApiError { status: response.status().as_u16(), message: None, body: None });
+        }
+        Ok(())
     }
 
     /// Returns a reference to the internal reqwest::Client.
@@ -112,10 +481,37 @@ impl MunicipalMoneyClient {
     }
 }
 
+/// Exponential backoff: `base * 2^attempt`, plus up to 50% jitter so that
+/// concurrent requests failing together don't retry in lockstep.
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    use std::hash::{BuildHasher, Hasher};
+    let exponential = base.saturating_mul(2u32.saturating_pow(attempt));
+    // RandomState is randomly seeded per instance: cheap jitter without a rand dependency.
+    let random = std::collections::hash_map::RandomState::new().build_hasher().finish();
+    let jitter_ms = exponential.as_millis() as u64 / 2;
+    let jitter = if jitter_ms > 0 { Duration::from_millis(random % jitter_ms) } else { Duration::ZERO };
+    exponential + jitter
+}
+
 impl Default for MunicipalMoneyClient {
     /// Provides a default instance of the client. Panics if initialization fails.
     /// Useful for simpler setups or tests where failure is unexpected.
     fn default() -> Self {
         Self::new().expect("Failed to create default MunicipalMoneyClient")
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_with_bounded_jitter() {
+        let base = Duration::from_millis(500);
+        for (attempt, expected_ms) in [(0, 500), (1, 1000), (2, 2000)] {
+            let delay = backoff_delay(base, attempt);
+            assert!(delay >= Duration::from_millis(expected_ms), "{delay:?}");
+            assert!(delay < Duration::from_millis(expected_ms * 3 / 2), "{delay:?}");
+        }
+        assert_eq!(backoff_delay(Duration::ZERO, 3), Duration::ZERO);
+    }
+}
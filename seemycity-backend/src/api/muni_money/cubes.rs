@@ -0,0 +1,38 @@
+// src/api/muni_money/cubes.rs
+
+// Names of the Treasury cubes the client queries. Kept apart from the fetchers
+// so config can check MUNI_MONEY_CUBE_TIMEOUTS against them without depending
+// on the client.
+
+/// Income and expenditure; also the `cube` key of its stored raw facts.
+pub const INCEXP_CUBE: &str = "incexp_v2";
+/// Financial position (debt); also the `cube` key of its stored raw facts.
+pub const FINPOS_CUBE: &str = "financial_position_v2";
+/// Capital expenditure; also the `cube` key of its stored raw facts.
+pub const CAPEX_CUBE: &str = "capital_v2";
+/// Cash flow; also the `cube` key of its stored raw facts.
+pub const CFLOW_CUBE: &str = "cflow_v2";
+/// Repairs and maintenance; also the `cube` key of its stored raw facts.
+pub const REPMAINT_CUBE: &str = "repmaint_v2";
+/// Unauthorised, irregular, fruitless and wasteful expenditure; also the
+/// `cube` key of its stored raw facts.
+pub const UIFW_CUBE: &str = "uifwexp";
+/// Population; also the label of its upstream errors in the metrics.
+pub const DEMOGRAPHICS_CUBE: &str = "demographics";
+/// Household service access.
+pub const SERVICES_CUBE: &str = "households";
+/// The Auditor-General's opinions.
+pub const AUDIT_OPINION_CUBE: &str = "audit_opinions";
+
+/// Every cube the client queries: the keys MUNI_MONEY_CUBE_TIMEOUTS accepts.
+pub const CUBES: [&str; 9] = [
+    INCEXP_CUBE,
+    FINPOS_CUBE,
+    CAPEX_CUBE,
+    CFLOW_CUBE,
+    REPMAINT_CUBE,
+    UIFW_CUBE,
+    DEMOGRAPHICS_CUBE,
+    SERVICES_CUBE,
+    AUDIT_OPINION_CUBE,
+];
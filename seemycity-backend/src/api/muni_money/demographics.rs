@@ -0,0 +1,67 @@
+// src/api/muni_money/demographics.rs
+
+use super::client::MunicipalMoneyClient;
+use super::cubes::DEMOGRAPHICS_CUBE;
+use super::query::CutBuilder;
+use super::types::{ApiClientError, FactsApiResponse, PopulationFact};
+use crate::utils::head_count;
+
+impl MunicipalMoneyClient {
+    /// Fetches the population aggregate for a municipality and year. The cube
+    /// is keyed by plain `year` (no financial year end, no amount type) and
+    /// holds StatsSA estimates, so a year without one is an empty response.
+    pub async fn fetch_population_aggregate(
+        &self,
+        municipality_code: &str,
+        year: i32,
+    ) -> Result<FactsApiResponse<PopulationFact>, ApiClientError> {
+        const DRILLDOWNS: &[&str] = &["demarcation.code"];
+        const AGGREGATES: &[&str] = &["total_population.sum"];
+
+        let cuts = CutBuilder::new().number("year", year).quoted("demarcation.code", municipality_code);
+        self.fetch_aggregate_of(DEMOGRAPHICS_CUBE, DRILLDOWNS, &cuts, AGGREGATES).await
+    }
+}
+
+/// Fetches a municipality's population for `year` from the demographics cube.
+///
+/// Returns `None` when the cube has no figure for that year, or when the
+/// figure is not a usable head count (see [`population_from_sum`]).
+pub async fn get_population(
+    client: &MunicipalMoneyClient,
+    muni_code: &str,
+    year: i32,
+) -> Result<Option<u32>, ApiClientError> {
+    let response = client.fetch_population_aggregate(muni_code, year).await?;
+    let Some(total) = response.cells.first().and_then(|cell| cell.total_population) else {
+        return Ok(None);
+    };
+    let population = population_from_sum(total);
+    if population.is_none() {
+        log::warn!("Ignoring population {} for {} in {}: not a head count", total, muni_code, year);
+    }
+    Ok(population)
+}
+
+/// Rounds an upstream population sum to a head count (see
+/// [`head_count`]). Zero is `None` too: the cube has no estimate, not an
+/// empty municipality.
+pub fn population_from_sum(total: f64) -> Option<u32> {
+    head_count(total).filter(|population| *population > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_positive_finite_sums_become_head_counts() {
+        assert_eq!(population_from_sum(4_772_846.0), Some(4_772_846));
+        assert_eq!(population_from_sum(1000.6), Some(1001));
+        assert_eq!(population_from_sum(f64::NAN), None);
+        assert_eq!(population_from_sum(f64::INFINITY), None);
+        assert_eq!(population_from_sum(-5.0), None);
+        assert_eq!(population_from_sum(0.2), None);
+        assert_eq!(population_from_sum(1e12), None);
+    }
+}
@@ -1,36 +1,110 @@
 //! Functions for fetching specific financial data points from the Municipal Money API.
 
 use super::client::MunicipalMoneyClient;
-use super::types::{ApiClientError, FinancialItemFact};
-use std::ops::RangeInclusive;
+use super::item_codes::{ItemCodeSet, ItemCodeSets};
+use super::types::{AmountType, ApiClientError, FactsApiResponse, FinancialItemFact};
+use std::future::Future;
 use sqlx::types::Decimal;
 use rust_decimal::prelude::FromPrimitive;
 
-// incexp_v2 item ranges on the cube's mSCOA basis, validated 2026-07-07
-// against Cape Town's audited AFS FY2024 (note 37.4.1 reconciliation:
-// mSCOA expenditure 58.45bn vs our sum 58.67bn, +0.4%; mSCOA revenue
-// 61.47bn vs our sum 61.84bn, +0.6%) and against the rollup identity
-// below across 8 sample municipalities. See docs/backend-tech.md.
-//
-// Operating revenue: property rates, service charges, transfers (2200),
-// fines, gains, etc. Excludes capital transfers (4600/4700) and all
-// below-the-line items (4900+).
-const REVENUE_ITEM_RANGE: RangeInclusive<u32> = 200..=2800;
-// Operating expenditure: payroll (3100), bulk purchases, depreciation,
-// contracted services, operational cost (4100), losses (4300), etc.
-const EXPENDITURE_ITEM_RANGE: RangeInclusive<u32> = 3000..=4300;
-// Item 2900 "Other expenditure" is in fact a TOTAL-REVENUE ROLLUP
-// (|2900 - Σrevenue| = 0.00% for every municipality tested) mislabeled in
-// the Treasury cube. It sits between the two ranges and must never be
-// summed into either.
-const REVENUE_ROLLUP_ITEM: u32 = 2900;
-
-/// Sums the amounts of cells whose numeric item code falls in `range`.
+/// Default amount-type preference for the incexp/finpos/capital cubes: audited
+/// actuals, then the original budget (ORGB), then the adjusted budget (ADJB).
+/// Audited figures lag by a year or more, so recent years often only have
+/// budgets published; a budget number beats no number, provided it is marked.
+pub const DEFAULT_AMOUNT_TYPES: [AmountType; 3] =
+    [AmountType::Audited, AmountType::OriginalBudget, AmountType::AdjustedBudget];
+
+/// A summed cube figure together with the amount type that produced it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourcedAmount {
+    pub amount: Option<Decimal>,
+    /// The amount type whose facts were summed; `None` when none yielded data.
+    pub amount_type: Option<AmountType>,
+    /// The cells behind `amount` (chosen amount type only, each labelled with
+    /// it), kept so the figure can be re-derived without refetching.
+    pub facts: Vec<FinancialItemFact>,
+}
+
+/// Calls `fetch` with each amount type in preference order until one returns
+/// any cells. Returns the response with the amount type that produced it, or
+/// `None` when every amount type came back empty. Transport/API errors abort
+/// the chain immediately: an unreachable upstream is not "no data".
+///
+/// When `batched`, all amount types are requested in a single call (the cube
+/// ORs `;`-separated cut values and the `amount_type.code` drilldown labels
+/// each cell), and the preferred type present is picked from that one
+/// response — one round-trip per cube instead of up to one per amount type.
+async fn fetch_with_amount_type_fallback<F, Fut>(
+    amount_types: &[AmountType],
+    batched: bool,
+    what: &str,
+    mut fetch: F,
+) -> Result<Option<(FactsApiResponse<FinancialItemFact>, AmountType)>, ApiClientError>
+where
+    F: FnMut(Vec<AmountType>) -> Fut,
+    Fut: Future<Output = Result<FactsApiResponse<FinancialItemFact>, ApiClientError>>,
+{
+    if batched {
+        let response = fetch(amount_types.to_vec()).await?;
+        return Ok(pick_preferred_amount_type(response, amount_types, what));
+    }
+    for &amount_type in amount_types {
+        let mut response = fetch(vec![amount_type]).await?;
+        if !response.cells.is_empty() {
+            // Label the cells as the batched drilldown would, so stored facts
+            // say which amount type they are.
+            for cell in &mut response.cells {
+                cell.amount_type_code.get_or_insert_with(|| amount_type.as_code().to_string());
+            }
+            if amount_type != AmountType::Audited {
+                log::info!("No audited {what} facts; using amount type {amount_type}");
+            }
+            return Ok(Some((response, amount_type)));
+        }
+        log::debug!("No {what} facts for amount type {amount_type}");
+    }
+    Ok(None)
+}
+
+/// Narrows a multi-amount-type response to the cells of the most preferred
+/// amount type present in it.
+fn pick_preferred_amount_type(
+    response: FactsApiResponse<FinancialItemFact>,
+    amount_types: &[AmountType],
+    what: &str,
+) -> Option<(FactsApiResponse<FinancialItemFact>, AmountType)> {
+    let chosen = *amount_types
+        .iter()
+        .find(|t| response.cells.iter().any(|c| c.amount_type_code.as_deref() == Some(t.as_code())))?;
+    if chosen != AmountType::Audited {
+        log::info!("No audited {what} facts; using amount type {chosen}");
+    }
+    let cells: Vec<FinancialItemFact> = response
+        .cells
+        .into_iter()
+        .filter(|c| c.amount_type_code.as_deref() == Some(chosen.as_code()))
+        .collect();
+    Some((FactsApiResponse { total_cell_count: cells.len() as u32, cells }, chosen))
+}
+
+/// The least authoritative amount type among the figures that make up one
+/// row, ranked by position in `preference`. A row only counts as audited when
+/// every figure in it was; one budgeted input makes the whole row budgeted.
+pub fn least_authoritative_amount_type(used: &[Option<AmountType>], preference: &[AmountType]) -> Option<AmountType> {
+    used.iter()
+        .flatten()
+        .max_by_key(|t| preference.iter().position(|p| p == *t).unwrap_or(usize::MAX))
+        .copied()
+}
+
+/// Sums the amounts of cells whose numeric item code is in `codes`, never
+/// counting `rollup` (a cube total, not an item).
 /// Returns `None` when no matching fact carried an amount — "no data",
 /// as distinct from a legitimate sum of zero.
-fn sum_item_range(
+fn sum_item_codes(
     cells: &[FinancialItemFact],
-    range: &RangeInclusive<u32>,
+    codes: &ItemCodeSet,
+    rollup: Option<u32>,
     what: &str,
 ) -> Option<Decimal> {
     let mut total = Decimal::ZERO;
@@ -40,7 +114,7 @@ fn sum_item_range(
         let Ok(code) = fact.item_code.parse::<u32>() else {
             continue;
         };
-        if code == REVENUE_ROLLUP_ITEM || !range.contains(&code) {
+        if Some(code) == rollup || !codes.contains(code) {
             continue;
         }
         if let Some(amount_f64) = fact.amount {
@@ -63,9 +137,54 @@ fn sum_item_range(
     facts_found.then_some(total)
 }
 
-/// Operational transfers/grants received (item 2200) — the denominator split
-/// for the own-revenue metric.
-const OPERATIONAL_TRANSFERS_ITEM: u32 = 2200;
+/// Sums every cell's amount, whatever its item code; `None` when no cell
+/// carried one.
+fn sum_all(cells: &[FinancialItemFact], what: &str) -> Option<Decimal> {
+    let mut total = Decimal::ZERO;
+    let mut facts_found = false;
+    for fact in cells {
+        let Some(amount_f64) = fact.amount else { continue };
+        match Decimal::from_f64(amount_f64) {
+            Some(amount_decimal) => {
+                log::trace!(
+                    "{} item: code={}, label='{}', amount={}",
+                    what, fact.item_code, fact.item_label, amount_decimal
+                );
+                total += amount_decimal;
+                facts_found = true;
+            }
+            None => log::warn!("Could not convert {} amount {} to Decimal for item {}", what, amount_f64, fact.item_code),
+        }
+    }
+    facts_found.then_some(total)
+}
+
+/// Screens a summed total for a negative sign. Revenue, expenditure, debt and
+/// capital spend cannot be below zero; a negative sum means reversals
+/// outweighed the postings in the return, so it is logged. With `clamp` it is
+/// replaced by zero; without, it is kept and the confidence layer grades the
+/// row unreliable (see `confidence.rs`). Cash is not screened: an overdraft
+/// is a real negative balance. The fetchers return totals as summed; the
+/// screen is applied once, where a row is scored, so fetched and replayed
+/// facts are treated alike.
+pub fn checked_total(
+    total: Option<Decimal>,
+    what: &str,
+    municipality_code: &str,
+    year: i32,
+    clamp: bool,
+) -> Option<Decimal> {
+    match total {
+        Some(amount) if amount < Decimal::ZERO => {
+            log::warn!(
+                "Negative {} total for {} year {}: {}{}",
+                what, municipality_code, year, amount, if clamp { " (clamped to 0)" } else { "" }
+            );
+            Some(if clamp { Decimal::ZERO } else { amount })
+        }
+        other => other,
+    }
+}
 
 /// Figures extracted from one incexp_v2 aggregate response.
 #[derive(Debug, Clone, Default)]
@@ -78,69 +197,210 @@ pub struct IncexpFigures {
     /// The cube's own total-revenue rollup (item 2900), used as a checksum
     /// against `revenue` by the data-confidence layer.
     pub revenue_checksum: Option<Decimal>,
+    /// Amount type the figures were taken from.
+    pub amount_type: Option<AmountType>,
+    /// The incexp cells the figures were summed from; see [`SourcedAmount::facts`].
+    pub facts: Vec<FinancialItemFact>,
+}
+
+/// Revenue, operational expenditure, transfers and the revenue checksum from
+/// incexp_v2 cells, as fetched or as stored in `raw_financial_facts`, using
+/// the item codes selected in `codes`. `facts` is left empty; the caller
+/// decides whether to keep the cells.
+pub fn incexp_figures_from_facts(
+    cells: &[FinancialItemFact],
+    amount_type: Option<AmountType>,
+    codes: &ItemCodeSets,
+) -> IncexpFigures {
+    let rollup = Some(codes.revenue_rollup);
+    IncexpFigures {
+        revenue: sum_item_codes(cells, &codes.revenue, rollup, "revenue"),
+        operational_expenditure: sum_item_codes(cells, &codes.operational_expenditure, rollup, "expenditure"),
+        transfers_operational: sum_item_codes(cells, &codes.transfers_operational, rollup, "operational transfers"),
+        revenue_checksum: cells
+            .iter()
+            .find(|c| c.item_code.parse::<u32>() == Ok(codes.revenue_rollup))
+            .and_then(|c| c.amount)
+            .and_then(Decimal::from_f64),
+        amount_type,
+        facts: Vec::new(),
+    }
+}
+
+/// Total liabilities from financial_position_v2 cells.
+pub fn debt_from_facts(cells: &[FinancialItemFact], codes: &ItemCodeSets) -> Option<Decimal> {
+    sum_item_codes(cells, &codes.debt, None, "debt")
+}
+
+/// Capital expenditure from capital_v2 cells: every item counts.
+pub fn capital_expenditure_from_facts(cells: &[FinancialItemFact]) -> Option<Decimal> {
+    sum_all(cells, "capital expenditure")
+}
+
+/// Cash at year end from cflow_v2 cells.
+pub fn cash_balance_from_facts(cells: &[FinancialItemFact], codes: &ItemCodeSets) -> Option<Decimal> {
+    sum_item_codes(cells, &codes.cash_balance, None, "cash at year end")
+}
+
+/// UIFW or repairs & maintenance total: every item counts.
+pub fn total_from_facts(cells: &[FinancialItemFact], what: &str) -> Option<Decimal> {
+    sum_all(cells, what)
 }
 
 /// Fetches total revenue and total operational expenditure together from a
-/// single incexp_v2 aggregate call ('AUDA' figures). Both metrics live in the
-/// same cube, so fetching them separately would download the identical
-/// response twice.
+/// single incexp_v2 aggregate call, trying `amount_types` in order (see
+/// [`DEFAULT_AMOUNT_TYPES`]). Both metrics live in the same cube, so fetching
+/// them separately would download the identical response twice.
 pub async fn get_revenue_and_expenditure(
     client: &MunicipalMoneyClient,
     municipality_code: &str,
     year: i32,
+    amount_types: &[AmountType],
 ) -> Result<IncexpFigures, ApiClientError> {
     log::info!(
         "Fetching incexp aggregate for revenue + expenditure, {} year {}",
         municipality_code, year
     );
-    let response = client
-        .fetch_incexp_aggregate(municipality_code, year, "AUDA")
-        .await?;
+    let Some((response, amount_type)) = fetch_with_amount_type_fallback(
+        amount_types,
+        client.batches_amount_types(),
+        "incexp",
+        |t| async move { client.fetch_incexp_aggregate(municipality_code, year, &t).await },
+    )
+    .await?
+    else {
+        return Ok(IncexpFigures::default());
+    };
 
-    let revenue = sum_item_range(&response.cells, &REVENUE_ITEM_RANGE, "revenue");
-    let expenditure = sum_item_range(&response.cells, &EXPENDITURE_ITEM_RANGE, "expenditure");
-    let transfers_operational = sum_item_range(
-        &response.cells,
-        &(OPERATIONAL_TRANSFERS_ITEM..=OPERATIONAL_TRANSFERS_ITEM),
-        "operational transfers",
-    );
-    let revenue_checksum = response
-        .cells
-        .iter()
-        .find(|c| c.item_code.parse::<u32>() == Ok(REVENUE_ROLLUP_ITEM))
-        .and_then(|c| c.amount)
-        .and_then(Decimal::from_f64);
+    let figures = incexp_figures_from_facts(&response.cells, Some(amount_type), client.item_codes());
     log::info!(
-        "Incexp results for {} in {}: revenue={:?}, expenditure={:?}",
-        municipality_code, year, revenue, expenditure
+        "Incexp results for {} in {} ({}): revenue={:?}, expenditure={:?}",
+        municipality_code, year, amount_type, figures.revenue, figures.operational_expenditure
     );
-    Ok(IncexpFigures {
-        revenue,
-        operational_expenditure: expenditure,
-        transfers_operational,
-        revenue_checksum,
-    })
+    Ok(IncexpFigures { facts: response.cells, ..figures })
+}
+
+/// Which side of the income statement a breakdown covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakdownKind {
+    Revenue,
+    Expenditure,
+}
+
+impl BreakdownKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BreakdownKind::Revenue => "revenue",
+            BreakdownKind::Expenditure => "expenditure",
+        }
+    }
+}
+
+/// One item code's facts summed, for a breakdown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakdownLine {
+    pub item_code: String,
+    pub item_label: String,
+    pub amount: Decimal,
+}
+
+/// The line items behind revenue or operational expenditure, largest first,
+/// with the amount type they were taken from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IncexpBreakdown {
+    pub amount_type: Option<AmountType>,
+    pub lines: Vec<BreakdownLine>,
+}
+
+/// Groups facts by item code over the items `codes` selects (never the
+/// rollup), summing each item's facts. Lines are ordered by amount,
+/// largest first, then by item code; items without any amount are left out.
+pub fn breakdown_from_facts(cells: &[FinancialItemFact], codes: &ItemCodeSet, rollup: u32) -> Vec<BreakdownLine> {
+    let mut by_item: std::collections::BTreeMap<&str, BreakdownLine> = std::collections::BTreeMap::new();
+    for fact in cells {
+        let Ok(code) = fact.item_code.parse::<u32>() else { continue };
+        if code == rollup || !codes.contains(code) {
+            continue;
+        }
+        let Some(amount) = fact.amount.and_then(Decimal::from_f64) else { continue };
+        by_item
+            .entry(fact.item_code.as_str())
+            .or_insert_with(|| BreakdownLine {
+                item_code: fact.item_code.clone(),
+                item_label: fact.item_label.clone(),
+                amount: Decimal::ZERO,
+            })
+            .amount += amount;
+    }
+    let mut lines: Vec<BreakdownLine> = by_item.into_values().collect();
+    // Stable sort: equal amounts keep item-code order.
+    lines.sort_by_key(|l| std::cmp::Reverse(l.amount));
+    lines
+}
+
+/// Revenue line items for a municipality-year, from the individual incexp
+/// facts, with the same item selection and amount-type fallback as
+/// [`get_revenue_and_expenditure`], so the lines add up to its revenue.
+pub async fn get_revenue_breakdown(
+    client: &MunicipalMoneyClient,
+    municipality_code: &str,
+    year: i32,
+    amount_types: &[AmountType],
+) -> Result<IncexpBreakdown, ApiClientError> {
+    get_incexp_breakdown(client, municipality_code, year, amount_types, BreakdownKind::Revenue).await
+}
+
+/// Operational expenditure line items; see [`get_revenue_breakdown`].
+pub async fn get_expenditure_breakdown(
+    client: &MunicipalMoneyClient,
+    municipality_code: &str,
+    year: i32,
+    amount_types: &[AmountType],
+) -> Result<IncexpBreakdown, ApiClientError> {
+    get_incexp_breakdown(client, municipality_code, year, amount_types, BreakdownKind::Expenditure).await
+}
+
+async fn get_incexp_breakdown(
+    client: &MunicipalMoneyClient,
+    municipality_code: &str,
+    year: i32,
+    amount_types: &[AmountType],
+    kind: BreakdownKind,
+) -> Result<IncexpBreakdown, ApiClientError> {
+    log::info!("Fetching incexp facts for {} breakdown, {} year {}", kind.as_str(), municipality_code, year);
+    let Some((response, amount_type)) = fetch_with_amount_type_fallback(
+        amount_types,
+        client.batches_amount_types(),
+        "incexp facts",
+        |t| async move { client.fetch_incexp_facts(municipality_code, year, &t).await },
+    )
+    .await?
+    else {
+        return Ok(IncexpBreakdown::default());
+    };
+
+    let codes = client.item_codes();
+    let selected = match kind {
+        BreakdownKind::Revenue => &codes.revenue,
+        BreakdownKind::Expenditure => &codes.operational_expenditure,
+    };
+    let lines = breakdown_from_facts(&response.cells, selected, codes.revenue_rollup);
+    Ok(IncexpBreakdown { amount_type: (!lines.is_empty()).then_some(amount_type), lines })
 }
 
 /// Total Unauthorised, Irregular, Fruitless & Wasteful expenditure for a
 /// municipality and financial year. `None` = no UIFW facts reported for the
 /// year (which, per the AG's publication practice, usually means none was
 /// identified — but is treated as "unknown" by scoring, never as an earned 0).
+/// The cube has no amount type, so `amount_type` is always `None`.
 pub async fn get_uifw_total(
     client: &MunicipalMoneyClient,
     municipality_code: &str,
     year: i32,
-) -> Result<Option<Decimal>, ApiClientError> {
+) -> Result<SourcedAmount, ApiClientError> {
     let response = client.fetch_uifw_aggregate(municipality_code, year).await?;
-    let mut total = Decimal::ZERO;
-    let mut found = false;
-    for cell in &response.cells {
-        if let Some(amount) = cell.amount.and_then(Decimal::from_f64) {
-            total += amount;
-            found = true;
-        }
-    }
-    Ok(found.then_some(total))
+    let amount = total_from_facts(&response.cells, "UIFW");
+    Ok(SourcedAmount { amount, amount_type: None, facts: response.cells })
 }
 
 /// Total repairs & maintenance spend (audited actuals) for a municipality-year.
@@ -148,19 +408,39 @@ pub async fn get_repairs_maintenance(
     client: &MunicipalMoneyClient,
     municipality_code: &str,
     year: i32,
-) -> Result<Option<Decimal>, ApiClientError> {
-    let response = client
-        .fetch_repmaint_aggregate(municipality_code, year, "AUDA")
+) -> Result<SourcedAmount, ApiClientError> {
+    let mut response = client
+        .fetch_repmaint_aggregate(municipality_code, year, &[AmountType::Audited])
         .await?;
-    let mut total = Decimal::ZERO;
-    let mut found = false;
-    for cell in &response.cells {
-        if let Some(amount) = cell.amount.and_then(Decimal::from_f64) {
-            total += amount;
-            found = true;
-        }
+    for cell in &mut response.cells {
+        cell.amount_type_code.get_or_insert_with(|| AmountType::Audited.as_code().to_string());
     }
-    Ok(found.then_some(total))
+    let amount = total_from_facts(&response.cells, "repairs & maintenance");
+    Ok(SourcedAmount { amount_type: amount.is_some().then_some(AmountType::Audited), amount, facts: response.cells })
+}
+
+/// Cash and cash equivalents at year end (`cflow_v2` item 4200 by default), trying
+/// `amount_types` in order (audited first by default). Feeds the Liquidity
+/// pillar. May be negative (an overdraft).
+pub async fn get_cash_balance(
+    client: &MunicipalMoneyClient,
+    municipality_code: &str,
+    year: i32,
+    amount_types: &[AmountType],
+) -> Result<SourcedAmount, ApiClientError> {
+    let Some((response, amount_type)) = fetch_with_amount_type_fallback(
+        amount_types,
+        client.batches_amount_types(),
+        "cflow",
+        |t| async move { client.fetch_cflow_aggregate(municipality_code, year, &t).await },
+    )
+    .await?
+    else {
+        return Ok(SourcedAmount::default());
+    };
+
+    let cash = cash_balance_from_facts(&response.cells, client.item_codes());
+    Ok(SourcedAmount { amount_type: cash.is_some().then_some(amount_type), amount: cash, facts: response.cells })
 }
 
 /// Fetches the total operating and capital revenue for a municipality in a given year.
@@ -171,57 +451,62 @@ pub async fn get_total_revenue(
     municipality_code: &str,
     year: i32,
 ) -> Result<Option<Decimal>, ApiClientError> {
-    Ok(get_revenue_and_expenditure(client, municipality_code, year).await?.revenue)
+    Ok(get_revenue_and_expenditure(client, municipality_code, year, &DEFAULT_AMOUNT_TYPES)
+        .await?
+        .revenue)
 }
 
-/// Fetches the total liabilities (debt) for a given municipality and year.
-/// Prioritizes 'AUDA' amount type.
+/// Operational grants and transfers received (incexp item 2200) for a
+/// municipality-year — the part of revenue the municipality did not raise
+/// itself. They live in the same cube as revenue, so this is a thin wrapper
+/// over [`get_revenue_and_expenditure`]; prefer that function when you also
+/// need revenue.
+pub async fn get_grants_and_transfers(
+    client: &MunicipalMoneyClient,
+    municipality_code: &str,
+    year: i32,
+) -> Result<Option<Decimal>, ApiClientError> {
+    Ok(get_revenue_and_expenditure(client, municipality_code, year, &DEFAULT_AMOUNT_TYPES)
+        .await?
+        .transfers_operational)
+}
+
+/// Fetches the total liabilities (debt) for a given municipality and year,
+/// trying `amount_types` in order (audited first by default).
 pub async fn get_total_debt(
     client: &MunicipalMoneyClient,
     municipality_code: &str,
     year: i32,
-) -> Result<Option<Decimal>, ApiClientError> { 
+    amount_types: &[AmountType],
+) -> Result<SourcedAmount, ApiClientError> {
     log::info!("Fetching all finpos items via aggregate for debt calculation {} year {}", municipality_code, year);
-    let response = client
-        .fetch_finpos_aggregate(municipality_code, year, "AUDA")
-        .await?;
+    let Some((response, amount_type)) = fetch_with_amount_type_fallback(
+        amount_types,
+        client.batches_amount_types(),
+        "finpos",
+        |t| async move { client.fetch_finpos_aggregate(municipality_code, year, &t).await },
+    )
+    .await?
+    else {
+        log::info!("No finpos facts found for {} in {}", municipality_code, year);
+        return Ok(SourcedAmount::default());
+    };
 
-    let mut total_debt = Decimal::ZERO; 
-    let mut facts_found = false;
-
-    for fact in response.cells.iter() {
-         if let Ok(code) = fact.item_code.parse::<u32>() {
-            if (310..=500).contains(&code) {
-                 if let Some(amount_f64) = fact.amount {
-                    if let Some(amount_decimal) = Decimal::from_f64(amount_f64) {
-                        log::trace!(
-                            "Debt item: code={}, label='{}', amount={}",
-                            fact.item_code,
-                            fact.item_label,
-                            amount_decimal 
-                        );
-                        total_debt += amount_decimal;
-                        facts_found = true;
-                    } else {
-                         log::warn!("Could not convert debt amount {} to Decimal for item {}", amount_f64, fact.item_code);
-                    }
-                 }
-            }
+    match debt_from_facts(&response.cells, client.item_codes()) {
+        Some(total_debt) => {
+            log::info!(
+                "Calculated total debt for {} year {} ({}): {}",
+                municipality_code, year, amount_type, total_debt
+            );
+            Ok(SourcedAmount { amount: Some(total_debt), amount_type: Some(amount_type), facts: response.cells })
+        }
+        None => {
+            log::info!(
+                "No valid debt facts found for {} in {}",
+                municipality_code, year
+            );
+            Ok(SourcedAmount { facts: response.cells, ..SourcedAmount::default() })
         }
-    }
-
-     if facts_found {
-         log::info!( 
-            "Calculated total debt for {} year {}: {}", 
-            municipality_code, year, total_debt
-        );
-        Ok(Some(total_debt))
-    } else {
-        log::info!(
-            "No valid debt facts found for {} in {}",
-            municipality_code, year
-        );
-        Ok(None)
     }
 }
 
@@ -233,56 +518,55 @@ pub async fn get_total_expenditure(
     municipality_code: &str,
     year: i32,
 ) -> Result<Option<Decimal>, ApiClientError> {
-    Ok(get_revenue_and_expenditure(client, municipality_code, year)
+    Ok(get_revenue_and_expenditure(client, municipality_code, year, &DEFAULT_AMOUNT_TYPES)
         .await?
         .operational_expenditure)
 }
 
-/// Fetches the total capital expenditure for a given municipality and year.
-/// Assumes the API returns an aggregated sum for the 'capital_v2' cube.
-/// Prioritizes 'AUDA' amount type.
+/// Fetches the total capital expenditure for a given municipality and year
+/// from the 'capital_v2' cube, trying `amount_types` in order (audited first
+/// by default).
 pub async fn get_capital_expenditure(
     client: &MunicipalMoneyClient,
     municipality_code: &str,
     year: i32,
-) -> Result<Option<Decimal>, ApiClientError> { 
+    amount_types: &[AmountType],
+) -> Result<SourcedAmount, ApiClientError> {
     log::info!("Fetching all capital items via aggregate for capital expenditure calculation {} year {}", municipality_code, year);
-    let response = client.fetch_capital_aggregate(municipality_code, year, "AUDA").await?;
+    let Some((response, amount_type)) = fetch_with_amount_type_fallback(
+        amount_types,
+        client.batches_amount_types(),
+        "capital",
+        |t| async move { client.fetch_capital_aggregate(municipality_code, year, &t).await },
+    )
+    .await?
+    else {
+        log::info!("No capital facts found for {} in {}", municipality_code, year);
+        return Ok(SourcedAmount::default());
+    };
 
-    let mut capital_expenditure = Decimal::ZERO; 
-    let mut facts_found = false;
-
-    for fact in response.cells.iter() {
-         if let Some(amount_f64) = fact.amount {
-             if let Some(amount_decimal) = Decimal::from_f64(amount_f64) {
-                log::trace!(
-                    "Capital expenditure item: code={}, label='{}', amount={}",
-                    fact.item_code,
-                    fact.item_label,
-                    amount_decimal 
-                );
-                capital_expenditure += amount_decimal;
-                facts_found = true;
-             } else {
-                 log::warn!("Could not convert capital expenditure amount {} to Decimal for item {}", amount_f64, fact.item_code);
-             }
-         }
+    match capital_expenditure_from_facts(&response.cells) {
+        Some(capital_expenditure) => {
+            log::info!(
+                "Fetched capital expenditure for {} year {} ({}): {}",
+                municipality_code, year, amount_type, capital_expenditure
+            );
+            Ok(SourcedAmount {
+                amount: Some(capital_expenditure),
+                amount_type: Some(amount_type),
+                facts: response.cells,
+            })
+        }
+        None => {
+            log::info!(
+                "No valid capital expenditure facts found for {} in {}",
+                municipality_code, year
+            );
+            Ok(SourcedAmount { facts: response.cells, ..SourcedAmount::default() })
+        }
     }
-
-     if facts_found {
-         log::info!( 
-            "Fetched capital expenditure for {} year {}: {}", 
-            municipality_code, year, capital_expenditure
-        );
-        Ok(Some(capital_expenditure))
-     } else {
-         log::info!(
-            "No valid capital expenditure facts found for {} in {}",
-            municipality_code, year
-        );
-         Ok(None)
-     }
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -294,10 +578,16 @@ mod tests {
             demarcation_label: "Test".to_string(),
             item_code: code.to_string(),
             item_label: format!("Item {code}"),
+            amount_type_code: None,
             amount,
         }
     }
 
+    /// Incexp figures under the built-in item-code selection.
+    fn incexp(cells: &[FinancialItemFact]) -> IncexpFigures {
+        incexp_figures_from_facts(cells, None, &ItemCodeSets::default())
+    }
+
     #[test]
     fn revenue_range_includes_gains_and_transfers_excludes_rollup_and_capital() {
         let cells = vec![
@@ -308,10 +598,7 @@ mod tests {
             fact("3100", Some(70.0)),  // payroll — expenditure, out
             fact("4600", Some(40.0)),  // capital transfers — out
         ];
-        assert_eq!(
-            sum_item_range(&cells, &REVENUE_ITEM_RANGE, "revenue"),
-            Some(dec!(175.0))
-        );
+        assert_eq!(incexp(&cells).revenue, Some(dec!(175.0)));
     }
 
     #[test]
@@ -324,16 +611,181 @@ mod tests {
             fact("4600", Some(40.0)),  // capital transfers — out
             fact("4900", Some(5.0)),   // income tax — out
         ];
+        assert_eq!(incexp(&cells).operational_expenditure, Some(dec!(100.0)));
+    }
+
+    fn response(cells: Vec<FinancialItemFact>) -> FactsApiResponse<FinancialItemFact> {
+        FactsApiResponse { total_cell_count: cells.len() as u32, cells }
+    }
+
+    #[tokio::test]
+    async fn amount_type_fallback_skips_empty_responses_in_order() {
+        let mut tried = Vec::new();
+        let result = fetch_with_amount_type_fallback(&DEFAULT_AMOUNT_TYPES, false, "test", |t| {
+            let cells = if t == [AmountType::AdjustedBudget] { vec![fact("1800", Some(1.0))] } else { vec![] };
+            tried.push(t);
+            async move { Ok(response(cells)) }
+        })
+        .await
+        .unwrap();
         assert_eq!(
-            sum_item_range(&cells, &EXPENDITURE_ITEM_RANGE, "expenditure"),
-            Some(dec!(100.0))
+            tried,
+            vec![vec![AmountType::Audited], vec![AmountType::OriginalBudget], vec![AmountType::AdjustedBudget]]
         );
+        let (resp, amount_type) = result.unwrap();
+        assert_eq!(amount_type, AmountType::AdjustedBudget);
+        assert_eq!(resp.cells[0].amount_type_code.as_deref(), Some("ADJB"), "cells are labelled for storage");
+    }
+
+    #[tokio::test]
+    async fn amount_type_fallback_stops_at_first_hit_and_honours_order() {
+        let mut tried = Vec::new();
+        let preference = [AmountType::OriginalBudget, AmountType::Audited];
+        let result = fetch_with_amount_type_fallback(&preference, false, "test", |t| {
+            tried.push(t);
+            async move { Ok(response(vec![fact("1800", Some(1.0))])) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(tried, vec![vec![AmountType::OriginalBudget]]);
+        assert_eq!(result.map(|(_, t)| t), Some(AmountType::OriginalBudget));
+    }
+
+    #[tokio::test]
+    async fn amount_type_fallback_all_empty_is_none_and_errors_abort() {
+        let none = fetch_with_amount_type_fallback(&DEFAULT_AMOUNT_TYPES, false, "test", |_| async {
+            Ok(response(vec![]))
+        })
+        .await
+        .unwrap();
+        assert!(none.is_none());
+
+        let mut calls = 0;
+        let err = fetch_with_amount_type_fallback(&DEFAULT_AMOUNT_TYPES, false, "test", |_| {
+            calls += 1;
+            async { Err(ApiClientError::NoData) }
+        })
+        .await;
+        assert!(err.is_err());
+        assert_eq!(calls, 1, "an upstream error must not be retried as 'no data'");
+    }
+
+    #[tokio::test]
+    async fn batched_fallback_makes_one_call_and_keeps_preferred_type_only() {
+        let mut tried = Vec::new();
+        let result = fetch_with_amount_type_fallback(&DEFAULT_AMOUNT_TYPES, true, "test", |t| {
+            tried.push(t);
+            let mut orgb = fact("1800", Some(1.0));
+            orgb.amount_type_code = Some("ORGB".to_string());
+            let mut adjb = fact("1800", Some(2.0));
+            adjb.amount_type_code = Some("ADJB".to_string());
+            async move { Ok(response(vec![adjb, orgb])) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(tried, vec![DEFAULT_AMOUNT_TYPES.to_vec()]);
+        let (resp, amount_type) = result.unwrap();
+        assert_eq!(amount_type, AmountType::OriginalBudget);
+        assert_eq!(resp.cells.len(), 1);
+        assert_eq!(resp.cells[0].amount, Some(1.0));
+    }
+
+    #[test]
+    fn row_amount_type_is_least_authoritative_used() {
+        let pref = DEFAULT_AMOUNT_TYPES;
+        let (auda, orgb) = (Some(AmountType::Audited), Some(AmountType::OriginalBudget));
+        assert_eq!(least_authoritative_amount_type(&[auda, auda, None], &pref), auda);
+        assert_eq!(least_authoritative_amount_type(&[auda, orgb, auda], &pref), orgb);
+        assert_eq!(least_authoritative_amount_type(&[None, None], &pref), None);
     }
 
     #[test]
     fn no_matching_facts_is_none_not_zero() {
         let cells = vec![fact("2900", Some(999.0)), fact("bogus", Some(1.0))];
-        assert_eq!(sum_item_range(&cells, &REVENUE_ITEM_RANGE, "revenue"), None);
-        assert_eq!(sum_item_range(&cells, &EXPENDITURE_ITEM_RANGE, "expenditure"), None);
+        assert_eq!(incexp(&cells).revenue, None);
+        assert_eq!(incexp(&cells).operational_expenditure, None);
+    }
+
+    #[test]
+    fn figures_from_stored_facts_match_the_fetch_path_sums() {
+        let cells = vec![
+            fact("1800", Some(100.0)),
+            fact("2200", Some(50.0)),
+            fact("2900", Some(150.0)),
+            fact("3100", Some(70.0)),
+            fact("4100", Some(30.0)),
+        ];
+        let codes = ItemCodeSets::default();
+        let figures = incexp_figures_from_facts(&cells, Some(AmountType::Audited), &codes);
+        assert_eq!(figures.revenue, Some(dec!(150)));
+        assert_eq!(figures.operational_expenditure, Some(dec!(100)));
+        assert_eq!(figures.transfers_operational, Some(dec!(50)));
+        assert_eq!(figures.revenue_checksum, Some(dec!(150)));
+        assert!(figures.facts.is_empty());
+
+        let finpos = vec![fact("0300", Some(1.0)), fact("0310", Some(20.0)), fact("0500", Some(5.0)), fact("0600", Some(9.0))];
+        assert_eq!(debt_from_facts(&finpos, &codes), Some(dec!(25)));
+        assert_eq!(capital_expenditure_from_facts(&[fact("x", Some(3.0)), fact("y", None)]), Some(dec!(3)));
+        assert_eq!(cash_balance_from_facts(&[fact("4200", Some(-12.0))], &codes), Some(dec!(-12)));
+        assert_eq!(total_from_facts(&[fact("unauthorised", None)], "UIFW"), None);
+    }
+
+    #[test]
+    fn negative_totals_are_kept_or_clamped() {
+        assert_eq!(checked_total(Some(dec!(-500)), "revenue", "TST", 2024, false), Some(dec!(-500)));
+        assert_eq!(checked_total(Some(dec!(-500)), "revenue", "TST", 2024, true), Some(Decimal::ZERO));
+        assert_eq!(checked_total(Some(dec!(500)), "revenue", "TST", 2024, true), Some(dec!(500)));
+        assert_eq!(checked_total(None, "revenue", "TST", 2024, true), None);
+    }
+
+    #[test]
+    fn custom_item_codes_change_the_totals() {
+        let cells = vec![
+            fact("1800", Some(100.0)),
+            fact("2200", Some(50.0)),
+            fact("2900", Some(150.0)),
+            fact("3100", Some(70.0)),
+            fact("4400", Some(20.0)), // outside the default expenditure range
+        ];
+        let revised = ItemCodeSets {
+            revenue: ItemCodeSet::parse(&["1800"]).unwrap(),
+            operational_expenditure: ItemCodeSet::parse(&["3000-4300", "4400"]).unwrap(),
+            transfers_operational: ItemCodeSet::parse(&["1800"]).unwrap(),
+            ..ItemCodeSets::default()
+        };
+        assert_eq!(revised.validate(), Ok(()));
+
+        let default = incexp(&cells);
+        let custom = incexp_figures_from_facts(&cells, None, &revised);
+        assert_eq!((default.revenue, custom.revenue), (Some(dec!(150)), Some(dec!(100))));
+        assert_eq!(
+            (default.operational_expenditure, custom.operational_expenditure),
+            (Some(dec!(70)), Some(dec!(90)))
+        );
+        assert_eq!(custom.transfers_operational, Some(dec!(100)));
+        assert_eq!(custom.revenue_checksum, Some(dec!(150)));
+    }
+
+    #[test]
+    fn breakdown_sums_facts_per_item_largest_first() {
+        let codes = ItemCodeSets::default();
+        let cells = vec![
+            fact("1800", Some(40.0)), // property rates, function A
+            fact("2200", Some(90.0)),
+            fact("1800", Some(60.0)), // property rates, function B
+            fact("2900", Some(190.0)), // rollup — never a line
+            fact("3100", Some(70.0)),  // expenditure
+            fact("2700", None),
+        ];
+        let lines = breakdown_from_facts(&cells, &codes.revenue, codes.revenue_rollup);
+        let summary: Vec<(&str, Decimal)> = lines.iter().map(|l| (l.item_code.as_str(), l.amount)).collect();
+        assert_eq!(summary, vec![("1800", dec!(100)), ("2200", dec!(90))]);
+        assert_eq!(lines[0].item_label, "Item 1800");
+        let total: Decimal = lines.iter().map(|l| l.amount).sum();
+        assert_eq!(Some(total), incexp(&cells).revenue, "lines add up to the aggregate figure");
+
+        let spend = breakdown_from_facts(&cells, &codes.operational_expenditure, codes.revenue_rollup);
+        assert_eq!(spend.len(), 1);
+        assert_eq!(spend[0].amount, dec!(70));
     }
 }
@@ -1,53 +1,51 @@
-use super::{client::MunicipalMoneyClient, types::*};
+use super::cubes::INCEXP_CUBE;
+use super::{client::MunicipalMoneyClient, query::CutBuilder, types::*};
 
 impl MunicipalMoneyClient {
     /// Fetches all income/expenditure items for a specific municipality and year
     /// using the aggregate endpoint.
+    /// Several `amount_types` are ORed into one request.
     pub async fn fetch_incexp_aggregate(
         &self,
         municipality_code: &str,
         year: i32,
-        amount_type: &str,
+        amount_types: &[AmountType],
     ) -> Result<FactsApiResponse<FinancialItemFact>, ApiClientError> {
-        const INCEXP_CUBE: &str = "incexp_v2";
-        const DRILLDOWNS: &str = "demarcation.code|demarcation.label|item.code|item.label";
-        const AGGREGATES: &str = "amount.sum";
+        // amount_type.code labels each cell, so a batched `AUDA;ORGB;ADJB` cut
+        // can be split back into amount types.
+        const DRILLDOWNS: &[&str] =
+            &["demarcation.code", "demarcation.label", "item.code", "item.label", "amount_type.code"];
 
-        let cuts = format!(
-            "amount_type.code:{}|financial_period.period:{}|demarcation.code:\"{}\"",
-            amount_type, year, municipality_code
-        );
+        let cuts = CutBuilder::new()
+            .amount_types(amount_types)
+            .number("financial_period.period", year)
+            .quoted("demarcation.code", municipality_code);
 
-        let url = format!(
-            "{}/cubes/{}/aggregate?drilldown={}&cut={}&aggregates={}",
-            self.base_url(), INCEXP_CUBE, DRILLDOWNS, cuts, AGGREGATES
-        );
-
-        log::debug!("Fetching Incexp Aggregate URL: {}", url);
-
-        let response = self.client().get(&url).send().await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Failed to read error body".to_string());
-            log::error!(
-                "Incexp Aggregate API request failed with status {}: {}",
-                status,
-                body
-            );
-            return Err(ApiClientError::ApiError {
-                status: status.as_u16(),
-                body: Some(body),
-            });
-        }
-
-        let data: FactsApiResponse<FinancialItemFact> = response.json().await.map_err(ApiClientError::RequestError)?;
-
-        log::trace!("Received Incexp Aggregate API response data: {:?}", data);
+        self.fetch_aggregate(INCEXP_CUBE, DRILLDOWNS, &cuts).await
+    }
 
-        Ok(data)
+    /// Fetches the individual income/expenditure facts (not summed by item)
+    /// for a municipality and year from the `/facts` endpoint, for line-item
+    /// breakdowns. One item usually has several facts, one per function.
+    /// Several `amount_types` are ORed into one request.
+    pub async fn fetch_incexp_facts(
+        &self,
+        municipality_code: &str,
+        year: i32,
+        amount_types: &[AmountType],
+    ) -> Result<FactsApiResponse<FinancialItemFact>, ApiClientError> {
+        const FIELDS: &[&str] =
+            &["demarcation.code", "demarcation.label", "item.code", "item.label", "amount_type.code", "amount"];
+        // Facts of one item differ by function, which isn't fetched; rows that
+        // still tie are identical in every field summed, so their order can't
+        // change a breakdown.
+        const ORDER: &[&str] = &["item.code", "amount_type.code", "amount"];
+
+        let cuts = CutBuilder::new()
+            .amount_types(amount_types)
+            .number("financial_period.period", year)
+            .quoted("demarcation.code", municipality_code);
+
+        self.fetch_facts(INCEXP_CUBE, FIELDS, ORDER, &cuts).await
     }
 }
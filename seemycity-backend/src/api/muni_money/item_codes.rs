@@ -0,0 +1,268 @@
+//! Which cube item codes feed which metric. The built-in default is the
+//! selection validated against audited AFS (see `financials.rs`); when the
+//! Treasury revises its chart of accounts, an ITEM_CODES_FILE can replace it
+//! without a code change, e.g.
+//!
+//! ```json
+//! {
+//!   "revenue": ["0200-2800"],
+//!   "operational_expenditure": ["3000-4300"],
+//!   "transfers_operational": ["2200"],
+//!   "revenue_rollup": "2900",
+//!   "debt": ["0310-0500"],
+//!   "cash_balance": ["4200"]
+//! }
+//! ```
+//!
+//! Each entry is one item code or an inclusive `from-to` range. Every metric
+//! must be present; unknown metrics are rejected. A changed selection applies
+//! to new fetches, and to stored facts via `POST /api/admin/recompute-from-raw`.
+
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// Item codes are four digits.
+const MAX_ITEM_CODE: u32 = 9999;
+
+/// The item codes of one metric: single codes and inclusive ranges, none
+/// overlapping another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<String>", into = "Vec<String>")]
+pub struct ItemCodeSet {
+    ranges: Vec<RangeInclusive<u32>>,
+}
+
+impl ItemCodeSet {
+    /// Parses entries like `"2200"` or `"0200-2800"`. Malformed entries,
+    /// reversed ranges and entries that repeat a code already covered are
+    /// errors: a code listed twice is a typo, not a request to count it twice.
+    pub fn parse<S: AsRef<str>>(entries: &[S]) -> Result<Self, String> {
+        let mut ranges: Vec<RangeInclusive<u32>> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let entry = entry.as_ref().trim();
+            let (from, to) = entry.split_once('-').unwrap_or((entry, entry));
+            let parse_code = |code: &str| {
+                code.trim()
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|code| *code <= MAX_ITEM_CODE)
+                    .ok_or_else(|| format!("'{entry}' is not an item code or a from-to range of item codes"))
+            };
+            let (from, to) = (parse_code(from)?, parse_code(to)?);
+            if from > to {
+                return Err(format!("range '{entry}' runs backwards"));
+            }
+            if let Some(clash) = ranges.iter().find(|r| from <= *r.end() && *r.start() <= to) {
+                return Err(format!(
+                    "'{entry}' repeats item codes already listed ({:04}-{:04})",
+                    clash.start(),
+                    clash.end()
+                ));
+            }
+            ranges.push(from..=to);
+        }
+        Ok(ItemCodeSet { ranges })
+    }
+
+    fn of(ranges: Vec<RangeInclusive<u32>>) -> Self {
+        ItemCodeSet { ranges }
+    }
+
+    /// True when the numeric item `code` is selected.
+    pub fn contains(&self, code: u32) -> bool {
+        self.ranges.iter().any(|r| r.contains(&code))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    fn overlaps(&self, other: &ItemCodeSet) -> bool {
+        self.ranges
+            .iter()
+            .any(|a| other.ranges.iter().any(|b| a.start() <= b.end() && b.start() <= a.end()))
+    }
+
+    fn is_subset_of(&self, other: &ItemCodeSet) -> bool {
+        self.ranges.iter().all(|r| (*r.start()..=*r.end()).all(|code| other.contains(code)))
+    }
+}
+
+impl TryFrom<Vec<String>> for ItemCodeSet {
+    type Error = String;
+
+    fn try_from(entries: Vec<String>) -> Result<Self, Self::Error> {
+        ItemCodeSet::parse(&entries)
+    }
+}
+
+impl From<ItemCodeSet> for Vec<String> {
+    fn from(set: ItemCodeSet) -> Self {
+        set.ranges
+            .iter()
+            .map(|r| {
+                if r.start() == r.end() {
+                    format!("{:04}", r.start())
+                } else {
+                    format!("{:04}-{:04}", r.start(), r.end())
+                }
+            })
+            .collect()
+    }
+}
+
+/// Item-code selection per metric. `Default` is the built-in selection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItemCodeSets {
+    /// incexp_v2 operating revenue.
+    pub revenue: ItemCodeSet,
+    /// incexp_v2 operating expenditure.
+    pub operational_expenditure: ItemCodeSet,
+    /// incexp_v2 operational grants received; part of revenue.
+    pub transfers_operational: ItemCodeSet,
+    /// incexp_v2 total-revenue rollup: read as the revenue checksum and never
+    /// summed into any metric.
+    #[serde(with = "rollup_code")]
+    pub revenue_rollup: u32,
+    /// financial_position_v2 liabilities summed into debt.
+    pub debt: ItemCodeSet,
+    /// cflow_v2 cash and cash equivalents at year end.
+    pub cash_balance: ItemCodeSet,
+}
+
+// incexp_v2 item ranges on the cube's mSCOA basis, validated 2026-07-07
+// against Cape Town's audited AFS FY2024 (note 37.4.1 reconciliation:
+// mSCOA expenditure 58.45bn vs our sum 58.67bn, +0.4%; mSCOA revenue
+// 61.47bn vs our sum 61.84bn, +0.6%) and against the rollup identity
+// below across 8 sample municipalities. See docs/backend-tech.md.
+impl Default for ItemCodeSets {
+    fn default() -> Self {
+        ItemCodeSets {
+            // Operating revenue: property rates, service charges, transfers
+            // (2200), fines, gains, etc. Excludes capital transfers
+            // (4600/4700) and all below-the-line items (4900+).
+            revenue: ItemCodeSet::of(vec![200..=2800]),
+            // Operating expenditure: payroll (3100), bulk purchases,
+            // depreciation, contracted services, operational cost (4100),
+            // losses (4300), etc.
+            operational_expenditure: ItemCodeSet::of(vec![3000..=4300]),
+            // The denominator split for the own-revenue metric.
+            transfers_operational: ItemCodeSet::of(vec![2200..=2200]),
+            // Item 2900 "Other expenditure" is in fact a TOTAL-REVENUE ROLLUP
+            // (|2900 - Σrevenue| = 0.00% for every municipality tested)
+            // mislabeled in the Treasury cube. It sits between the two ranges
+            // and must never be summed into either.
+            revenue_rollup: 2900,
+            debt: ItemCodeSet::of(vec![310..=500]),
+            cash_balance: ItemCodeSet::of(vec![4200..=4200]),
+        }
+    }
+}
+
+impl ItemCodeSets {
+    /// Checks the selection is coherent: no empty metric, no item counted as
+    /// both revenue and expenditure, transfers inside revenue (own revenue is
+    /// revenue minus transfers) and the rollup outside both sums. Returns
+    /// every violation.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        let metrics = [
+            ("revenue", &self.revenue),
+            ("operational_expenditure", &self.operational_expenditure),
+            ("transfers_operational", &self.transfers_operational),
+            ("debt", &self.debt),
+            ("cash_balance", &self.cash_balance),
+        ];
+        for (name, set) in metrics {
+            if set.is_empty() {
+                errors.push(format!("{name}: must list at least one item code"));
+            }
+        }
+        if self.revenue.overlaps(&self.operational_expenditure) {
+            errors.push("revenue and operational_expenditure share item codes".to_string());
+        }
+        if !self.transfers_operational.is_subset_of(&self.revenue) {
+            errors.push("transfers_operational must only list revenue item codes".to_string());
+        }
+        for (name, set) in &metrics[..2] {
+            if set.contains(self.revenue_rollup) {
+                errors.push(format!("{name}: must not include the revenue rollup item {:04}", self.revenue_rollup));
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Loads and validates a selection from a JSON file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read item-code file {}: {e}", path.display()))?;
+        let sets: ItemCodeSets = serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid item-code file {}: {e}", path.display()))?;
+        sets.validate()
+            .map_err(|errors| format!("Invalid item-code file {}: {}", path.display(), errors.join("; ")))?;
+        log::info!("Item-code selection loaded from {}", path.display());
+        Ok(sets)
+    }
+}
+
+/// The rollup is a single code, written like any other entry.
+mod rollup_code {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(code: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{code:04}"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        code.trim()
+            .parse()
+            .map_err(|_| serde::de::Error::custom(format!("'{code}' is not an item code")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_selection_is_valid_and_round_trips() {
+        let default = ItemCodeSets::default();
+        assert_eq!(default.validate(), Ok(()));
+        let json = serde_json::to_string(&default).unwrap();
+        assert!(json.contains("\"0200-2800\""), "{json}");
+        assert_eq!(serde_json::from_str::<ItemCodeSets>(&json).unwrap(), default);
+    }
+
+    #[test]
+    fn malformed_and_repeated_codes_are_rejected() {
+        assert!(ItemCodeSet::parse(&["0200", "0300-0400"]).is_ok());
+        assert!(ItemCodeSet::parse(&["revenue"]).unwrap_err().contains("not an item code"));
+        assert!(ItemCodeSet::parse(&["0200-99999"]).unwrap_err().contains("not an item code"));
+        assert!(ItemCodeSet::parse(&["0400-0300"]).unwrap_err().contains("backwards"));
+        assert!(ItemCodeSet::parse(&["0200-2800", "2200"]).unwrap_err().contains("repeats"));
+        assert!(ItemCodeSet::parse(&["0250", "0250"]).unwrap_err().contains("repeats"));
+    }
+
+    #[test]
+    fn incoherent_selections_fail_validation() {
+        let sets = ItemCodeSets {
+            operational_expenditure: ItemCodeSet::parse(&["2800-4300"]).unwrap(),
+            transfers_operational: ItemCodeSet::parse(&["5000"]).unwrap(),
+            cash_balance: ItemCodeSet::parse::<&str>(&[]).unwrap(),
+            ..ItemCodeSets::default()
+        };
+        let errors = sets.validate().unwrap_err();
+        assert_eq!(errors.len(), 4, "{errors:?}");
+        assert!(errors.iter().any(|e| e.contains("share item codes")));
+        assert!(errors.iter().any(|e| e.contains("transfers_operational")));
+        assert!(errors.iter().any(|e| e.starts_with("cash_balance")));
+        assert!(errors.iter().any(|e| e.contains("rollup item 2900")));
+
+        let unknown = r#"{"revenue": ["0200"], "grants": ["2200"]}"#;
+        assert!(serde_json::from_str::<ItemCodeSets>(unknown).is_err());
+    }
+}
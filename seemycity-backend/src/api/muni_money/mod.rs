@@ -2,14 +2,20 @@
 
 // Declare the modules within this submodule
 pub mod client;
+pub mod cubes;
 pub mod incexp;
 pub mod finpos;
 pub mod capex;
+pub mod cflow;
 pub mod financials;
+pub mod item_codes;
+pub mod query;
 pub mod repmaint;
+pub mod services;
 pub mod types;
 pub mod audit;
 pub mod uifw;
+pub mod demographics;
 
 // Optional: Re-export key items for easier access within the muni_money module itself, if needed.
 // pub use client::MunicipalMoneyClient;
@@ -0,0 +1,29 @@
+// src/api/muni_money/services.rs
+
+use super::cubes::SERVICES_CUBE;
+use super::{client::MunicipalMoneyClient, query::CutBuilder, types::*};
+
+impl MunicipalMoneyClient {
+    /// Fetches household service-access facts (water, electricity, sanitation,
+    /// refuse removal) for a municipality and financial year. Cells are
+    /// returned as-is so a later `get_service_access` in `financials.rs` can
+    /// pick out and sum the relevant items.
+    ///
+    /// Cube shape (not yet probed against the live API — confirm with
+    /// `test_fetch_real_household_services`): keyed by `financial_year_end.year`
+    /// like `uifwexp`, with no `amount_type` dimension; `item.*` identifies the
+    /// service and level of access, and `amount.sum` is a household count.
+    pub async fn fetch_household_services_aggregate(
+        &self,
+        municipality_code: &str,
+        year: i32,
+    ) -> Result<FactsApiResponse<FinancialItemFact>, ApiClientError> {
+        const DRILLDOWNS: &[&str] = &["demarcation.code", "demarcation.label", "item.code", "item.label"];
+
+        let cuts = CutBuilder::new()
+            .number("financial_year_end.year", year)
+            .quoted("demarcation.code", municipality_code);
+
+        self.fetch_aggregate(SERVICES_CUBE, DRILLDOWNS, &cuts).await
+    }
+}
@@ -13,10 +13,16 @@ pub enum ApiClientError {
     #[error("Failed to parse JSON response: {0}")]
     ParseError(#[from] serde_json::Error),
 
-    /// The API returned an error status code (e.g., 4xx, 5xx).
-    #[error("API request failed with status {status}: {body:?}")]
+    /// The API returned an error status code (e.g., 4xx, 5xx). `message` is
+    /// the `message`/`detail` of a JSON error body; `body` is the raw text.
+    /// Build from a response with [`ApiClientError::from_error_body`].
+    #[error(
+        "API request failed with status {status}: {}",
+        message.as_deref().or(body.as_deref()).unwrap_or("(no body)")
+    )]
     ApiError {
         status: u16,
+        message: Option<String>,
         body: Option<String>,
     },
 
@@ -35,23 +41,119 @@ pub enum ApiClientError {
     /// Invalid parameters provided.
     #[error("Invalid parameters provided: {0}")]
     InvalidParameters(String),
+
+    /// A fetch (retries and amount-type fallbacks included) outlasted its
+    /// overall deadline and was abandoned.
+    #[error("Gave up waiting for the Treasury API after {0:?}")]
+    DeadlineExceeded(std::time::Duration),
+}
+
+impl ApiClientError {
+    /// An `ApiError` for a non-success response: the raw `body`, plus its
+    /// message when the body is JSON (see [`error_body_message`]).
+    pub fn from_error_body(status: u16, body: String) -> Self {
+        ApiClientError::ApiError { status, message: error_body_message(&body), body: Some(body) }
+    }
 }
 
-/// Represents a single fact from the audit_opinions cube.
+/// The human-readable part of an upstream JSON error body: its top-level
+/// `message` or, failing that, `detail` (e.g. the reason a cut was rejected).
+/// `None` when the body is not JSON or has neither field; a non-string field
+/// is kept as its JSON text.
+pub fn error_body_message(body: &str) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    let field = ["message", "detail"]
+        .into_iter()
+        .filter_map(|key| json.get(key))
+        .find(|value| !value.is_null())?;
+    Some(match field {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    })
+}
+
+/// The `amount_type.code` dimension of the incexp/finpos/capital/cflow/repmaint
+/// cubes: which version of a figure a fact is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AmountType {
+    /// Audited actuals (`AUDA`): what every figure is scored on by preference.
+    Audited,
+    /// Pre-audit outcome (`PAUD`): year-end actuals before the AG's audit.
+    PreAudit,
+    /// Original budget (`ORGB`).
+    OriginalBudget,
+    /// Adjusted (mid-year) budget (`ADJB`).
+    AdjustedBudget,
+}
+
+impl AmountType {
+    /// The cube's code for this amount type, as used in cuts and stored rows.
+    pub fn as_code(self) -> &'static str {
+        match self {
+            AmountType::Audited => "AUDA",
+            AmountType::PreAudit => "PAUD",
+            AmountType::OriginalBudget => "ORGB",
+            AmountType::AdjustedBudget => "ADJB",
+        }
+    }
+
+    /// Cut value selecting every one of `types` (the cube ORs `;`-separated
+    /// values).
+    pub fn cut_value(types: &[AmountType]) -> String {
+        types.iter().map(|t| t.as_code()).collect::<Vec<_>>().join(";")
+    }
+}
+
+impl std::fmt::Display for AmountType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_code())
+    }
+}
+
+/// Parses a cube code (`"AUDA"`, ...), e.g. from a stored row or cell.
+impl TryFrom<&str> for AmountType {
+    type Error = ApiClientError;
+
+    fn try_from(code: &str) -> Result<Self, Self::Error> {
+        match code {
+            "AUDA" => Ok(AmountType::Audited),
+            "PAUD" => Ok(AmountType::PreAudit),
+            "ORGB" => Ok(AmountType::OriginalBudget),
+            "ADJB" => Ok(AmountType::AdjustedBudget),
+            other => Err(ApiClientError::InvalidParameters(format!("unknown amount type '{other}'"))),
+        }
+    }
+}
+
+/// One row of the audit_opinions cube's `/facts` endpoint: the opinion the
+/// AG issued on a municipality's year. `year` is `None` only when a response
+/// leaves the field out.
 #[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct AuditOpinionFact {
     #[serde(rename = "demarcation.code")]
     pub demarcation_code: String,
     #[serde(rename = "demarcation.label")]
     pub demarcation_label: String,
+    #[serde(rename = "financial_year_end.year", default)]
+    pub year: Option<i32>,
     #[serde(rename = "opinion.label")]
     pub opinion_label: String,
     #[serde(rename = "opinion.code")]
     pub opinion_code: String,
 }
 
+/// One aggregate cell of the demographics cube: a municipality's population
+/// for the cut year.
+#[derive(Debug, Deserialize, Clone, Serialize, PartialEq)]
+pub struct PopulationFact {
+    #[serde(rename = "demarcation.code")]
+    pub demarcation_code: String,
+    #[serde(rename = "total_population.sum")]
+    pub total_population: Option<f64>,
+}
+
 /// Represents a single financial item entry from the aggregate endpoint.
-#[derive(Debug, Deserialize, Clone, Serialize)]
+#[derive(Debug, Deserialize, Clone, Serialize, PartialEq)]
 pub struct FinancialItemFact {
     #[serde(rename = "demarcation.code")]
     pub demarcation_code: String,
@@ -61,6 +163,9 @@ pub struct FinancialItemFact {
     pub item_code: String,
     #[serde(rename = "item.label")]
     pub item_label: String,
+    /// Present when `amount_type.code` is drilled down (batched amount types).
+    #[serde(rename = "amount_type.code", default)]
+    pub amount_type_code: Option<String>,
     #[serde(rename = "amount.sum")]
     pub amount: Option<f64>,
 }
@@ -72,10 +177,82 @@ pub struct FactsApiResponse<T> {
     pub cells: Vec<T>,
 }
 
-/// Represents the overall structure of the /facts API response for the audit_opinions cube.
+/// One row of a cube's `/facts` endpoint: a single, un-summed fact. Fields
+/// are requested by name, so the keys match the aggregate cells' drilldowns,
+/// except the measure, which is the raw `amount`.
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct FactsEndpointRow {
+    #[serde(rename = "demarcation.code")]
+    pub demarcation_code: String,
+    #[serde(rename = "demarcation.label")]
+    pub demarcation_label: String,
+    #[serde(rename = "item.code")]
+    pub item_code: String,
+    #[serde(rename = "item.label")]
+    pub item_label: String,
+    #[serde(rename = "amount_type.code", default)]
+    pub amount_type_code: Option<String>,
+    pub amount: Option<f64>,
+}
+
+impl From<FactsEndpointRow> for FinancialItemFact {
+    fn from(row: FactsEndpointRow) -> Self {
+        FinancialItemFact {
+            demarcation_code: row.demarcation_code,
+            demarcation_label: row.demarcation_label,
+            item_code: row.item_code,
+            item_label: row.item_label,
+            amount_type_code: row.amount_type_code,
+            amount: row.amount,
+        }
+    }
+}
+
+/// One page of a cube's `/facts` endpoint.
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct FactsEndpointResponse {
+    pub total_fact_count: u32,
+    pub data: Vec<FactsEndpointRow>,
+}
+
+/// The audit_opinions cube's `/facts` response.
 #[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct AuditApiResponse {
-    pub total_cell_count: u32,
-    pub cells: Vec<AuditOpinionFact>,
+    pub total_fact_count: u32,
+    pub data: Vec<AuditOpinionFact>,
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_error_bodies_yield_their_message() {
+        let error = ApiClientError::from_error_body(400, r#"{"message": "Invalid cut: 'year'"}"#.to_string());
+        assert_eq!(error.to_string(), "API request failed with status 400: Invalid cut: 'year'");
+        assert_eq!(error_body_message(r#"{"detail": ["bad drilldown"]}"#).as_deref(), Some(r#"["bad drilldown"]"#));
+        assert_eq!(error_body_message(r#"{"message": null, "detail": "bad cut"}"#).as_deref(), Some("bad cut"));
+        assert_eq!(error_body_message(r#"{"status": "error"}"#), None);
+
+        let error = ApiClientError::from_error_body(502, "<html>Bad Gateway</html>".to_string());
+        assert!(matches!(&error, ApiClientError::ApiError { message: None, body: Some(_), .. }));
+        assert_eq!(error.to_string(), "API request failed with status 502: <html>Bad Gateway</html>");
+    }
+
+    #[test]
+    fn amount_type_codes_round_trip() {
+        for amount_type in [
+            AmountType::Audited,
+            AmountType::PreAudit,
+            AmountType::OriginalBudget,
+            AmountType::AdjustedBudget,
+        ] {
+            assert_eq!(AmountType::try_from(amount_type.as_code()).unwrap(), amount_type);
+        }
+        assert!(matches!(AmountType::try_from("auda"), Err(ApiClientError::InvalidParameters(_))));
+        assert_eq!(
+            AmountType::cut_value(&[AmountType::Audited, AmountType::AdjustedBudget]),
+            "AUDA;ADJB"
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use super::{client::MunicipalMoneyClient, types::*};
+use super::cubes::UIFW_CUBE;
+use super::{client::MunicipalMoneyClient, query::CutBuilder, types::*};
 
 impl MunicipalMoneyClient {
     /// Fetches Unauthorised, Irregular, Fruitless & Wasteful expenditure facts
@@ -12,40 +13,12 @@ impl MunicipalMoneyClient {
         municipality_code: &str,
         year: i32,
     ) -> Result<FactsApiResponse<FinancialItemFact>, ApiClientError> {
-        const UIFW_CUBE: &str = "uifwexp";
-        const DRILLDOWNS: &str = "demarcation.code|demarcation.label|item.code|item.label";
-        const AGGREGATES: &str = "amount.sum";
+        const DRILLDOWNS: &[&str] = &["demarcation.code", "demarcation.label", "item.code", "item.label"];
 
-        let cuts = format!(
-            "financial_year_end.year:{}|demarcation.code:\"{}\"",
-            year, municipality_code
-        );
+        let cuts = CutBuilder::new()
+            .number("financial_year_end.year", year)
+            .quoted("demarcation.code", municipality_code);
 
-        let url = format!(
-            "{}/cubes/{}/aggregate?drilldown={}&cut={}&aggregates={}",
-            self.base_url(), UIFW_CUBE, DRILLDOWNS, cuts, AGGREGATES
-        );
-
-        log::debug!("Fetching UIFW Aggregate URL: {}", url);
-
-        let response = self.client().get(&url).send().await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Failed to read error body".to_string());
-            log::error!("UIFW Aggregate API request failed with status {}: {}", status, body);
-            return Err(ApiClientError::ApiError {
-                status: status.as_u16(),
-                body: Some(body),
-            });
-        }
-
-        let data: FactsApiResponse<FinancialItemFact> =
-            response.json().await.map_err(ApiClientError::RequestError)?;
-
-        Ok(data)
+        self.fetch_aggregate(UIFW_CUBE, DRILLDOWNS, &cuts).await
     }
 }
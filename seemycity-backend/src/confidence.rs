@@ -56,41 +56,46 @@ pub fn evaluate(input: &ConfidenceInput) -> ConfidenceGrade {
         ("capital expenditure", input.capital_expenditure),
         ("debt", input.debt),
     ] {
-        if let Some(v) = value {
-            if v < Decimal::ZERO {
-                unreliable.push(format!("negative {name} reported"));
-            }
+        if let Some(v) = value
+            && v < Decimal::ZERO
+        {
+            unreliable.push(format!("negative {name} reported"));
         }
     }
 
     // Hard check: revenue implausibly small for the population served.
     // A town of 20k+ people cannot run on under R10m of annual revenue —
     // this pattern indicates an incomplete return, not a poor municipality.
-    if let (Some(rev), Some(pop)) = (input.revenue, input.population) {
-        if pop >= 20_000 && rev >= Decimal::ZERO && rev < dec!(10_000_000) {
-            unreliable.push(format!("revenue of R{rev:.0} is implausibly low for a population of {pop}"));
-        }
+    if let (Some(rev), Some(pop)) = (input.revenue, input.population)
+        && pop >= 20_000
+        && rev >= Decimal::ZERO
+        && rev < dec!(10_000_000)
+    {
+        unreliable.push(format!("revenue of R{rev:.0} is implausibly low for a population of {pop}"));
     }
 
-    // Ratio checks (both sides must be present and positive).
-    if let (Some(opex), Some(rev)) = (input.operational_expenditure, input.revenue) {
-        if rev > Decimal::ZERO && opex > Decimal::ZERO {
-            let ratio = opex / rev;
-            if ratio > dec!(3.0) {
-                unreliable.push(format!(
-                    "operating spend is {ratio:.1}x revenue — one side of the statement is likely missing"
-                ));
-            } else if ratio < dec!(0.1) {
-                suspect.push(format!("operating spend is only {ratio:.2}x revenue"));
-            }
+    // Ratio checks (both sides must be present and positive). A ratio too
+    // large for `Decimal` saturates rather than panicking: it fails the check.
+    if let (Some(opex), Some(rev)) = (input.operational_expenditure, input.revenue)
+        && rev > Decimal::ZERO
+        && opex > Decimal::ZERO
+    {
+        let ratio = opex.checked_div(rev).unwrap_or(Decimal::MAX);
+        if ratio > dec!(3.0) {
+            unreliable.push(format!(
+                "operating spend is {ratio:.1}x revenue — one side of the statement is likely missing"
+            ));
+        } else if ratio < dec!(0.1) {
+            suspect.push(format!("operating spend is only {ratio:.2}x revenue"));
         }
     }
-    if let (Some(debt), Some(rev)) = (input.debt, input.revenue) {
-        if rev > Decimal::ZERO && debt > Decimal::ZERO {
-            let ratio = debt / rev;
-            if ratio > dec!(5.0) {
-                suspect.push(format!("reported liabilities are {ratio:.1}x annual revenue"));
-            }
+    if let (Some(debt), Some(rev)) = (input.debt, input.revenue)
+        && rev > Decimal::ZERO
+        && debt > Decimal::ZERO
+    {
+        let ratio = debt.checked_div(rev).unwrap_or(Decimal::MAX);
+        if ratio > dec!(5.0) {
+            suspect.push(format!("reported liabilities are {ratio:.1}x annual revenue"));
         }
     }
 
@@ -104,11 +109,14 @@ pub fn evaluate(input: &ConfidenceInput) -> ConfidenceGrade {
     // Checksum: the cube's own revenue rollup should match our summation.
     if let (Some(rev), Some(check)) = (input.revenue, input.revenue_checksum) {
         let denom = rev.abs().max(Decimal::ONE);
-        let deviation = (check - rev).abs() / denom;
+        let deviation = check
+            .checked_sub(rev)
+            .and_then(|gap| gap.abs().checked_div(denom))
+            .unwrap_or(Decimal::MAX);
         if deviation > dec!(0.01) {
             suspect.push(format!(
                 "revenue checksum deviates by {:.1}% from the Treasury rollup",
-                deviation * dec!(100)
+                deviation.saturating_mul(dec!(100))
             ));
         }
     }
@@ -205,4 +213,20 @@ mod tests {
         let g = evaluate(&ConfidenceInput::default());
         assert_eq!(g.grade, CONFIDENCE_OK);
     }
+
+    #[test]
+    fn extreme_figures_grade_without_panicking() {
+        let tiny = Decimal::new(1, 28);
+        let g = evaluate(&ConfidenceInput {
+            revenue: Some(tiny),
+            operational_expenditure: Some(Decimal::MAX),
+            debt: Some(Decimal::MAX),
+            revenue_checksum: Some(Decimal::MIN),
+            ..Default::default()
+        });
+        assert_eq!(g.grade, CONFIDENCE_UNRELIABLE);
+        let notes = g.notes.unwrap();
+        assert!(notes.contains("operating spend is"), "{notes}");
+        assert!(notes.contains("revenue checksum deviates"), "{notes}");
+    }
 }
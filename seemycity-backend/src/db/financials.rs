@@ -1,13 +1,78 @@
 // src/db/financials.rs
 use sqlx::PgPool;
-use crate::models::FinancialDataDb;
+use crate::models::{
+    FinancialDataDb, FinancialYearAvailability, MetricInputsDb, PillarStatistics, RankingEntry, RankingOrder,
+    ScoreExportRow, ScorePercentile, ScoreStatistics,
+};
 use crate::errors::AppError;
  // For upsert function
-use chrono::Utc; // For upsert and timestamp checks
+use chrono::{DateTime, Duration, Utc}; // For upsert and timestamp checks
+use futures_util::TryStreamExt;
+use tokio::sync::mpsc;
  // Import Uuid
 
+// True when a cached row is at least `max_age` old at `now` and should be
+// re-fetched from the Treasury API, however complete its fields are.
+pub fn is_record_stale(record: &FinancialDataDb, max_age: Duration, now: DateTime<Utc>) -> bool {
+    now - record.updated_at >= max_age
+}
+
 // --- Financial Data Query Functions ---
 
+// Whether financial_data has a unique index on exactly (municipality_id, year),
+// the conflict target of both upserts below. Without it Postgres rejects every
+// upsert (42P10), and rows inserted by other means could repeat a year that
+// the "latest year" queries would then pick between arbitrarily. Checked once
+// at startup; migration 0008 restores it.
+pub async fn has_unique_year_constraint(pool: &PgPool) -> Result<bool, AppError> {
+    let present = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM pg_index i
+            WHERE i.indrelid = 'public.financial_data'::regclass
+              AND i.indisunique
+              AND i.indpred IS NULL
+              AND (
+                  SELECT array_agg(a.attname::text ORDER BY a.attname)
+                  FROM pg_attribute a
+                  WHERE a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey)
+              ) = ARRAY['municipality_id', 'year']
+              AND i.indnatts = 2
+        ) AS "present!"
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(present)
+}
+
+// Postgres' "no unique or exclusion constraint matching the ON CONFLICT
+// specification": the upserts' conflict target is gone.
+const NO_CONFLICT_TARGET: &str = "42P10";
+
+// Upsert failures, with a missing conflict target spelled out rather than
+// reported as a bare database error. A unique violation the ON CONFLICT clause
+// did not absorb (e.g. on the row id) becomes `Conflict` naming the constraint.
+fn upsert_error(error: sqlx::Error) -> AppError {
+    let Some(db_error) = error.as_database_error() else {
+        return AppError::from(error);
+    };
+    if db_error.code().as_deref() == Some(NO_CONFLICT_TARGET) {
+        return AppError::InternalError(
+            "financial_data lacks its unique (municipality_id, year) constraint; apply migration 0008".to_string(),
+        );
+    }
+    if db_error.is_unique_violation() {
+        return AppError::Conflict(format!(
+            "duplicate row rejected by unique constraint {}: {}",
+            db_error.constraint().unwrap_or("(unnamed)"),
+            db_error.message()
+        ));
+    }
+    AppError::from(error)
+}
+
 // Inserts or updates a complete financial record for a municipality and year in
 // the cache (DB). The row's id is used only on INSERT; an existing row keeps its
 // own id, and updated_at is stamped fresh either way.
@@ -21,12 +86,12 @@ pub async fn upsert_complete_financial_record(
         r#"
         INSERT INTO financial_data (
             id, municipality_id, year, revenue, operational_expenditure, capital_expenditure, debt, audit_outcome,
-            transfers_operational, uifw_expenditure, repairs_maintenance,
+            transfers_operational, uifw_expenditure, repairs_maintenance, cash_balance, operating_surplus, amount_type,
             overall_score, financial_health_score, infrastructure_score, efficiency_score, accountability_score,
-            data_confidence, confidence_notes, score_version,
+            liquidity_score, data_confidence, confidence_notes, score_version,
             created_at, updated_at
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25)
         ON CONFLICT (municipality_id, year) DO UPDATE SET
             revenue = EXCLUDED.revenue,
             operational_expenditure = EXCLUDED.operational_expenditure,
@@ -36,11 +101,15 @@ pub async fn upsert_complete_financial_record(
             transfers_operational = EXCLUDED.transfers_operational,
             uifw_expenditure = EXCLUDED.uifw_expenditure,
             repairs_maintenance = EXCLUDED.repairs_maintenance,
+            cash_balance = EXCLUDED.cash_balance,
+            operating_surplus = EXCLUDED.operating_surplus,
+            amount_type = EXCLUDED.amount_type,
             overall_score = EXCLUDED.overall_score,
             financial_health_score = EXCLUDED.financial_health_score,
             infrastructure_score = EXCLUDED.infrastructure_score,
             efficiency_score = EXCLUDED.efficiency_score,
             accountability_score = EXCLUDED.accountability_score,
+            liquidity_score = EXCLUDED.liquidity_score,
             data_confidence = EXCLUDED.data_confidence,
             confidence_notes = EXCLUDED.confidence_notes,
             score_version = EXCLUDED.score_version,
@@ -57,11 +126,15 @@ pub async fn upsert_complete_financial_record(
         row.transfers_operational,
         row.uifw_expenditure,
         row.repairs_maintenance,
+        row.cash_balance,
+        row.operating_surplus,
+        row.amount_type.as_deref(),
         row.overall_score,
         row.financial_health_score,
         row.infrastructure_score,
         row.efficiency_score,
         row.accountability_score,
+        row.liquidity_score,
         row.data_confidence.as_deref(),
         row.confidence_notes.as_deref(),
         row.score_version,
@@ -69,7 +142,8 @@ pub async fn upsert_complete_financial_record(
         now  // updated_at (set on INSERT and UPDATE)
     )
     .execute(pool)
-    .await?;
+    .await
+    .map_err(upsert_error)?;
 
     log::info!(
         "Successfully upserted financial record cache for {} year {}",
@@ -78,6 +152,297 @@ pub async fn upsert_complete_financial_record(
     Ok(())
 }
 
+// Upserts many rows (e.g. a nightly batch across every municipality) with one
+// multi-row INSERT ... ON CONFLICT, in one transaction: all rows land or none
+// do. Conflict handling matches `upsert_complete_financial_record`. Postgres
+// rejects a statement that updates the same row twice, so when `rows` repeats
+// a (municipality, year) the last occurrence wins. Returns the rows written.
+pub async fn upsert_financial_records_batch(pool: &PgPool, rows: &[FinancialDataDb]) -> Result<u64, AppError> {
+    let mut latest: std::collections::HashMap<(&str, i32), &FinancialDataDb> = std::collections::HashMap::new();
+    for row in rows {
+        latest.insert((row.municipality_id.as_str(), row.year), row);
+    }
+    let rows: Vec<&FinancialDataDb> = latest.into_values().collect();
+    if rows.is_empty() {
+        return Ok(0);
+    }
+
+    // One array per column, unnested back into rows by Postgres.
+    let col = |f: fn(&FinancialDataDb) -> Option<rust_decimal::Decimal>| rows.iter().map(|r| f(r)).collect::<Vec<_>>();
+    let text = |f: fn(&FinancialDataDb) -> Option<String>| rows.iter().map(|r| f(r)).collect::<Vec<_>>();
+    let ids: Vec<uuid::Uuid> = rows.iter().map(|r| r.id).collect();
+    let municipality_ids: Vec<String> = rows.iter().map(|r| r.municipality_id.clone()).collect();
+    let years: Vec<i32> = rows.iter().map(|r| r.year).collect();
+    let score_versions: Vec<Option<i32>> = rows.iter().map(|r| r.score_version).collect();
+    let now = Utc::now();
+
+    let mut tx = pool.begin().await?;
+    let written = sqlx::query!(
+        r#"
+        INSERT INTO financial_data (
+            id, municipality_id, year, revenue, operational_expenditure, capital_expenditure, debt, audit_outcome,
+            transfers_operational, uifw_expenditure, repairs_maintenance, cash_balance, operating_surplus, amount_type,
+            overall_score, financial_health_score, infrastructure_score, efficiency_score, accountability_score,
+            liquidity_score, data_confidence, confidence_notes, score_version,
+            created_at, updated_at
+        )
+        SELECT u.*, $24::timestamptz, $24::timestamptz
+        FROM UNNEST(
+            $1::uuid[], $2::text[], $3::int4[], $4::numeric[], $5::numeric[], $6::numeric[], $7::numeric[],
+            $8::text[], $9::numeric[], $10::numeric[], $11::numeric[], $12::numeric[], $13::numeric[],
+            $14::text[], $15::numeric[], $16::numeric[], $17::numeric[], $18::numeric[], $19::numeric[],
+            $20::numeric[], $21::text[], $22::text[], $23::int4[]
+        ) AS u
+        ON CONFLICT (municipality_id, year) DO UPDATE SET
+            revenue = EXCLUDED.revenue,
+            operational_expenditure = EXCLUDED.operational_expenditure,
+            capital_expenditure = EXCLUDED.capital_expenditure,
+            debt = EXCLUDED.debt,
+            audit_outcome = EXCLUDED.audit_outcome,
+            transfers_operational = EXCLUDED.transfers_operational,
+            uifw_expenditure = EXCLUDED.uifw_expenditure,
+            repairs_maintenance = EXCLUDED.repairs_maintenance,
+            cash_balance = EXCLUDED.cash_balance,
+            operating_surplus = EXCLUDED.operating_surplus,
+            amount_type = EXCLUDED.amount_type,
+            overall_score = EXCLUDED.overall_score,
+            financial_health_score = EXCLUDED.financial_health_score,
+            infrastructure_score = EXCLUDED.infrastructure_score,
+            efficiency_score = EXCLUDED.efficiency_score,
+            accountability_score = EXCLUDED.accountability_score,
+            liquidity_score = EXCLUDED.liquidity_score,
+            data_confidence = EXCLUDED.data_confidence,
+            confidence_notes = EXCLUDED.confidence_notes,
+            score_version = EXCLUDED.score_version,
+            updated_at = EXCLUDED.updated_at
+        "#,
+        &ids,
+        &municipality_ids,
+        &years,
+        &col(|r| r.revenue) as &[_],
+        &col(|r| r.operational_expenditure) as &[_],
+        &col(|r| r.capital_expenditure) as &[_],
+        &col(|r| r.debt) as &[_],
+        &text(|r| r.audit_outcome.clone()) as &[_],
+        &col(|r| r.transfers_operational) as &[_],
+        &col(|r| r.uifw_expenditure) as &[_],
+        &col(|r| r.repairs_maintenance) as &[_],
+        &col(|r| r.cash_balance) as &[_],
+        &col(|r| r.operating_surplus) as &[_],
+        &text(|r| r.amount_type.clone()) as &[_],
+        &col(|r| r.overall_score) as &[_],
+        &col(|r| r.financial_health_score) as &[_],
+        &col(|r| r.infrastructure_score) as &[_],
+        &col(|r| r.efficiency_score) as &[_],
+        &col(|r| r.accountability_score) as &[_],
+        &col(|r| r.liquidity_score) as &[_],
+        &text(|r| r.data_confidence.clone()) as &[_],
+        &text(|r| r.confidence_notes.clone()) as &[_],
+        &score_versions as &[_],
+        now
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(upsert_error)?
+    .rows_affected();
+    tx.commit().await?;
+
+    log::info!("Batch-upserted {} financial records", written);
+    Ok(written)
+}
+
+// Marks the rest of `tx` as a rewrite rather than a fetch: the update trigger
+// then leaves each row's updated_at as it was (migration 0010).
+async fn keep_updated_at(tx: &mut sqlx::Transaction<'_, sqlx::Postgres>) -> Result<(), AppError> {
+    sqlx::query!("SELECT set_config('seemycity.keep_updated_at', 'on', true)").fetch_one(&mut **tx).await?;
+    Ok(())
+}
+
+// Rewrites the columns a recompute re-derives from stored raw facts (the
+// summed figures, amount type, operating surplus, scores and confidence) on
+// existing rows, matched on (municipality, year). Unlike the upserts,
+// updated_at is kept: the figures come from the same fetch as before, so the
+// row is no fresher. The audit outcome is not a cube sum and is left alone;
+// rows with no stored counterpart are skipped. Returns the rows updated.
+pub async fn update_recomputed_records(pool: &PgPool, rows: &[FinancialDataDb]) -> Result<u64, AppError> {
+    if rows.is_empty() {
+        return Ok(0);
+    }
+    let col = |f: fn(&FinancialDataDb) -> Option<rust_decimal::Decimal>| rows.iter().map(f).collect::<Vec<_>>();
+    let text = |f: fn(&FinancialDataDb) -> Option<String>| rows.iter().map(f).collect::<Vec<_>>();
+    let municipality_ids: Vec<String> = rows.iter().map(|r| r.municipality_id.clone()).collect();
+    let years: Vec<i32> = rows.iter().map(|r| r.year).collect();
+    let score_versions: Vec<Option<i32>> = rows.iter().map(|r| r.score_version).collect();
+
+    let mut tx = pool.begin().await?;
+    keep_updated_at(&mut tx).await?;
+    let updated = sqlx::query!(
+        r#"
+        UPDATE financial_data fd SET
+            revenue = u.revenue,
+            operational_expenditure = u.operational_expenditure,
+            capital_expenditure = u.capital_expenditure,
+            debt = u.debt,
+            transfers_operational = u.transfers_operational,
+            uifw_expenditure = u.uifw_expenditure,
+            repairs_maintenance = u.repairs_maintenance,
+            cash_balance = u.cash_balance,
+            operating_surplus = u.operating_surplus,
+            amount_type = u.amount_type,
+            overall_score = u.overall_score,
+            financial_health_score = u.financial_health_score,
+            infrastructure_score = u.infrastructure_score,
+            efficiency_score = u.efficiency_score,
+            accountability_score = u.accountability_score,
+            liquidity_score = u.liquidity_score,
+            data_confidence = u.data_confidence,
+            confidence_notes = u.confidence_notes,
+            score_version = u.score_version
+        FROM UNNEST(
+            $1::text[], $2::int4[], $3::numeric[], $4::numeric[], $5::numeric[], $6::numeric[], $7::numeric[],
+            $8::numeric[], $9::numeric[], $10::numeric[], $11::numeric[], $12::text[], $13::numeric[],
+            $14::numeric[], $15::numeric[], $16::numeric[], $17::numeric[], $18::numeric[], $19::text[],
+            $20::text[], $21::int4[]
+        ) AS u(
+            municipality_id, year, revenue, operational_expenditure, capital_expenditure, debt,
+            transfers_operational, uifw_expenditure, repairs_maintenance, cash_balance, operating_surplus,
+            amount_type, overall_score, financial_health_score, infrastructure_score, efficiency_score,
+            accountability_score, liquidity_score, data_confidence, confidence_notes, score_version
+        )
+        WHERE fd.municipality_id = u.municipality_id AND fd.year = u.year
+        "#,
+        &municipality_ids,
+        &years,
+        &col(|r| r.revenue) as &[_],
+        &col(|r| r.operational_expenditure) as &[_],
+        &col(|r| r.capital_expenditure) as &[_],
+        &col(|r| r.debt) as &[_],
+        &col(|r| r.transfers_operational) as &[_],
+        &col(|r| r.uifw_expenditure) as &[_],
+        &col(|r| r.repairs_maintenance) as &[_],
+        &col(|r| r.cash_balance) as &[_],
+        &col(|r| r.operating_surplus) as &[_],
+        &text(|r| r.amount_type.clone()) as &[_],
+        &col(|r| r.overall_score) as &[_],
+        &col(|r| r.financial_health_score) as &[_],
+        &col(|r| r.infrastructure_score) as &[_],
+        &col(|r| r.efficiency_score) as &[_],
+        &col(|r| r.accountability_score) as &[_],
+        &col(|r| r.liquidity_score) as &[_],
+        &text(|r| r.data_confidence.clone()) as &[_],
+        &text(|r| r.confidence_notes.clone()) as &[_],
+        &score_versions as &[_]
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    tx.commit().await?;
+    Ok(updated)
+}
+
+// Rewrites only the columns a rescore derives from the stored figures (the
+// operating surplus, scores, confidence and score version) on existing rows,
+// matched on (municipality, year), in one statement. The figures themselves
+// and updated_at are left as they were: nothing was fetched. Returns the rows
+// updated.
+pub async fn update_scores_batch(pool: &PgPool, rows: &[FinancialDataDb]) -> Result<u64, AppError> {
+    if rows.is_empty() {
+        return Ok(0);
+    }
+    let col = |f: fn(&FinancialDataDb) -> Option<rust_decimal::Decimal>| rows.iter().map(f).collect::<Vec<_>>();
+    let text = |f: fn(&FinancialDataDb) -> Option<String>| rows.iter().map(f).collect::<Vec<_>>();
+    let municipality_ids: Vec<String> = rows.iter().map(|r| r.municipality_id.clone()).collect();
+    let years: Vec<i32> = rows.iter().map(|r| r.year).collect();
+    let score_versions: Vec<Option<i32>> = rows.iter().map(|r| r.score_version).collect();
+
+    let mut tx = pool.begin().await?;
+    keep_updated_at(&mut tx).await?;
+    let updated = sqlx::query!(
+        r#"
+        UPDATE financial_data fd SET
+            operating_surplus = u.operating_surplus,
+            overall_score = u.overall_score,
+            financial_health_score = u.financial_health_score,
+            infrastructure_score = u.infrastructure_score,
+            efficiency_score = u.efficiency_score,
+            accountability_score = u.accountability_score,
+            liquidity_score = u.liquidity_score,
+            data_confidence = u.data_confidence,
+            confidence_notes = u.confidence_notes,
+            score_version = u.score_version
+        FROM UNNEST(
+            $1::text[], $2::int4[], $3::numeric[], $4::numeric[], $5::numeric[], $6::numeric[],
+            $7::numeric[], $8::numeric[], $9::numeric[], $10::text[], $11::text[], $12::int4[]
+        ) AS u(
+            municipality_id, year, operating_surplus, overall_score, financial_health_score,
+            infrastructure_score, efficiency_score, accountability_score, liquidity_score,
+            data_confidence, confidence_notes, score_version
+        )
+        WHERE fd.municipality_id = u.municipality_id AND fd.year = u.year
+        "#,
+        &municipality_ids,
+        &years,
+        &col(|r| r.operating_surplus) as &[_],
+        &col(|r| r.overall_score) as &[_],
+        &col(|r| r.financial_health_score) as &[_],
+        &col(|r| r.infrastructure_score) as &[_],
+        &col(|r| r.efficiency_score) as &[_],
+        &col(|r| r.accountability_score) as &[_],
+        &col(|r| r.liquidity_score) as &[_],
+        &text(|r| r.data_confidence.clone()) as &[_],
+        &text(|r| r.confidence_notes.clone()) as &[_],
+        &score_versions as &[_]
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    tx.commit().await?;
+    Ok(updated)
+}
+
+// Fetches every stored financial-year row, for all municipalities, ordered by
+// municipality then year. Backs the bulk rescore, which would otherwise need
+// one query per municipality.
+pub async fn get_all_financial_rows(pool: &PgPool) -> Result<Vec<FinancialDataDb>, AppError> {
+    let financials = sqlx::query_as!(
+        FinancialDataDb,
+        r#"
+        SELECT
+            id,
+            municipality_id,
+            year,
+            revenue,
+            operational_expenditure,
+            capital_expenditure,
+            debt,
+            audit_outcome,
+            transfers_operational,
+            uifw_expenditure,
+            repairs_maintenance,
+            cash_balance,
+            operating_surplus,
+            amount_type,
+            overall_score,
+            financial_health_score,
+            infrastructure_score,
+            efficiency_score,
+            accountability_score,
+            liquidity_score,
+            data_confidence,
+            confidence_notes,
+            score_version,
+            created_at,
+            updated_at
+        FROM financial_data
+        ORDER BY municipality_id, year
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(financials)
+}
+
 // Fetches all financial-year rows (including cache timestamps) for a municipality.
 // Rows with every metric NULL act as negative-cache entries and are filtered out
 // of API responses by the handler.
@@ -98,11 +463,15 @@ pub async fn get_all_financial_years_db(pool: &PgPool, muni_id: &str) -> Result<
             transfers_operational,
             uifw_expenditure,
             repairs_maintenance,
+            cash_balance,
+            operating_surplus,
+            amount_type,
             overall_score,
             financial_health_score,
             infrastructure_score,
             efficiency_score,
             accountability_score,
+            liquidity_score,
             data_confidence,
             confidence_notes,
             score_version,
@@ -119,3 +488,294 @@ pub async fn get_all_financial_years_db(pool: &PgPool, muni_id: &str) -> Result<
 
     Ok(financials)
 }
+
+// Every year with a cached financial_data row for the municipality, oldest
+// first, flagged with whether any row for the year has an overall score.
+pub async fn get_available_years(pool: &PgPool, muni_id: &str) -> Result<Vec<FinancialYearAvailability>, AppError> {
+    let years = sqlx::query_as!(
+        FinancialYearAvailability,
+        r#"
+        SELECT year, BOOL_OR(overall_score IS NOT NULL) as "has_score!"
+        FROM financial_data
+        WHERE municipality_id = $1
+        GROUP BY year
+        ORDER BY year
+        "#,
+        muni_id
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(years)
+}
+
+// Percentile rank of a municipality's overall score among every municipality
+// scored for `year` (PERCENT_RANK: share of the others scoring strictly lower,
+// so ties share a value). `excluded_ids` (matched case-insensitively) are left
+// out of the population before ranking, as in `get_rankings`. None when the
+// municipality has no score that year.
+pub async fn get_score_percentile(
+    pool: &PgPool,
+    muni_id: &str,
+    year: i32,
+    excluded_ids: &[String],
+) -> Result<Option<ScorePercentile>, AppError> {
+    log::debug!("Computing score percentile for {} in {}", muni_id, year);
+    let excluded_ids: Vec<String> = excluded_ids.iter().map(|id| id.to_uppercase()).collect();
+    let percentile = sqlx::query_as!(
+        ScorePercentile,
+        r#"
+        WITH ranked AS (
+            SELECT
+                municipality_id,
+                year,
+                overall_score,
+                ROUND((PERCENT_RANK() OVER (ORDER BY overall_score) * 100)::numeric, 2) AS percentile,
+                RANK() OVER (ORDER BY overall_score DESC) AS rank,
+                COUNT(*) OVER () AS scored_count
+            FROM financial_data
+            WHERE year = $2
+              AND overall_score IS NOT NULL
+              AND NOT (upper(municipality_id) = ANY($3::text[]))
+        )
+        SELECT
+            municipality_id,
+            year,
+            overall_score as "overall_score!",
+            percentile as "percentile!",
+            rank as "rank!",
+            scored_count as "scored_count!"
+        FROM ranked
+        WHERE municipality_id = $1
+        "#,
+        muni_id,
+        year,
+        &excluded_ids
+    )
+    .fetch_optional(pool)
+    .await?;
+    Ok(percentile)
+}
+
+// Most recent year with at least one overall score, if any.
+pub async fn get_latest_scored_year(pool: &PgPool) -> Result<Option<i32>, AppError> {
+    let year = sqlx::query_scalar!("SELECT MAX(year) FROM financial_data WHERE overall_score IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+    Ok(year)
+}
+
+// Leaderboard of overall scores for `year`. Ranks run from the best score (1)
+// with name, then id, breaking ties, so numbering is stable; `order` only picks
+// which end is listed first and `limit` truncates after ordering. Unscored
+// municipalities and `excluded_ids` (matched case-insensitively) are left out
+// before ranking.
+pub async fn get_rankings(
+    pool: &PgPool,
+    year: i32,
+    limit: Option<i64>,
+    order: RankingOrder,
+    excluded_ids: &[String],
+) -> Result<Vec<RankingEntry>, AppError> {
+    log::debug!("Fetching {:?} rankings for {} (limit: {:?})", order, year, limit);
+    let excluded_ids: Vec<String> = excluded_ids.iter().map(|id| id.to_uppercase()).collect();
+    let rankings = sqlx::query_as!(
+        RankingEntry,
+        r#"
+        WITH ranked AS (
+            SELECT
+                ROW_NUMBER() OVER (ORDER BY fd.overall_score DESC, m.name, m.id) AS rank,
+                m.id,
+                m.name,
+                m.province,
+                fd.overall_score
+            FROM financial_data fd
+            JOIN municipalities m ON m.id = fd.municipality_id
+            WHERE fd.year = $1
+              AND fd.overall_score IS NOT NULL
+              AND NOT (upper(m.id) = ANY($4::text[]))
+        )
+        SELECT
+            rank as "rank!",
+            id,
+            name,
+            province,
+            overall_score as "overall_score!"
+        FROM ranked
+        ORDER BY CASE WHEN $3 THEN rank END ASC, rank DESC
+        LIMIT $2
+        "#,
+        year,
+        limit.unwrap_or(i64::MAX),
+        order == RankingOrder::Desc,
+        &excluded_ids
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rankings)
+}
+
+// Rows read ahead of the client by `stream_score_export`.
+pub const SCORE_EXPORT_BUFFER: usize = 64;
+
+// Every municipality's cached row for `year` with its headline figures, scored
+// or not, ordered by province then name (then id). All-NULL negative-cache rows
+// (see `FinancialDataDb::has_any_data`) and `excluded_ids` (matched
+// case-insensitively) are left out. The rows are streamed from the cursor
+// (`fetch`, not `fetch_all`) by a spawned task into a channel of
+// `SCORE_EXPORT_BUFFER` rows, so a slow download holds back the query instead
+// of the result piling up in memory. A query error is sent as the last item.
+pub fn stream_score_export(
+    pool: &PgPool,
+    year: i32,
+    excluded_ids: &[String],
+) -> mpsc::Receiver<Result<ScoreExportRow, AppError>> {
+    log::debug!("Streaming score export for {}", year);
+    let pool = pool.clone();
+    let excluded_ids: Vec<String> = excluded_ids.iter().map(|id| id.to_uppercase()).collect();
+    let (tx, rx) = mpsc::channel(SCORE_EXPORT_BUFFER);
+    tokio::spawn(crate::request_id::inherit(async move {
+        let mut rows = sqlx::query_as!(
+            ScoreExportRow,
+            r#"
+        SELECT
+            m.id,
+            m.name,
+            m.province,
+            m.classification,
+            fd.revenue,
+            fd.debt,
+            fd.overall_score
+        FROM financial_data fd
+        JOIN municipalities m ON m.id = fd.municipality_id
+        WHERE fd.year = $1
+          AND NOT (upper(m.id) = ANY($2::text[]))
+          AND num_nonnulls(fd.revenue, fd.operational_expenditure, fd.capital_expenditure, fd.debt,
+                           fd.audit_outcome, fd.overall_score) > 0
+        ORDER BY m.province, m.name, m.id
+        "#,
+            year,
+            &excluded_ids
+        )
+        .fetch(&pool);
+        loop {
+            let item = match rows.try_next().await {
+                Ok(Some(row)) => Ok(row),
+                Ok(None) => break,
+                Err(e) => {
+                    log::error!("Score export for {} failed mid-stream: {}", year, e);
+                    Err(AppError::from(e))
+                }
+            };
+            let failed = item.is_err();
+            // A closed channel means the client went away; stop reading.
+            if tx.send(item).await.is_err() || failed {
+                break;
+            }
+        }
+    }));
+    rx
+}
+
+// Min, max, mean and median of each score column over every municipality's row
+// for `year`. Each column is aggregated on its own (aggregates skip NULLs), so
+// a row missing one pillar still counts towards the others. `excluded_ids`
+// (matched case-insensitively) are left out; `province`, when given, limits
+// the rows to that province (case-insensitive exact match).
+pub async fn get_pillar_statistics(
+    pool: &PgPool,
+    year: i32,
+    excluded_ids: &[String],
+    province: Option<&str>,
+) -> Result<ScoreStatistics, AppError> {
+    log::debug!("Computing pillar statistics for {} (province: {:?})", year, province);
+    let excluded_ids: Vec<String> = excluded_ids.iter().map(|id| id.to_uppercase()).collect();
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            s.pillar as "pillar!",
+            COUNT(s.score) as "count!",
+            MIN(s.score) as min,
+            MAX(s.score) as max,
+            ROUND(AVG(s.score), 2) as average,
+            ROUND((percentile_cont(0.5) WITHIN GROUP (ORDER BY s.score))::numeric, 2) as median
+        FROM financial_data fd
+        CROSS JOIN LATERAL (VALUES
+            ('overall', fd.overall_score),
+            ('financial_health', fd.financial_health_score),
+            ('infrastructure', fd.infrastructure_score),
+            ('efficiency', fd.efficiency_score),
+            ('accountability', fd.accountability_score),
+            ('liquidity', fd.liquidity_score)
+        ) AS s(pillar, score)
+        JOIN municipalities m ON m.id = fd.municipality_id
+        WHERE fd.year = $1
+          AND NOT (upper(fd.municipality_id) = ANY($2::text[]))
+          AND ($3::text IS NULL OR lower(m.province) = lower($3))
+        GROUP BY s.pillar
+        "#,
+        year,
+        &excluded_ids,
+        province
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut statistics = ScoreStatistics { year: Some(year), ..ScoreStatistics::default() };
+    for row in rows {
+        let pillar = PillarStatistics {
+            count: row.count,
+            min: row.min,
+            max: row.max,
+            average: row.average,
+            median: row.median,
+        };
+        match row.pillar.as_str() {
+            "overall" => statistics.overall = pillar,
+            "financial_health" => statistics.financial_health = pillar,
+            "infrastructure" => statistics.infrastructure = pillar,
+            "efficiency" => statistics.efficiency = pillar,
+            "accountability" => statistics.accountability = pillar,
+            "liquidity" => statistics.liquidity = pillar,
+            other => log::warn!("Unexpected pillar '{}' in statistics", other),
+        }
+    }
+    Ok(statistics)
+}
+
+// The stored figures of every municipality's row for `year`, from which
+// `services::normalization` builds the metric distribution for percentile
+// normalization. Rows graded unreliable are left out, as their raw-derived
+// pillars are; so are `excluded_ids` (case-insensitive).
+pub async fn get_metric_inputs(
+    pool: &PgPool,
+    year: i32,
+    excluded_ids: &[String],
+) -> Result<Vec<MetricInputsDb>, AppError> {
+    log::debug!("Fetching metric inputs for {}", year);
+    let excluded_ids: Vec<String> = excluded_ids.iter().map(|id| id.to_uppercase()).collect();
+    let rows = sqlx::query_as!(
+        MetricInputsDb,
+        r#"
+        SELECT
+            m.classification,
+            fd.revenue,
+            fd.operational_expenditure,
+            fd.capital_expenditure,
+            fd.debt,
+            fd.transfers_operational,
+            fd.uifw_expenditure,
+            fd.repairs_maintenance,
+            fd.cash_balance
+        FROM financial_data fd
+        JOIN municipalities m ON m.id = fd.municipality_id
+        WHERE fd.year = $1
+          AND NOT (upper(m.id) = ANY($2::text[]))
+          AND fd.data_confidence IS DISTINCT FROM 'unreliable'
+        "#,
+        year,
+        &excluded_ids
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
@@ -0,0 +1,196 @@
+// src/db/geo.rs
+use sqlx::PgPool;
+use crate::errors::AppError;
+use crate::models::{GeometryValidity, InvalidGeometry, MapMunicipalityProperties, NearbyMunicipality};
+use geojson;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// SRID the API assumes for stored boundaries (WGS 84 lon/lat), e.g. when
+/// building viewport envelopes.
+pub const GEOMETRY_SRID: i32 = 4326;
+
+static SRID_VERIFIED: AtomicBool = AtomicBool::new(false);
+
+// --- Geometry Query Functions ---
+
+// Fetches the boundary of a single municipality as a GeoJSON Geometry.
+// Returns Ok(None) when the municipality has no row in municipal_geometries
+// (or the stored geometry cannot be parsed) — callers render without a boundary.
+pub async fn get_geometry_for_municipality(
+    pool: &PgPool,
+    muni_id: &str,
+) -> Result<Option<geojson::Geometry>, AppError> {
+    log::debug!("Fetching geometry for municipality: {}", muni_id);
+    // Unsimplified, unlike the country-level map: a single boundary is small
+    // enough to ship at full detail. 5-decimal (~1 m) coordinates. An invalid
+    // boundary is repaired the same way as on the map.
+    let row = sqlx::query!(
+        r#"
+        SELECT ST_AsGeoJSON(
+                   CASE WHEN ST_IsValid(geom) THEN geom
+                        ELSE ST_CollectionExtract(ST_MakeValid(geom), 3) END,
+                   5
+               )::TEXT as geometry_geojson_str
+        FROM municipal_geometries
+        WHERE munic_id = $1
+        LIMIT 1
+        "#,
+        muni_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(geojson_str) = row.and_then(|r| r.geometry_geojson_str) else {
+        log::debug!("No geometry stored for municipality {}", muni_id);
+        return Ok(None);
+    };
+
+    match geojson_str.parse::<geojson::GeoJson>() {
+        Ok(geojson::GeoJson::Geometry(geom)) => Ok(Some(geom)),
+        Ok(_) => {
+            log::warn!("Parsed GeoJSON is not a Geometry for {}", muni_id);
+            Ok(None)
+        }
+        Err(e) => {
+            log::error!("Failed to parse GeoJSON geometry from DB for {}: {}", muni_id, e);
+            Ok(None)
+        }
+    }
+}
+
+// Errors unless every stored boundary uses GEOMETRY_SRID. Degree-based
+// envelopes compared against, say, Web Mercator metres would silently match
+// nothing, so spatial filters call this first. Boundaries are only loaded by
+// the ETL, so a passing check is remembered for the life of the process.
+pub async fn ensure_geometry_srid(pool: &PgPool) -> Result<(), AppError> {
+    if SRID_VERIFIED.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+    let srids: Vec<i32> = sqlx::query_scalar!(
+        r#"
+        SELECT DISTINCT ST_SRID(geom) as "srid!"
+        FROM municipal_geometries
+        WHERE geom IS NOT NULL
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    if let Some(other) = srids.iter().find(|&&srid| srid != GEOMETRY_SRID) {
+        return Err(AppError::InternalError(format!(
+            "Stored municipal geometries use SRID {other}, expected {GEOMETRY_SRID}"
+        )));
+    }
+    SRID_VERIFIED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+// Counts stored boundaries that fail `ST_IsValid` and lists each with
+// PostGIS's reason (e.g. "Self-intersection[18.4 -33.9]"), ordered by id. The
+// map and geometry endpoints repair these on the fly; this is for fixing them
+// at the source.
+pub async fn get_geometry_validity(pool: &PgPool) -> Result<GeometryValidity, AppError> {
+    log::debug!("Checking validity of stored municipal geometries");
+    let rows = sqlx::query!(
+        r#"
+        SELECT munic_id, ST_IsValid(geom) as "valid!", ST_IsValidReason(geom) as "reason!"
+        FROM municipal_geometries
+        WHERE geom IS NOT NULL
+        ORDER BY munic_id
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let total = rows.len();
+    let invalid_geometries: Vec<InvalidGeometry> = rows
+        .into_iter()
+        .filter(|row| !row.valid)
+        .map(|row| InvalidGeometry { id: row.munic_id, reason: row.reason })
+        .collect();
+    Ok(GeometryValidity { total, invalid: invalid_geometries.len(), invalid_geometries })
+}
+
+// The `n` municipalities closest to (lon, lat), nearest first, leaving out
+// `excluded_ids` (matched case-insensitively). Candidates are picked by the
+// geography `<->` KNN operator (sphere distance, through the geography GiST
+// index of migration 0011); the result is then ordered by geodesic distance,
+// which is 0 for a boundary containing the point.
+pub async fn get_nearest_municipalities(
+    pool: &PgPool,
+    lon: f64,
+    lat: f64,
+    n: i64,
+    excluded_ids: &[String],
+) -> Result<Vec<NearbyMunicipality>, AppError> {
+    log::debug!("Fetching {} municipalities nearest to ({}, {})", n, lon, lat);
+    ensure_geometry_srid(pool).await?;
+    let excluded_ids: Vec<String> = excluded_ids.iter().map(|id| id.to_uppercase()).collect();
+
+    let rows = sqlx::query!(
+        r#"
+        WITH Point AS (
+            SELECT ST_SetSRID(ST_MakePoint($1, $2), 4326) AS geom
+        ),
+        Candidates AS (
+            SELECT mg.munic_id, mg.geom
+            FROM municipal_geometries mg, Point pt
+            WHERE mg.geom IS NOT NULL
+              AND NOT (upper(mg.munic_id) = ANY($4::text[]))
+            ORDER BY mg.geom::geography <-> pt.geom::geography
+            LIMIT $3
+        ),
+        LatestScores AS (
+            SELECT
+                municipality_id,
+                overall_score,
+                ROW_NUMBER() OVER(PARTITION BY municipality_id ORDER BY year DESC) as rn
+            FROM financial_data
+            WHERE overall_score IS NOT NULL
+        )
+        SELECT
+            m.id,
+            m.name,
+            m.province,
+            m.population,
+            m.classification,
+            ls.overall_score as "latest_score?",
+            (SELECT MAX(fd.updated_at) FROM financial_data fd
+             WHERE fd.municipality_id = m.id
+               AND num_nonnulls(fd.revenue, fd.operational_expenditure, fd.capital_expenditure, fd.debt,
+                                fd.audit_outcome, fd.overall_score) > 0) as last_updated,
+            ST_Distance(c.geom::geography, pt.geom::geography) / 1000.0 as "distance_km!"
+        FROM Candidates c
+        CROSS JOIN Point pt
+        JOIN municipalities m ON m.id = c.munic_id
+        LEFT JOIN LatestScores ls ON m.id = ls.municipality_id AND ls.rn = 1
+        ORDER BY 8, m.name
+        "#,
+        lon,
+        lat,
+        n,
+        &excluded_ids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| NearbyMunicipality {
+            properties: MapMunicipalityProperties {
+                id: row.id,
+                name: row.name,
+                province: row.province,
+                population: row.population,
+                classification: row.classification,
+                latest_score: row.latest_score,
+                last_updated: row.last_updated,
+                centroid: None,
+                bbox: None,
+                area_km2: None,
+            },
+            // Metre precision is plenty for a "near me" list.
+            distance_km: (row.distance_km * 1000.0).round() / 1000.0,
+        })
+        .collect())
+}
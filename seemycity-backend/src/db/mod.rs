@@ -10,16 +10,20 @@ pub type DbPool = PgPool;
 // Declare the new modules
 pub mod municipalities;
 pub mod financials;
+pub mod geo;
+pub mod raw_facts;
 
 // Function to create the database connection pool
 pub async fn create_pool(config: &Config) -> Result<DbPool, sqlx::Error> {
+    // load_config already validated DATABASE_URL; this only fails for a
+    // hand-built Config.
+    let connect_options = config
+        .connect_options()
+        .map_err(|e| sqlx::Error::Configuration(e.to_string().into()))?;
     PgPoolOptions::new()
-        .max_connections(10) // Example: Set max connections
-        .connect_with(sqlx::postgres::PgConnectOptions::new()
-            .host(&config.db_host)
-            .port(config.db_port)
-            .username(&config.db_user)
-            .password(&config.db_password)
-            .database(&config.db_name))
+        .max_connections(config.db_max_connections)
+        .min_connections(config.db_min_connections)
+        .acquire_timeout(std::time::Duration::from_secs(config.db_acquire_timeout_secs))
+        .connect_with(connect_options)
         .await
 }
\ No newline at end of file
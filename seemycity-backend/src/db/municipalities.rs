@@ -1,20 +1,24 @@
 // src/db/municipalities.rs
 use sqlx::PgPool;
-use crate::models::{MunicipalityDb, MapFeature, MapMunicipalityProperties};
+use crate::models::{
+    MapFeature, MapMunicipalityProperties, MunicipalityBasicInfo, MunicipalityDb, MunicipalitySummary, ProvinceSummary,
+};
 use crate::errors::AppError;
+use crate::db::geo::ensure_geometry_srid;
 use geojson;
 use rust_decimal::Decimal;
 
 // --- Municipality Query Functions ---
 
-// Lightweight (id, population) list for the cache warmer.
-pub async fn get_all_municipality_populations(
+// Lightweight (id, population, classification) list for the cache warmer —
+// everything scoring needs beyond the financial figures.
+pub async fn get_all_municipality_scoring_profiles(
     pool: &PgPool,
-) -> Result<Vec<(String, Option<f32>)>, AppError> {
-    let rows = sqlx::query!("SELECT id, population FROM municipalities ORDER BY id")
+) -> Result<Vec<(String, Option<f32>, Option<String>)>, AppError> {
+    let rows = sqlx::query!("SELECT id, population, classification FROM municipalities ORDER BY id")
         .fetch_all(pool)
         .await?;
-    Ok(rows.into_iter().map(|r| (r.id, r.population)).collect())
+    Ok(rows.into_iter().map(|r| (r.id, r.population, r.classification)).collect())
 }
 
 // Function to get just the base MunicipalityDb info for a single municipality
@@ -31,24 +35,140 @@ pub async fn get_municipality_base_info_db(pool: &PgPool, muni_id: &str) -> Resu
     Ok(base_info)
 }
 
-// Fetches data required for the map's GeoJSON FeatureCollection
-pub async fn get_municipalities_summary_for_map(pool: &PgPool, limit: Option<i64>) -> Result<Vec<MapFeature>, AppError> {
-    log::info!("Fetching summary data for map view (limit: {:?})", limit);
+// Fills in a municipality's population from the Treasury demographics cube.
+// Only a missing population is written: a seeded figure is never replaced.
+// Returns whether the row was updated.
+pub async fn store_population_if_missing(pool: &PgPool, muni_id: &str, population: u32) -> Result<bool, AppError> {
+    let result = sqlx::query!(
+        r#"UPDATE municipalities SET population = $2 WHERE id = $1 AND population IS NULL"#,
+        muni_id,
+        population as f32
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
 
-    // Temporary struct to hold the raw query result
+// Claims a demographics lookup for a municipality with no population: stamps
+// population_checked_at, but only if the last lookup is older than
+// `checked_before` (or there was none). Returns whether the caller may look
+// it up; concurrent callers can't both win, and a lookup that found nothing
+// is not repeated until the stamp ages out.
+pub async fn claim_population_lookup(
+    pool: &PgPool,
+    muni_id: &str,
+    checked_before: chrono::DateTime<chrono::Utc>,
+) -> Result<bool, AppError> {
+    let result = sqlx::query!(
+        r#"UPDATE municipalities SET population_checked_at = now()
+           WHERE id = $1 AND population IS NULL
+             AND (population_checked_at IS NULL OR population_checked_at < $2)"#,
+        muni_id,
+        checked_before
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+// Popup summary for one municipality: base info plus the latest scored year's
+// overall score and audit outcome. Reads cached rows only (no upstream calls).
+pub async fn get_municipality_summary_db(pool: &PgPool, muni_id: &str) -> Result<Option<MunicipalitySummary>, AppError> {
+    log::debug!("Fetching summary for municipality: {}", muni_id);
+    let summary = sqlx::query_as!(
+        MunicipalitySummary,
+        r#"
+        SELECT
+            m.id,
+            m.name,
+            m.province,
+            m.population,
+            fd.year as "year?",
+            fd.overall_score as "overall_score?",
+            fd.audit_outcome as "audit_outcome?"
+        FROM municipalities m
+        LEFT JOIN LATERAL (
+            SELECT year, overall_score, audit_outcome
+            FROM financial_data
+            WHERE municipality_id = m.id AND overall_score IS NOT NULL
+            ORDER BY year DESC
+            LIMIT 1
+        ) fd ON TRUE
+        WHERE m.id = $1
+        "#,
+        muni_id
+    )
+    .fetch_optional(pool)
+    .await?;
+    Ok(summary)
+}
+
+/// Boundaries average ~5,500 points each (18 MB total raw). For a country-level
+/// choropleth the map simplifies them to ~200 m (degrees, SRID 4326).
+pub const DEFAULT_MAP_SIMPLIFY_TOLERANCE: f64 = 0.002;
+
+/// Which municipalities the map/list query returns. `Default` = all of them.
+#[derive(Debug, Clone, Default)]
+pub struct MapQueryOptions {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// Ids to leave out (embargoed), matched case-insensitively.
+    pub excluded_ids: Vec<String>,
+    /// Exact province name, case-insensitive.
+    pub province: Option<String>,
+    /// Exact classification (e.g. "A", "B4"), case-insensitive.
+    pub classification: Option<String>,
+    /// Inclusive bounds on the latest overall score. Setting either excludes
+    /// municipalities without a score.
+    pub min_score: Option<Decimal>,
+    pub max_score: Option<Decimal>,
+    /// `ST_SimplifyPreserveTopology` tolerance in degrees; `None` uses
+    /// `DEFAULT_MAP_SIMPLIFY_TOLERANCE`, `Some(0.0)` keeps full resolution.
+    pub simplify_tolerance: Option<f64>,
+    /// Viewport `[min_lon, min_lat, max_lon, max_lat]`: only boundaries
+    /// intersecting it are returned.
+    pub bbox: Option<[f64; 4]>,
+}
+
+// Fetches data required for the map's GeoJSON FeatureCollection, plus the total
+// number of municipalities matching `options` before limit/offset are applied
+// (for pagination). Municipalities without a stored boundary are not counted.
+pub async fn get_municipalities_summary_for_map(
+    pool: &PgPool,
+    options: &MapQueryOptions,
+) -> Result<(Vec<MapFeature>, i64), AppError> {
+    log::info!("Fetching summary data for map view ({:?})", options);
+
+    // Temporary struct to hold the raw query result. Page columns are NULL on
+    // the single row returned for a page past the end (it still carries the total).
     #[derive(sqlx::FromRow, Debug)]
     struct MapQueryResult {
-        id: String,
-        name: String,
-        province: String,
+        total: i64,
+        id: Option<String>,
+        name: Option<String>,
+        province: Option<String>,
         population: Option<f32>,
         classification: Option<String>,
         latest_score: Option<Decimal>,
+        last_updated: Option<chrono::DateTime<chrono::Utc>>,
+        centroid_lon: Option<f64>,
+        centroid_lat: Option<f64>,
+        bbox_min_lon: Option<f64>,
+        bbox_min_lat: Option<f64>,
+        bbox_max_lon: Option<f64>,
+        bbox_max_lat: Option<f64>,
+        area_km2: Option<f64>,
         geometry_geojson_str: Option<String>, 
+        geometry_repaired: Option<bool>,
     }
 
     // Use COALESCE for limit to handle None case cleanly in SQL
-    let query_limit = limit.unwrap_or(i64::MAX); 
+    let query_limit = options.limit.unwrap_or(i64::MAX);
+    let query_offset = options.offset.unwrap_or(0);
+    let excluded_ids: Vec<String> = options.excluded_ids.iter().map(|id| id.to_uppercase()).collect();
+    if options.bbox.is_some() {
+        ensure_geometry_srid(pool).await?;
+    }
 
     // SQL query to fetch municipality info, geometry, and latest score
     let results = sqlx::query_as!(
@@ -61,45 +181,120 @@ pub async fn get_municipalities_summary_for_map(pool: &PgPool, limit: Option<i64
                 ROW_NUMBER() OVER(PARTITION BY municipality_id ORDER BY year DESC) as rn
             FROM financial_data
             WHERE overall_score IS NOT NULL
+        ),
+        LastUpdated AS (
+            SELECT municipality_id, MAX(updated_at) as last_updated
+            FROM financial_data
+            WHERE num_nonnulls(revenue, operational_expenditure, capital_expenditure, debt,
+                               audit_outcome, overall_score) > 0
+            GROUP BY municipality_id
+        ),
+        Filtered AS (
+            SELECT
+                m.id,
+                m.name,
+                m.province,
+                m.population,
+                m.classification,
+                ls.overall_score as latest_score,
+                lu.last_updated,
+                g.geom,
+                NOT v.valid as geometry_repaired
+            FROM municipalities m
+            JOIN municipal_geometries mg ON m.id = mg.munic_id
+            -- Invalid boundaries (e.g. self-intersections) are repaired rather
+            -- than dropped. ST_MakeValid can return a GeometryCollection with
+            -- stray lines or points; only the polygonal parts are kept.
+            CROSS JOIN LATERAL (SELECT ST_IsValid(mg.geom) as valid) v
+            CROSS JOIN LATERAL (
+                SELECT CASE WHEN v.valid THEN mg.geom
+                            ELSE ST_CollectionExtract(ST_MakeValid(mg.geom), 3) END as geom
+            ) g
+            LEFT JOIN LatestScores ls ON m.id = ls.municipality_id AND ls.rn = 1
+            LEFT JOIN LastUpdated lu ON m.id = lu.municipality_id
+            WHERE NOT (upper(m.id) = ANY($3::text[]))
+              AND ($4::text IS NULL OR lower(m.province) = lower($4))
+              AND ($5::text IS NULL OR lower(m.classification) = lower($5))
+              AND ($6::numeric IS NULL OR ls.overall_score >= $6)
+              AND ($7::numeric IS NULL OR ls.overall_score <= $7)
+              AND ($9::float8 IS NULL
+                   OR ST_Intersects(g.geom, ST_MakeEnvelope($9, $10, $11, $12, 4326)))
+        ),
+        Page AS (
+            SELECT * FROM Filtered
+            ORDER BY name, id
+            LIMIT $1 OFFSET $2
         )
         SELECT
-            m.id,
-            m.name,
-            m.province,
-            m.population,
-            m.classification,
-            ls.overall_score as latest_score,
-            -- Boundaries average ~5,500 points each (18 MB total raw). For a
-            -- country-level choropleth, simplify to ~200 m tolerance and 5-decimal
-            -- (~1 m) coordinates, cutting the payload by an order of magnitude.
-            ST_AsGeoJSON(ST_SimplifyPreserveTopology(mg.geom, 0.002), 5)::TEXT as geometry_geojson_str
-        FROM municipalities m
-        LEFT JOIN municipal_geometries mg ON m.id = mg.munic_id
-        LEFT JOIN LatestScores ls ON m.id = ls.municipality_id AND ls.rn = 1
-        ORDER BY m.name
-        LIMIT $1
+            (SELECT COUNT(*) FROM Filtered) as "total!",
+            p.id as "id?",
+            p.name as "name?",
+            p.province as "province?",
+            p.population as "population?",
+            p.classification as "classification?",
+            p.latest_score as "latest_score?",
+            p.last_updated as "last_updated?",
+            -- Label anchor, rounded to 6 decimals (~0.1 m).
+            ROUND(ST_X(anchor.point)::numeric, 6)::float8 as centroid_lon,
+            ROUND(ST_Y(anchor.point)::numeric, 6)::float8 as centroid_lat,
+            -- Extent of the full boundary, for fitting the map to it, and its
+            -- geodesic area for area-normalized choropleths.
+            ST_XMin(p.geom) as bbox_min_lon,
+            ST_YMin(p.geom) as bbox_min_lat,
+            ST_XMax(p.geom) as bbox_max_lon,
+            ST_YMax(p.geom) as bbox_max_lat,
+            ROUND((ST_Area(p.geom::geography) / 1e6)::numeric, 2)::float8 as area_km2,
+            -- Simplified boundaries with 5-decimal (~1 m) coordinates cut the
+            -- payload by an order of magnitude (see DEFAULT_MAP_SIMPLIFY_TOLERANCE).
+            ST_AsGeoJSON(ST_SimplifyPreserveTopology(p.geom, $8), 5)::TEXT as geometry_geojson_str,
+            p.geometry_repaired as "geometry_repaired?"
+        FROM (SELECT 1) AS always_one_row
+        LEFT JOIN Page p ON TRUE
+        -- ST_PointOnSurface, unlike ST_Centroid, always lies inside the
+        -- boundary, even for crescent or multi-part shapes. Computed once per
+        -- row for both coordinates.
+        LEFT JOIN LATERAL (SELECT ST_PointOnSurface(p.geom) as point) anchor ON TRUE
+        ORDER BY p.name, p.id
         "#,
-        query_limit
+        query_limit,
+        query_offset,
+        &excluded_ids,
+        options.province.as_deref(),
+        options.classification.as_deref(),
+        options.min_score,
+        options.max_score,
+        options.simplify_tolerance.unwrap_or(DEFAULT_MAP_SIMPLIFY_TOLERANCE),
+        options.bbox.map(|b| b[0]),
+        options.bbox.map(|b| b[1]),
+        options.bbox.map(|b| b[2]),
+        options.bbox.map(|b| b[3])
     )
     .fetch_all(pool)
     .await?;
 
+    let total = results.first().map_or(0, |row| row.total);
     log::debug!("Fetched {} raw results from DB for map summary", results.len());
+    let repaired = results.iter().filter(|row| row.geometry_repaired == Some(true)).count();
+    if repaired > 0 {
+        log::warn!("Repaired {} invalid boundaries with ST_MakeValid for the map", repaired);
+    }
 
     // Process results into MapFeature vector
     let features: Vec<MapFeature> = results
         .into_iter()
         .filter_map(|row| {
+            // The lone row of an empty page carries only the total.
+            let (id, name, province) = (row.id?, row.name?, row.province?);
             // Parse the geometry string
             let geometry = row.geometry_geojson_str.and_then(|geojson_str| {
                 match geojson_str.parse::<geojson::GeoJson>() {
                     Ok(geojson::GeoJson::Geometry(geom)) => Some(geom),
                     Ok(_) => {
-                        log::warn!("Parsed GeoJSON is not a Geometry for {}", row.id);
+                        log::warn!("Parsed GeoJSON is not a Geometry for {}", id);
                         None
                     },
                     Err(e) => {
-                        log::error!("Failed to parse GeoJSON geometry from DB for {}: {}", row.id, e);
+                        log::error!("Failed to parse GeoJSON geometry from DB for {}: {}", id, e);
                         None
                     }
                 }
@@ -108,17 +303,24 @@ pub async fn get_municipalities_summary_for_map(pool: &PgPool, limit: Option<i64
             // If geometry parsing fails or is None, we might still want to include
             // the feature properties, or skip it. Skipping for now if geometry is essential.
             if geometry.is_none() {
-                log::warn!("Skipping municipality {} due to missing or invalid geometry.", row.id);
+                log::warn!("Skipping municipality {} due to missing or invalid geometry.", id);
                 return None; 
             }
 
             let properties = MapMunicipalityProperties {
-                id: row.id.clone(),
-                name: row.name,
-                province: row.province,
+                id,
+                name,
+                province,
                 population: row.population,
                 classification: row.classification,
                 latest_score: row.latest_score, 
+                last_updated: row.last_updated,
+                centroid: row.centroid_lon.zip(row.centroid_lat).map(|(lon, lat)| [lon, lat]),
+                bbox: match (row.bbox_min_lon, row.bbox_min_lat, row.bbox_max_lon, row.bbox_max_lat) {
+                    (Some(a), Some(b), Some(c), Some(d)) => Some([a, b, c, d]),
+                    _ => None,
+                },
+                area_km2: row.area_km2,
             };
 
             Some(MapFeature {
@@ -129,6 +331,145 @@ pub async fn get_municipalities_summary_for_map(pool: &PgPool, limit: Option<i64
         })
         .collect();
 
-    log::info!("Successfully processed {} features for map summary.", features.len());
-    Ok(features)
+    log::info!("Successfully processed {} of {} features for map summary.", features.len(), total);
+    Ok((features, total))
+}
+
+// Rolls municipalities up by province. Each municipality contributes one row:
+// its latest scored year, or `year` when given, so nothing is counted twice.
+// `excluded_ids` drops whole municipalities; `excluded_years` drops single
+// (id, year) rows, in which case the municipality falls back to its previous
+// scored year (ids matched case-insensitively).
+pub async fn get_province_summary(
+    pool: &PgPool,
+    year: Option<i32>,
+    excluded_ids: &[String],
+    excluded_years: &[(String, i32)],
+) -> Result<Vec<ProvinceSummary>, AppError> {
+    log::debug!("Fetching province summary (year: {:?})", year);
+    let excluded_ids: Vec<String> = excluded_ids.iter().map(|id| id.to_uppercase()).collect();
+    let (excluded_year_ids, excluded_year_values): (Vec<String>, Vec<i32>) = excluded_years
+        .iter()
+        .map(|(id, y)| (id.to_uppercase(), *y))
+        .unzip();
+
+    let rows = sqlx::query!(
+        r#"
+        WITH LatestScored AS (
+            SELECT
+                municipality_id,
+                year,
+                revenue,
+                operational_expenditure,
+                capital_expenditure,
+                debt,
+                audit_outcome,
+                overall_score,
+                ROW_NUMBER() OVER(PARTITION BY municipality_id ORDER BY year DESC) as rn
+            FROM financial_data
+            WHERE overall_score IS NOT NULL
+              AND ($1::int IS NULL OR year = $1)
+              AND (upper(municipality_id), year) NOT IN (
+                  SELECT * FROM unnest($3::text[], $4::int[])
+              )
+        ),
+        PerMunicipality AS (
+            SELECT m.province, ls.*
+            FROM municipalities m
+            LEFT JOIN LatestScored ls ON ls.municipality_id = m.id AND ls.rn = 1
+            WHERE NOT (upper(m.id) = ANY($2::text[]))
+        )
+        SELECT
+            p.province as "province!",
+            COUNT(*) as "municipality_count!",
+            COUNT(p.year) as "reporting_count!",
+            SUM(p.revenue) as total_revenue,
+            SUM(p.operational_expenditure) as total_operational_expenditure,
+            SUM(p.capital_expenditure) as total_capital_expenditure,
+            SUM(p.debt) as total_debt,
+            ROUND(AVG(p.overall_score), 2) as average_score,
+            (
+                SELECT jsonb_object_agg(outcome, n)
+                FROM (
+                    SELECT COALESCE(q.audit_outcome, 'Unknown') as outcome, COUNT(*) as n
+                    FROM PerMunicipality q
+                    WHERE q.province = p.province AND q.year IS NOT NULL
+                    GROUP BY 1
+                ) counts
+            ) as audit_outcomes
+        FROM PerMunicipality p
+        GROUP BY p.province
+        ORDER BY p.province
+        "#,
+        year,
+        &excluded_ids,
+        &excluded_year_ids,
+        &excluded_year_values
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|row| {
+            let audit_outcomes = match row.audit_outcomes {
+                Some(value) => serde_json::from_value(value).map_err(|e| {
+                    AppError::InternalError(format!("Invalid audit outcome counts for {}: {e}", row.province))
+                })?,
+                None => Default::default(),
+            };
+            Ok(ProvinceSummary {
+                province: row.province,
+                municipality_count: row.municipality_count,
+                reporting_count: row.reporting_count,
+                total_revenue: row.total_revenue,
+                total_operational_expenditure: row.total_operational_expenditure,
+                total_capital_expenditure: row.total_capital_expenditure,
+                total_debt: row.total_debt,
+                average_score: row.average_score,
+                audit_outcomes,
+            })
+        })
+        .collect()
 }
+
+// Municipalities whose name contains `term` (case-insensitive; `%`, `_` and `\`
+// match literally), best matches first: exact name, then name prefix, then a
+// word inside the name starting with `term`, then any substring — alphabetical
+// within each tier. `excluded_ids` are matched case-insensitively.
+pub async fn search_municipalities_by_name(
+    pool: &PgPool,
+    term: &str,
+    limit: i64,
+    excluded_ids: &[String],
+) -> Result<Vec<MunicipalityBasicInfo>, AppError> {
+    log::debug!("Searching municipalities for {:?} (limit {})", term, limit);
+    let pattern = term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    let excluded_ids: Vec<String> = excluded_ids.iter().map(|id| id.to_uppercase()).collect();
+    let results = sqlx::query_as!(
+        MunicipalityBasicInfo,
+        r#"
+        SELECT id, name, province, classification
+        FROM municipalities
+        WHERE name ILIKE '%' || $1 || '%'
+          AND NOT (upper(id) = ANY($4::text[]))
+        ORDER BY
+            CASE
+                WHEN lower(name) = lower($2) THEN 0
+                WHEN name ILIKE $1 || '%' THEN 1
+                WHEN name ILIKE '% ' || $1 || '%' THEN 2
+                ELSE 3
+            END,
+            name,
+            id
+        LIMIT $3
+        "#,
+        pattern,
+        term,
+        limit,
+        &excluded_ids
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(results)
+}
+
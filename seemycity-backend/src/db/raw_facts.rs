@@ -0,0 +1,104 @@
+// src/db/raw_facts.rs
+use sqlx::PgPool;
+use crate::api::muni_money::types::FinancialItemFact;
+use crate::errors::AppError;
+use crate::models::RawFinancialFactDb;
+use chrono::Utc;
+
+// Replaces the stored cells of one cube for a municipality-year with `cells`
+// (an empty slice clears them: the cube was fetched and had nothing). Runs in
+// one transaction, so readers never see a half-written cube. A repeated item
+// code keeps its last occurrence. Returns the cells written.
+pub async fn replace_raw_facts(
+    pool: &PgPool,
+    muni_id: &str,
+    year: i32,
+    cube: &str,
+    cells: &[FinancialItemFact],
+) -> Result<u64, AppError> {
+    let mut latest: std::collections::HashMap<&str, &FinancialItemFact> = std::collections::HashMap::new();
+    for cell in cells {
+        latest.insert(cell.item_code.as_str(), cell);
+    }
+    let cells: Vec<&FinancialItemFact> = latest.into_values().collect();
+    let item_codes: Vec<String> = cells.iter().map(|c| c.item_code.clone()).collect();
+    let item_labels: Vec<String> = cells.iter().map(|c| c.item_label.clone()).collect();
+    let amount_types: Vec<Option<String>> = cells.iter().map(|c| c.amount_type_code.clone()).collect();
+    let amounts: Vec<Option<f64>> = cells.iter().map(|c| c.amount).collect();
+
+    let mut tx = pool.begin().await?;
+    sqlx::query!(
+        r#"
+        DELETE FROM raw_financial_facts
+        WHERE municipality_id = $1 AND year = $2 AND cube = $3
+        "#,
+        muni_id,
+        year,
+        cube
+    )
+    .execute(&mut *tx)
+    .await?;
+    let written = sqlx::query!(
+        r#"
+        INSERT INTO raw_financial_facts (municipality_id, year, cube, item_code, item_label, amount_type, amount, fetched_at)
+        SELECT $1::varchar, $2::int4, $3::text, u.item_code, u.item_label, u.amount_type, u.amount, $8::timestamptz
+        FROM UNNEST($4::text[], $5::text[], $6::text[], $7::float8[]) AS u(item_code, item_label, amount_type, amount)
+        "#,
+        muni_id,
+        year,
+        cube,
+        &item_codes,
+        &item_labels,
+        &amount_types as &[_],
+        &amounts as &[_],
+        Utc::now()
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    tx.commit().await?;
+
+    log::debug!("Stored {} raw {} facts for {} year {}", written, cube, muni_id, year);
+    Ok(written)
+}
+
+// Every stored cell for a municipality-year, all cubes, ordered by cube then
+// item code. Empty when nothing was stored (e.g. rows cached before raw facts
+// were kept).
+pub async fn get_raw_facts(pool: &PgPool, muni_id: &str, year: i32) -> Result<Vec<RawFinancialFactDb>, AppError> {
+    let facts = sqlx::query_as!(
+        RawFinancialFactDb,
+        r#"
+        SELECT cube, item_code, item_label, amount_type, amount
+        FROM raw_financial_facts
+        WHERE municipality_id = $1 AND year = $2
+        ORDER BY cube, item_code
+        "#,
+        muni_id,
+        year
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(facts)
+}
+
+// (municipality id, year, population, classification) for every
+// municipality-year with stored facts: the work list of a full recompute.
+pub async fn get_raw_fact_years(
+    pool: &PgPool,
+) -> Result<Vec<(String, i32, Option<f32>, Option<String>)>, AppError> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT r.municipality_id as "municipality_id!", r.year as "year!", m.population, m.classification
+        FROM (SELECT DISTINCT municipality_id, year FROM raw_financial_facts) r
+        JOIN municipalities m ON m.id = r.municipality_id
+        ORDER BY r.municipality_id, r.year
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|r| (r.municipality_id, r.year, r.population, r.classification))
+        .collect())
+}
@@ -0,0 +1,158 @@
+//! Embargo list: municipalities (or single financial years of one) whose
+//! upstream data is known to be wrong and must not be shown until it is fixed.
+//!
+//! Loaded from the JSON file named by EMBARGO_FILE, e.g.
+//!
+//! ```json
+//! [
+//!   { "id": "BUF", "reason": "duplicated FY2023 revenue upstream", "years": [2023] },
+//!   { "id": "NC453" }
+//! ]
+//! ```
+//!
+//! An entry without `years` withholds the whole municipality: it is dropped
+//! from the map and its detail/summary requests get a 404 explaining why. An
+//! entry with `years` withholds only those years. The file can be edited and
+//! reloaded at runtime via `POST /api/admin/reload-embargo`.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmbargoEntry {
+    pub id: String,
+    /// Years withheld; empty = the whole municipality.
+    #[serde(default)]
+    pub years: Vec<i32>,
+    /// Maintainer note; not shown to clients.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// The active embargo list, shared across workers.
+#[derive(Debug, Default)]
+pub struct Embargo {
+    path: Option<PathBuf>,
+    entries: RwLock<Vec<EmbargoEntry>>,
+}
+
+impl Embargo {
+    /// A fixed list with no backing file (`reload` is then a no-op).
+    pub fn new(entries: Vec<EmbargoEntry>) -> Self {
+        Embargo { path: None, entries: RwLock::new(entries) }
+    }
+
+    /// Loads the list from a JSON file; `reload` re-reads the same file.
+    pub fn from_file(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let embargo = Embargo { path: Some(path.into()), entries: RwLock::new(Vec::new()) };
+        embargo.reload()?;
+        Ok(embargo)
+    }
+
+    /// Re-reads the backing file, replacing the active list only if the new
+    /// one parses. Returns the number of entries now active.
+    pub fn reload(&self) -> Result<usize, String> {
+        let Some(path) = &self.path else {
+            return Ok(self.read().len());
+        };
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read embargo file {}: {e}", path.display()))?;
+        let entries: Vec<EmbargoEntry> = serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid embargo file {}: {e}", path.display()))?;
+        let count = entries.len();
+        if let Ok(mut guard) = self.entries.write() {
+            *guard = entries;
+        }
+        log::info!("Embargo list loaded from {}: {} entries", path.display(), count);
+        Ok(count)
+    }
+
+    /// True when the whole municipality is withheld.
+    pub fn blocks_municipality(&self, id: &str) -> bool {
+        self.read()
+            .iter()
+            .any(|e| e.id.eq_ignore_ascii_case(id) && e.years.is_empty())
+    }
+
+    /// True when `year` of the municipality is withheld, directly or because
+    /// the whole municipality is.
+    pub fn blocks_year(&self, id: &str, year: i32) -> bool {
+        self.read()
+            .iter()
+            .any(|e| e.id.eq_ignore_ascii_case(id) && (e.years.is_empty() || e.years.contains(&year)))
+    }
+
+    /// Ids of the municipalities withheld entirely (not year-only entries).
+    pub fn blocked_municipalities(&self) -> Vec<String> {
+        self.read().iter().filter(|e| e.years.is_empty()).map(|e| e.id.clone()).collect()
+    }
+
+    /// `(id, year)` pairs withheld by year-only entries.
+    pub fn blocked_years(&self) -> Vec<(String, i32)> {
+        self.read()
+            .iter()
+            .flat_map(|e| e.years.iter().map(move |&year| (e.id.clone(), year)))
+            .collect()
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Vec<EmbargoEntry>> {
+        // A poisoned lock still holds a valid list: entries are swapped whole.
+        self.entries.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, years: &[i32]) -> EmbargoEntry {
+        EmbargoEntry { id: id.to_string(), years: years.to_vec(), reason: None }
+    }
+
+    #[test]
+    fn whole_municipality_entry_blocks_every_year() {
+        let embargo = Embargo::new(vec![entry("BUF", &[])]);
+        assert!(embargo.blocks_municipality("BUF"));
+        assert!(embargo.blocks_municipality("buf"));
+        assert!(embargo.blocks_year("BUF", 2019));
+        assert!(!embargo.blocks_municipality("CPT"));
+    }
+
+    #[test]
+    fn year_entry_blocks_only_listed_years() {
+        let embargo = Embargo::new(vec![entry("CPT", &[2023])]);
+        assert!(!embargo.blocks_municipality("CPT"));
+        assert!(embargo.blocks_year("CPT", 2023));
+        assert!(!embargo.blocks_year("CPT", 2024));
+    }
+
+    #[test]
+    fn blocked_lists_split_whole_and_year_entries() {
+        let embargo = Embargo::new(vec![entry("BUF", &[]), entry("CPT", &[2022, 2023])]);
+        assert_eq!(embargo.blocked_municipalities(), vec!["BUF".to_string()]);
+        assert_eq!(
+            embargo.blocked_years(),
+            vec![("CPT".to_string(), 2022), ("CPT".to_string(), 2023)]
+        );
+    }
+
+    #[test]
+    fn reload_swaps_list_and_keeps_old_one_on_parse_error() {
+        let path = std::env::temp_dir().join(format!("embargo-test-{}.json", std::process::id()));
+        std::fs::write(&path, r#"[{ "id": "BUF" }]"#).unwrap();
+        let embargo = Embargo::from_file(&path).unwrap();
+        assert!(embargo.blocks_municipality("BUF"));
+
+        std::fs::write(&path, r#"[{ "id": "CPT", "years": [2022] }]"#).unwrap();
+        assert_eq!(embargo.reload(), Ok(1));
+        assert!(!embargo.blocks_municipality("BUF"));
+        assert!(embargo.blocks_year("CPT", 2022));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(embargo.reload().is_err());
+        assert!(embargo.blocks_year("CPT", 2022), "a bad file must not clear the list");
+        std::fs::remove_file(&path).ok();
+    }
+}
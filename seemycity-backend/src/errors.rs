@@ -1,6 +1,44 @@
 // src/errors.rs
 use thiserror::Error;
-use actix_web::{ResponseError, HttpResponse, http::StatusCode};
+use crate::api::muni_money::types::ApiClientError;
+use actix_web::{ResponseError, HttpResponse, http::{header, StatusCode}};
+use serde::Serialize;
+use utoipa::openapi::{ObjectBuilder, RefOr, Schema, SchemaType};
+use utoipa::ToSchema;
+
+/// Retry-After sent with 503s: long enough for in-flight detail refreshes
+/// (a few seconds each) to drain.
+const RETRY_AFTER_SECS: u64 = 5;
+
+/// Every `code` an error body can carry, as listed in the OpenAPI document.
+pub const ERROR_CODES: [&str; 8] = [
+    "DB_ERROR",
+    "UPSTREAM_ERROR",
+    "NOT_FOUND",
+    "BAD_REQUEST",
+    "UNAUTHORIZED",
+    "CONFLICT",
+    "SERVICE_UNAVAILABLE",
+    "INTERNAL_ERROR",
+];
+
+/// Body of every error response.
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ErrorResponse {
+    /// Human-readable message; wording may change.
+    pub error: String,
+    /// Stable machine-readable code (`AppError::code`).
+    #[schema(schema_with = error_code_schema)]
+    pub code: &'static str,
+}
+
+fn error_code_schema() -> RefOr<Schema> {
+    ObjectBuilder::new()
+        .schema_type(SchemaType::String)
+        .enum_values(Some(ERROR_CODES))
+        .description(Some("Stable machine-readable code"))
+        .into()
+}
 
 #[derive(Error, Debug)]
 pub enum AppError {
@@ -16,20 +54,88 @@ pub enum AppError {
     #[error("Bad request: {0}")]
     BadRequest(String), // Add BadRequest variant
 
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String), // An upsert hit a unique constraint; the message names it
+
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String), // Load shedding; sent with Retry-After
+
     #[error("Internal server error: {0}")]
     InternalError(String),
     // Add other specific error types as needed
 }
 
+impl AppError {
+    /// Stable, machine-readable error code sent as `code` in every error body.
+    /// Derived from the variant only, so clients can branch on it while the
+    /// human-readable message changes freely. Never rename an existing code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::SqlxError(_) => "DB_ERROR",
+            AppError::ApiClientError(_) => "UPSTREAM_ERROR",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::BadRequest(_) => "BAD_REQUEST",
+            AppError::Unauthorized(_) => "UNAUTHORIZED",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            AppError::InternalError(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    /// The message sent to clients for this error. NotFound/BadRequest/
+    /// Unauthorized/ServiceUnavailable messages are written for clients;
+    /// Conflict names a constraint, so clients get a fixed message;
+    /// everything else carries internal detail (SQL text, upstream bodies)
+    /// that must not leak, so it gets a generic message for its status.
+    pub fn client_message(&self) -> String {
+        match self {
+            AppError::NotFound(msg) => msg.clone(),
+            AppError::BadRequest(msg) => msg.clone(),
+            AppError::Unauthorized(msg) => msg.clone(),
+            AppError::ServiceUnavailable(msg) => msg.clone(),
+            AppError::Conflict(_) => "The record conflicts with one that already exists.".to_string(),
+            AppError::ApiClientError(e) => match upstream_status(e) {
+                StatusCode::GATEWAY_TIMEOUT => "The upstream data source timed out.".to_string(),
+                StatusCode::BAD_GATEWAY => "The upstream data source is unavailable.".to_string(),
+                _ => "An internal error occurred.".to_string(),
+            },
+            _ => "An internal error occurred.".to_string(),
+        }
+    }
+}
+
+/// Treasury API failures are not our server's fault: an unreachable or erroring
+/// upstream is a 502, a timed-out one a 504. A response we failed to decode, or
+/// a request we built wrongly, is our bug and stays a 500.
+fn upstream_status(error: &ApiClientError) -> StatusCode {
+    match error {
+        ApiClientError::RequestError(e) if e.is_timeout() => StatusCode::GATEWAY_TIMEOUT,
+        ApiClientError::DeadlineExceeded(_) => StatusCode::GATEWAY_TIMEOUT,
+        ApiClientError::RequestError(e) if e.is_decode() => StatusCode::INTERNAL_SERVER_ERROR,
+        ApiClientError::RequestError(_) | ApiClientError::ApiError { .. } | ApiClientError::NoData => {
+            StatusCode::BAD_GATEWAY
+        }
+        ApiClientError::ParseError(_)
+        | ApiClientError::MissingField(_)
+        | ApiClientError::UnexpectedDataFormat(_)
+        | ApiClientError::InvalidParameters(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 // Implement ResponseError for Actix Web integration
 impl ResponseError for AppError {
     fn status_code(&self) -> StatusCode {
         match *self {
             AppError::SqlxError(_) => StatusCode::INTERNAL_SERVER_ERROR,
-            // Upstream Treasury API failures are not our server's fault
-            AppError::ApiClientError(_) => StatusCode::BAD_GATEWAY,
+            AppError::ApiClientError(ref e) => upstream_status(e),
             AppError::NotFound(_) => StatusCode::NOT_FOUND,
             AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
             AppError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -37,16 +143,62 @@ impl ResponseError for AppError {
     fn error_response(&self) -> HttpResponse {
         log::error!("Responding with error: {}", self); // Log the detailed error server-side
 
-        // NotFound/BadRequest messages are written for clients; everything else
-        // carries internal detail (SQL text, upstream bodies) that must not leak.
-        let client_message = match self {
-            AppError::NotFound(msg) => msg.clone(),
-            AppError::BadRequest(msg) => msg.clone(),
-            AppError::ApiClientError(_) => "The upstream data source is unavailable.".to_string(),
-            _ => "An internal error occurred.".to_string(),
-        };
+        let client_message = self.client_message();
 
-        HttpResponse::build(self.status_code())
-            .json(serde_json::json!({ "error": client_message }))
+        let mut response = HttpResponse::build(self.status_code());
+        if let AppError::ServiceUnavailable(_) = self {
+            response.insert_header((header::RETRY_AFTER, RETRY_AFTER_SECS.to_string()));
+        }
+        response.json(ErrorResponse { error: client_message, code: self.code() })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_of(error: ApiClientError) -> StatusCode {
+        AppError::from(error).status_code()
+    }
+
+    #[actix_web::test]
+    async fn every_variant_has_a_stable_code_in_the_body() {
+        let cases = [
+            (AppError::SqlxError(sqlx::Error::RowNotFound), 500, "DB_ERROR", "An internal error occurred."),
+            (AppError::from(ApiClientError::NoData), 502, "UPSTREAM_ERROR", "The upstream data source is unavailable."),
+            (AppError::NotFound("No municipality XYZ".to_string()), 404, "NOT_FOUND", "No municipality XYZ"),
+            (AppError::BadRequest("limit must be positive".to_string()), 400, "BAD_REQUEST", "limit must be positive"),
+            (AppError::Unauthorized("Missing token".to_string()), 401, "UNAUTHORIZED", "Missing token"),
+            (AppError::ServiceUnavailable("Busy".to_string()), 503, "SERVICE_UNAVAILABLE", "Busy"),
+            (
+                AppError::Conflict("duplicate row rejected by unique constraint financial_data_key".to_string()),
+                409,
+                "CONFLICT",
+                "The record conflicts with one that already exists.",
+            ),
+            (AppError::InternalError("secret detail".to_string()), 500, "INTERNAL_ERROR", "An internal error occurred."),
+        ];
+        for (error, status, code, message) in cases {
+            let response = error.error_response();
+            assert_eq!(response.status().as_u16(), status, "{code}");
+            let body = actix_web::body::to_bytes(response.into_body()).await.unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body, serde_json::json!({ "error": message, "code": code }));
+            assert!(ERROR_CODES.contains(&code), "{code} is missing from ERROR_CODES");
+        }
+    }
+
+    #[test]
+    fn upstream_failures_map_to_gateway_statuses() {
+        let api_error = ApiClientError::ApiError { status: 503, message: None, body: None };
+        assert_eq!(status_of(api_error), StatusCode::BAD_GATEWAY);
+        assert_eq!(status_of(ApiClientError::NoData), StatusCode::BAD_GATEWAY);
+        let deadline = ApiClientError::DeadlineExceeded(std::time::Duration::from_secs(20));
+        assert_eq!(status_of(deadline), StatusCode::GATEWAY_TIMEOUT);
+
+        let parse_error = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        assert_eq!(status_of(ApiClientError::ParseError(parse_error)), StatusCode::INTERNAL_SERVER_ERROR);
+        let format_error = ApiClientError::UnexpectedDataFormat("cells".to_string());
+        assert_eq!(status_of(format_error), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
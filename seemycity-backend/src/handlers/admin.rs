@@ -0,0 +1,258 @@
+// src/handlers/admin.rs
+use crate::api::muni_money::client::MunicipalMoneyClient;
+use crate::config::Config;
+use crate::db::geo::get_geometry_validity;
+use crate::db::municipalities::get_municipality_base_info_db;
+use crate::embargo::Embargo;
+use crate::errors::AppError;
+use crate::handlers::municipalities::MapResponseCache;
+use crate::jobs::{Job, JobRegistry};
+use crate::models::MunicipalityId;
+use crate::scoring::ScoringConfig;
+use crate::services::municipality::{build_and_store_detail, recompute_all_from_raw, rescore_all, UpstreamHealth};
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+
+/// Rejects the request unless it carries `Authorization: Bearer <ADMIN_TOKEN>`.
+/// With no ADMIN_TOKEN configured, every admin request is rejected.
+fn require_admin(req: &HttpRequest, config: &Config) -> Result<(), AppError> {
+    let expected = config
+        .admin_token
+        .as_deref()
+        .ok_or_else(|| AppError::Unauthorized("Admin endpoints are disabled".to_string()))?;
+    let provided = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    if constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+        Ok(())
+    } else {
+        Err(AppError::Unauthorized("Missing or invalid admin token".to_string()))
+    }
+}
+
+/// Compares without short-circuiting so response timing doesn't reveal how
+/// much of the token matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Dry-runs `ScoringConfig::validate` on the posted config. Nothing is stored
+/// or applied. Responds `{"valid": true}`, or `{"valid": false, "errors": [...]}`
+/// with one `{field, message}` entry per violated rule. The body is parsed only
+/// after the admin check, so unauthenticated callers learn nothing from it.
+pub async fn validate_scoring_config_handler(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    body: web::Bytes,
+) -> Result<HttpResponse, AppError> {
+    require_admin(&req, &config)?;
+    let scoring_config: ScoringConfig = serde_json::from_slice(&body)
+        .map_err(|e| AppError::BadRequest(format!("Invalid scoring config: {e}")))?;
+    let response = match scoring_config.validate() {
+        Ok(()) => serde_json::json!({ "valid": true }),
+        Err(errors) => serde_json::json!({ "valid": false, "errors": errors }),
+    };
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Re-reads EMBARGO_FILE and drops the cached map payload so the change is
+/// visible immediately. A file that fails to parse leaves the active list in
+/// place and is reported as a 400. Responds `{"entries": <count>}`.
+pub async fn reload_embargo_handler(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    embargo: web::Data<Embargo>,
+    map_cache: web::Data<MapResponseCache>,
+) -> Result<HttpResponse, AppError> {
+    require_admin(&req, &config)?;
+    let entries = embargo.reload().map_err(AppError::BadRequest)?;
+    map_cache.invalidate();
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "entries": entries })))
+}
+
+/// Drops every cached map payload, e.g. after a bulk data load, so the next
+/// map requests rebuild from the database. Responds `{"evicted": <count>}`.
+pub async fn invalidate_map_cache_handler(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    map_cache: web::Data<MapResponseCache>,
+) -> Result<HttpResponse, AppError> {
+    require_admin(&req, &config)?;
+    let evicted = map_cache.invalidate();
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "evicted": evicted })))
+}
+
+/// Re-sums and re-scores every municipality-year from its stored raw facts
+/// (`services::municipality::recompute_all_from_raw`), e.g. after an item-code
+/// selection changes, then drops the cached map payload. Sums use the API
+/// client's item-code selection and negative-total clamping. No Treasury API calls. Responds
+/// `{"recomputed": <rows rewritten>}`.
+pub async fn recompute_from_raw_handler(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    pool: web::Data<PgPool>,
+    api_client: web::Data<MunicipalMoneyClient>,
+    map_cache: web::Data<MapResponseCache>,
+) -> Result<HttpResponse, AppError> {
+    require_admin(&req, &config)?;
+    let recomputed =
+        recompute_all_from_raw(&pool, api_client.item_codes(), api_client.clamps_negative_totals()).await?;
+    map_cache.invalidate();
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "recomputed": recomputed })))
+}
+
+/// Error recorded on a refresh job the circuit breaker kept from the upstream.
+const CIRCUIT_OPEN: &str = "Treasury API circuit breaker is open; cached data was not refreshed";
+
+/// Starts a rescore of every stored municipality-year from its stored figures
+/// under the current scoring formula and thresholds on a background task, then
+/// drops the cached map payload. Use after a scoring change that needs no new
+/// figures (a recalibrated threshold, a reweighted pillar). Only the score
+/// columns are written, so `updated_at` still dates the last fetch. No
+/// Treasury API calls. Responds 202 at once with the pending job and a
+/// `Location` to poll; the finished job reports `rescored`, the rows rewritten.
+/// While a rescore is unfinished, that job is returned instead.
+pub async fn rescore_handler(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    pool: web::Data<PgPool>,
+    map_cache: web::Data<MapResponseCache>,
+    jobs: web::Data<JobRegistry>,
+) -> Result<HttpResponse, AppError> {
+    require_admin(&req, &config)?;
+    let job = match jobs.create_rescore() {
+        Ok(job) => job,
+        Err(running) => return Ok(job_accepted(running)),
+    };
+    log::info!("Admin rescore started as job {}", job.id);
+    let job_id = job.id.clone();
+    let registry = jobs.clone().into_inner();
+    registry.spawn(job_id.clone(), async move {
+        jobs.mark_running(&job_id);
+        let result = rescore_all(&pool).await;
+        match &result {
+            Ok(rescored) => {
+                map_cache.invalidate();
+                log::info!("Admin rescore job {} done: {} rows rewritten", job_id, rescored);
+            }
+            Err(e) => log::error!("Admin rescore job {} failed: {}", job_id, e),
+        }
+        jobs.finish_rescore(&job_id, result.map_err(|e| e.to_string()));
+    });
+
+    Ok(job_accepted(job))
+}
+
+/// 202 with `job` as the body and a `Location` to poll it at.
+fn job_accepted(job: Job) -> HttpResponse {
+    HttpResponse::Accepted()
+        .insert_header((header::LOCATION, format!("/api/admin/jobs/{}", job.id)))
+        .json(job)
+}
+
+/// Starts a forced refresh of one municipality (every year refetched from the
+/// Treasury API, rescored and stored, as a cold detail request would) on a
+/// background task, then drops the cached map payloads. Responds 202 at once
+/// with the pending job and a `Location` to poll; 400 for a malformed id, 404
+/// for an unknown one. While a refresh of the municipality is unfinished, that
+/// job is returned instead of starting another. The job fails if the circuit
+/// breaker is open at the start or end, since the cache then stood in for the
+/// Treasury API.
+#[allow(clippy::too_many_arguments)] // actix extractors, one per shared resource
+pub async fn refresh_municipality_handler(
+    req: HttpRequest,
+    path: web::Path<String>,
+    config: web::Data<Config>,
+    pool: web::Data<PgPool>,
+    api_client: web::Data<MunicipalMoneyClient>,
+    upstream_health: web::Data<UpstreamHealth>,
+    map_cache: web::Data<MapResponseCache>,
+    jobs: web::Data<JobRegistry>,
+) -> Result<HttpResponse, AppError> {
+    require_admin(&req, &config)?;
+    let muni_id = MunicipalityId::try_from(path.into_inner())?;
+    let base_info = get_municipality_base_info_db(&pool, muni_id.as_str())
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Municipality with ID {} not found", muni_id)))?;
+
+    let job = match jobs.create(&base_info.id) {
+        Ok(job) => job,
+        Err(running) => return Ok(job_accepted(running)),
+    };
+    log::info!("Admin refresh of {} started as job {}", base_info.id, job.id);
+    let (job_id, muni_id) = (job.id.clone(), base_info.id);
+    let newest_year = config.current_financial_year();
+    let registry = jobs.clone().into_inner();
+    registry.spawn(job_id.clone(), async move {
+        jobs.mark_running(&job_id);
+        // With the breaker open nothing is refetched, and the cached rows
+        // would pass for a completed refresh.
+        if !upstream_health.is_up() {
+            log::warn!("Admin refresh job {} for {} not run: Treasury API circuit breaker is open", job_id, muni_id);
+            jobs.finish(&job_id, Err(CIRCUIT_OPEN.to_string()));
+            return;
+        }
+        // A zero TTL makes every cached year stale, so each one is refetched.
+        let result = build_and_store_detail(
+            &pool,
+            &api_client,
+            &upstream_health,
+            &muni_id,
+            &[],
+            false,
+            newest_year,
+            chrono::Duration::zero(),
+        )
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+        // Tripped mid-refresh: some years were served from the cache.
+        .and_then(|()| if upstream_health.is_up() { Ok(()) } else { Err(CIRCUIT_OPEN.to_string()) });
+        match &result {
+            Ok(()) => {
+                map_cache.invalidate();
+                log::info!("Admin refresh job {} for {} done", job_id, muni_id);
+            }
+            Err(e) => log::error!("Admin refresh job {} for {} failed: {}", job_id, muni_id, e),
+        }
+        jobs.finish(&job_id, result);
+    });
+
+    Ok(job_accepted(job))
+}
+
+/// Reports a background job: `{id, kind, municipality_id, status, error,
+/// rescored, created_at, updated_at}` with `kind` refresh or rescore and
+/// `status` one of pending, running, done or error. 404 for an unknown id, or one finished over an hour ago.
+pub async fn get_job_handler(
+    req: HttpRequest,
+    path: web::Path<String>,
+    config: web::Data<Config>,
+    jobs: web::Data<JobRegistry>,
+) -> Result<HttpResponse, AppError> {
+    require_admin(&req, &config)?;
+    let job_id = path.into_inner();
+    let job = jobs
+        .get(&job_id)
+        .ok_or_else(|| AppError::NotFound(format!("Job {} not found", job_id)))?;
+    Ok(HttpResponse::Ok().json(job))
+}
+
+/// Checks every stored boundary with `ST_IsValid`. Responds `{total, invalid,
+/// invalid_geometries: [{id, reason}]}`. The map repairs invalid boundaries on
+/// the fly; this lists the ones to fix at the source.
+pub async fn geometry_validity_handler(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, AppError> {
+    require_admin(&req, &config)?;
+    let validity = get_geometry_validity(&pool).await?;
+    if validity.invalid > 0 {
+        log::warn!("{} of {} stored boundaries are invalid", validity.invalid, validity.total);
+    }
+    Ok(HttpResponse::Ok().json(validity))
+}
@@ -0,0 +1,95 @@
+// src/handlers/conditional.rs
+
+// Conditional GET for the detail and map endpoints. The ETag is a hash of the
+// serialized body (so any change a client could see changes it) and
+// Last-Modified is the newest `updated_at` behind the payload. A matching
+// If-None-Match gets 304 with no body. If-Modified-Since is not honoured:
+// `updated_at` dates the last fetch, and scores rewritten since (a rescore,
+// a recompute, new thresholds) or an embargo change alter the body without
+// moving it, so a date match could hand out a stale copy. Last-Modified is
+// informational only.
+use actix_web::http::header::{self, HttpDate};
+use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder};
+use chrono::{DateTime, Utc};
+use std::time::SystemTime;
+
+/// Weak ETag (`W/"..."`) for a response body: 64-bit FNV-1a, which is stable
+/// across processes and workers, unlike `DefaultHasher`. Weak because the
+/// `Compress` middleware changes the bytes on the wire while the
+/// representation stays the same.
+pub fn body_etag(body: &[u8]) -> String {
+    let hash = body.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("W/\"{hash:016x}\"")
+}
+
+/// `W/"x"` and `"x"` compare equal: If-None-Match uses weak comparison.
+fn opaque_tag(tag: &str) -> &str {
+    tag.trim().trim_start_matches("W/")
+}
+
+/// True when the request's If-None-Match shows the client already has this
+/// representation. If-Modified-Since is ignored (see the module comment).
+pub fn is_not_modified(req: &HttpRequest, etag: &str) -> bool {
+    let Some(if_none_match) = req.headers().get(header::IF_NONE_MATCH) else { return false };
+    let Ok(candidates) = if_none_match.to_str() else { return false };
+    candidates
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque_tag(candidate) == opaque_tag(etag))
+}
+
+/// Finishes `builder` as a 200 carrying `body`, or as a bodiless 304 when the
+/// client's copy is current. Either way the validators are set, along with any
+/// headers already on `builder` (Cache-Control, content type).
+pub fn conditional_response(
+    req: &HttpRequest,
+    mut builder: HttpResponseBuilder,
+    body: String,
+    etag: &str,
+    last_modified: Option<DateTime<Utc>>,
+) -> HttpResponse {
+    builder.insert_header((header::ETAG, etag.to_string()));
+    if let Some(last_modified) = last_modified {
+        builder.insert_header((header::LAST_MODIFIED, HttpDate::from(SystemTime::from(last_modified))));
+    }
+    if is_not_modified(req, etag) {
+        builder.status(actix_web::http::StatusCode::NOT_MODIFIED);
+        return builder.finish();
+    }
+    builder.body(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn etag_is_weak_stable_and_content_sensitive() {
+        assert_eq!(body_etag(b"{}"), body_etag(b"{}"));
+        assert_ne!(body_etag(b"{\"a\":1}"), body_etag(b"{\"a\":2}"));
+        assert!(body_etag(b"").starts_with("W/\"") && body_etag(b"").ends_with('"'));
+    }
+
+    #[test]
+    fn only_if_none_match_can_give_a_304() {
+        let etag = body_etag(b"body");
+        let strong = etag.trim_start_matches("W/");
+
+        let req = TestRequest::default().insert_header(("If-None-Match", format!("\"other\", {strong}"))).to_http_request();
+        assert!(is_not_modified(&req, &etag));
+        let req = TestRequest::default().insert_header(("If-None-Match", etag.as_str())).to_http_request();
+        assert!(is_not_modified(&req, &etag));
+
+        let req = TestRequest::default()
+            .insert_header(("If-None-Match", "\"other\""))
+            .insert_header(("If-Modified-Since", "Fri, 01 Jan 2100 00:00:00 GMT"))
+            .to_http_request();
+        assert!(!is_not_modified(&req, &etag));
+
+        // A date alone never matches, however recent.
+        let req = TestRequest::default().insert_header(("If-Modified-Since", "Fri, 01 Jan 2100 00:00:00 GMT")).to_http_request();
+        assert!(!is_not_modified(&req, &etag));
+    }
+}
@@ -0,0 +1,106 @@
+// src/handlers/csv_stream.rs
+
+// A CSV response body written one record per chunk, header first, like
+// `feature_stream` does for GeoJSON, with the rows taken from a channel as the
+// query yields them (`financials::stream_score_export`): only the rows in
+// flight are held, never the result set or the whole file. The header is
+// written even with no rows. A row that fails to load ends the body with that
+// error, so the client sees a broken download rather than a short file.
+use crate::errors::AppError;
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::web::Bytes;
+use serde::Serialize;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+pub struct CsvBody<R, T> {
+    header: Option<&'static [&'static str]>,
+    rows: mpsc::Receiver<Result<R, AppError>>,
+    to_record: fn(R) -> T,
+}
+
+impl<R, T: Serialize> CsvBody<R, T> {
+    /// Each row is written as `to_record(row)`; `header` names the columns in
+    /// the order `T` serializes its fields.
+    pub fn new(
+        header: &'static [&'static str],
+        rows: mpsc::Receiver<Result<R, AppError>>,
+        to_record: fn(R) -> T,
+    ) -> Self {
+        CsvBody { header: Some(header), rows, to_record }
+    }
+
+    fn write_chunk(write: impl FnOnce(&mut csv::Writer<Vec<u8>>) -> csv::Result<()>) -> Result<Bytes, AppError> {
+        let csv_error = |e: csv::Error| AppError::InternalError(format!("Failed to write CSV: {e}"));
+        let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+        write(&mut writer).map_err(csv_error)?;
+        let chunk = writer.into_inner().map_err(|e| csv_error(csv::Error::from(e.into_error())))?;
+        Ok(Bytes::from(chunk))
+    }
+
+    fn poll_chunk(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes, AppError>>> {
+        if let Some(header) = self.header.take() {
+            return Poll::Ready(Some(Self::write_chunk(|w| w.write_record(header))));
+        }
+        match self.rows.poll_recv(cx) {
+            Poll::Ready(Some(Ok(row))) => {
+                let record = (self.to_record)(row);
+                Poll::Ready(Some(Self::write_chunk(|w| w.serialize(record))))
+            }
+            Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e))),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<R, T: Serialize> MessageBody for CsvBody<R, T> {
+    type Error = AppError;
+
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        self.get_mut().poll_chunk(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Row {
+        id: &'static str,
+        score: Option<f64>,
+    }
+
+    fn channel(rows: Vec<Result<Row, AppError>>) -> mpsc::Receiver<Result<Row, AppError>> {
+        let (tx, rx) = mpsc::channel(rows.len().max(1));
+        for row in rows {
+            tx.try_send(row).unwrap();
+        }
+        rx
+    }
+
+    #[actix_web::test]
+    async fn streamed_body_is_the_header_then_one_record_per_row() {
+        let rows = channel(vec![Ok(Row { id: "CPT", score: Some(71.5) }), Ok(Row { id: "WC011, \"x\"", score: None })]);
+        let body = actix_web::body::to_bytes(CsvBody::new(&["id", "score"], rows, |row: Row| row)).await.unwrap();
+        assert_eq!(std::str::from_utf8(&body).unwrap(), "id,score\nCPT,71.5\n\"WC011, \"\"x\"\"\",\n");
+
+        let empty = actix_web::body::to_bytes(CsvBody::new(&["id", "score"], channel(vec![]), |row: Row| row)).await.unwrap();
+        assert_eq!(std::str::from_utf8(&empty).unwrap(), "id,score\n");
+    }
+
+    #[actix_web::test]
+    async fn a_row_that_fails_to_load_fails_the_body() {
+        let rows = channel(vec![
+            Ok(Row { id: "CPT", score: Some(71.5) }),
+            Err(AppError::InternalError("connection reset".to_string())),
+        ]);
+        assert!(actix_web::body::to_bytes(CsvBody::new(&["id", "score"], rows, |row: Row| row)).await.is_err());
+    }
+}
@@ -0,0 +1,22 @@
+// src/handlers/docs.rs
+
+// API documentation: the OpenAPI document (see `crate::openapi`) and a
+// Swagger UI page that renders it. The UI's assets are compiled into the
+// binary by utoipa-swagger-ui, so the page loads nothing from a CDN and its
+// version is pinned by Cargo.lock.
+use crate::openapi::ApiDoc;
+use actix_web::{http::header, HttpResponse};
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+/// Serves the UI under /swagger/ and the document at /api-docs/openapi.json.
+pub fn swagger_ui() -> SwaggerUi {
+    SwaggerUi::new("/swagger/{_:.*}").url("/api-docs/openapi.json", ApiDoc::openapi())
+}
+
+/// The UI loads its assets relative to /swagger/, so the bare path redirects.
+pub async fn swagger_redirect_handler() -> HttpResponse {
+    HttpResponse::PermanentRedirect()
+        .insert_header((header::LOCATION, "/swagger/"))
+        .finish()
+}
@@ -0,0 +1,103 @@
+// src/handlers/export.rs
+use crate::db::financials::{get_latest_scored_year, stream_score_export};
+use crate::embargo::Embargo;
+use crate::errors::AppError;
+use crate::handlers::csv_stream::CsvBody;
+use crate::models::ScoreExportRow;
+use crate::scoring::grade_for_score;
+use actix_web::{http::header, web, HttpResponse};
+use tokio::sync::mpsc;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool as DbPool;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScoreExportQuery {
+    /// Defaults to the most recent year with any overall score.
+    year: Option<i32>,
+}
+
+const SCORE_CSV_HEADER: &[&str] =
+    &["id", "name", "province", "classification", "revenue", "debt", "overall_score", "grade"];
+
+/// One CSV row, in `SCORE_CSV_HEADER` order. Decimals keep their own
+/// `Serialize` (full precision), as in the single-municipality export.
+#[derive(Serialize)]
+struct ScoreCsvRow {
+    id: String,
+    name: String,
+    province: String,
+    classification: Option<String>,
+    revenue: Option<Decimal>,
+    debt: Option<Decimal>,
+    overall_score: Option<Decimal>,
+    grade: Option<String>,
+}
+
+impl From<ScoreExportRow> for ScoreCsvRow {
+    fn from(row: ScoreExportRow) -> Self {
+        ScoreCsvRow {
+            grade: row.overall_score.map(|s| grade_for_score(s).to_string()),
+            id: row.id,
+            name: row.name,
+            province: row.province,
+            classification: row.classification,
+            revenue: row.revenue,
+            debt: row.debt,
+            overall_score: row.overall_score,
+        }
+    }
+}
+
+/// Every municipality's headline figures and score for one year as a single
+/// CSV download, from cached data only, streamed one row per chunk.
+/// Municipalities embargoed entirely or for that year are left out; with
+/// nothing scored yet the file is just the header.
+#[utoipa::path(
+    get,
+    path = "/api/export/scores.csv",
+    params(ScoreExportQuery),
+    responses(
+        (
+            status = 200,
+            description = "CSV attachment: id, name, province, classification, revenue, debt, overall_score, grade",
+            body = String,
+            content_type = "text/csv"
+        ),
+        (status = 400, description = "Invalid year", body = ErrorResponse)
+    )
+)]
+pub async fn export_scores_csv_handler(
+    query: web::Query<ScoreExportQuery>,
+    pool: web::Data<DbPool>,
+    embargo: web::Data<Embargo>,
+) -> Result<HttpResponse, AppError> {
+    log::debug!("Handling request for /api/export/scores.csv with {:?}", query);
+    let year = match query.year {
+        Some(y) => Some(y),
+        None => get_latest_scored_year(&pool).await?,
+    };
+    let rows = match year {
+        Some(y) => {
+            let excluded: Vec<String> = embargo
+                .blocked_municipalities()
+                .into_iter()
+                .chain(embargo.blocked_years().into_iter().filter(|(_, by)| *by == y).map(|(id, _)| id))
+                .collect();
+            stream_score_export(&pool, y, &excluded)
+        }
+        // Nothing scored yet: a closed channel, so the file is just the header.
+        None => mpsc::channel(1).1,
+    };
+    let filename = match year {
+        Some(y) => format!("scores-{y}.csv"),
+        None => "scores.csv".to_string(),
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header((header::CONTENT_DISPOSITION, format!("attachment; filename=\"{filename}\"")))
+        .body(CsvBody::new(SCORE_CSV_HEADER, rows, ScoreCsvRow::from)))
+}
@@ -0,0 +1,57 @@
+// src/handlers/feature_stream.rs
+
+// A FeatureCollection response body written one feature per chunk. The
+// full-country download is tens of MB of JSON; serializing lazily means only
+// the parsed features are held, never a second copy as one big string.
+use crate::models::MapFeature;
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::web::Bytes;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+const OPENING: &[u8] = br#"{"type":"FeatureCollection","features":["#;
+const CLOSING: &[u8] = b"]}";
+
+pub struct FeatureCollectionBody {
+    features: std::vec::IntoIter<MapFeature>,
+    opened: bool,
+    written: usize,
+    closed: bool,
+}
+
+impl FeatureCollectionBody {
+    pub fn new(features: Vec<MapFeature>) -> Self {
+        FeatureCollectionBody { features: features.into_iter(), opened: false, written: 0, closed: false }
+    }
+
+    fn next_chunk(&mut self) -> Result<Option<Bytes>, serde_json::Error> {
+        if !self.opened {
+            self.opened = true;
+            return Ok(Some(Bytes::from_static(OPENING)));
+        }
+        if let Some(feature) = self.features.next() {
+            let mut chunk = if self.written == 0 { Vec::new() } else { b",".to_vec() };
+            serde_json::to_writer(&mut chunk, &feature)?;
+            self.written += 1;
+            return Ok(Some(Bytes::from(chunk)));
+        }
+        if !self.closed {
+            self.closed = true;
+            return Ok(Some(Bytes::from_static(CLOSING)));
+        }
+        Ok(None)
+    }
+}
+
+impl MessageBody for FeatureCollectionBody {
+    type Error = serde_json::Error;
+
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        Poll::Ready(self.get_mut().next_chunk().transpose())
+    }
+}
+
@@ -0,0 +1,103 @@
+// src/handlers/health.rs
+
+// Probes for load balancers and uptime monitors.
+use crate::api::muni_money::client::MunicipalMoneyClient;
+use actix_web::{web, HttpResponse};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+// A probe that takes longer than this is as good as down; monitors typically
+// time out at 5s, so both checks together stay under that.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct HealthStatus {
+    /// "ok" or "unavailable".
+    #[schema(value_type = String)]
+    pub status: &'static str,
+    /// "up" or "down".
+    #[schema(value_type = String)]
+    pub database: &'static str,
+    /// "up" or "down"; only reported by the readiness probe.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub upstream: Option<&'static str>,
+}
+
+fn up_down(up: bool) -> &'static str {
+    if up { "up" } else { "down" }
+}
+
+async fn database_is_up(pool: &PgPool) -> bool {
+    match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, sqlx::query("SELECT 1").execute(pool)).await {
+        Ok(Ok(_)) => true,
+        Ok(Err(e)) => {
+            log::warn!("Health check: database query failed: {}", e);
+            false
+        }
+        Err(_) => {
+            log::warn!("Health check: database did not answer within {:?}", HEALTH_CHECK_TIMEOUT);
+            false
+        }
+    }
+}
+
+fn respond(status: HealthStatus) -> HttpResponse {
+    if status.status == "ok" {
+        HttpResponse::Ok().json(status)
+    } else {
+        HttpResponse::ServiceUnavailable().json(status)
+    }
+}
+
+/// GET /health — liveness: the process is serving and the database answers.
+/// Deliberately ignores the Treasury API: cached data is still served while it
+/// is down, so an upstream outage must not pull every instance out of rotation.
+#[utoipa::path(
+    get,
+    path = "/health",
+    responses(
+        (status = 200, description = "Healthy", body = HealthStatus),
+        (status = 503, description = "Database unavailable", body = HealthStatus)
+    )
+)]
+pub async fn health_handler(pool: web::Data<PgPool>) -> HttpResponse {
+    let database = database_is_up(&pool).await;
+    respond(HealthStatus {
+        status: if database { "ok" } else { "unavailable" },
+        database: up_down(database),
+        upstream: None,
+    })
+}
+
+/// GET /health/ready — readiness: the database and the Municipal Money API
+/// both answer within the probe timeout. Checks run concurrently.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    responses(
+        (status = 200, description = "Ready", body = HealthStatus),
+        (status = 503, description = "A dependency is down", body = HealthStatus)
+    )
+)]
+pub async fn readiness_handler(
+    pool: web::Data<PgPool>,
+    api_client: web::Data<MunicipalMoneyClient>,
+) -> HttpResponse {
+    let (database, upstream) = tokio::join!(database_is_up(&pool), async {
+        match api_client.ping(HEALTH_CHECK_TIMEOUT).await {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("Health check: Treasury API unreachable: {}", e);
+                false
+            }
+        }
+    });
+    respond(HealthStatus {
+        status: if database && upstream { "ok" } else { "unavailable" },
+        database: up_down(database),
+        upstream: Some(up_down(upstream)),
+    })
+}
@@ -0,0 +1,17 @@
+// src/handlers/metrics.rs
+
+// Prometheus scrape target; see `crate::metrics` for the exported series.
+use crate::metrics::metrics;
+use actix_web::HttpResponse;
+
+/// Prometheus metrics.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses((status = 200, description = "Text exposition format 0.0.4", body = String, content_type = "text/plain"))
+)]
+pub async fn metrics_handler() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(metrics().render())
+}
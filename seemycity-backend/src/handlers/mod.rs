@@ -1 +1,13 @@
+pub mod admin;
+pub mod conditional;
+pub mod csv_stream;
+pub mod docs;
+pub mod export;
+pub mod feature_stream;
+pub mod health;
+pub mod metrics;
 pub mod municipalities;
+pub mod provinces;
+pub mod rankings;
+pub mod scoring;
+pub mod statistics;
\ No newline at end of file
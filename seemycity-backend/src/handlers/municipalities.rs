@@ -1,505 +1,1429 @@
-use actix_web::{get, web, HttpResponse};
-use chrono::{Datelike, Duration, Utc};
-use serde::Deserialize;
-use crate::api::muni_money::audit::get_audit_outcome;
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use crate::api::muni_money::client::MunicipalMoneyClient;
-use crate::api::muni_money::financials::{
-    get_capital_expenditure, get_repairs_maintenance, get_revenue_and_expenditure, get_total_debt,
-    get_uifw_total, IncexpFigures,
+use crate::api::muni_money::financials::BreakdownKind;
+use crate::embargo::Embargo;
+use crate::db::financials::{
+    get_all_financial_years_db, get_available_years, get_pillar_statistics, get_score_percentile,
 };
-use crate::confidence::{evaluate as evaluate_confidence, ConfidenceInput, CONFIDENCE_UNRELIABLE};
-use crate::scoring::SCORE_VERSION;
-use crate::db::financials::{get_all_financial_years_db, upsert_complete_financial_record};
+use crate::db::geo::{get_geometry_for_municipality, get_nearest_municipalities};
 use crate::db::municipalities::{
-    get_all_municipality_populations, get_municipality_base_info_db,
-    get_municipalities_summary_for_map,
+    MapQueryOptions,
+    get_municipality_base_info_db,
+    get_municipalities_summary_for_map, get_municipality_summary_db, search_municipalities_by_name,
 };
+use crate::config::{Config, DEFAULT_MAP_CACHE_TTL_SECS};
 use crate::errors::AppError;
-use crate::models::{FinancialDataDb, FinancialYearData, MunicipalityDetail, MapFeatureCollection};
-use crate::scoring::{calculate_financial_score, ScoreBreakdown, ScoringInput};
+use crate::handlers::conditional::{body_etag, conditional_response};
+use crate::handlers::feature_stream::FeatureCollectionBody;
+use crate::metrics::metrics;
+use crate::models::{
+    features_extent, BatchDetailError, BatchDetailResponse, ComparedMunicipality, ComparisonResponse, FinancialDataDb,
+    FinancialYearData, MapFeature, MapFeatureCollection, MunicipalityBasicInfo, MunicipalityDb, MunicipalityDetail,
+    MunicipalityGeometryFeature, MunicipalityId, MunicipalityListPage, MunicipalityBenchmarks, PillarBenchmark,
+    PillarStatistics,
+};
+use crate::scoring::{calculate_financial_score, ScoringInput};
+use crate::services::municipality::{
+    build_and_store_detail, build_breakdown, default_breakdown_year, ensure_financials_fresh, UpstreamHealth,
+};
+use crate::services::normalization::normalization_for_year;
 use sqlx::PgPool as DbPool;
-use uuid::Uuid;
-
-/// How long a cached financial_data row (including an all-NULL negative-cache row)
-/// is trusted before the Treasury API is consulted again. Municipal figures change
-/// at most quarterly, so a week keeps us fresh without hammering the upstream.
-const CACHE_TTL_DAYS: i64 = 7;
-
-/// How many financial years to walk back looking for usable data. Audited actuals
-/// lag the calendar year by roughly one year, and some municipalities publish later.
-const YEAR_FALLBACK_DEPTH: i32 = 3;
-
-/// The map payload is ~1 MB of mostly-static geometry that is expensive to pull
-/// and simplify per request. Serve a cached copy for this long; scores changing
-/// through the detail endpoint appear on the map within this window.
-const MAP_CACHE_TTL_SECS: u64 = 60;
-
-/// After a refresh round where every upstream call failed at the transport level,
-/// skip the Treasury API for this long and serve cached data only. Prevents a
-/// degraded upstream from stalling every cold request on timeouts.
-const UPSTREAM_COOLDOWN_SECS: u64 = 300;
-
-/// Circuit breaker for the Treasury API. Shared across workers.
-#[derive(Default)]
-pub struct UpstreamHealth {
-    down_until: std::sync::RwLock<Option<std::time::Instant>>,
-}
-
-impl UpstreamHealth {
-    fn is_up(&self) -> bool {
-        match self.down_until.read() {
-            Ok(guard) => guard.is_none_or(|t| std::time::Instant::now() >= t),
-            Err(_) => true,
-        }
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use utoipa::{IntoParams, ToSchema};
+
+/// Distinct map queries kept in memory. Viewport panning produces a new bbox
+/// key per request, so the oldest entry is evicted past this.
+const MAP_CACHE_MAX_ENTRIES: usize = 64;
+
+/// `cache` label of the map cache in `seemycity_cache_{hits,misses}_total`.
+const MAP_CACHE_METRIC: &str = "map";
+
+/// A serialized map FeatureCollection (or table page) with its validators,
+/// computed once per build rather than per request.
+#[derive(Debug, Clone)]
+pub struct MapPayload {
+    pub body: String,
+    pub etag: String,
+    /// Newest `last_updated` across the features; None when nothing is cached.
+    pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
+    /// Table pages only: rows remain past this page (drives `Link: rel="next"`).
+    pub has_more: bool,
+}
+
+impl MapPayload {
+    pub fn new(body: String, last_modified: Option<chrono::DateTime<chrono::Utc>>) -> Self {
+        let etag = body_etag(body.as_bytes());
+        MapPayload { body, etag, last_modified, has_more: false }
+    }
+}
+
+/// A key's build lock and how many requests currently hold or await it.
+type FlightSlot = (std::sync::Arc<tokio::sync::Mutex<()>>, usize);
+
+/// In-memory cache of map FeatureCollection responses, keyed by the
+/// normalized query. Rebuilds are single-flight per key: concurrent misses for
+/// the same query wait for the first one's result instead of each running the
+/// PostGIS query.
+pub struct MapResponseCache {
+    ttl: std::time::Duration,
+    entries: std::sync::RwLock<HashMap<String, (std::time::Instant, MapPayload)>>,
+    /// Build slot per key with a build in progress.
+    in_flight: std::sync::Mutex<HashMap<String, FlightSlot>>,
+}
+
+/// One request's claim on a key's build lock. Joining and leaving both happen
+/// under the `in_flight` lock, and the last request out removes the entry, so
+/// a request arriving mid-build always waits on the same lock as the others.
+struct MapFlight<'a> {
+    cache: &'a MapResponseCache,
+    key: String,
+    lock: std::sync::Arc<tokio::sync::Mutex<()>>,
+}
+
+impl<'a> MapFlight<'a> {
+    fn join(cache: &'a MapResponseCache, key: &str) -> Option<Self> {
+        let mut flights = cache.in_flight.lock().ok()?;
+        let (lock, holders) = flights.entry(key.to_string()).or_default();
+        *holders += 1;
+        Some(MapFlight { cache, key: key.to_string(), lock: lock.clone() })
     }
+}
 
-    fn mark_down(&self) {
-        if let Ok(mut guard) = self.down_until.write() {
-            *guard = Some(std::time::Instant::now() + std::time::Duration::from_secs(UPSTREAM_COOLDOWN_SECS));
+impl Drop for MapFlight<'_> {
+    fn drop(&mut self) {
+        let Ok(mut flights) = self.cache.in_flight.lock() else { return };
+        if let Some((_, holders)) = flights.get_mut(&self.key) {
+            *holders -= 1;
+            if *holders == 0 {
+                flights.remove(&self.key);
+            }
         }
-        log::warn!(
-            "Treasury API unreachable — skipping upstream fetches for {}s, serving cached data only",
-            UPSTREAM_COOLDOWN_SECS
-        );
     }
 }
 
-/// In-memory cache for the full (no-limit) map FeatureCollection response body.
-#[derive(Default)]
-pub struct MapResponseCache {
-    inner: std::sync::RwLock<Option<(std::time::Instant, String)>>,
+impl Default for MapResponseCache {
+    fn default() -> Self {
+        Self::new(std::time::Duration::from_secs(DEFAULT_MAP_CACHE_TTL_SECS))
+    }
+}
+
+/// Admission limit for the detail endpoint, whose cold path fans out to the
+/// Treasury API and upserts rows. Past the limit, requests are shed with 503 +
+/// Retry-After rather than piling up. Cached endpoints (map, summary) are not
+/// limited. Shared across workers.
+pub struct DetailAdmission {
+    permits: tokio::sync::Semaphore,
+}
+
+impl DetailAdmission {
+    /// `max_concurrent` = 0 disables the limit.
+    pub fn new(max_concurrent: usize) -> Self {
+        let permits = if max_concurrent == 0 { tokio::sync::Semaphore::MAX_PERMITS } else { max_concurrent };
+        DetailAdmission { permits: tokio::sync::Semaphore::new(permits) }
+    }
+
+    /// Takes a slot for the lifetime of the returned permit, or fails
+    /// immediately with `ServiceUnavailable` when all slots are taken.
+    pub fn try_admit(&self) -> Result<tokio::sync::SemaphorePermit<'_>, AppError> {
+        self.permits.try_acquire().map_err(|_| {
+            log::warn!("Detail admission limit reached; shedding request");
+            AppError::ServiceUnavailable("The server is busy; please retry shortly.".to_string())
+        })
+    }
 }
 
 impl MapResponseCache {
-    fn get_fresh(&self) -> Option<String> {
-        let guard = self.inner.read().ok()?;
-        let (created, body) = guard.as_ref()?;
-        (created.elapsed().as_secs() < MAP_CACHE_TTL_SECS).then(|| body.clone())
+    /// A zero `ttl` disables caching: every request queries the database.
+    pub fn new(ttl: std::time::Duration) -> Self {
+        MapResponseCache {
+            ttl,
+            entries: std::sync::RwLock::new(HashMap::new()),
+            in_flight: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn ttl(&self) -> std::time::Duration {
+        self.ttl
+    }
+
+    fn get_fresh(&self, key: &str) -> Option<MapPayload> {
+        let guard = self.entries.read().ok()?;
+        let (created, payload) = guard.get(key)?;
+        (created.elapsed() < self.ttl).then(|| payload.clone())
+    }
+
+    fn store(&self, key: String, payload: MapPayload) {
+        let Ok(mut guard) = self.entries.write() else { return };
+        guard.retain(|_, (created, _)| created.elapsed() < self.ttl);
+        if guard.len() >= MAP_CACHE_MAX_ENTRIES
+            && !guard.contains_key(&key)
+            && let Some(oldest) = guard.iter().min_by_key(|(_, (created, _))| *created).map(|(k, _)| k.clone())
+        {
+            guard.remove(&oldest);
+        }
+        guard.insert(key, (std::time::Instant::now(), payload));
+    }
+
+    /// Returns the cached payload for `key`, or runs `build` and caches its
+    /// result. Only one `build` per key runs at a time; requests arriving
+    /// meanwhile wait and then read what it stored. Errors are not cached.
+    pub async fn get_or_build<F, Fut>(&self, key: String, build: F) -> Result<MapPayload, AppError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<MapPayload, AppError>>,
+    {
+        if self.ttl.is_zero() {
+            return build().await;
+        }
+        if let Some(payload) = self.get_fresh(&key) {
+            log::debug!("Serving /api/municipalities from in-memory cache");
+            metrics().record_cache(MAP_CACHE_METRIC, true);
+            return Ok(payload);
+        }
+
+        let Some(flight) = MapFlight::join(self, &key) else {
+            return build().await;
+        };
+        let _turn = flight.lock.lock().await;
+        // Whoever held the lock before us has likely just filled the entry.
+        if let Some(payload) = self.get_fresh(&key) {
+            metrics().record_cache(MAP_CACHE_METRIC, true);
+            return Ok(payload);
+        }
+        metrics().record_cache(MAP_CACHE_METRIC, false);
+        let result = build().await;
+        if let Ok(payload) = &result {
+            self.store(key, payload.clone());
+        }
+        result
+    }
+
+    /// Drops every cached payload so the next requests rebuild them, e.g. after
+    /// the embargo list changes. Returns how many entries were dropped.
+    pub fn invalidate(&self) -> usize {
+        match self.entries.write() {
+            Ok(mut guard) => {
+                let dropped = guard.len();
+                guard.clear();
+                dropped
+            }
+            Err(_) => 0,
+        }
+    }
+}
+
+/// 404 for a municipality (or year) on the embargo list. Worded differently
+/// from "not found" so clients can tell withheld data from a bad id.
+fn withheld(muni_id: &str) -> AppError {
+    AppError::NotFound(format!(
+        "Data for municipality {muni_id} is temporarily withheld pending a data-quality review"
+    ))
+}
+
+// Query parameters for the detail endpoint
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DetailQuery {
+    /// Restrict `financials` to one financial year (refreshing it if needed).
+    year: Option<i32>,
+    /// Restrict `financials` to several years, comma-separated
+    /// (`2020,2021,2022`); the missing or expired ones are refreshed
+    /// concurrently. Not combined with `year`.
+    #[param(example = "2020,2021,2022")]
+    years: Option<String>,
+    /// Attach each year's full score derivation (`score_audit`). Opt-in: it
+    /// roughly triples the payload.
+    #[serde(default)]
+    audit: bool,
+}
+
+/// Most years one `?years=` list may name. Each uncached year costs a full
+/// round of cube requests.
+pub const DETAIL_MAX_YEARS: usize = 6;
+
+impl DetailQuery {
+    /// The years `financials` is restricted to: `year`, or the `years` list
+    /// (duplicates dropped, order kept); empty for all years. Setting both, a
+    /// malformed or over-long list, or a year outside `requestable` is a 400.
+    fn selected_years(&self, requestable: &RangeInclusive<i32>) -> Result<Vec<i32>, AppError> {
+        let years = match (self.year, self.years.as_deref()) {
+            (Some(_), Some(_)) => {
+                return Err(AppError::BadRequest("Pass either year or years, not both".to_string()));
+            }
+            (Some(year), None) => vec![year],
+            (None, Some(list)) => parse_year_list(list)?,
+            (None, None) => Vec::new(),
+        };
+        for &year in &years {
+            validate_requested_year(Some(year), requestable)?;
+        }
+        Ok(years)
     }
+}
 
-    fn store(&self, body: String) {
-        if let Ok(mut guard) = self.inner.write() {
-            *guard = Some((std::time::Instant::now(), body));
+/// Parses a `?years=` list such as `2021,2022`. The entries are counted
+/// before anything is parsed or deduplicated, so an oversized list is
+/// rejected cheaply, even when it only repeats years.
+fn parse_year_list(list: &str) -> Result<Vec<i32>, AppError> {
+    let count = list.split(',').count();
+    if count > DETAIL_MAX_YEARS {
+        return Err(AppError::BadRequest(format!("At most {DETAIL_MAX_YEARS} years per request; got {count}")));
+    }
+    let mut years = Vec::with_capacity(count);
+    for part in list.split(',').map(str::trim) {
+        let year: i32 = part.parse().map_err(|_| {
+            AppError::BadRequest(format!("Invalid year '{part}' in years; expected e.g. years=2021,2022"))
+        })?;
+        if !years.contains(&year) {
+            years.push(year);
         }
     }
+    Ok(years)
 }
 
-// Replace the existing function with this one:
-// Handler to get details for a single municipality by ID
+/// Rejects financial years outside the configured requestable range
+/// (`Config::requestable_years`). Later years cannot have data yet and
+/// earlier ones predate the cubes, so asking the Treasury API would only burn
+/// requests on guaranteed-empty responses.
+fn validate_requested_year(year: Option<i32>, requestable: &RangeInclusive<i32>) -> Result<(), AppError> {
+    match year {
+        Some(y) if y > *requestable.end() => Err(AppError::BadRequest(format!(
+            "No data is available for financial year {y}; the latest available year is {}",
+            requestable.end()
+        ))),
+        Some(y) if y < *requestable.start() => Err(AppError::BadRequest(format!(
+            "No data is available for financial year {y}; the earliest available year is {}",
+            requestable.start()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// 404 for an embargoed municipality, or an embargoed `year` of it. Checked
+/// before touching the DB or the upstream.
+fn check_embargo(embargo: &Embargo, muni_id: &str, year: Option<i32>) -> Result<(), AppError> {
+    if embargo.blocks_municipality(muni_id) || year.is_some_and(|y| embargo.blocks_year(muni_id, y)) {
+        return Err(withheld(muni_id));
+    }
+    Ok(())
+}
+
+/// Municipality detail with every cached financial year.
+#[utoipa::path(
+    get,
+    path = "/api/municipalities/{id}",
+    params(("id" = String, Path, description = "Municipality code, e.g. CPT (case-insensitive)"), DetailQuery),
+    responses(
+        (status = 200, description = "Municipality detail", body = MunicipalityDetail),
+        (status = 400, description = "Malformed id or years list, or a year outside the available range", body = ErrorResponse),
+        (status = 404, description = "Unknown or withheld municipality", body = ErrorResponse),
+        (status = 502, description = "Upstream data source unavailable", body = ErrorResponse),
+        (status = 503, description = "Busy; retry after the Retry-After delay", body = ErrorResponse),
+        (status = 504, description = "Upstream data source timed out", body = ErrorResponse)
+    )
+)]
+#[allow(clippy::too_many_arguments)] // actix extractors, one per shared resource
 pub async fn get_municipality_detail_handler(
+    req: HttpRequest,
     path: web::Path<String>,
+    query: web::Query<DetailQuery>,
     pool: web::Data<DbPool>,
     api_client: web::Data<MunicipalMoneyClient>,
     upstream_health: web::Data<UpstreamHealth>,
+    admission: web::Data<DetailAdmission>,
+    embargo: web::Data<Embargo>,
+    config: web::Data<Config>,
 ) -> Result<HttpResponse, AppError> {
-    let muni_id_str = path.into_inner();
+    // Checked before touching the DB or the upstream.
+    let muni_id = MunicipalityId::try_from(path.into_inner())?;
+    let muni_id_str = muni_id.as_str();
     log::info!("START: Handling request for /api/municipalities/{}", muni_id_str);
 
-    // Fetch base municipality info
-    let base_info = get_municipality_base_info_db(&pool, &muni_id_str).await?;
-    let base_info_unwrapped = base_info.ok_or_else(|| {
-        log::warn!("Municipality base info not found for ID: {}", muni_id_str);
-        AppError::NotFound(format!("Municipality with ID {} not found", muni_id_str))
-    })?;
-    let muni_code = base_info_unwrapped.id.clone();
-    let population_opt = base_info_unwrapped.population;
-
-    let mut rows =
-        ensure_financials_fresh(&pool, &api_client, &upstream_health, &muni_code, population_opt)
-            .await?;
-
-    // All-NULL rows are cache internals, not user data; newest year first.
-    rows.sort_by(|a, b| b.year.cmp(&a.year));
-    let financials: Vec<FinancialYearData> = rows
-        .iter()
-        .filter(|r| r.has_any_data())
-        .map(FinancialYearData::from)
-        .collect();
+    let years = query.selected_years(&config.requestable_years())?;
+    check_embargo(&embargo, muni_id_str, None)?;
+    for &year in &years {
+        check_embargo(&embargo, muni_id_str, Some(year))?;
+    }
+    let _admitted = admission.try_admit()?;
 
-    // Geometry is intentionally omitted here: the detail view renders no map, and
-    // boundary polygons average ~90 KB each. The map endpoint serves geometry.
-    let response = MunicipalityDetail {
-        id: base_info_unwrapped.id,
-        name: base_info_unwrapped.name,
-        province: base_info_unwrapped.province,
-        population: base_info_unwrapped.population,
-        classification: base_info_unwrapped.classification,
-        website: base_info_unwrapped.website,
-        financials,
-        geometry: None,
-    };
+    let response = build_municipality_detail(
+        &pool,
+        &api_client,
+        &upstream_health,
+        &embargo,
+        &config,
+        DetailRequest { muni_id: muni_id_str, years: &years, audit: query.audit },
+    )
+    .await?;
 
     log::info!("END: Handling request for /api/municipalities/{}", muni_id_str);
-    Ok(HttpResponse::Ok().json(response))
+    // The body includes `last_updated` and every figure, so hashing it covers
+    // both new data and embargo or `?audit` changes.
+    let body = serde_json::to_string(&response)
+        .map_err(|e| AppError::InternalError(format!("Failed to serialize detail payload: {e}")))?;
+    let etag = body_etag(body.as_bytes());
+    let mut builder = HttpResponse::Ok();
+    builder.content_type("application/json");
+    Ok(conditional_response(&req, builder, body, &etag, response.last_updated))
+}
+
+/// The municipality a detail is built for and the request's options.
+struct DetailRequest<'a> {
+    muni_id: &'a str,
+    /// As for `build_and_store_detail`: empty for all years.
+    years: &'a [i32],
+    audit: bool,
 }
 
-/// Brings a municipality's financial-year rows up to date and returns them:
-/// walks candidate years newest-first (audited actuals lag the calendar by
-/// roughly a year) until one yields usable data, refreshing missing/expired
-/// rows from the Treasury API; then re-derives scores for every cached row so
-/// formula changes propagate without upstream calls.
-///
-/// Fresh cached rows are trusted as-is — including all-NULL negative-cache
-/// rows. When the upstream circuit breaker is open, cached (even stale) data
-/// is returned immediately. Used by both the detail handler and the
-/// background cache warmer.
-pub async fn ensure_financials_fresh(
+/// `build_and_store_detail` with embargoed years dropped from `financials`.
+/// Shared by the single and batch detail endpoints; year validation, the
+/// municipality-level embargo check and admission are the caller's job.
+async fn build_municipality_detail(
     pool: &DbPool,
     api_client: &MunicipalMoneyClient,
     upstream_health: &UpstreamHealth,
-    muni_code: &str,
-    population_opt: Option<f32>,
-) -> Result<Vec<FinancialDataDb>, AppError> {
-    let mut rows = get_all_financial_years_db(pool, muni_code).await?;
-    let now = Utc::now();
-
-    // Walk until a year yields a *scorable* row (all four pillars), not merely
-    // any data: the newest financial year often publishes figures months before
-    // its audit opinion, and stopping there would leave the municipality
-    // unscored while a complete prior year sits one step further back.
-    let newest_candidate_year = now.year() - 1;
-    for year in ((newest_candidate_year - YEAR_FALLBACK_DEPTH + 1)..=newest_candidate_year).rev() {
-        let cached_fresh_has_score = rows
-            .iter()
-            .find(|r| r.year == year)
-            .filter(|r| now - r.updated_at < Duration::days(CACHE_TTL_DAYS))
-            .map(|r| r.overall_score.is_some());
-
-        let has_score = match cached_fresh_has_score {
-            Some(has_score) => has_score,
-            None if !upstream_health.is_up() => {
-                // Circuit open: serve whatever is cached (stale included) rather
-                // than stalling the request on a known-degraded upstream.
-                log::debug!("Muni: {muni_code}, upstream cooling down; skipping refresh of {year}");
-                break;
-            }
-            None => {
-                let prior = rows.iter().find(|r| r.year == year).cloned();
-                match refresh_financial_year(pool, api_client, muni_code, year, population_opt, prior.as_ref())
+    embargo: &Embargo,
+    config: &Config,
+    request: DetailRequest<'_>,
+) -> Result<MunicipalityDetail, AppError> {
+    let mut detail = build_and_store_detail(
+        pool,
+        api_client,
+        upstream_health,
+        request.muni_id,
+        request.years,
+        request.audit,
+        config.current_financial_year(),
+        config.cache_ttl(),
+    )
+    .await?;
+    detail.financials.retain(|f| !embargo.blocks_year(&detail.id, f.year));
+    detail.has_financial_data = !detail.financials.is_empty();
+    Ok(detail)
+}
+
+/// Most ids accepted by one batch detail request; a dashboard shows far fewer.
+pub const BATCH_DETAIL_MAX_IDS: usize = 25;
+// Municipalities refreshed at once within one batch. Each cold one fires six
+// cube requests; the client's own concurrency cap still bounds the total.
+const BATCH_DETAIL_CONCURRENCY: usize = 4;
+
+// Body of the batch detail endpoint
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct BatchDetailRequest {
+    /// Municipality codes, at most `BATCH_DETAIL_MAX_IDS`.
+    #[schema(max_items = 25)]
+    ids: Vec<String>,
+    /// Restrict every municipality's `financials` to one year, as `?year=` does.
+    year: Option<i32>,
+}
+
+/// Several municipalities' details in one call, for comparison and dashboard
+/// views. Each id goes through the same flow as the detail endpoint, a few at
+/// a time, under a single admission slot. Per-id failures (unknown, withheld,
+/// upstream errors) land in `errors` instead of failing the batch; only a bad
+/// request as a whole (too many ids, a future year) is rejected outright.
+#[utoipa::path(
+    post,
+    path = "/api/municipalities/batch",
+    request_body = BatchDetailRequest,
+    responses(
+        (status = 200, description = "Found municipalities and per-id errors", body = BatchDetailResponse),
+        (status = 400, description = "Too many ids or a year outside the available range", body = ErrorResponse),
+        (status = 503, description = "Busy; retry after the Retry-After delay", body = ErrorResponse)
+    )
+)]
+pub async fn batch_municipality_detail_handler(
+    body: web::Json<BatchDetailRequest>,
+    pool: web::Data<DbPool>,
+    api_client: web::Data<MunicipalMoneyClient>,
+    upstream_health: web::Data<UpstreamHealth>,
+    admission: web::Data<DetailAdmission>,
+    embargo: web::Data<Embargo>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let BatchDetailRequest { mut ids, year } = body.into_inner();
+    log::info!("START: Handling batch detail for {} ids (year: {:?})", ids.len(), year);
+
+    validate_requested_year(year, &config.requestable_years())?;
+    let mut seen = std::collections::HashSet::new();
+    ids.retain(|id| seen.insert(id.clone()));
+    if ids.len() > BATCH_DETAIL_MAX_IDS {
+        return Err(AppError::BadRequest(format!(
+            "At most {BATCH_DETAIL_MAX_IDS} ids per batch; got {}",
+            ids.len()
+        )));
+    }
+    let _admitted = admission.try_admit()?;
+
+    let limiter = std::sync::Arc::new(tokio::sync::Semaphore::new(BATCH_DETAIL_CONCURRENCY));
+    let mut tasks = tokio::task::JoinSet::new();
+    for id in ids {
+        let (pool, api_client, upstream_health) = (pool.clone(), api_client.clone(), upstream_health.clone());
+        let (embargo, config, limiter) = (embargo.clone(), config.clone(), limiter.clone());
+        tasks.spawn(crate::request_id::inherit(async move {
+            let _permit = limiter.acquire_owned().await.expect("batch limiter is never closed");
+            let result = match check_embargo(&embargo, &id, year) {
+                Ok(()) => {
+                    build_municipality_detail(
+                        &pool,
+                        &api_client,
+                        &upstream_health,
+                        &embargo,
+                        &config,
+                        DetailRequest { muni_id: &id, years: year.as_slice(), audit: false },
+                    )
                     .await
-                {
-                    Some(refreshed) => {
-                        let has_score = refreshed.overall_score.is_some();
-                        rows.retain(|r| r.year != year);
-                        rows.push(refreshed);
-                        has_score
-                    }
-                    None => {
-                        // Every upstream call failed at the transport level. Not
-                        // the same as "no data" — nothing is persisted, and we
-                        // stop trying older years against a dead upstream.
-                        upstream_health.mark_down();
-                        break;
-                    }
                 }
-            }
-        };
+                Err(e) => Err(e),
+            };
+            (id, result)
+        }));
+    }
 
-        if has_score {
-            break;
+    let mut response = BatchDetailResponse::default();
+    while let Some(joined) = tasks.join_next().await {
+        let (id, result) = joined.map_err(|e| AppError::InternalError(format!("Batch detail task failed: {e}")))?;
+        match result {
+            Ok(detail) => {
+                response.municipalities.insert(id, detail);
+            }
+            Err(e) => {
+                log::warn!("Batch detail: {} failed: {}", id, e);
+                response.errors.insert(id, BatchDetailError { error: e.client_message(), code: e.code().to_string() });
+            }
         }
     }
 
-    // Scores are pure derivations of the stored raw metrics, so recompute them for
-    // every cached row and heal any that disagree with the current formula. This
-    // propagates scoring-rubric changes to historical years (and to the map, which
-    // reads persisted scores) lazily, without any Treasury API calls.
-    for row in rows.iter_mut() {
-        if !row.has_any_data() {
-            continue;
+    log::info!(
+        "END: Batch detail with {} results and {} errors",
+        response.municipalities.len(),
+        response.errors.len()
+    );
+    Ok(HttpResponse::Ok().json(response))
+}
+
+// Query parameters for the comparison endpoint
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CompareQuery {
+    /// First municipality code.
+    a: String,
+    /// Second municipality code; `deltas` are a - b.
+    b: String,
+    /// Financial year to compare; defaults to the latest year both have data for.
+    year: Option<i32>,
+}
+
+/// Two municipalities side by side for one financial year: each one's figures
+/// and score breakdown, plus per-pillar deltas (a - b). Both are refreshed
+/// through the same cache flow as the detail endpoint, concurrently, under a
+/// single admission slot.
+#[utoipa::path(
+    get,
+    path = "/api/municipalities/compare",
+    params(CompareQuery),
+    responses(
+        (status = 200, description = "Comparison", body = ComparisonResponse),
+        (status = 400, description = "Malformed id, the same id twice, or a year outside the available range", body = ErrorResponse),
+        (status = 404, description = "Unknown municipality or no common year", body = ErrorResponse)
+    )
+)]
+pub async fn compare_municipalities_handler(
+    query: web::Query<CompareQuery>,
+    pool: web::Data<DbPool>,
+    api_client: web::Data<MunicipalMoneyClient>,
+    upstream_health: web::Data<UpstreamHealth>,
+    admission: web::Data<DetailAdmission>,
+    embargo: web::Data<Embargo>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let CompareQuery { a, b, year } = query.into_inner();
+    log::info!("START: Handling comparison of {} and {} (year: {:?})", a, b, year);
+
+    // Checked before touching the DB or the upstream. The same id twice would
+    // refresh one municipality in two concurrent flows.
+    let (a, b) = (MunicipalityId::try_from(a)?, MunicipalityId::try_from(b)?);
+    if a.as_str().eq_ignore_ascii_case(b.as_str()) {
+        return Err(AppError::BadRequest(format!("Compare two different municipalities; got {} twice", a.as_str())));
+    }
+    let (a, b) = (a.as_str(), b.as_str());
+    validate_requested_year(year, &config.requestable_years())?;
+    for id in [a, b] {
+        check_embargo(&embargo, id, year)?;
+    }
+    let _admitted = admission.try_admit()?;
+
+    let (info_a, info_b) = tokio::join!(
+        get_municipality_base_info_db(&pool, a),
+        get_municipality_base_info_db(&pool, b)
+    );
+    let not_found = |id: &str| AppError::NotFound(format!("Municipality with ID {} not found", id));
+    let info_a = info_a?.ok_or_else(|| not_found(a))?;
+    let info_b = info_b?.ok_or_else(|| not_found(b))?;
+
+    let (rows_a, rows_b) = tokio::join!(
+        ensure_financials_fresh(
+            &pool,
+            &api_client,
+            &upstream_health,
+            &info_a.id,
+            info_a.population,
+            info_a.classification.as_deref(),
+            year.as_slice(),
+            config.current_financial_year(),
+            config.cache_ttl(),
+        ),
+        ensure_financials_fresh(
+            &pool,
+            &api_client,
+            &upstream_health,
+            &info_b.id,
+            info_b.population,
+            info_b.classification.as_deref(),
+            year.as_slice(),
+            config.current_financial_year(),
+            config.cache_ttl(),
+        )
+    );
+    let (rows_a, rows_b) = (rows_a?, rows_b?);
+
+    // Years each side can show: real data, not embargoed.
+    let usable = |info: &MunicipalityDb, rows: &[FinancialDataDb]| -> Vec<i32> {
+        rows.iter()
+            .filter(|r| r.has_any_data() && !embargo.blocks_year(&info.id, r.year))
+            .map(|r| r.year)
+            .collect()
+    };
+    let (years_a, years_b) = (usable(&info_a, &rows_a), usable(&info_b, &rows_b));
+    let year = match year {
+        Some(y) => y,
+        None => years_a.iter().copied().filter(|y| years_b.contains(y)).max().ok_or_else(|| {
+            AppError::NotFound(format!("{} and {} have no financial year with data in common", info_a.id, info_b.id))
+        })?,
+    };
+    for (info, years) in [(&info_a, &years_a), (&info_b, &years_b)] {
+        if !years.contains(&year) {
+            return Err(AppError::NotFound(format!("Municipality {} has no data for {}", info.id, year)));
         }
-        // Confidence backfill from stored values for rows never evaluated.
-        // A grade set at fetch time (which may reflect the revenue checksum)
-        // is kept as-is.
-        let (confidence, confidence_notes) = if row.data_confidence.is_none() {
-            let grade = evaluate_confidence(&ConfidenceInput {
-                revenue: row.revenue,
-                operational_expenditure: row.operational_expenditure,
-                capital_expenditure: row.capital_expenditure,
-                debt: row.debt,
-                population: population_opt.map(|p| p as u32),
-                revenue_checksum: None,
-            });
-            (Some(grade.grade.to_string()), grade.notes)
-        } else {
-            (row.data_confidence.clone(), row.confidence_notes.clone())
+    }
+
+    let normalization = normalization_for_year(&pool, year).await;
+    let side = |info: MunicipalityDb, rows: &[FinancialDataDb]| -> ComparedMunicipality {
+        let row = rows.iter().find(|r| r.year == year).expect("year checked above");
+        let input = ScoringInput {
+            normalization: normalization.clone(),
+            ..ScoringInput::from_db_record(row, info.population, info.classification.as_deref())
         };
-        let data_unreliable = confidence.as_deref() == Some(CONFIDENCE_UNRELIABLE);
-        let breakdown = calculate_financial_score(&ScoringInput {
+        ComparedMunicipality {
+            financials: FinancialYearData::from(row).with_per_capita(info.population),
+            breakdown: calculate_financial_score(&input),
+            id: info.id,
+            name: info.name,
+            province: info.province,
+            population: info.population,
+            classification: info.classification,
+        }
+    };
+    let (a, b) = (side(info_a, &rows_a), side(info_b, &rows_b));
+    let deltas = a.breakdown.deltas(&b.breakdown);
+
+    log::info!("END: Comparison of {} and {} for {}", a.id, b.id, year);
+    Ok(HttpResponse::Ok().json(ComparisonResponse { year, a, b, deltas }))
+}
+
+/// Lightweight popup payload for one municipality. Served entirely from the
+/// DB cache — no Treasury API client is involved, so it stays fast even on a
+/// cold or degraded upstream.
+#[utoipa::path(
+    get,
+    path = "/api/municipalities/{id}/summary",
+    params(("id" = String, Path, description = "Municipality code, e.g. CPT (case-insensitive)")),
+    responses(
+        (status = 200, description = "Summary", body = MunicipalitySummary),
+        (status = 400, description = "Malformed id", body = ErrorResponse),
+        (status = 404, description = "Unknown or withheld municipality", body = ErrorResponse)
+    )
+)]
+pub async fn get_municipality_summary_handler(
+    path: web::Path<String>,
+    pool: web::Data<DbPool>,
+    embargo: web::Data<Embargo>,
+) -> Result<HttpResponse, AppError> {
+    let muni_id = MunicipalityId::try_from(path.into_inner())?;
+    let muni_id_str = muni_id.as_str();
+    log::debug!("Handling request for /api/municipalities/{}/summary", muni_id_str);
+
+    if embargo.blocks_municipality(muni_id_str) {
+        return Err(withheld(muni_id_str));
+    }
+    let mut summary = get_municipality_summary_db(&pool, muni_id_str)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Municipality with ID {} not found", muni_id_str)))?;
+    // The popup shows one year; if that year is withheld, show no score.
+    if summary.year.is_some_and(|y| embargo.blocks_year(&summary.id, y)) {
+        summary.year = None;
+        summary.overall_score = None;
+        summary.audit_outcome = None;
+    }
+
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+/// One municipality's full boundary as a GeoJSON Feature, so map clients can
+/// load it lazily instead of from the detail payload. 404 for unknown or
+/// embargoed ids and for municipalities with no stored boundary.
+#[utoipa::path(
+    get,
+    path = "/api/municipalities/{id}/geometry",
+    params(("id" = String, Path, description = "Municipality code, e.g. CPT (case-insensitive)")),
+    responses(
+        (status = 200, description = "Feature", body = MunicipalityGeometryFeature, content_type = "application/geo+json"),
+        (status = 400, description = "Malformed id", body = ErrorResponse),
+        (status = 404, description = "Unknown or withheld municipality, or no stored boundary", body = ErrorResponse)
+    )
+)]
+pub async fn get_municipality_geometry_handler(
+    req: HttpRequest,
+    path: web::Path<String>,
+    pool: web::Data<DbPool>,
+    embargo: web::Data<Embargo>,
+) -> Result<HttpResponse, AppError> {
+    let muni_id = MunicipalityId::try_from(path.into_inner())?;
+    let muni_id_str = muni_id.as_str();
+    log::debug!("Handling request for /api/municipalities/{}/geometry", muni_id_str);
+
+    if embargo.blocks_municipality(muni_id_str) {
+        return Err(withheld(muni_id_str));
+    }
+    let base_info = get_municipality_base_info_db(&pool, muni_id_str)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Municipality with ID {} not found", muni_id_str)))?;
+    let geometry = get_geometry_for_municipality(&pool, &base_info.id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("No boundary is stored for municipality {}", base_info.id)))?;
+
+    let feature = MunicipalityGeometryFeature {
+        feature_type: "Feature".to_string(),
+        geometry,
+        properties: MunicipalityBasicInfo {
+            id: base_info.id,
+            name: base_info.name,
+            province: base_info.province,
+            classification: base_info.classification,
+        },
+    };
+    // Boundaries only change with an ETL run, so the ETag alone is enough.
+    let body = serde_json::to_string(&feature)
+        .map_err(|e| AppError::InternalError(format!("Failed to serialize geometry payload: {e}")))?;
+    let etag = body_etag(body.as_bytes());
+    let mut builder = HttpResponse::Ok();
+    builder.content_type("application/geo+json");
+    Ok(conditional_response(&req, builder, body, &etag, None))
+}
+
+/// One CSV row. Decimals use their own `Serialize` (a plain decimal string),
+/// not the f64 conversion the JSON endpoints use, so figures keep full precision.
+#[derive(Serialize)]
+struct FinancialCsvRow<'a> {
+    year: i32,
+    revenue: Option<Decimal>,
+    operational_expenditure: Option<Decimal>,
+    capital_expenditure: Option<Decimal>,
+    debt: Option<Decimal>,
+    audit_outcome: Option<&'a str>,
+    overall_score: Option<Decimal>,
+    financial_health_score: Option<Decimal>,
+    infrastructure_score: Option<Decimal>,
+    efficiency_score: Option<Decimal>,
+    accountability_score: Option<Decimal>,
+    liquidity_score: Option<Decimal>,
+}
+
+impl<'a> From<&'a FinancialDataDb> for FinancialCsvRow<'a> {
+    fn from(row: &'a FinancialDataDb) -> Self {
+        FinancialCsvRow {
+            year: row.year,
             revenue: row.revenue,
             operational_expenditure: row.operational_expenditure,
             capital_expenditure: row.capital_expenditure,
             debt: row.debt,
-            audit_outcome: row.audit_outcome.clone(),
-            population: population_opt.map(|p| p as u32),
-            transfers_operational: row.transfers_operational,
-            uifw_expenditure: row.uifw_expenditure,
-            repairs_maintenance: row.repairs_maintenance,
-            data_unreliable,
-        });
-        let up_to_date = row.score_version == Some(SCORE_VERSION)
-            && row.overall_score == breakdown.overall_score
-            && row.financial_health_score == breakdown.financial_health_score
-            && row.infrastructure_score == breakdown.infrastructure_score
-            && row.efficiency_score == breakdown.efficiency_score
-            && row.accountability_score == breakdown.accountability_score
-            && row.data_confidence == confidence;
-        if up_to_date {
-            continue;
-        }
-        log::info!(
-            "Muni: {}, healing year {} (score_version {:?} -> {})",
-            muni_code, row.year, row.score_version, SCORE_VERSION
-        );
-        row.overall_score = breakdown.overall_score;
-        row.financial_health_score = breakdown.financial_health_score;
-        row.infrastructure_score = breakdown.infrastructure_score;
-        row.efficiency_score = breakdown.efficiency_score;
-        row.accountability_score = breakdown.accountability_score;
-        row.data_confidence = confidence;
-        row.confidence_notes = confidence_notes;
-        row.score_version = Some(SCORE_VERSION);
-        if let Err(e) = upsert_complete_financial_record(pool, row).await {
-            log::error!("Muni: {}, failed to persist healed scores for {}: {}", muni_code, row.year, e);
+            audit_outcome: row.audit_outcome.as_deref(),
+            overall_score: row.overall_score,
+            financial_health_score: row.financial_health_score,
+            infrastructure_score: row.infrastructure_score,
+            efficiency_score: row.efficiency_score,
+            accountability_score: row.accountability_score,
+            liquidity_score: row.liquidity_score,
         }
     }
+}
+
+/// Every cached financial year of one municipality as CSV, oldest first, for
+/// spreadsheets. Served from the DB cache only; embargoed years are left out
+/// and an embargoed municipality is withheld.
+#[utoipa::path(
+    get,
+    path = "/api/municipalities/{id}/export.csv",
+    params(("id" = String, Path, description = "Municipality code, e.g. CPT (case-insensitive)")),
+    responses(
+        (status = 200, description = "CSV attachment", body = String, content_type = "text/csv"),
+        (status = 400, description = "Malformed id", body = ErrorResponse),
+        (status = 404, description = "Unknown or withheld municipality", body = ErrorResponse)
+    )
+)]
+pub async fn export_municipality_csv_handler(
+    path: web::Path<String>,
+    pool: web::Data<DbPool>,
+    embargo: web::Data<Embargo>,
+) -> Result<HttpResponse, AppError> {
+    let muni_id = MunicipalityId::try_from(path.into_inner())?;
+    let muni_id_str = muni_id.as_str();
+    log::debug!("Handling request for /api/municipalities/{}/export.csv", muni_id_str);
+
+    if embargo.blocks_municipality(muni_id_str) {
+        return Err(withheld(muni_id_str));
+    }
+    let base_info = get_municipality_base_info_db(&pool, muni_id_str)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Municipality with ID {} not found", muni_id_str)))?;
+
+    let mut rows = get_all_financial_years_db(&pool, &base_info.id).await?;
+    rows.sort_by_key(|r| r.year);
 
-    Ok(rows)
+    // Every row has the same columns, so the header is written even without rows.
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+    let header = [
+        "year",
+        "revenue",
+        "operational_expenditure",
+        "capital_expenditure",
+        "debt",
+        "audit_outcome",
+        "overall_score",
+        "financial_health_score",
+        "infrastructure_score",
+        "efficiency_score",
+        "accountability_score",
+        "liquidity_score",
+    ];
+    let csv_error = |e: csv::Error| AppError::InternalError(format!("Failed to write CSV: {e}"));
+    writer.write_record(header).map_err(csv_error)?;
+    for row in rows
+        .iter()
+        .filter(|r| r.has_any_data() && !embargo.blocks_year(&base_info.id, r.year))
+    {
+        writer.serialize(FinancialCsvRow::from(row)).map_err(csv_error)?;
+    }
+    let body = writer
+        .into_inner()
+        .map_err(|e| AppError::InternalError(format!("Failed to write CSV: {e}")))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header((
+            actix_web::http::header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}-financials.csv\"", base_info.id),
+        ))
+        .body(body))
 }
 
-/// Warms the score cache for every municipality so the map is fully colored
-/// without depending on detail-page traffic. Fresh rows are skipped by the
-/// cache logic, so repeat runs are cheap; the run aborts early if the Treasury
-/// API circuit breaker opens.
-pub async fn warm_all_municipalities(
-    pool: &DbPool,
-    api_client: &MunicipalMoneyClient,
-    upstream_health: &UpstreamHealth,
-) {
-    let munis = match get_all_municipality_populations(pool).await {
-        Ok(m) => m,
-        Err(e) => {
-            log::error!("Cache warmer: failed to list municipalities: {e}");
-            return;
-        }
-    };
+/// Financial years with cached data for one municipality, oldest first, for
+/// the frontend's year selector. Served from the DB cache only: an empty array
+/// means nothing has been fetched yet, 404 means the id is unknown. Embargoed
+/// years are left out.
+#[utoipa::path(
+    get,
+    path = "/api/municipalities/{id}/years",
+    params(("id" = String, Path, description = "Municipality code, e.g. CPT (case-insensitive)")),
+    responses(
+        (status = 200, description = "Cached years, oldest first", body = [FinancialYearAvailability]),
+        (status = 400, description = "Malformed id", body = ErrorResponse),
+        (status = 404, description = "Unknown or withheld municipality", body = ErrorResponse)
+    )
+)]
+pub async fn get_municipality_years_handler(
+    path: web::Path<String>,
+    pool: web::Data<DbPool>,
+    embargo: web::Data<Embargo>,
+) -> Result<HttpResponse, AppError> {
+    let muni_id = MunicipalityId::try_from(path.into_inner())?;
+    let muni_id_str = muni_id.as_str();
+    log::debug!("Handling request for /api/municipalities/{}/years", muni_id_str);
+
+    if embargo.blocks_municipality(muni_id_str) {
+        return Err(withheld(muni_id_str));
+    }
+    let base_info = get_municipality_base_info_db(&pool, muni_id_str)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Municipality with ID {} not found", muni_id_str)))?;
+
+    let mut years = get_available_years(&pool, &base_info.id).await?;
+    years.retain(|y| !embargo.blocks_year(&base_info.id, y.year));
+    Ok(HttpResponse::Ok().json(years))
+}
+
+// Query parameters for the breakdown endpoint
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BreakdownQuery {
+    /// Financial year; defaults to the municipality's newest cached year.
+    year: Option<i32>,
+    /// "revenue" or "expenditure".
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+/// The line items behind one year's revenue or operational expenditure,
+/// largest first, so citizens can see which categories dominate. Fetched live
+/// from the Treasury API's `/facts` endpoint on every request.
+#[utoipa::path(
+    get,
+    path = "/api/municipalities/{id}/breakdown",
+    params(("id" = String, Path, description = "Municipality code, e.g. CPT (case-insensitive)"), BreakdownQuery),
+    responses(
+        (status = 200, description = "Breakdown", body = FinancialBreakdown),
+        (status = 400, description = "Malformed id, unknown type or year out of range", body = ErrorResponse),
+        (status = 404, description = "Unknown or withheld municipality, or no cached year", body = ErrorResponse),
+        (status = 503, description = "Treasury API unavailable", body = ErrorResponse)
+    )
+)]
+pub async fn get_municipality_breakdown_handler(
+    path: web::Path<String>,
+    query: web::Query<BreakdownQuery>,
+    pool: web::Data<DbPool>,
+    api_client: web::Data<MunicipalMoneyClient>,
+    upstream_health: web::Data<UpstreamHealth>,
+    embargo: web::Data<Embargo>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let muni_id = MunicipalityId::try_from(path.into_inner())?;
+    let muni_id_str = muni_id.as_str();
+    log::debug!("Handling request for /api/municipalities/{}/breakdown", muni_id_str);
 
-    log::info!("Cache warmer: checking {} municipalities", munis.len());
-    let (mut scored, mut no_data) = (0u32, 0u32);
-    for (id, population) in &munis {
-        if !upstream_health.is_up() {
-            log::warn!("Cache warmer: upstream circuit open, aborting run early");
-            break;
+    let kind = match query.kind.as_str() {
+        "revenue" => BreakdownKind::Revenue,
+        "expenditure" => BreakdownKind::Expenditure,
+        other => {
+            return Err(AppError::BadRequest(format!(
+                "Unknown breakdown type '{other}'; expected 'revenue' or 'expenditure'"
+            )))
         }
-        match ensure_financials_fresh(pool, api_client, upstream_health, id, *population).await {
-            Ok(rows) if rows.iter().any(|r| r.overall_score.is_some()) => scored += 1,
-            Ok(_) => no_data += 1,
-            Err(e) => log::error!("Cache warmer: {id} failed: {e}"),
+    };
+    validate_requested_year(query.year, &config.requestable_years())?;
+    check_embargo(&embargo, muni_id_str, query.year)?;
+    // A defaulted year is resolved and checked before the live fetch, so an
+    // embargoed year never reaches the upstream.
+    let year = match query.year {
+        Some(year) => year,
+        None => {
+            let year = default_breakdown_year(&pool, muni_id_str).await?;
+            check_embargo(&embargo, muni_id_str, Some(year))?;
+            year
         }
+    };
+
+    let breakdown = build_breakdown(&pool, &api_client, &upstream_health, muni_id_str, year, kind).await?;
+    Ok(HttpResponse::Ok().json(breakdown))
+}
+
+// Query parameters for the percentile endpoint
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PercentileQuery {
+    /// Financial year to rank; defaults to the municipality's latest scored year.
+    year: Option<i32>,
+}
+
+/// Where a municipality's overall score sits nationally for one year, among
+/// the municipalities not embargoed entirely or for that year. Served from
+/// cached scores only; 404 for an unknown id or a year without a score.
+#[utoipa::path(
+    get,
+    path = "/api/municipalities/{id}/percentile",
+    params(("id" = String, Path, description = "Municipality code, e.g. CPT (case-insensitive)"), PercentileQuery),
+    responses(
+        (status = 200, description = "Percentile", body = ScorePercentile),
+        (status = 400, description = "Malformed id", body = ErrorResponse),
+        (status = 404, description = "Unknown municipality or no score for the year", body = ErrorResponse)
+    )
+)]
+pub async fn get_municipality_percentile_handler(
+    path: web::Path<String>,
+    query: web::Query<PercentileQuery>,
+    pool: web::Data<DbPool>,
+    embargo: web::Data<Embargo>,
+) -> Result<HttpResponse, AppError> {
+    let muni_id = MunicipalityId::try_from(path.into_inner())?;
+    let muni_id_str = muni_id.as_str();
+    log::debug!("Handling request for /api/municipalities/{}/percentile", muni_id_str);
+
+    if embargo.blocks_municipality(muni_id_str) {
+        return Err(withheld(muni_id_str));
     }
-    log::info!(
-        "Cache warmer: done — {scored} municipalities scored, {no_data} without data (of {})",
-        munis.len()
-    );
+    let summary = get_municipality_summary_db(&pool, muni_id_str)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Municipality with ID {} not found", muni_id_str)))?;
+    let year = query
+        .year
+        .or(summary.year)
+        .ok_or_else(|| AppError::NotFound(format!("Municipality {} has no overall score yet", summary.id)))?;
+    if embargo.blocks_year(&summary.id, year) {
+        return Err(withheld(&summary.id));
+    }
+
+    // Embargoed municipalities are not part of the population they rank in.
+    let excluded: Vec<String> = embargo
+        .blocked_municipalities()
+        .into_iter()
+        .chain(embargo.blocked_years().into_iter().filter(|(_, by)| *by == year).map(|(id, _)| id))
+        .collect();
+    let percentile = get_score_percentile(&pool, &summary.id, year, &excluded)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Municipality {} has no overall score for {}", summary.id, year)))?;
+    Ok(HttpResponse::Ok().json(percentile))
 }
 
-/// Fetches all five metrics for one municipality-year from the Treasury API
-/// (concurrently), recomputes scores, evaluates data confidence, and upserts
-/// the result — NULLs included, so the row doubles as a negative-cache marker.
-/// Individual fetch or upsert failures degrade to NULL fields rather than
-/// failing the request.
-///
-/// Returns `None` when **every** upstream call failed at the transport level:
-/// that means the Treasury API is unreachable, which must not be cached as
-/// "this year has no data".
-///
-/// When the fetch succeeds but yields **no data at all** while `prior` holds
-/// real data, the prior row is kept untouched: the Treasury API has been
-/// observed returning empty-but-successful responses while degraded, and
-/// stale-but-real beats fresh-but-empty.
-async fn refresh_financial_year(
-    pool: &DbPool,
-    api_client: &MunicipalMoneyClient,
-    muni_code: &str,
-    year: i32,
-    population: Option<f32>,
-    prior: Option<&FinancialDataDb>,
-) -> Option<FinancialDataDb> {
-    log::info!("Muni: {}, refreshing financial data for {} from Treasury API", muni_code, year);
-
-    // Revenue/opex/transfers share one incexp cube fetch; capex, debt, audit,
-    // UIFW, and repairs & maintenance each have their own cube. Six concurrent
-    // upstream calls in total.
-    let (incexp_res, capex_res, debt_res, audit_res, uifw_res, rm_res) = tokio::join!(
-        get_revenue_and_expenditure(api_client, muni_code, year),
-        get_capital_expenditure(api_client, muni_code, year),
-        get_total_debt(api_client, muni_code, year),
-        get_audit_outcome(api_client, muni_code, year),
-        get_uifw_total(api_client, muni_code, year),
-        get_repairs_maintenance(api_client, muni_code, year),
-    );
+/// A municipality's overall and pillar scores for one year beside the
+/// provincial and national averages, from cached scores only. The averages
+/// come from the statistics query (`get_pillar_statistics`), so they leave out
+/// municipalities embargoed entirely or for that year. `year` defaults to the
+/// municipality's latest scored year; 404 for an unknown id or a year without
+/// a cached row.
+#[utoipa::path(
+    get,
+    path = "/api/municipalities/{id}/benchmarks",
+    params(("id" = String, Path, description = "Municipality code, e.g. CPT (case-insensitive)"), PercentileQuery),
+    responses(
+        (status = 200, description = "Scores and averages per pillar", body = MunicipalityBenchmarks),
+        (status = 400, description = "Malformed id", body = ErrorResponse),
+        (status = 404, description = "Unknown or withheld municipality, or no data for the year", body = ErrorResponse)
+    )
+)]
+pub async fn get_municipality_benchmarks_handler(
+    path: web::Path<String>,
+    query: web::Query<PercentileQuery>,
+    pool: web::Data<DbPool>,
+    embargo: web::Data<Embargo>,
+) -> Result<HttpResponse, AppError> {
+    let muni_id = MunicipalityId::try_from(path.into_inner())?;
+    let muni_id_str = muni_id.as_str();
+    log::debug!("Handling request for /api/municipalities/{}/benchmarks", muni_id_str);
 
-    // Reachability judged on the four core cubes; UIFW/R&M are enrichments.
-    if incexp_res.is_err() && capex_res.is_err() && debt_res.is_err() && audit_res.is_err() {
-        log::error!("Muni: {muni_code}, all Treasury API calls failed for {year}; upstream unreachable");
-        return None;
-    }
-
-    let IncexpFigures { revenue, operational_expenditure, transfers_operational, revenue_checksum } =
-        incexp_res
-            .map_err(|e| log::error!("Muni: {muni_code}, Failed Revenue/Expenditure fetch for {year}: {e}"))
-            .unwrap_or_default();
-    let capital_expenditure = capex_res
-        .map_err(|e| log::error!("Muni: {muni_code}, Failed Capex fetch for {year}: {e}"))
-        .ok()
-        .flatten();
-    let debt = debt_res
-        .map_err(|e| log::error!("Muni: {muni_code}, Failed Debt fetch for {year}: {e}"))
-        .ok()
-        .flatten();
-    let audit_outcome = audit_res
-        .map_err(|e| log::error!("Muni: {muni_code}, Failed Audit fetch for {year}: {e}"))
-        .ok()
-        .flatten();
-    let uifw_expenditure = uifw_res
-        .map_err(|e| log::error!("Muni: {muni_code}, Failed UIFW fetch for {year}: {e}"))
-        .ok()
-        .flatten();
-    let repairs_maintenance = rm_res
-        .map_err(|e| log::error!("Muni: {muni_code}, Failed R&M fetch for {year}: {e}"))
-        .ok()
-        .flatten();
-
-    // Empty-but-successful responses during upstream degradation must not
-    // erase real cached data (observed 2026-07-07: 9 municipalities were
-    // wrongly negative-cached this way, including eThekwini).
-    let fetched_nothing = revenue.is_none()
-        && operational_expenditure.is_none()
-        && capital_expenditure.is_none()
-        && debt.is_none()
-        && audit_outcome.is_none();
-    if fetched_nothing {
-        if let Some(prior_row) = prior.filter(|p| p.has_any_data()) {
-            log::warn!(
-                "Muni: {muni_code}, year {year}: upstream returned no data but real data is cached — keeping the cached row (possible upstream degradation)"
-            );
-            return Some(prior_row.clone());
-        }
+    if embargo.blocks_municipality(muni_id_str) {
+        return Err(withheld(muni_id_str));
+    }
+    let summary = get_municipality_summary_db(&pool, muni_id_str)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Municipality with ID {} not found", muni_id_str)))?;
+    let year = query
+        .year
+        .or(summary.year)
+        .ok_or_else(|| AppError::NotFound(format!("Municipality {} has no overall score yet", summary.id)))?;
+    if embargo.blocks_year(&summary.id, year) {
+        return Err(withheld(&summary.id));
     }
+    let row = get_all_financial_years_db(&pool, &summary.id)
+        .await?
+        .into_iter()
+        .find(|r| r.year == year && r.has_any_data())
+        .ok_or_else(|| AppError::NotFound(format!("Municipality {} has no data for {}", summary.id, year)))?;
 
-    // Confidence first: an `unreliable` grade suppresses raw-derived pillars.
-    let grade = evaluate_confidence(&ConfidenceInput {
-        revenue,
-        operational_expenditure,
-        capital_expenditure,
-        debt,
-        population: population.map(|p| p as u32),
-        revenue_checksum,
-    });
-
-    let scoring_input = ScoringInput {
-        revenue,
-        operational_expenditure,
-        capital_expenditure,
-        debt,
-        audit_outcome: audit_outcome.clone(),
-        population: population.map(|p| p as u32),
-        transfers_operational,
-        uifw_expenditure,
-        repairs_maintenance,
-        data_unreliable: grade.grade == CONFIDENCE_UNRELIABLE,
+    let excluded: Vec<String> = embargo
+        .blocked_municipalities()
+        .into_iter()
+        .chain(embargo.blocked_years().into_iter().filter(|(_, by)| *by == year).map(|(id, _)| id))
+        .collect();
+    let (provincial, national) = tokio::try_join!(
+        get_pillar_statistics(&pool, year, &excluded, Some(&summary.province)),
+        get_pillar_statistics(&pool, year, &excluded, None)
+    )?;
+    let benchmark = |score, provincial: &PillarStatistics, national: &PillarStatistics| {
+        PillarBenchmark { score, provincial_average: provincial.average, national_average: national.average }
     };
-    let ScoreBreakdown {
-        overall_score,
-        financial_health_score,
-        infrastructure_score,
-        efficiency_score,
-        accountability_score,
-    } = calculate_financial_score(&scoring_input);
-
-    let now = Utc::now();
-    let row = FinancialDataDb {
-        id: Uuid::new_v4(), // in-memory only; an existing DB row keeps its own id
-        municipality_id: muni_code.to_string(),
+    Ok(HttpResponse::Ok().json(MunicipalityBenchmarks {
+        municipality_id: summary.id,
+        province: summary.province,
         year,
-        revenue,
-        operational_expenditure,
-        capital_expenditure,
-        debt,
-        audit_outcome,
-        transfers_operational,
-        uifw_expenditure,
-        repairs_maintenance,
-        overall_score,
-        financial_health_score,
-        infrastructure_score,
-        efficiency_score,
-        accountability_score,
-        data_confidence: Some(grade.grade.to_string()),
-        confidence_notes: grade.notes,
-        score_version: Some(SCORE_VERSION),
-        created_at: now,
-        updated_at: now,
-    };
+        overall: benchmark(row.overall_score, &provincial.overall, &national.overall),
+        financial_health: benchmark(row.financial_health_score, &provincial.financial_health, &national.financial_health),
+        infrastructure: benchmark(row.infrastructure_score, &provincial.infrastructure, &national.infrastructure),
+        efficiency: benchmark(row.efficiency_score, &provincial.efficiency, &national.efficiency),
+        accountability: benchmark(row.accountability_score, &provincial.accountability, &national.accountability),
+        liquidity: benchmark(row.liquidity_score, &provincial.liquidity, &national.liquidity),
+    }))
+}
+
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQuery {
+    /// Search text; blank returns no matches.
+    #[serde(default)]
+    q: String,
+}
+
+/// Most results a search returns.
+const MAX_SEARCH_RESULTS: i64 = 20;
+
+/// Name search for the search box: up to `MAX_SEARCH_RESULTS` matches, best
+/// first. A blank `q` returns an empty list without querying. Embargoed
+/// municipalities are left out, as on the map.
+#[utoipa::path(
+    get,
+    path = "/api/municipalities/search",
+    params(SearchQuery),
+    responses((status = 200, description = "Matches", body = [MunicipalityBasicInfo]))
+)]
+pub async fn search_municipalities_handler(
+    query: web::Query<SearchQuery>,
+    pool: web::Data<DbPool>,
+    embargo: web::Data<Embargo>,
+) -> Result<HttpResponse, AppError> {
+    let term = query.q.trim();
+    if term.is_empty() {
+        return Ok(HttpResponse::Ok().json(Vec::<MunicipalityBasicInfo>::new()));
+    }
+    let results =
+        search_municipalities_by_name(&pool, term, MAX_SEARCH_RESULTS, &embargo.blocked_municipalities()).await?;
+    Ok(HttpResponse::Ok().json(results))
+}
+
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct NearestQuery {
+    /// Longitude (WGS 84).
+    lon: f64,
+    /// Latitude (WGS 84).
+    lat: f64,
+    /// How many to return; defaults to `DEFAULT_NEAREST`, capped at `MAX_NEAREST`.
+    n: Option<i64>,
+}
+
+/// Results returned when `?n=` is omitted.
+const DEFAULT_NEAREST: i64 = 5;
+/// Larger requests are capped to this many results.
+const MAX_NEAREST: i64 = 25;
+
+fn valid_lon(v: f64) -> bool {
+    v.is_finite() && (-180.0..=180.0).contains(&v)
+}
 
-    if let Err(e) = upsert_complete_financial_record(pool, &row).await {
-        // Serve the fetched data anyway; the cache simply retries next request.
-        log::error!("Muni: {muni_code}, Failed to upsert data for {year}: {e}");
+fn valid_lat(v: f64) -> bool {
+    v.is_finite() && (-90.0..=90.0).contains(&v)
+}
+
+/// The municipalities closest to a point, nearest first, with their distance
+/// in km. Embargoed municipalities are left out, as on the map.
+#[utoipa::path(
+    get,
+    path = "/api/municipalities/nearest",
+    params(NearestQuery),
+    responses(
+        (status = 200, description = "Nearest first", body = [NearbyMunicipality]),
+        (status = 400, description = "Coordinates or n out of range", body = ErrorResponse)
+    )
+)]
+pub async fn get_nearest_municipalities_handler(
+    query: web::Query<NearestQuery>,
+    pool: web::Data<DbPool>,
+    embargo: web::Data<Embargo>,
+) -> Result<HttpResponse, AppError> {
+    let NearestQuery { lon, lat, n } = query.into_inner();
+    if !valid_lon(lon) || !valid_lat(lat) {
+        return Err(AppError::BadRequest(format!(
+            "Point ({lon}, {lat}) is outside longitude -180..180 / latitude -90..90"
+        )));
+    }
+    let n = n.unwrap_or(DEFAULT_NEAREST);
+    if n <= 0 {
+        return Err(AppError::BadRequest(format!("n must be positive, got {n}")));
     }
 
-    Some(row)
+    let nearest = get_nearest_municipalities(
+        &pool,
+        lon,
+        lat,
+        n.min(MAX_NEAREST),
+        &embargo.blocked_municipalities(),
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(nearest))
 }
 
 // --- Handler for fetching municipality list/summary (GeoJSON) ---
 
+/// Shape of the list response (`?format=`).
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ListFormat {
+    /// The map's GeoJSON FeatureCollection.
+    #[default]
+    Geojson,
+    /// One page of table rows in a `MunicipalityListPage` envelope, with
+    /// `Link` headers to the neighbouring pages.
+    Table,
+}
+
 // Define query parameters for the list endpoint
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ListQuery { 
-    limit: Option<i64>, // Optional limit parameter
+    /// Page size.
+    limit: Option<i64>,
+    /// Rows to skip, for paged table views.
+    offset: Option<i64>,
+    /// geojson (map, default) or table (paged envelope with Link headers).
+    #[serde(default)]
+    format: ListFormat,
+    /// Case-insensitive exact match.
+    province: Option<String>,
+    /// Case-insensitive exact match (A, B1..B4, C1, C2).
+    classification: Option<String>,
+    /// Inclusive bound on the latest overall score; excludes unscored municipalities.
+    min_score: Option<f64>,
+    /// Inclusive bound on the latest overall score; excludes unscored municipalities.
+    max_score: Option<f64>,
+    /// Geometry simplification tolerance in degrees; 0 = full resolution.
+    simplify: Option<f64>,
+    /// Viewport bounding box (WGS 84): all four or none.
+    min_lon: Option<f64>,
+    /// Viewport bounding box (WGS 84): all four or none.
+    min_lat: Option<f64>,
+    /// Viewport bounding box (WGS 84): all four or none.
+    max_lon: Option<f64>,
+    /// Viewport bounding box (WGS 84): all four or none.
+    max_lat: Option<f64>,
 }
 
-// GET /api/municipalities
+/// Validates the optional viewport: all four corners set, finite, within
+/// lon/lat range, and min <= max on each axis.
+fn viewport(query: &ListQuery) -> Result<Option<[f64; 4]>, AppError> {
+    let bbox = match (query.min_lon, query.min_lat, query.max_lon, query.max_lat) {
+        (None, None, None, None) => return Ok(None),
+        (Some(a), Some(b), Some(c), Some(d)) => [a, b, c, d],
+        _ => {
+            return Err(AppError::BadRequest(
+                "min_lon, min_lat, max_lon and max_lat must be given together".to_string(),
+            ))
+        }
+    };
+    let [min_lon, min_lat, max_lon, max_lat] = bbox;
+    if !(valid_lon(min_lon) && valid_lon(max_lon) && valid_lat(min_lat) && valid_lat(max_lat)) {
+        return Err(AppError::BadRequest(format!(
+            "Bounding box {bbox:?} is outside longitude -180..180 / latitude -90..90"
+        )));
+    }
+    if min_lon > max_lon || min_lat > max_lat {
+        return Err(AppError::BadRequest(format!(
+            "Bounding box {bbox:?} has a minimum greater than its maximum"
+        )));
+    }
+    Ok(Some(bbox))
+}
+
+/// Coarser than ~5 km collapses small municipalities to slivers.
+const MAX_SIMPLIFY_TOLERANCE: f64 = 0.05;
+
+/// Validates an optional score bound from the query string: finite and within
+/// the 0-100 score range.
+fn score_bound(name: &str, value: Option<f64>) -> Result<Option<Decimal>, AppError> {
+    let Some(v) = value else { return Ok(None) };
+    if !v.is_finite() || !(0.0..=100.0).contains(&v) {
+        return Err(AppError::BadRequest(format!("{name} must be between 0 and 100, got {v}")));
+    }
+    Decimal::try_from(v)
+        .map(Some)
+        .map_err(|e| AppError::BadRequest(format!("Invalid {name}: {e}")))
+}
+
+/// Map FeatureCollection of every municipality with its latest score.
+#[utoipa::path(
+    get,
+    path = "/api/municipalities",
+    params(ListQuery),
+    responses(
+        (
+            status = 200,
+            description = "GeoJSON FeatureCollection, or with format=table a page of rows (Link: rel=next/prev when limit is set)",
+            body = MunicipalityList
+        ),
+        (status = 400, description = "Invalid filter or viewport", body = ErrorResponse)
+    )
+)]
 #[get("/api/municipalities")]
 pub async fn get_municipalities_list_handler(
+    req: HttpRequest,
     pool: web::Data<DbPool>,
     query: web::Query<ListQuery>, // Extract query parameters
     cache: web::Data<MapResponseCache>,
+    embargo: web::Data<Embargo>,
 ) -> Result<HttpResponse, AppError> {
-    let limit = query.limit;
-    if let Some(l) = limit {
-        if l <= 0 {
-            return Err(AppError::BadRequest(format!("limit must be positive, got {l}")));
-        }
+    let (limit, offset) = (query.limit, query.offset);
+    if let Some(l) = limit
+        && l <= 0
+    {
+        return Err(AppError::BadRequest(format!("limit must be positive, got {l}")));
     }
-    log::info!("START: Handling request for /api/municipalities with limit: {:?}", limit);
-
-    // The unlimited payload (the map's landing request) is served from memory.
-    if limit.is_none() {
-        if let Some(body) = cache.get_fresh() {
-            log::debug!("Serving /api/municipalities from in-memory cache");
-            return Ok(geojson_response(body));
-        }
+    if let Some(o) = offset
+        && o < 0
+    {
+        return Err(AppError::BadRequest(format!("offset must not be negative, got {o}")));
+    }
+    let min_score = score_bound("min_score", query.min_score)?;
+    let max_score = score_bound("max_score", query.max_score)?;
+    if let (Some(min), Some(max)) = (min_score, max_score)
+        && min > max
+    {
+        return Err(AppError::BadRequest(format!("min_score ({min}) must not exceed max_score ({max})")));
     }
+    if let Some(t) = query.simplify
+        && !(t.is_finite() && (0.0..=MAX_SIMPLIFY_TOLERANCE).contains(&t))
+    {
+        return Err(AppError::BadRequest(format!(
+            "simplify must be between 0 and {MAX_SIMPLIFY_TOLERANCE}, got {t}"
+        )));
+    }
+    let bbox = viewport(&query)?;
+    log::info!("START: Handling request for /api/municipalities with {:?}", query);
+
+    // An empty filter value (`?province=`) means no filter.
+    let non_empty = |value: &Option<String>| value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
 
-    let map_features = get_municipalities_summary_for_map(&pool, limit).await?;
-    let feature_collection = MapFeatureCollection {
-        collection_type: "FeatureCollection".to_string(),
-        features: map_features,
+    // Fully embargoed municipalities are left off the map (and out of the
+    // total). Year-only embargoes don't affect it: the map shows each
+    // municipality's latest score.
+    let options = MapQueryOptions {
+        limit,
+        offset,
+        excluded_ids: embargo.blocked_municipalities(),
+        province: non_empty(&query.province),
+        classification: non_empty(&query.classification),
+        min_score,
+        max_score,
+        simplify_tolerance: query.simplify,
+        bbox,
     };
 
-    let body = serde_json::to_string(&feature_collection)
-        .map_err(|e| AppError::InternalError(format!("Failed to serialize map payload: {e}")))?;
-    if limit.is_none() {
-        cache.store(body.clone());
+    // Keyed on the normalized options (embargo exclusions included), so
+    // `?province=` and no province share an entry.
+    let format = query.format;
+    let paged = limit.is_some() || offset.is_some();
+    let key = format!("{format:?} {options:?}");
+    let payload = cache
+        .get_or_build(key, || async {
+            let (map_features, total) = get_municipalities_summary_for_map(&pool, &options).await?;
+            if format == ListFormat::Table {
+                return table_payload(map_features, total, limit, offset.unwrap_or(0));
+            }
+            let feature_collection = MapFeatureCollection {
+                collection_type: "FeatureCollection".to_string(),
+                bbox: features_extent(&map_features),
+                features: map_features,
+                total: paged.then_some(total),
+            };
+            log::info!("END: Built {} features for /api/municipalities", feature_collection.features.len());
+            let last_modified = feature_collection.features.iter().filter_map(|f| f.properties.last_updated).max();
+            let body = serde_json::to_string(&feature_collection)
+                .map_err(|e| AppError::InternalError(format!("Failed to serialize map payload: {e}")))?;
+            Ok(MapPayload::new(body, last_modified))
+        })
+        .await?;
+
+    let link = match (format, limit) {
+        (ListFormat::Table, Some(limit)) => page_links(&req, limit, offset.unwrap_or(0), payload.has_more),
+        _ => None,
+    };
+    let mut response = geojson_response(&req, payload, cache.ttl());
+    if let Some(link) = link
+        && let Ok(value) = actix_web::http::header::HeaderValue::from_str(&link)
+    {
+        response.headers_mut().insert(actix_web::http::header::LINK, value);
     }
+    Ok(response)
+}
+
+/// The `?format=table` body: the features' properties as rows, with the
+/// total and whether rows remain past this page.
+fn table_payload(
+    features: Vec<MapFeature>,
+    total: i64,
+    limit: Option<i64>,
+    offset: i64,
+) -> Result<MapPayload, AppError> {
+    let items: Vec<_> = features.into_iter().map(|f| f.properties).collect();
+    let has_more = offset.saturating_add(items.len() as i64) < total;
+    let last_modified = items.iter().filter_map(|p| p.last_updated).max();
+    log::info!("END: Built a table page of {} of {} rows for /api/municipalities", items.len(), total);
+    let page = MunicipalityListPage { items, total, limit, offset, has_more };
+    let body = serde_json::to_string(&page)
+        .map_err(|e| AppError::InternalError(format!("Failed to serialize list page: {e}")))?;
+    Ok(MapPayload { has_more, ..MapPayload::new(body, last_modified) })
+}
+
+/// RFC 8288 `Link` header for a table page: `next` while rows remain, `prev`
+/// unless this is the first page. Other query parameters are kept as sent.
+fn page_links(req: &HttpRequest, limit: i64, offset: i64, has_more: bool) -> Option<String> {
+    let kept: Vec<&str> = req
+        .query_string()
+        .split('&')
+        .filter(|p| !p.is_empty() && !p.starts_with("limit=") && !p.starts_with("offset="))
+        .collect();
+    let link = |offset: i64, rel: &str| {
+        let paging = format!("limit={limit}&offset={offset}");
+        let query = kept.iter().copied().chain([paging.as_str()]).collect::<Vec<_>>().join("&");
+        format!("<{}?{query}>; rel=\"{rel}\"", req.path())
+    };
+    let mut links = Vec::new();
+    if has_more {
+        links.push(link(offset.saturating_add(limit), "next"));
+    }
+    if offset > 0 {
+        links.push(link((offset - limit).max(0), "prev"));
+    }
+    (!links.is_empty()).then(|| links.join(", "))
+}
+
+/// Every municipality with a boundary, as one `.geojson` attachment for
+/// offline analysis. Same features as the unfiltered map (embargoed
+/// municipalities left out), streamed rather than cached: downloads are rare
+/// and the body is large.
+#[utoipa::path(
+    get,
+    path = "/api/municipalities/download.geojson",
+    responses(
+        (
+            status = 200,
+            description = "FeatureCollection attachment (municipalities.geojson)",
+            body = MapFeatureCollection,
+            content_type = "application/geo+json"
+        )
+    )
+)]
+pub async fn download_municipalities_geojson_handler(
+    pool: web::Data<DbPool>,
+    embargo: web::Data<Embargo>,
+) -> Result<HttpResponse, AppError> {
+    log::info!("START: Handling request for /api/municipalities/download.geojson");
+    let options = MapQueryOptions { excluded_ids: embargo.blocked_municipalities(), ..MapQueryOptions::default() };
+    let (features, _) = get_municipalities_summary_for_map(&pool, &options).await?;
+    log::info!("END: Streaming {} features for /api/municipalities/download.geojson", features.len());
 
-    log::info!("END: Returning {} features for /api/municipalities", feature_collection.features.len());
-    Ok(geojson_response(body))
+    Ok(HttpResponse::Ok()
+        .content_type("application/geo+json")
+        .insert_header((
+            actix_web::http::header::CONTENT_DISPOSITION,
+            "attachment; filename=\"municipalities.geojson\"",
+        ))
+        .body(FeatureCollectionBody::new(features)))
 }
 
-fn geojson_response(body: String) -> HttpResponse {
-    HttpResponse::Ok()
+fn geojson_response(req: &HttpRequest, payload: MapPayload, max_age: std::time::Duration) -> HttpResponse {
+    let mut builder = HttpResponse::Ok();
+    builder
         .content_type("application/json")
-        .insert_header((actix_web::http::header::CACHE_CONTROL, "public, max-age=60"))
-        .body(body)
+        .insert_header((actix_web::http::header::CACHE_CONTROL, format!("public, max-age={}", max_age.as_secs())));
+    conditional_response(req, builder, payload.body, &payload.etag, payload.last_modified)
 }
\ No newline at end of file
@@ -0,0 +1,40 @@
+// src/handlers/provinces.rs
+use crate::db::municipalities::get_province_summary;
+use crate::embargo::Embargo;
+use crate::errors::AppError;
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use sqlx::PgPool as DbPool;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProvinceSummaryQuery {
+    /// Defaults to each municipality's latest scored year.
+    year: Option<i32>,
+}
+
+/// Provincial roll-up of cached figures: per province, totals and the average
+/// overall score over each municipality's latest scored year (or `?year=`).
+/// Embargoed municipalities and years are left out.
+#[utoipa::path(
+    get,
+    path = "/api/provinces/summary",
+    params(ProvinceSummaryQuery),
+    responses((status = 200, description = "One row per province", body = [ProvinceSummary]))
+)]
+pub async fn get_province_summary_handler(
+    query: web::Query<ProvinceSummaryQuery>,
+    pool: web::Data<DbPool>,
+    embargo: web::Data<Embargo>,
+) -> Result<HttpResponse, AppError> {
+    log::debug!("Handling request for /api/provinces/summary with {:?}", query);
+    let summaries = get_province_summary(
+        &pool,
+        query.year,
+        &embargo.blocked_municipalities(),
+        &embargo.blocked_years(),
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(summaries))
+}
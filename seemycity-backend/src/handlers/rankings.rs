@@ -0,0 +1,63 @@
+// src/handlers/rankings.rs
+use crate::db::financials::{get_latest_scored_year, get_rankings};
+use crate::embargo::Embargo;
+use crate::errors::AppError;
+use crate::models::{RankingOrder, RankingsResponse};
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use sqlx::PgPool as DbPool;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RankingsQuery {
+    /// Defaults to the most recent year with any overall score.
+    year: Option<i32>,
+    /// Rows to return.
+    limit: Option<i64>,
+    /// Listing direction; ranks are numbered from the best score either way.
+    #[serde(default)]
+    order: RankingOrder,
+}
+
+/// National leaderboard of overall scores for one year, from cached scores
+/// only. Municipalities embargoed entirely or for that year are left out.
+#[utoipa::path(
+    get,
+    path = "/api/rankings",
+    params(RankingsQuery),
+    responses(
+        (status = 200, description = "Rankings", body = RankingsResponse),
+        (status = 400, description = "Invalid limit or order", body = ErrorResponse)
+    )
+)]
+pub async fn get_rankings_handler(
+    query: web::Query<RankingsQuery>,
+    pool: web::Data<DbPool>,
+    embargo: web::Data<Embargo>,
+) -> Result<HttpResponse, AppError> {
+    log::debug!("Handling request for /api/rankings with {:?}", query);
+    if let Some(l) = query.limit
+        && l <= 0
+    {
+        return Err(AppError::BadRequest(format!("limit must be positive, got {l}")));
+    }
+
+    let year = match query.year {
+        Some(y) => Some(y),
+        None => get_latest_scored_year(&pool).await?,
+    };
+    let rankings = match year {
+        Some(y) => {
+            let excluded: Vec<String> = embargo
+                .blocked_municipalities()
+                .into_iter()
+                .chain(embargo.blocked_years().into_iter().filter(|(_, by)| *by == y).map(|(id, _)| id))
+                .collect();
+            get_rankings(&pool, y, query.limit, query.order, &excluded).await?
+        }
+        None => Vec::new(),
+    };
+
+    Ok(HttpResponse::Ok().json(RankingsResponse { year, order: query.order, rankings }))
+}
@@ -0,0 +1,15 @@
+// src/handlers/scoring.rs
+use crate::scoring::{methodology, ScoringConfig};
+use actix_web::{web, HttpResponse};
+
+/// The weights, normalization anchors, audit ladder and grade cut-offs the
+/// scores are computed with, so the frontend can explain a score without
+/// hardcoding the model.
+#[utoipa::path(
+    get,
+    path = "/api/scoring/methodology",
+    responses((status = 200, description = "The scoring model", body = ScoringMethodology))
+)]
+pub async fn get_scoring_methodology_handler(scoring_config: web::Data<ScoringConfig>) -> HttpResponse {
+    HttpResponse::Ok().json(methodology(&scoring_config))
+}
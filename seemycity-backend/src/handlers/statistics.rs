@@ -0,0 +1,53 @@
+// src/handlers/statistics.rs
+use crate::db::financials::{get_latest_scored_year, get_pillar_statistics};
+use crate::embargo::Embargo;
+use crate::errors::AppError;
+use crate::models::ScoreStatistics;
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use sqlx::PgPool as DbPool;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatisticsQuery {
+    /// Defaults to the most recent year with any overall score.
+    year: Option<i32>,
+}
+
+/// National distribution (count, min, max, mean, median) of the overall score
+/// and each pillar for one year, from cached scores only, so a municipality's
+/// scores can be read in context. Municipalities embargoed entirely or for
+/// that year are left out.
+#[utoipa::path(
+    get,
+    path = "/api/statistics",
+    params(StatisticsQuery),
+    responses(
+        (status = 200, description = "Per-score count, min, max, mean and median", body = ScoreStatistics),
+        (status = 400, description = "Invalid year", body = ErrorResponse)
+    )
+)]
+pub async fn get_statistics_handler(
+    query: web::Query<StatisticsQuery>,
+    pool: web::Data<DbPool>,
+    embargo: web::Data<Embargo>,
+) -> Result<HttpResponse, AppError> {
+    log::debug!("Handling request for /api/statistics with {:?}", query);
+    let year = match query.year {
+        Some(y) => Some(y),
+        None => get_latest_scored_year(&pool).await?,
+    };
+    let statistics = match year {
+        Some(y) => {
+            let excluded: Vec<String> = embargo
+                .blocked_municipalities()
+                .into_iter()
+                .chain(embargo.blocked_years().into_iter().filter(|(_, by)| *by == y).map(|(id, _)| id))
+                .collect();
+            get_pillar_statistics(&pool, y, &excluded, None).await?
+        }
+        None => ScoreStatistics::default(),
+    };
+    Ok(HttpResponse::Ok().json(statistics))
+}
@@ -0,0 +1,248 @@
+//! Background admin jobs: a refresh started via
+//! `POST /api/admin/municipalities/{id}/refresh`, or a rescore of every stored
+//! row started via `POST /api/admin/rescore`, runs on its own task and is
+//! tracked here so the admin panel can poll `GET /api/admin/jobs/{job_id}`
+//! instead of holding a request open until the work is done.
+//!
+//! State is in memory only: jobs are lost on restart, and finished jobs are
+//! forgotten [`JOB_RETENTION_HOURS`] after they end.
+
+use crate::request_id;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+
+/// How long a finished job stays pollable. An admin panel polls every few
+/// seconds, so an hour is ample without letting the map grow unbounded.
+pub const JOB_RETENTION_HOURS: i64 = 1;
+
+fn retention() -> chrono::Duration {
+    chrono::Duration::hours(JOB_RETENTION_HOURS)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Done,
+    Error,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Done | JobStatus::Error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobKind {
+    /// A forced refetch of one municipality.
+    Refresh,
+    /// A rescore of every stored municipality-year.
+    Rescore,
+}
+
+/// One job as reported to the admin panel.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Job {
+    pub id: String,
+    pub kind: JobKind,
+    /// The municipality a refresh works on; `None` for a rescore.
+    pub municipality_id: Option<String>,
+    pub status: JobStatus,
+    /// The failure, when `status` is `error`.
+    pub error: Option<String>,
+    /// Rows rewritten, once a rescore is done.
+    pub rescored: Option<usize>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Jobs by id, shared across workers.
+#[derive(Debug, Default)]
+pub struct JobRegistry {
+    jobs: RwLock<HashMap<String, Job>>,
+}
+
+impl JobRegistry {
+    /// Records a new pending refresh of `municipality_id` and returns it.
+    /// Jobs that finished more than [`JOB_RETENTION_HOURS`] ago are dropped
+    /// first. While a refresh of the same municipality is still pending or
+    /// running, no new job is made: `Err` carries that one instead.
+    pub fn create(&self, municipality_id: &str) -> Result<Job, Job> {
+        self.insert(JobKind::Refresh, Some(municipality_id.to_string()))
+    }
+
+    /// Records a new pending rescore, as for `create`: one at a time.
+    pub fn create_rescore(&self) -> Result<Job, Job> {
+        self.insert(JobKind::Rescore, None)
+    }
+
+    fn insert(&self, kind: JobKind, municipality_id: Option<String>) -> Result<Job, Job> {
+        let now = Utc::now();
+        let job = Job {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind,
+            municipality_id,
+            status: JobStatus::Pending,
+            error: None,
+            rescored: None,
+            created_at: now,
+            updated_at: now,
+        };
+        // Checked and inserted under one lock, so two racing requests can't
+        // both start the same job.
+        if let Ok(mut jobs) = self.jobs.write() {
+            jobs.retain(|_, j| !j.status.is_finished() || now - j.updated_at < retention());
+            if let Some(unfinished) = jobs.values().find(|j| {
+                !j.status.is_finished() && j.kind == job.kind && j.municipality_id == job.municipality_id
+            }) {
+                return Err(unfinished.clone());
+            }
+            jobs.insert(job.id.clone(), job.clone());
+        }
+        Ok(job)
+    }
+
+    pub fn get(&self, id: &str) -> Option<Job> {
+        self.jobs.read().ok().and_then(|jobs| jobs.get(id).cloned())
+    }
+
+    pub fn mark_running(&self, id: &str) {
+        self.update(id, JobStatus::Running, None);
+    }
+
+    /// Records the job's outcome.
+    pub fn finish(&self, id: &str, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.update(id, JobStatus::Done, None),
+            Err(e) => self.update(id, JobStatus::Error, Some(e)),
+        }
+    }
+
+    /// Records a rescore's outcome: on success, how many rows it rewrote.
+    pub fn finish_rescore(&self, id: &str, result: Result<usize, String>) {
+        if let Ok(rescored) = result
+            && let Ok(mut jobs) = self.jobs.write()
+            && let Some(job) = jobs.get_mut(id)
+        {
+            job.rescored = Some(rescored);
+        }
+        self.finish(id, result.map(|_| ()));
+    }
+
+    /// Runs `work` for job `id` on its own task. `work` records the outcome
+    /// itself; should it panic instead, the job is finished as an error here
+    /// rather than being left `running` until restart.
+    pub fn spawn<F>(self: Arc<Self>, id: String, work: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let handle = tokio::spawn(request_id::inherit(work));
+        tokio::spawn(async move {
+            if let Err(e) = handle.await {
+                log::error!("Admin job {} aborted: {}", id, e);
+                self.finish(&id, Err(format!("Job aborted: {e}")));
+            }
+        });
+    }
+
+    fn update(&self, id: &str, status: JobStatus, error: Option<String>) {
+        if let Ok(mut jobs) = self.jobs.write()
+            && let Some(job) = jobs.get_mut(id)
+        {
+            job.status = status;
+            job.error = error;
+            job.updated_at = Utc::now();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jobs_move_from_pending_to_a_final_status() {
+        let registry = JobRegistry::default();
+        let job = registry.create("CPT").unwrap();
+        assert_eq!(registry.get(&job.id).unwrap().status, JobStatus::Pending);
+
+        registry.mark_running(&job.id);
+        assert_eq!(registry.get(&job.id).unwrap().status, JobStatus::Running);
+
+        registry.finish(&job.id, Err("upstream unavailable".to_string()));
+        let failed = registry.get(&job.id).unwrap();
+        assert_eq!(failed.status, JobStatus::Error);
+        assert_eq!(failed.error.as_deref(), Some("upstream unavailable"));
+        assert_eq!(serde_json::to_value(&failed).unwrap()["status"], "error");
+
+        assert_eq!(registry.get("no-such-job"), None);
+    }
+
+    #[test]
+    fn a_finished_rescore_reports_its_row_count() {
+        let registry = JobRegistry::default();
+        let job = registry.create_rescore().unwrap();
+        assert_eq!((job.kind, job.municipality_id.as_deref()), (JobKind::Rescore, None));
+
+        registry.finish_rescore(&job.id, Ok(42));
+        let done = registry.get(&job.id).unwrap();
+        assert_eq!((done.status, done.rescored), (JobStatus::Done, Some(42)));
+        assert_eq!(serde_json::to_value(&done).unwrap()["kind"], "rescore");
+    }
+
+    #[test]
+    fn an_unfinished_refresh_is_reused_for_the_same_municipality() {
+        let registry = JobRegistry::default();
+        let first = registry.create("CPT").unwrap();
+        assert_eq!(registry.create("CPT"), Err(first.clone()));
+        registry.mark_running(&first.id);
+        assert_eq!(registry.create("CPT").unwrap_err().id, first.id);
+        assert!(registry.create("JHB").is_ok(), "other municipalities are not held up");
+        let rescore = registry.create_rescore().unwrap();
+        assert_eq!(registry.create_rescore().unwrap_err().id, rescore.id);
+
+        registry.finish(&first.id, Ok(()));
+        assert_ne!(registry.create("CPT").unwrap().id, first.id);
+    }
+
+    #[actix_web::test]
+    async fn a_panicking_job_is_finished_as_an_error() {
+        let registry = Arc::new(JobRegistry::default());
+        let job = registry.create("CPT").unwrap();
+        registry.mark_running(&job.id);
+        registry.clone().spawn(job.id.clone(), async { panic!("boom") });
+
+        for _ in 0..100 {
+            if registry.get(&job.id).unwrap().status.is_finished() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let failed = registry.get(&job.id).unwrap();
+        assert_eq!(failed.status, JobStatus::Error);
+        assert!(failed.error.unwrap().contains("panic"));
+    }
+
+    #[test]
+    fn finished_jobs_are_dropped_after_the_retention_period() {
+        let registry = JobRegistry::default();
+        let (old, running) = (registry.create("CPT").unwrap(), registry.create("JHB").unwrap());
+        registry.finish(&old.id, Ok(()));
+        if let Ok(mut jobs) = registry.jobs.write() {
+            for job in jobs.values_mut() {
+                job.updated_at -= retention();
+            }
+        }
+
+        let new = registry.create("ETH").unwrap();
+        assert_eq!(registry.get(&old.id), None);
+        assert!(registry.get(&running.id).is_some(), "unfinished jobs are kept");
+        assert!(registry.get(&new.id).is_some());
+    }
+}
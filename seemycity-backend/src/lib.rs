@@ -6,9 +6,16 @@ pub mod api;
 pub mod config;
 pub mod confidence;
 pub mod db;
+pub mod embargo;
 pub mod errors;
 pub mod handlers;
+pub mod jobs;
+pub mod logging;
+pub mod metrics;
 pub mod models;
+pub mod openapi;
+pub mod request_id;
+pub mod services;
 pub mod utils;
 pub mod scoring;
 
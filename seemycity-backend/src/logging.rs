@@ -0,0 +1,101 @@
+// src/logging.rs
+
+// Logger setup. Every `log::` macro in the crate goes through `env_logger`;
+// this only picks its default level (LOG_LEVEL, with RUST_LOG still taking
+// precedence) and its line format (LOG_FORMAT): the plain-text layout from
+// `request_id::format_log_record`, or one JSON object per line for log
+// aggregation.
+
+use crate::request_id::{self, format_log_record};
+use chrono::{SecondsFormat, Utc};
+use std::io::Write;
+use std::str::FromStr;
+
+/// How each log record is written to stderr.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// `[timestamp LEVEL target req=<id>] message`
+    #[default]
+    Text,
+    /// One JSON object per line; see [`json_record`].
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!("'{other}' is not a log format (expected text or json)")),
+        }
+    }
+}
+
+/// The JSON form of one record:
+/// `{"timestamp":"2026-07-07T10:00:00.123Z","level":"INFO","target":"seemycity_backend::services::municipality","message":"…","request_id":"…"}`.
+/// `request_id` is present only for lines logged while handling a request.
+pub fn json_record(record: &log::Record, request_id: Option<String>) -> serde_json::Value {
+    let mut line = serde_json::json!({
+        "timestamp": Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        "level": record.level().as_str(),
+        "target": record.target(),
+        "message": record.args().to_string(),
+    });
+    if let Some(id) = request_id {
+        line["request_id"] = serde_json::Value::String(id);
+    }
+    line
+}
+
+fn format_json_record(buf: &mut env_logger::fmt::Formatter, record: &log::Record) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *buf, &json_record(record, request_id::current()))?;
+    writeln!(buf)
+}
+
+/// Installs the global logger. `level` is the default filter; a RUST_LOG in
+/// the environment overrides it (e.g. `RUST_LOG=info,sqlx=warn`).
+pub fn init(level: log::LevelFilter, format: LogFormat) {
+    let mut builder =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(level.to_string()));
+    match format {
+        LogFormat::Text => builder.format(format_log_record),
+        LogFormat::Json => builder.format(format_json_record),
+    };
+    builder.init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_format_parses_case_insensitively() {
+        assert_eq!("JSON".parse::<LogFormat>(), Ok(LogFormat::Json));
+        assert_eq!("text".parse::<LogFormat>(), Ok(LogFormat::Text));
+        assert!("logfmt".parse::<LogFormat>().unwrap_err().contains("text or json"));
+    }
+
+    #[test]
+    fn json_record_carries_the_request_id_only_when_present() {
+        let check = |record: &log::Record| {
+            let line = json_record(record, Some("abc".to_string()));
+            assert_eq!(line["level"], "WARN");
+            assert_eq!(line["target"], "seemycity_backend::api");
+            assert_eq!(line["message"], "Fetched \"revenue\" for CPT");
+            assert_eq!(line["request_id"], "abc");
+            assert!(line["timestamp"].as_str().unwrap().ends_with('Z'));
+
+            let line = json_record(record, None);
+            assert!(line.get("request_id").is_none());
+        };
+        check(
+            &log::Record::builder()
+                .args(format_args!("Fetched \"{}\" for CPT", "revenue"))
+                .level(log::Level::Warn)
+                .target("seemycity_backend::api")
+                .build(),
+        );
+    }
+}
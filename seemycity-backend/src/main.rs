@@ -1,31 +1,72 @@
-use actix_web::{App, HttpServer, web, middleware::{Compress, Logger}, http};
+use actix_web::{App, HttpServer, web, middleware::{from_fn, Compress, Logger}, http};
 use dotenvy::dotenv; // To load .env file
 use seemycity_backend::db; // Import db module (which contains create_pool and queries)
 use seemycity_backend::config; // Import config module
 use seemycity_backend::api::muni_money::client::MunicipalMoneyClient; // Import API Client
+use seemycity_backend::api::muni_money::item_codes::ItemCodeSets;
+use seemycity_backend::embargo::Embargo;
+use seemycity_backend::jobs::JobRegistry;
+use seemycity_backend::scoring::{self, ScoringConfig, ScoringThresholds};
+use seemycity_backend::handlers::admin::{
+    geometry_validity_handler, get_job_handler, invalidate_map_cache_handler, recompute_from_raw_handler,
+    refresh_municipality_handler, reload_embargo_handler, rescore_handler, validate_scoring_config_handler,
+};
+use seemycity_backend::handlers::docs::{swagger_redirect_handler, swagger_ui};
+use seemycity_backend::handlers::export::export_scores_csv_handler;
+use seemycity_backend::handlers::health::{health_handler, readiness_handler};
+use seemycity_backend::handlers::metrics::metrics_handler;
+use seemycity_backend::metrics::track_requests;
+use seemycity_backend::logging::{self, LogFormat};
+use seemycity_backend::request_id::{assign_request_id, REQUEST_ID_HEADER};
+use seemycity_backend::handlers::provinces::get_province_summary_handler;
+use seemycity_backend::handlers::rankings::get_rankings_handler;
+use seemycity_backend::handlers::scoring::get_scoring_methodology_handler;
+use seemycity_backend::handlers::statistics::get_statistics_handler;
 use seemycity_backend::handlers::municipalities::{ // Import handlers
+    batch_municipality_detail_handler,
+    compare_municipalities_handler,
+    download_municipalities_geojson_handler,
+    export_municipality_csv_handler,
+    get_municipality_benchmarks_handler,
+    get_municipality_breakdown_handler,
     get_municipality_detail_handler,
+    get_municipality_geometry_handler,
+    get_nearest_municipalities_handler,
+    get_municipality_percentile_handler,
+    get_municipality_years_handler,
+    get_municipality_summary_handler,
     get_municipalities_list_handler, // Import the new handler
-    warm_all_municipalities,
+    search_municipalities_handler,
+    DetailAdmission,
     MapResponseCache,
-    UpstreamHealth,
 };
+use seemycity_backend::services::municipality::{warm_all_municipalities, UpstreamHealth};
+use seemycity_backend::services::normalization;
 use std::sync::Arc; // Import Arc if needed for Cache later, good practice
 use actix_cors::Cors; // Import CORS
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    // Initialize logger
-    env_logger::init_from_env(env_logger::Env::default().default_filter_or("info"));
-
     // Load environment variables from .env file
-    dotenv().ok();
-    log::info!("Loaded .env file using dotenvy");
+    let dotenv_loaded = dotenv().is_ok();
+
+    // Initialize logger at the configured level and format; lines logged while
+    // handling a request carry its id. A broken configuration still gets the
+    // defaults, so its errors are reported.
+    let config = config::load_config(); // Use imported config module
+    match &config {
+        Ok(cfg) => logging::init(cfg.log_level, cfg.log_format),
+        Err(_) => logging::init(log::LevelFilter::Info, LogFormat::Text),
+    }
+    if dotenv_loaded {
+        log::info!("Loaded .env file using dotenvy");
+    }
 
-    // Load configuration
-    let config = match config::load_config() { // Use imported config module
+    let config = match config {
         Ok(cfg) => cfg,
         Err(e) => {
+            // Multiple problems render one per line, so the operator sees
+            // everything to fix before the next restart.
             log::error!("Failed to load configuration: {}", e);
             std::process::exit(1);
         }
@@ -44,38 +85,112 @@ async fn main() -> std::io::Result<()> {
             std::process::exit(1); // Exit if connection fails
         }
     };
+    // Upserts target the unique (municipality_id, year) constraint; refuse to
+    // start against a schema that lacks it rather than fail every refresh.
+    match db::financials::has_unique_year_constraint(&pool).await {
+        Ok(true) => {}
+        Ok(false) => {
+            log::error!(
+                "financial_data has no unique (municipality_id, year) constraint; \
+                 apply migrations/0008_financial_data_unique_year.sql and restart"
+            );
+            std::process::exit(1);
+        }
+        Err(e) => {
+            log::error!("Failed to check the financial_data constraints: {}", e);
+            std::process::exit(1);
+        }
+    }
 
     // Create Municipal Money API Client instance
-    let api_client = match MunicipalMoneyClient::new() { 
+    let api_client = match MunicipalMoneyClient::from_settings(&config_arc.muni_money) {
         Ok(client) => client,
         Err(e) => {
             log::error!("Failed to create Municipal Money API client: {}", e);
             std::process::exit(1);
         }
     };
+    // Item codes summed into each metric; the built-in selection unless
+    // ITEM_CODES_FILE replaces it
+    let api_client = match &config_arc.item_codes_file {
+        Some(path) => match ItemCodeSets::from_file(path) {
+            Ok(codes) => api_client.with_item_codes(codes),
+            Err(e) => {
+                log::error!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        None => api_client,
+    };
+    // Normalization anchors for every score computed by this process; the
+    // built-in ones unless SCORING_THRESHOLDS_FILE overrides them
+    if let Some(path) = &config_arc.scoring_thresholds_file {
+        let installed = ScoringThresholds::from_file(path).and_then(scoring::set_thresholds);
+        if let Err(e) = installed {
+            log::error!("{}", e);
+            std::process::exit(1);
+        }
+    }
+
+    // How the overall score treats a pillar it could not compute
+    if let Err(e) = scoring::set_missing_pillar_policy(config_arc.missing_pillar_policy) {
+        log::error!("{}", e);
+        std::process::exit(1);
+    }
+    if config_arc.missing_pillar_policy == scoring::MissingPillarPolicy::RequireRawPillars {
+        log::info!("Overall scores require every raw-figure pillar (MISSING_PILLAR_POLICY)");
+    }
+
+    if config_arc.percentile_normalization {
+        normalization::enable_percentile_normalization();
+        log::info!("Scores are normalized by percentile rank within each year");
+    }
 
     let server_host = config_arc.server_host.clone();
     let server_port = config_arc.server_port;
     log::info!("Starting HTTP server at http://{}:{}", server_host, server_port);
 
     // Shared across workers so the map payload is built once per TTL, not per worker
-    let map_cache = web::Data::new(MapResponseCache::default());
+    let map_cache = web::Data::new(MapResponseCache::new(config_arc.map_cache_ttl()));
     // Circuit breaker for the Treasury API, shared across workers
     let upstream_health = web::Data::new(UpstreamHealth::default());
+    // Load shedding for the detail endpoint, shared across workers
+    let detail_admission = web::Data::new(DetailAdmission::new(config_arc.detail_max_concurrency));
+    // Municipalities/years withheld from the read endpoints, reloadable at runtime
+    let embargo = match &config_arc.embargo_file {
+        Some(path) => match Embargo::from_file(path) {
+            Ok(e) => web::Data::new(e),
+            Err(e) => {
+                log::error!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        None => web::Data::new(Embargo::default()),
+    };
+
+    // Admin refresh jobs, pollable from any worker
+    let jobs = web::Data::new(JobRegistry::default());
 
     // Background cache warmer: keeps every municipality scored so the map is
-    // fully colored without depending on detail-page traffic. Fresh rows are
-    // skipped, so each daily pass is cheap.
+    // fully colored and detail requests are DB-only, without depending on
+    // detail-page traffic. Fresh rows are skipped, so each pass is cheap.
     if config_arc.cache_warmer_enabled {
         let warm_pool = pool.clone();
         let warm_client = api_client.clone();
-        let warm_health = upstream_health.clone();
+        let warm_health = upstream_health.clone().into_inner();
+        let warm_config = config_arc.clone();
+        let warm_ttl = config_arc.cache_ttl();
+        let warm_interval = config_arc.cache_warmer_interval();
+        let warm_concurrency = config_arc.cache_warmer_concurrency;
         tokio::spawn(async move {
             // Short delay so startup traffic settles first.
             tokio::time::sleep(std::time::Duration::from_secs(15)).await;
             loop {
-                warm_all_municipalities(&warm_pool, &warm_client, &warm_health).await;
-                tokio::time::sleep(std::time::Duration::from_secs(24 * 60 * 60)).await;
+                // Re-read each pass so a long-running server rolls over to a new year.
+                let newest_year = warm_config.current_financial_year();
+                warm_all_municipalities(&warm_pool, &warm_client, &warm_health, newest_year, warm_ttl, warm_concurrency)
+                    .await;
+                tokio::time::sleep(warm_interval).await;
             }
         });
     } else {
@@ -84,30 +199,122 @@ async fn main() -> std::io::Result<()> {
 
     // Start Actix Web server
     let cors_origins = config_arc.cors_allowed_origins.clone();
-    HttpServer::new(move || {
+    let app_pool = pool.clone();
+    let shutdown_timeout_secs = config_arc.shutdown_timeout_secs;
+    let server = HttpServer::new(move || {
         // Origins come from CORS_ALLOWED_ORIGINS (comma-separated)
         let mut cors = Cors::default()
               .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
-              .allowed_headers(vec![http::header::AUTHORIZATION, http::header::ACCEPT, http::header::CONTENT_TYPE])
+              .allowed_headers(vec![
+                  http::header::AUTHORIZATION,
+                  http::header::ACCEPT,
+                  http::header::CONTENT_TYPE,
+                  http::header::HeaderName::from_static(REQUEST_ID_HEADER),
+              ])
+              .expose_headers(vec![REQUEST_ID_HEADER])
               .max_age(3600);
         for origin in &cors_origins {
             cors = cors.allowed_origin(origin);
         }
 
         App::new()
-            .wrap(Logger::default()) // Add logger middleware
+            .wrap(from_fn(assign_request_id)) // X-Request-Id in and out, and on every log line
+            // Default format plus the request id, which is set on the response by then
+            .wrap(Logger::new(r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T req=%{x-request-id}o"#))
+            .wrap(from_fn(track_requests)) // Per-route request counts and latency for /metrics
             .wrap(Compress::default()) // gzip/brotli — GeoJSON compresses ~5-10x
             .wrap(cors) // Add CORS middleware
-            .app_data(web::Data::new(pool.clone())) // Share the pool
+            .app_data(web::Data::from(config_arc.clone())) // Share the config
+            .app_data(web::Data::new(app_pool.clone())) // Share the pool
             .app_data(web::Data::new(api_client.clone())) // Share the API client
             .app_data(map_cache.clone()) // Shared map response cache
             .app_data(upstream_health.clone()) // Treasury API circuit breaker
+            .app_data(detail_admission.clone()) // Detail endpoint admission limit
+            .app_data(embargo.clone()) // Withheld municipalities/years
+            .app_data(jobs.clone()) // Admin background jobs
+            .app_data(web::Data::new(ScoringConfig::with_thresholds(scoring::thresholds()))) // The model the scores are computed with
             // Explicitly register the detail route
+            // Before the `{id}` route, which would otherwise capture these paths
+            .route("/api/municipalities/nearest", web::get().to(get_nearest_municipalities_handler))
+            .route("/api/municipalities/compare", web::get().to(compare_municipalities_handler))
+            .route("/api/municipalities/batch", web::post().to(batch_municipality_detail_handler))
+            .route("/api/municipalities/search", web::get().to(search_municipalities_handler))
+            .route("/api/municipalities/download.geojson", web::get().to(download_municipalities_geojson_handler))
             .route("/api/municipalities/{id}", web::get().to(get_municipality_detail_handler))
+            .route("/api/municipalities/{id}/summary", web::get().to(get_municipality_summary_handler))
+            .route("/api/municipalities/{id}/export.csv", web::get().to(export_municipality_csv_handler))
+            .route("/api/municipalities/{id}/percentile", web::get().to(get_municipality_percentile_handler))
+            .route("/api/municipalities/{id}/benchmarks", web::get().to(get_municipality_benchmarks_handler))
+            .route("/api/municipalities/{id}/years", web::get().to(get_municipality_years_handler))
+            .route("/api/municipalities/{id}/breakdown", web::get().to(get_municipality_breakdown_handler))
+            .route("/api/municipalities/{id}/geometry", web::get().to(get_municipality_geometry_handler))
+            .route("/api/provinces/summary", web::get().to(get_province_summary_handler))
+            .route("/api/rankings", web::get().to(get_rankings_handler))
+            .route("/api/statistics", web::get().to(get_statistics_handler))
+            .route("/api/export/scores.csv", web::get().to(export_scores_csv_handler))
+            .route("/api/scoring/methodology", web::get().to(get_scoring_methodology_handler))
+            .route("/health", web::get().to(health_handler))
+            .route("/health/ready", web::get().to(readiness_handler))
+            .route("/metrics", web::get().to(metrics_handler))
+            .route("/swagger", web::get().to(swagger_redirect_handler))
+            .service(swagger_ui()) // /swagger/ and /api-docs/openapi.json
+            .route("/api/admin/validate-config", web::post().to(validate_scoring_config_handler))
+            .route("/api/admin/reload-embargo", web::post().to(reload_embargo_handler))
+            .route("/api/admin/invalidate-map-cache", web::post().to(invalidate_map_cache_handler))
+            .route("/api/admin/recompute-from-raw", web::post().to(recompute_from_raw_handler))
+            .route("/api/admin/rescore", web::post().to(rescore_handler))
+            .route("/api/admin/municipalities/{id}/refresh", web::post().to(refresh_municipality_handler))
+            .route("/api/admin/jobs/{job_id}", web::get().to(get_job_handler))
+            .route("/api/admin/geometry-validity", web::get().to(geometry_validity_handler))
              // Keep using .service() for the list handler as its path is defined by its macro
             .service(get_municipalities_list_handler)
     })
     .bind((server_host.as_str(), server_port))?
-    .run()
-    .await
+    // Signals are handled below, so the pool is closed only once requests
+    // have drained.
+    .disable_signals()
+    .shutdown_timeout(shutdown_timeout_secs)
+    .run();
+
+    let server_handle = server.handle();
+    tokio::spawn(async move {
+        let signal = wait_for_shutdown_signal().await;
+        log::info!(
+            "Received {}; draining in-flight requests (up to {}s)",
+            signal,
+            shutdown_timeout_secs
+        );
+        server_handle.stop(true).await;
+    });
+
+    server.await?;
+    log::info!("HTTP server stopped; closing database pool");
+    pool.close().await;
+    log::info!("Shutdown complete");
+    Ok(())
+}
+
+/// Resolves on SIGTERM (how orchestrators stop a container) or Ctrl-C, with
+/// the signal's name for the log.
+async fn wait_for_shutdown_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        let mut sigterm = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(sigterm) => sigterm,
+            Err(e) => {
+                log::error!("Failed to install SIGTERM handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                return "SIGINT";
+            }
+        };
+        tokio::select! {
+            _ = sigterm.recv() => "SIGTERM",
+            _ = tokio::signal::ctrl_c() => "SIGINT",
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "Ctrl-C"
+    }
 }
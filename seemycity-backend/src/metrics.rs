@@ -0,0 +1,227 @@
+// src/metrics.rs
+
+// Process-wide Prometheus metrics, served by `GET /metrics` in the text
+// exposition format. Hand-rolled rather than pulling in a client crate: the
+// handful of counters and one histogram below are all we export.
+//
+// Exported series:
+// - `seemycity_http_requests_total{method, route, status}` (counter)
+// - `seemycity_http_request_duration_seconds{method, route}` (histogram)
+// - `seemycity_upstream_errors_total{cube, status}` (counter) — failed
+//   Municipal Money API calls; `status` is the HTTP status, or `timeout`,
+//   `transport`, `parse` for failures without one
+// - `seemycity_cache_hits_total{cache}` / `seemycity_cache_misses_total{cache}`
+//   (counters) — `cache` is `map` (the map payload) or `financials` (cached
+//   financial_data rows)
+//
+// `route` is the matched route pattern (e.g. `/api/municipalities/{id}`), so
+// ids do not explode the label set; unmatched paths share `unmatched`.
+
+use crate::api::ApiClientError;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Upper bounds (seconds) of the request-duration histogram buckets.
+const DURATION_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Route label for requests that matched no route.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::default);
+
+/// The process-wide registry every recorder writes to.
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+#[derive(Debug, Default, Clone)]
+struct Histogram {
+    buckets: [u64; DURATION_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(DURATION_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Metrics {
+    requests: Mutex<BTreeMap<(String, String, u16), u64>>,
+    durations: Mutex<BTreeMap<(String, String), Histogram>>,
+    upstream_errors: Mutex<BTreeMap<(String, String), u64>>,
+    cache: Mutex<BTreeMap<(&'static str, bool), u64>>,
+}
+
+impl Metrics {
+    /// Counts one served request and its latency under its route pattern.
+    pub fn record_request(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        if let Ok(mut requests) = self.requests.lock() {
+            *requests.entry((method.to_string(), route.to_string(), status)).or_default() += 1;
+        }
+        if let Ok(mut durations) = self.durations.lock() {
+            durations
+                .entry((method.to_string(), route.to_string()))
+                .or_default()
+                .observe(elapsed.as_secs_f64());
+        }
+    }
+
+    /// Counts one failed Municipal Money API call against `cube`.
+    pub fn record_upstream_error(&self, cube: &str, error: &ApiClientError) {
+        let status = match error {
+            ApiClientError::ApiError { status, .. } => status.to_string(),
+            ApiClientError::RequestError(e) if e.is_timeout() => "timeout".to_string(),
+            ApiClientError::RequestError(e) if e.is_decode() => "parse".to_string(),
+            ApiClientError::RequestError(_) => "transport".to_string(),
+            ApiClientError::ParseError(_) => "parse".to_string(),
+            _ => "other".to_string(),
+        };
+        if let Ok(mut errors) = self.upstream_errors.lock() {
+            *errors.entry((cube.to_string(), status)).or_default() += 1;
+        }
+    }
+
+    /// Counts a lookup in one of the named caches.
+    pub fn record_cache(&self, cache: &'static str, hit: bool) {
+        if let Ok(mut counts) = self.cache.lock() {
+            *counts.entry((cache, hit)).or_default() += 1;
+        }
+    }
+
+    /// Every series in the Prometheus text exposition format (version 0.0.4).
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP seemycity_http_requests_total HTTP requests served, by route pattern and status.\n");
+        out.push_str("# TYPE seemycity_http_requests_total counter\n");
+        if let Ok(requests) = self.requests.lock() {
+            for ((method, route, status), count) in requests.iter() {
+                let _ = writeln!(
+                    out,
+                    "seemycity_http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                    escape(method), escape(route), status, count
+                );
+            }
+        }
+
+        out.push_str("# HELP seemycity_http_request_duration_seconds HTTP request latency, by route pattern.\n");
+        out.push_str("# TYPE seemycity_http_request_duration_seconds histogram\n");
+        if let Ok(durations) = self.durations.lock() {
+            for ((method, route), histogram) in durations.iter() {
+                let labels = format!("method=\"{}\",route=\"{}\"", escape(method), escape(route));
+                for (bound, count) in DURATION_BUCKETS.iter().zip(histogram.buckets) {
+                    let _ = writeln!(out, "seemycity_http_request_duration_seconds_bucket{{{labels},le=\"{bound}\"}} {count}");
+                }
+                let _ = writeln!(
+                    out,
+                    "seemycity_http_request_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+                    histogram.count
+                );
+                let _ = writeln!(out, "seemycity_http_request_duration_seconds_sum{{{labels}}} {}", histogram.sum);
+                let _ = writeln!(out, "seemycity_http_request_duration_seconds_count{{{labels}}} {}", histogram.count);
+            }
+        }
+
+        out.push_str("# HELP seemycity_upstream_errors_total Failed Municipal Money API calls, by cube and status.\n");
+        out.push_str("# TYPE seemycity_upstream_errors_total counter\n");
+        if let Ok(errors) = self.upstream_errors.lock() {
+            for ((cube, status), count) in errors.iter() {
+                let _ = writeln!(
+                    out,
+                    "seemycity_upstream_errors_total{{cube=\"{}\",status=\"{}\"}} {}",
+                    escape(cube), escape(status), count
+                );
+            }
+        }
+
+        if let Ok(counts) = self.cache.lock() {
+            for (hit, name, help) in [
+                (true, "seemycity_cache_hits_total", "Lookups answered from a cache."),
+                (false, "seemycity_cache_misses_total", "Lookups that had to rebuild or refetch."),
+            ] {
+                let _ = writeln!(out, "# HELP {name} {help}");
+                let _ = writeln!(out, "# TYPE {name} counter");
+                for ((cache, _), count) in counts.iter().filter(|((_, h), _)| *h == hit) {
+                    let _ = writeln!(out, "{name}{{cache=\"{cache}\"}} {count}");
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Label values may not contain raw backslashes, quotes or newlines.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Middleware (`middleware::from_fn`) recording every request's count and
+/// latency under its matched route pattern.
+pub async fn track_requests(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let started = Instant::now();
+    let method = req.method().to_string();
+    let route = req.match_pattern().unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let result = next.call(req).await;
+    let status = match &result {
+        Ok(response) => response.status(),
+        Err(e) => e.as_response_error().status_code(),
+    };
+    metrics().record_request(&method, &route, status.as_u16(), started.elapsed());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let mut histogram = Histogram::default();
+        histogram.observe(0.004);
+        histogram.observe(0.3);
+        histogram.observe(60.0);
+        assert_eq!(histogram.buckets[0], 1); // le 0.005
+        assert_eq!(histogram.buckets[6], 2); // le 0.5
+        assert_eq!(histogram.buckets[10], 2); // le 10: the 60s request only counts in +Inf
+        assert_eq!(histogram.count, 3);
+    }
+
+    #[test]
+    fn render_emits_labelled_series() {
+        let metrics = Metrics::default();
+        metrics.record_request("GET", "/api/municipalities/{id}", 200, Duration::from_millis(20));
+        metrics.record_upstream_error("incexp_v2", &ApiClientError::ApiError { status: 503, message: None, body: None });
+        metrics.record_cache("map", true);
+        metrics.record_cache("map", false);
+        metrics.record_cache("map", false);
+
+        let text = metrics.render();
+        assert!(text.contains(
+            "seemycity_http_requests_total{method=\"GET\",route=\"/api/municipalities/{id}\",status=\"200\"} 1"
+        ), "{text}");
+        assert!(text.contains(
+            "seemycity_http_request_duration_seconds_bucket{method=\"GET\",route=\"/api/municipalities/{id}\",le=\"0.025\"} 1"
+        ), "{text}");
+        assert!(text.contains("seemycity_upstream_errors_total{cube=\"incexp_v2\",status=\"503\"} 1"), "{text}");
+        assert!(text.contains("seemycity_cache_hits_total{cache=\"map\"} 1"), "{text}");
+        assert!(text.contains("seemycity_cache_misses_total{cache=\"map\"} 2"), "{text}");
+    }
+}
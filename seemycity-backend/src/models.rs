@@ -3,6 +3,68 @@ use sqlx::{FromRow, types::chrono};
 use uuid::Uuid;
 use rust_decimal::Decimal;
 use geojson::Geometry;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+use crate::scoring::{ScoreAudit, ScoreBreakdown, ScoreDeltas};
+
+// --- Identifiers ---
+
+/// Longest accepted municipality code. Real MDB codes run to six characters
+/// ("KZN252", "WC011", "DC10"); the slack leaves room for test fixtures.
+pub const MUNICIPALITY_ID_MAX_LEN: usize = 10;
+
+/// A municipality code as used in paths and the `municipalities.id` column,
+/// e.g. "CPT" or "WC011". Parsing accepts either case and stores the code
+/// upper-cased; anything that is not 1-10 ASCII letters and digits is a 400,
+/// rejected before it reaches the DB or the Treasury API. The query and
+/// fetch functions keep taking `&str`; pass `as_str()` or `as_ref()`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(transparent)]
+pub struct MunicipalityId(String);
+
+impl MunicipalityId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::str::FromStr for MunicipalityId {
+    type Err = crate::errors::AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let valid = !s.is_empty()
+            && s.len() <= MUNICIPALITY_ID_MAX_LEN
+            && s.bytes().all(|b| b.is_ascii_alphanumeric());
+        if !valid {
+            return Err(crate::errors::AppError::BadRequest(format!(
+                "Invalid municipality id '{}': expected 1-{} letters and digits, e.g. CPT or WC011",
+                s.chars().take(32).collect::<String>(),
+                MUNICIPALITY_ID_MAX_LEN
+            )));
+        }
+        Ok(MunicipalityId(s.to_ascii_uppercase()))
+    }
+}
+
+impl TryFrom<String> for MunicipalityId {
+    type Error = crate::errors::AppError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl AsRef<str> for MunicipalityId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for MunicipalityId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
 
 // --- Database Table Models ---
 
@@ -43,12 +105,17 @@ pub struct FinancialDataDb {
     pub transfers_operational: Option<Decimal>, // grants within revenue (item 2200)
     pub uifw_expenditure: Option<Decimal>,      // unauthorised/irregular/fruitless & wasteful
     pub repairs_maintenance: Option<Decimal>,   // R&M spend (repmaint_v2)
+    pub cash_balance: Option<Decimal>,          // cash at year end (cflow_v2, scoring v3)
+    pub operating_surplus: Option<Decimal>,     // revenue - operational_expenditure (derived)
+    // "AUDA" (audited) | "ORGB" / "ADJB" (budgeted fallback); None = legacy row
+    pub amount_type: Option<String>,
     // Add the new score fields to match the DB table
     pub overall_score: Option<Decimal>,
     pub financial_health_score: Option<Decimal>,
     pub infrastructure_score: Option<Decimal>,
     pub efficiency_score: Option<Decimal>,
     pub accountability_score: Option<Decimal>,
+    pub liquidity_score: Option<Decimal>,
     // Plausibility grade of the raw figures: "ok" | "suspect" | "unreliable".
     // None = not yet evaluated (backfilled by the healing pass).
     pub data_confidence: Option<String>,
@@ -61,6 +128,47 @@ pub struct FinancialDataDb {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+// One municipality-year's stored figures, as read for the metric distribution
+// behind percentile normalization (see db::financials::get_metric_inputs).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, FromRow)]
+pub struct MetricInputsDb {
+    pub classification: Option<String>,
+    pub revenue: Option<Decimal>,
+    pub operational_expenditure: Option<Decimal>,
+    pub capital_expenditure: Option<Decimal>,
+    pub debt: Option<Decimal>,
+    pub transfers_operational: Option<Decimal>,
+    pub uifw_expenditure: Option<Decimal>,
+    pub repairs_maintenance: Option<Decimal>,
+    pub cash_balance: Option<Decimal>,
+}
+
+// One stored cube cell from the 'raw_financial_facts' table: what a
+// financial_data row's figures were summed from (see db::raw_facts).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, FromRow)]
+pub struct RawFinancialFactDb {
+    pub cube: String, // e.g. "incexp_v2"
+    pub item_code: String,
+    pub item_label: String,
+    // "AUDA" | "ORGB" | "ADJB"; None for cubes without amount types (uifwexp)
+    pub amount_type: Option<String>,
+    pub amount: Option<f64>, // as the API returned it, so re-summing matches the original
+}
+
+impl RawFinancialFactDb {
+    /// Back into the API's cell shape, so the fetchers' summing functions apply.
+    pub fn to_item_fact(&self, municipality_id: &str) -> crate::api::muni_money::types::FinancialItemFact {
+        crate::api::muni_money::types::FinancialItemFact {
+            demarcation_code: municipality_id.to_string(),
+            demarcation_label: String::new(),
+            item_code: self.item_code.clone(),
+            item_label: self.item_label.clone(),
+            amount_type_code: self.amount_type.clone(),
+            amount: self.amount,
+        }
+    }
+}
+
 impl FinancialDataDb {
     /// True when the row carries at least one real metric or score. Rows that are
     /// all-NULL exist only as negative-cache markers and are not user-facing data.
@@ -86,23 +194,54 @@ impl From<&FinancialDataDb> for FinancialYearData {
             transfers_operational: row.transfers_operational,
             uifw_expenditure: row.uifw_expenditure,
             repairs_maintenance: row.repairs_maintenance,
+            cash_balance: row.cash_balance,
+            operating_surplus: row.operating_surplus,
+            amount_type: row.amount_type.clone(),
             overall_score: row.overall_score,
             financial_health_score: row.financial_health_score,
             infrastructure_score: row.infrastructure_score,
             efficiency_score: row.efficiency_score,
             accountability_score: row.accountability_score,
+            liquidity_score: row.liquidity_score,
+            grade: row.overall_score.map(|s| crate::scoring::grade_for_score(s).to_string()),
             data_confidence: row.data_confidence.clone(),
             confidence_notes: row.confidence_notes.clone(),
+            // Population lives on the municipality, not the row: see `with_per_capita`.
+            revenue_per_capita: None,
+            expenditure_per_capita: None,
+            debt_per_capita: None,
+            score_audit: None,
         }
     }
 }
 
+impl FinancialYearData {
+    /// Fills the per-capita figures from the municipality's population. They
+    /// stay `None` when the population is missing or zero.
+    pub fn with_per_capita(mut self, population: Option<f32>) -> Self {
+        let population = crate::scoring::population_count(population);
+        self.revenue_per_capita = crate::scoring::per_capita(self.revenue, population);
+        self.expenditure_per_capita = crate::scoring::per_capita(self.operational_expenditure, population);
+        self.debt_per_capita = crate::scoring::per_capita(self.debt, population);
+        self
+    }
+}
+
 
 // --- API Response / Query Result Models ---
 
-// Data structure for the /api/municipalities map view properties
-// Corresponds to data-spec.md section 3.1 properties
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Search result: just enough to label a municipality and link to its page.
+#[derive(Serialize, Deserialize, Debug, Clone, FromRow, ToSchema)]
+pub struct MunicipalityBasicInfo {
+    pub id: String,
+    pub name: String,
+    pub province: String,
+    pub classification: Option<String>,
+}
+
+/// Properties of one municipality on the /api/municipalities map (data-spec.md
+/// section 3.1), also the rows of the table view.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct MapMunicipalityProperties {
     pub id: String,
     pub name: String,
@@ -111,86 +250,555 @@ pub struct MapMunicipalityProperties {
     #[serde(serialize_with = "crate::utils::serialize_option_f32_as_f64")]
     pub population: Option<f32>,
     pub classification: Option<String>,
-    // Canonical name across the API: matches the financial_data column and the
-    // detail endpoint's field, and is what the map's data-driven styling reads.
+    /// Latest overall score. Canonical name across the API: matches the
+    /// financial_data column and the detail endpoint's field, and is what the
+    /// map's data-driven styling reads.
     #[serde(rename = "overall_score")]
     #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
     pub latest_score: Option<Decimal>,
+    /// Newest updated_at across the municipality's financial_data rows that
+    /// hold figures (ISO 8601), i.e. the last real fetch; null when none are
+    /// cached yet. Negative-cache rows don't count.
+    pub last_updated: Option<chrono::DateTime<chrono::Utc>>,
+    /// Label anchor as [lon, lat]: a point guaranteed inside the boundary
+    /// (ST_PointOnSurface), rounded to 6 decimals. Map features only; left
+    /// out elsewhere.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<f64>>)]
+    pub centroid: Option<[f64; 2]>,
+    /// Extent of the boundary as [min_lon, min_lat, max_lon, max_lat], for
+    /// fitting the map to it. Map features only; left out when there is no
+    /// boundary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<f64>>)]
+    pub bbox: Option<[f64; 4]>,
+    /// Geodesic area of the boundary in km²; left out with `bbox`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub area_km2: Option<f64>,
 }
 
-// Data structure for individual financial year data within MunicipalityDetail
-// Corresponds to data-spec.md section 3.2 financials array items
-#[derive(Serialize, Deserialize, Debug, Clone, Default, FromRow)]
+/// One financial year of a municipality (data-spec.md section 3.2 `financials`
+/// items). Rand amounts are exact numbers; scores are 0-100 and null when not
+/// computable.
+// Fields missing from stored JSON read as None (`default` on each custom
+// deserializer, as they would without one); `year` is required.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, FromRow, ToSchema)]
 pub struct FinancialYearData {
+    /// Financial year (year end).
     pub year: i32,
-    // Use Option<f64> for JSON compatibility, convert from Decimal
-    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    // Rand amounts are emitted as exact JSON numbers (no f64 round trip)
+    /// Operating revenue (rand).
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_exact",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
     pub revenue: Option<Decimal>,
-    // Rename this field as well
-    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    /// Operating expenditure (rand).
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_exact",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
     pub operational_expenditure: Option<Decimal>, 
-    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    /// Capital expenditure (rand).
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_exact",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
     pub capital_expenditure: Option<Decimal>, // Added this field
-    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    /// Total liabilities (rand).
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_exact",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
     pub debt: Option<Decimal>,
-    // Make audit_outcome optional
+    /// Auditor-General opinion label as published.
     pub audit_outcome: Option<String>,
     // Scoring v2 inputs (also useful for UI: own-revenue share, wasteful spend)
-    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    /// Operational grants received, part of revenue (rand).
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_exact",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
     pub transfers_operational: Option<Decimal>,
-    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    /// Unauthorised, irregular, fruitless & wasteful expenditure (rand).
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_exact",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
     pub uifw_expenditure: Option<Decimal>,
-    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    /// Repairs & maintenance spend (rand).
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_exact",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
     pub repairs_maintenance: Option<Decimal>,
+    /// Cash and equivalents at year end (rand); negative = overdraft.
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_exact",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
+    pub cash_balance: Option<Decimal>,
+    /// Revenue minus operational expenditure (rand); negative = deficit, null
+    /// when either figure is missing.
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_exact",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
+    pub operating_surplus: Option<Decimal>,
+    /// "AUDA" = audited actuals; "ORGB"/"ADJB" = budget figures used because
+    /// audited ones were not yet published. null for legacy rows.
+    pub amount_type: Option<String>,
     // Add the new score fields
-    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
     pub overall_score: Option<Decimal>,
-    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
     pub financial_health_score: Option<Decimal>,
-    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
     pub infrastructure_score: Option<Decimal>,
-    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
     pub efficiency_score: Option<Decimal>,
-    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
     pub accountability_score: Option<Decimal>,
-    // "ok" | "suspect" | "unreliable" | null (not yet evaluated)
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
+    pub liquidity_score: Option<Decimal>,
+    /// "A".."F" badge for overall_score (see scoring::grade_for_score); null without one.
+    #[schema(value_type = Option<crate::scoring::Grade>, inline)]
+    pub grade: Option<String>,
+    /// Plausibility grade of the raw figures: "ok" | "suspect" | "unreliable" |
+    /// null (not yet evaluated).
     pub data_confidence: Option<String>,
+    /// Why the figures were graded as they were.
     pub confidence_notes: Option<String>,
+    /// Figures divided by the municipality's population (rand); null without one.
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_exact",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
+    pub revenue_per_capita: Option<Decimal>,
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_exact",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
+    pub expenditure_per_capita: Option<Decimal>,
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_exact",
+        deserialize_with = "crate::utils::deserialize_option_decimal",
+        default
+    )]
+    pub debt_per_capita: Option<Decimal>,
+    /// Full derivation of the scores; only built for `?audit=true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(skip)]
+    pub score_audit: Option<ScoreAudit>,
 }
 
-// Detailed data structure for the /api/municipality/{id} view
-// Corresponds to data-spec.md section 3.2
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Detailed data for the /api/municipalities/{id} view (data-spec.md section 3.2).
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct MunicipalityDetail {
     pub id: String,
     pub name: String,
     pub province: String,
     // Convert population to Option<f64> for JSON
-    #[serde(serialize_with = "crate::utils::serialize_option_f32_as_f64")]
+    #[serde(
+        default,
+        serialize_with = "crate::utils::serialize_option_f32_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_f64_as_f32"
+    )]
     pub population: Option<f32>,
     pub classification: Option<String>,
     pub website: Option<String>,
-    // Add other fields from municipalities table as needed (address, phone, district...)
+    /// Contact and district details from the municipalities table; null when unknown.
+    pub address: Option<String>,
+    pub phone: Option<String>,
+    pub district_id: Option<String>,
+    pub district_name: Option<String>,
     pub financials: Vec<FinancialYearData>,
+    /// False when no metric at all is available (nothing published, or the
+    /// requested year is empty): `financials` is then [], and clients should
+    /// show "no data" rather than render missing scores as zeros. A year with
+    /// even one metric (e.g. only an audit outcome) counts as data.
+    pub has_financial_data: bool,
     // pub score_breakdown: Option<serde_json::Value>, // Placeholder if needed later
-    pub geometry: Option<serde_json::Value>, // Full geometry for single view
-    // Potentially add overall latest update timestamp if useful
-    // pub last_updated: Option<chrono::DateTime<chrono::Utc>>,
+    #[schema(value_type = Option<crate::openapi::GeoJsonGeometry>, inline)]
+    pub geometry: Option<Geometry>, // Full boundary for single view; None if not stored
+    /// Newest updated_at across the cached financial rows that hold figures
+    /// (ISO 8601), so users can tell how fresh the figures are; null when none
+    /// are cached. Negative-cache rows don't count.
+    pub last_updated: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Payload for POST /api/municipalities/batch: the detail of every id that
+/// resolved, plus the error each failed id would have got on its own, so one
+/// bad id doesn't sink the batch. Keyed by the id as requested.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
+pub struct BatchDetailResponse {
+    pub municipalities: BTreeMap<String, MunicipalityDetail>,
+    pub errors: BTreeMap<String, BatchDetailError>,
+}
+
+/// Same shape as the body of an error response (`AppError::error_response`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct BatchDetailError {
+    pub error: String,
+    pub code: String,
+}
+
+/// Lightweight payload for GET /api/municipalities/{id}/summary (map popups).
+/// Served from cached DB data only; score and audit outcome come from the
+/// latest scored year, matching the score the map colors by.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct MunicipalitySummary {
+    pub id: String,
+    pub name: String,
+    pub province: String,
+    #[serde(serialize_with = "crate::utils::serialize_option_f32_as_f64")]
+    pub population: Option<f32>,
+    pub year: Option<i32>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub overall_score: Option<Decimal>,
+    pub audit_outcome: Option<String>,
+}
+
+// Result of the stored-boundary validity check (GET /api/admin/geometry-validity).
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct GeometryValidity {
+    // Boundaries checked (rows with a geometry).
+    pub total: usize,
+    pub invalid: usize,
+    pub invalid_geometries: Vec<InvalidGeometry>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct InvalidGeometry {
+    pub id: String,
+    // ST_IsValidReason, e.g. "Self-intersection[18.4 -33.9]".
+    pub reason: String,
+}
+
+/// One result of GET /api/municipalities/nearest: the map properties plus the
+/// geodesic distance from the query point (0 when the point lies inside).
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct NearbyMunicipality {
+    #[serde(flatten)]
+    pub properties: MapMunicipalityProperties,
+    pub distance_km: f64,
+}
+
+/// One entry of GET /api/municipalities/{id}/years: a financial year with a
+/// cached row, and whether that row carries an overall score or raw figures only.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct FinancialYearAvailability {
+    pub year: i32,
+    pub has_score: bool,
+}
+
+/// Payload for GET /api/municipalities/{id}/percentile: where one year's overall
+/// score sits among every municipality scored for that year.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ScorePercentile {
+    pub municipality_id: String,
+    pub year: i32,
+    #[serde(serialize_with = "crate::utils::serialize_decimal_as_f64")]
+    pub overall_score: Decimal,
+    /// Percent (0-100, 2 dp) of the other scored municipalities with a strictly
+    /// lower score; tied municipalities share a percentile.
+    #[serde(serialize_with = "crate::utils::serialize_decimal_as_f64")]
+    pub percentile: Decimal,
+    /// 1 = best; ties share a rank (1, 2, 2, 4).
+    pub rank: i64,
+    /// Municipalities with an overall score for the year.
+    pub scored_count: i64,
+}
+
+/// Payload for GET /api/municipalities/{id}/breakdown: the line items behind
+/// one year's revenue or operational expenditure, largest first, fetched live
+/// from the individual incexp facts. `items` add up to `total`.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct FinancialBreakdown {
+    pub municipality_id: String,
+    pub year: i32,
+    /// "revenue" | "expenditure"
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Amount type the items were taken from ("AUDA", "ORGB", "ADJB"); null
+    /// when the year has no facts.
+    pub amount_type: Option<String>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub total: Option<Decimal>,
+    pub items: Vec<BreakdownItem>,
+}
+
+/// One item code of a breakdown.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct BreakdownItem {
+    pub item_code: String,
+    pub item_label: String,
+    #[serde(serialize_with = "crate::utils::serialize_decimal_as_f64")]
+    pub amount: Decimal,
+    /// Percent (0-100, 2 dp) of `total`; null when the total is zero.
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub share: Option<Decimal>,
 }
 
 // --- GeoJSON Structures for Map Summary ---
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct MapFeature {
     #[serde(rename = "type")]
     pub feature_type: String, // Should always be "Feature"
+    #[schema(value_type = Option<crate::openapi::GeoJsonGeometry>, inline)]
     pub geometry: Option<Geometry>, // Use geojson crate's Geometry type
     pub properties: MapMunicipalityProperties,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Body of GET /api/municipalities/{id}/geometry: one boundary as a GeoJSON
+/// Feature, labelled just enough to match it to the detail payload.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct MunicipalityGeometryFeature {
+    #[serde(rename = "type")]
+    pub feature_type: String, // Always "Feature"
+    #[schema(value_type = crate::openapi::GeoJsonGeometry, inline)]
+    pub geometry: Geometry,
+    pub properties: MunicipalityBasicInfo,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct MapFeatureCollection {
     #[serde(rename = "type")]
     pub collection_type: String, // Should always be "FeatureCollection"
+    /// GeoJSON bbox of the returned features (see `features_extent`), so a
+    /// client can set its viewport without scanning them; absent when empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<f64>>)]
+    pub bbox: Option<[f64; 4]>,
     pub features: Vec<MapFeature>,
-}
\ No newline at end of file
+    /// Paged requests only: matching municipalities before limit/offset (a
+    /// GeoJSON foreign member, ignored by map clients).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+}
+
+/// GET /api/municipalities?format=table: one page of the list as table rows
+/// (no geometry), with what a pager needs.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct MunicipalityListPage {
+    pub items: Vec<MapMunicipalityProperties>,
+    /// Matching municipalities before limit/offset.
+    pub total: i64,
+    /// The requested page size; null when unpaged (everything is one page).
+    pub limit: Option<i64>,
+    pub offset: i64,
+    /// True while rows remain past this page.
+    pub has_more: bool,
+}
+
+// Smallest [min_lon, min_lat, max_lon, max_lat] enclosing every feature's
+// own `bbox`; None when no feature has one.
+pub fn features_extent(features: &[MapFeature]) -> Option<[f64; 4]> {
+    features.iter().filter_map(|f| f.properties.bbox).reduce(|acc, b| {
+        [acc[0].min(b[0]), acc[1].min(b[1]), acc[2].max(b[2]), acc[3].max(b[3])]
+    })
+}
+
+/// One row of GET /api/provinces/summary: a province's municipalities rolled up
+/// over each one's latest scored year (or the requested year).
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ProvinceSummary {
+    pub province: String,
+    /// Municipalities in the province / those with a scored row counted below.
+    pub municipality_count: i64,
+    pub reporting_count: i64,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_exact")]
+    pub total_revenue: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_exact")]
+    pub total_operational_expenditure: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_exact")]
+    pub total_capital_expenditure: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_exact")]
+    pub total_debt: Option<Decimal>,
+    /// Unweighted mean of the reporting municipalities' overall scores, 2 dp.
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub average_score: Option<Decimal>,
+    /// Reporting municipalities per audit outcome as stored ("Unknown" if none).
+    pub audit_outcomes: BTreeMap<String, i64>,
+}
+
+/// One side of GET /api/municipalities/compare.
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct ComparedMunicipality {
+    pub id: String,
+    pub name: String,
+    pub province: String,
+    #[serde(serialize_with = "crate::utils::serialize_option_f32_as_f64")]
+    pub population: Option<f32>,
+    pub classification: Option<String>,
+    pub financials: FinancialYearData,
+    pub breakdown: ScoreBreakdown,
+}
+
+/// Two municipalities side by side for one financial year; `deltas` = a - b.
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct ComparisonResponse {
+    pub year: i32,
+    pub a: ComparedMunicipality,
+    pub b: ComparedMunicipality,
+    pub deltas: ScoreDeltas,
+}
+
+/// Listing direction for GET /api/rankings. Ranks are always numbered from the
+/// best score (1); `asc` just lists from the bottom of the table.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RankingOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// One leaderboard row.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct RankingEntry {
+    /// 1 = best; ties are broken by name (then id), so ranks are unique.
+    pub rank: i64,
+    pub id: String,
+    pub name: String,
+    pub province: String,
+    #[serde(serialize_with = "crate::utils::serialize_decimal_as_f64")]
+    pub overall_score: Decimal,
+}
+
+// One municipality's headline figures for a year, as exported in bulk.
+#[derive(Debug, Clone)]
+pub struct ScoreExportRow {
+    pub id: String,
+    pub name: String,
+    pub province: String,
+    pub classification: Option<String>,
+    pub revenue: Option<Decimal>,
+    pub debt: Option<Decimal>,
+    pub overall_score: Option<Decimal>,
+}
+
+/// Distribution of one score column across municipalities for a year. Each
+/// column is summarised over the rows where it is set, so `count` differs per
+/// pillar; the figures are null when no municipality has that score.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, ToSchema)]
+pub struct PillarStatistics {
+    pub count: i64,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub min: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub max: Option<Decimal>,
+    /// Mean and median, rounded to 2 dp; the median interpolates between the
+    /// two middle scores when the count is even.
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub average: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub median: Option<Decimal>,
+}
+
+/// Payload for GET /api/statistics. `year` is null only when nothing is scored yet.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, ToSchema)]
+pub struct ScoreStatistics {
+    pub year: Option<i32>,
+    pub overall: PillarStatistics,
+    pub financial_health: PillarStatistics,
+    pub infrastructure: PillarStatistics,
+    pub efficiency: PillarStatistics,
+    pub accountability: PillarStatistics,
+    pub liquidity: PillarStatistics,
+}
+
+/// One pillar of GET /api/municipalities/{id}/benchmarks: the municipality's
+/// score beside the mean over its province and over the country for the same
+/// year. Each mean is over the municipalities with that pillar scored, the
+/// municipality itself included; null when none are.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, ToSchema)]
+pub struct PillarBenchmark {
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub score: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub provincial_average: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub national_average: Option<Decimal>,
+}
+
+/// Payload for GET /api/municipalities/{id}/benchmarks.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct MunicipalityBenchmarks {
+    pub municipality_id: String,
+    pub province: String,
+    pub year: i32,
+    pub overall: PillarBenchmark,
+    pub financial_health: PillarBenchmark,
+    pub infrastructure: PillarBenchmark,
+    pub efficiency: PillarBenchmark,
+    pub accountability: PillarBenchmark,
+    pub liquidity: PillarBenchmark,
+}
+
+/// Payload for GET /api/rankings. `year` is null only when nothing is scored yet.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct RankingsResponse {
+    pub year: Option<i32>,
+    pub order: RankingOrder,
+    pub rankings: Vec<RankingEntry>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn municipality_ids_parse_to_upper_case_codes() {
+        for (input, expected) in [("CPT", "CPT"), ("wc011", "WC011"), ("Dc10", "DC10"), ("A", "A")] {
+            let id: MunicipalityId = input.parse().unwrap();
+            assert_eq!(id.as_str(), expected);
+        }
+        assert_eq!(MunicipalityId::try_from("buf".to_string()).unwrap().to_string(), "BUF");
+        assert_eq!(serde_json::to_value(MunicipalityId::try_from("CPT".to_string()).unwrap()).unwrap(), "CPT");
+    }
+
+    #[test]
+    fn malformed_municipality_ids_are_bad_requests() {
+        for input in ["", "CPT ", "WC-011", "C%27PT", "ÇPT", "ABCDEFGHIJK", "'; DROP TABLE"] {
+            let err = input.parse::<MunicipalityId>().unwrap_err();
+            assert!(matches!(err, crate::errors::AppError::BadRequest(_)), "{input:?}");
+        }
+    }
+}
@@ -0,0 +1,151 @@
+// src/openapi.rs
+
+// OpenAPI 3.0 description of the HTTP API, served at /api-docs/openapi.json
+// (with a Swagger UI at /swagger/). Derived with utoipa: the schemas come from
+// the model structs themselves (`ToSchema`, field docs as descriptions) and
+// each handler carries its own `#[utoipa::path]`. tests/openapi_test.rs still
+// checks fully-populated models against the schemas, so a custom serializer
+// that changes a field's JSON type fails CI.
+//
+// Decimal fields are described as `number` (utoipa's `decimal_float`), since
+// `utils::serialize_*decimal*` emit JSON numbers, never strings.
+//
+// Types named in `#[utoipa::path]` attributes (`body = ErrorResponse`) become
+// `$ref`s by name only: write them bare, without importing them into the
+// handler module (rustc would flag the import as unused), and register each
+// one under `components` below.
+
+use crate::errors::ErrorResponse;
+use crate::handlers::health::HealthStatus;
+use crate::handlers::municipalities::{BatchDetailRequest, ListFormat};
+use crate::handlers::{
+    export, health, metrics, municipalities, provinces, rankings, scoring as scoring_handlers, statistics,
+};
+use crate::models::{
+    BatchDetailError, BatchDetailResponse, BreakdownItem, ComparedMunicipality, ComparisonResponse, FinancialBreakdown,
+    FinancialYearAvailability, FinancialYearData, MapFeature, MapFeatureCollection, MapMunicipalityProperties,
+    MunicipalityBasicInfo, MunicipalityBenchmarks, MunicipalityDetail, MunicipalityGeometryFeature, MunicipalityListPage,
+    MunicipalitySummary, NearbyMunicipality, PillarBenchmark, PillarStatistics, ProvinceSummary, RankingEntry,
+    RankingOrder, RankingsResponse, ScorePercentile, ScoreStatistics,
+};
+use crate::scoring::{
+    AuditMethodology, AuditOutcomeScore, BlendWeights, ComponentAudit, Grade, GradeCutoff, NormalizationAnchors,
+    NormalizationRange, PeerGroup, PillarAudit, PillarWeights, ScoreAudit, ScoreBreakdown, ScoreDeltas,
+    ScoringMethodology,
+};
+use serde::Serialize;
+use utoipa::openapi::{ArrayBuilder, ObjectBuilder, RefOr, Schema, SchemaType};
+use utoipa::{OpenApi, ToSchema};
+
+/// GeoJSON geometry object (RFC 7946, WGS 84), the schema of the
+/// `geojson::Geometry` fields. Only describes the shape; never built.
+pub struct GeoJsonGeometry;
+
+impl<'s> ToSchema<'s> for GeoJsonGeometry {
+    fn schema() -> (&'s str, RefOr<Schema>) {
+        let kinds = ["Point", "MultiPoint", "LineString", "MultiLineString", "Polygon", "MultiPolygon", "GeometryCollection"];
+        let schema = ObjectBuilder::new()
+            .schema_type(SchemaType::Object)
+            .description(Some("GeoJSON geometry object (RFC 7946), WGS 84"))
+            .property("type", ObjectBuilder::new().schema_type(SchemaType::String).enum_values(Some(kinds)))
+            .required("type")
+            // Nesting depth depends on the type, so positions are left untyped.
+            .property("coordinates", ArrayBuilder::new().items(ObjectBuilder::new().schema_type(SchemaType::Value)))
+            .property("geometries", ArrayBuilder::new().items(ObjectBuilder::new().schema_type(SchemaType::Object)))
+            .into();
+        ("GeoJsonGeometry", schema)
+    }
+}
+
+/// Body of GET /api/municipalities: the map's FeatureCollection, or with
+/// `format=table` one page of rows. Only describes the response; the handler
+/// serializes either body itself.
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum MunicipalityList {
+    Map(MapFeatureCollection),
+    Table(MunicipalityListPage),
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "SeeMyCity API",
+        description = "Municipal financial health scores for South Africa, from National Treasury's Municipal Money data."
+    ),
+    paths(
+        municipalities::get_municipalities_list_handler,
+        municipalities::get_municipality_detail_handler,
+        municipalities::batch_municipality_detail_handler,
+        municipalities::get_municipality_summary_handler,
+        municipalities::get_municipality_years_handler,
+        municipalities::export_municipality_csv_handler,
+        municipalities::get_municipality_breakdown_handler,
+        municipalities::download_municipalities_geojson_handler,
+        municipalities::get_municipality_geometry_handler,
+        municipalities::search_municipalities_handler,
+        municipalities::get_municipality_percentile_handler,
+        municipalities::get_municipality_benchmarks_handler,
+        municipalities::get_nearest_municipalities_handler,
+        municipalities::compare_municipalities_handler,
+        provinces::get_province_summary_handler,
+        rankings::get_rankings_handler,
+        statistics::get_statistics_handler,
+        export::export_scores_csv_handler,
+        scoring_handlers::get_scoring_methodology_handler,
+        health::health_handler,
+        health::readiness_handler,
+        metrics::metrics_handler
+    ),
+    components(schemas(
+        ErrorResponse,
+        GeoJsonGeometry,
+        MunicipalityList,
+        ListFormat,
+        FinancialYearData,
+        ScoreAudit,
+        PillarAudit,
+        ComponentAudit,
+        PeerGroup,
+        Grade,
+        MunicipalityDetail,
+        MapMunicipalityProperties,
+        MapFeature,
+        MapFeatureCollection,
+        MunicipalityGeometryFeature,
+        MunicipalityListPage,
+        MunicipalitySummary,
+        FinancialYearAvailability,
+        MunicipalityBasicInfo,
+        BatchDetailRequest,
+        BatchDetailResponse,
+        BatchDetailError,
+        NearbyMunicipality,
+        ScorePercentile,
+        FinancialBreakdown,
+        BreakdownItem,
+        ScoreBreakdown,
+        ScoreDeltas,
+        ComparedMunicipality,
+        ComparisonResponse,
+        ProvinceSummary,
+        HealthStatus,
+        RankingOrder,
+        RankingEntry,
+        RankingsResponse,
+        PillarStatistics,
+        ScoreStatistics,
+        PillarBenchmark,
+        MunicipalityBenchmarks,
+        ScoringMethodology,
+        PillarWeights,
+        BlendWeights,
+        NormalizationAnchors,
+        NormalizationRange,
+        AuditMethodology,
+        AuditOutcomeScore,
+        GradeCutoff
+    ))
+)]
+pub struct ApiDoc;
+
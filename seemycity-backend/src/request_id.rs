@@ -0,0 +1,104 @@
+// src/request_id.rs
+
+// Per-request correlation id. The `assign_request_id` middleware takes the
+// caller's `X-Request-Id` (or generates one), keeps it in a task-local for the
+// duration of the request and echoes it on the response. `format_log_record`
+// prefixes every log line written inside the request with it, so the
+// handler's START/END lines and the API client's retries/failures for one
+// request can be grepped together.
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use std::future::Future;
+use std::io::Write;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest caller-supplied id that is reused as-is.
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// The id of the request being handled on this task, if any.
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok().filter(|id| !id.is_empty())
+}
+
+/// Runs `fut` under the current request's id. Task-locals don't cross
+/// `tokio::spawn`, so work spawned on behalf of a request is wrapped in this.
+pub fn inherit<F: Future>(fut: F) -> impl Future<Output = F::Output> {
+    REQUEST_ID.scope(current().unwrap_or_default(), fut)
+}
+
+/// Only short ids of `[A-Za-z0-9._-]` are taken from the caller; anything else
+/// could forge or break log lines, and is replaced by a fresh UUID.
+fn is_acceptable(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+/// Middleware (`middleware::from_fn`) assigning each request its correlation id.
+pub async fn assign_request_id(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_acceptable(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let header_value = HeaderValue::from_str(&id).expect("request ids are visible ASCII");
+
+    let mut response = REQUEST_ID.scope(id, next.call(req)).await?;
+    response
+        .headers_mut()
+        .insert(HeaderName::from_static(REQUEST_ID_HEADER), header_value);
+    Ok(response)
+}
+
+/// `env_logger` format: the default layout, plus `req=<id>` for lines logged
+/// while handling a request.
+pub fn format_log_record(buf: &mut env_logger::fmt::Formatter, record: &log::Record) -> std::io::Result<()> {
+    match current() {
+        Some(id) => writeln!(
+            buf,
+            "[{} {:<5} {} req={}] {}",
+            buf.timestamp(),
+            record.level(),
+            record.target(),
+            id,
+            record.args()
+        ),
+        None => writeln!(buf, "[{} {:<5} {}] {}", buf.timestamp(), record.level(), record.target(), record.args()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_short_plain_ids_are_accepted() {
+        assert!(is_acceptable("3f2c9a1e-7b7d-4c1e-9a47-0c1b2d3e4f50"));
+        assert!(is_acceptable("lb.trace_42"));
+        assert!(!is_acceptable(""));
+        assert!(!is_acceptable("forged] INFO other"));
+        assert!(!is_acceptable(&"a".repeat(MAX_REQUEST_ID_LEN + 1)));
+    }
+
+    #[tokio::test]
+    async fn inherit_carries_the_id_into_spawned_tasks() {
+        let seen = REQUEST_ID
+            .scope("abc".to_string(), async { tokio::spawn(inherit(async { current() })).await.unwrap() })
+            .await;
+        assert_eq!(seen.as_deref(), Some("abc"));
+        assert_eq!(current(), None);
+    }
+}
@@ -1,21 +1,55 @@
 use rust_decimal::{Decimal, RoundingStrategy};
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal_macros::dec;
 use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use crate::confidence::CONFIDENCE_UNRELIABLE;
+use crate::models::FinancialDataDb;
+use crate::utils::head_count;
+use std::borrow::Cow;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use utoipa::ToSchema;
 
 /// Version stamp persisted with every scored row. Bump on any formula or
-/// anchor change: the healing pass re-derives rows whose stored version
-/// differs, migrating the whole cache lazily without upstream calls.
+/// default-anchor change: the healing pass re-derives rows whose stored version
+/// differs, migrating the whole cache lazily without upstream calls. Rows
+/// scored under a SCORING_THRESHOLDS_FILE carry `score_version_for` those
+/// anchors instead.
 ///
 /// v2 (2026-07): own-revenue share replaces revenue-per-capita in Financial
 /// Health; repairs & maintenance joins Infrastructure; UIFW joins
 /// Accountability; unreliable-quality figures suppress raw-derived pillars.
-pub const SCORE_VERSION: i32 = 2;
-
-// Pillar weights (must sum to 1.0)
-const WEIGHT_FIN_HEALTH: Decimal = dec!(0.30);
-const WEIGHT_INFRA: Decimal = dec!(0.25);
-const WEIGHT_EFFICIENCY: Decimal = dec!(0.25);
-const WEIGHT_ACCOUNTABILITY: Decimal = dec!(0.20);
+///
+/// v3 (2026-10): Liquidity (cash coverage) joins as a fifth pillar.
+///
+/// v4 (2026-10): own-revenue share is normalized against the municipality's
+/// peer group (metro / local / district) rather than one national band.
+///
+/// v5 (2026-10): an outstanding audit ("not yet released") is no opinion rather
+/// than an earned 0; a missing Accountability pillar drops out of the weighting.
+///
+/// v6 (2026-10): any missing pillar drops out of the weighting, provided the
+/// scored pillars carry at least `MIN_SCORED_WEIGHT` (see `MissingPillarPolicy`).
+///
+/// v7 (2026-10): the debt ratio is taken against own revenue (revenue less
+/// operational transfers) when transfers are reported.
+pub const SCORE_VERSION: i32 = 7;
+
+// Pillar weights (must sum to 1.0). Without cash data the Liquidity weight is
+// dropped and the rest renormalized; these are chosen so that renormalization
+// lands exactly on the v2 four-pillar weights (30/25/25/20), leaving scores of
+// municipalities without cash data unchanged.
+const WEIGHT_FIN_HEALTH: Decimal = dec!(0.24);
+const WEIGHT_INFRA: Decimal = dec!(0.20);
+const WEIGHT_EFFICIENCY: Decimal = dec!(0.20);
+const WEIGHT_ACCOUNTABILITY: Decimal = dec!(0.16);
+const WEIGHT_LIQUIDITY: Decimal = dec!(0.20);
+
+// Share of the total pillar weight that must be scored for an overall score
+// under `MissingPillarPolicy::Renormalize`. Keeps e.g. an audit-only year (all
+// raw figures unreliable) from being ranked on 16% of the rubric.
+const MIN_SCORED_WEIGHT: Decimal = dec!(0.5);
 
 // Normalization ranges, tuned against real AUDA data (see docs/prd.md scoring rubric)
 const DEBT_RATIO_MIN: Decimal = dec!(0.1); // Score 100 at or below this ratio
@@ -29,6 +63,21 @@ const DEBT_RATIO_MAX: Decimal = dec!(1.0); // Score 0 at or above this ratio
 const OWN_REVENUE_SHARE_MIN: Decimal = dec!(0.25);
 const OWN_REVENUE_SHARE_MAX: Decimal = dec!(0.75);
 
+// Peer-group bands for own-revenue share. The national band above suits local
+// municipalities (category B) and is the fallback for an unknown class.
+// Metros (category A) run every trading service and raise 70-90% themselves,
+// so a share that is excellent for a rural local is weak for a metro. District
+// municipalities (category C) hold few revenue-raising powers and live largely
+// on the equitable share, so the bar sits far lower.
+const OWN_REVENUE_SHARE_METRO_MIN: Decimal = dec!(0.60);
+const OWN_REVENUE_SHARE_METRO_MAX: Decimal = dec!(0.90);
+const OWN_REVENUE_SHARE_DISTRICT_MIN: Decimal = dec!(0.05);
+const OWN_REVENUE_SHARE_DISTRICT_MAX: Decimal = dec!(0.40);
+
+// Sub-score weights within Financial Health (must sum to 1.0).
+const FH_OWN_REVENUE_WEIGHT: Decimal = dec!(0.5);
+const FH_DEBT_RATIO_WEIGHT: Decimal = dec!(0.5);
+
 // Efficiency (OpEx/Revenue) thresholds: linear 100 -> 0 across [BEST, WORST],
 // so break-even (ratio 1.0) lands exactly at 50.
 const EFFICIENCY_RATIO_BEST: Decimal = dec!(0.85); // Score 100
@@ -47,11 +96,529 @@ const UIFW_RATIO_WORST: Decimal = dec!(0.10);
 // Weight of UIFW within the Accountability pillar when reported.
 const ACC_UIFW_WEIGHT: Decimal = dec!(0.30);
 
+// Liquidity: cash coverage in months of operating spend. Treasury's MFMA
+// Circular 71 norm is 1-3 months; below one month a municipality cannot meet
+// its next payroll from cash (0), three months or more is comfortable (100).
+const LIQUIDITY_MONTHS_MIN: Decimal = dec!(1); // Score 0 at or below
+const LIQUIDITY_MONTHS_MAX: Decimal = dec!(3); // Score 100 at or above
+
 // Define thresholds for Infrastructure Score normalization
 const INFRA_RATIO_WORST: Decimal = dec!(0.00); // Score 0
 const INFRA_RATIO_MID: Decimal = dec!(0.10); // Score 50
 const INFRA_RATIO_BEST: Decimal = dec!(0.30); // Score 100
 
+// Percentile normalization (`Normalization::Percentile`): a metric's sub-score
+// follows its rank in the year's national distribution, 0 at or below the
+// 10th percentile and 100 at or above the 90th, linear between. Saturating the
+// tails keeps a handful of outliers from stretching the scale. A metric with
+// fewer values than PERCENTILE_MIN_SAMPLE is too thin to rank against and
+// keeps its fixed anchors.
+const PERCENTILE_SCORE_0_AT: Decimal = dec!(0.10);
+const PERCENTILE_SCORE_100_AT: Decimal = dec!(0.90);
+const PERCENTILE_MIN_SAMPLE: usize = 20;
+
+// Letter-grade cut-offs: each grade's lower bound is inclusive.
+const GRADE_A_MIN: Decimal = dec!(80);
+const GRADE_B_MIN: Decimal = dec!(65);
+const GRADE_C_MIN: Decimal = dec!(50);
+const GRADE_D_MIN: Decimal = dec!(35);
+// Highest grade first; anything below the last is an F.
+const GRADE_CUTOFFS: [(Grade, Decimal); 4] =
+    [(Grade::A, GRADE_A_MIN), (Grade::B, GRADE_B_MIN), (Grade::C, GRADE_C_MIN), (Grade::D, GRADE_D_MIN)];
+
+/// The normalization anchors of the scoring model: where each metric scores 0
+/// and 100. `DEFAULT` is the built-in calibration (the constants above);
+/// SCORING_THRESHOLDS_FILE can replace any of them at startup when they are
+/// recalibrated (see `from_file` and `set_thresholds`). The weights are not
+/// part of this.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoringThresholds {
+    pub debt_ratio_min: Decimal,
+    pub debt_ratio_max: Decimal,
+    pub own_revenue_share_min: Decimal,
+    pub own_revenue_share_max: Decimal,
+    pub own_revenue_share_metro_min: Decimal,
+    pub own_revenue_share_metro_max: Decimal,
+    pub own_revenue_share_district_min: Decimal,
+    pub own_revenue_share_district_max: Decimal,
+    pub efficiency_ratio_best: Decimal,
+    pub efficiency_ratio_worst: Decimal,
+    pub rm_intensity_max: Decimal,
+    pub uifw_ratio_worst: Decimal,
+    pub infra_ratio_worst: Decimal,
+    pub infra_ratio_mid: Decimal,
+    pub infra_ratio_best: Decimal,
+    pub liquidity_months_min: Decimal,
+    pub liquidity_months_max: Decimal,
+}
+
+impl ScoringThresholds {
+    pub const DEFAULT: ScoringThresholds = ScoringThresholds {
+        debt_ratio_min: DEBT_RATIO_MIN,
+        debt_ratio_max: DEBT_RATIO_MAX,
+        own_revenue_share_min: OWN_REVENUE_SHARE_MIN,
+        own_revenue_share_max: OWN_REVENUE_SHARE_MAX,
+        own_revenue_share_metro_min: OWN_REVENUE_SHARE_METRO_MIN,
+        own_revenue_share_metro_max: OWN_REVENUE_SHARE_METRO_MAX,
+        own_revenue_share_district_min: OWN_REVENUE_SHARE_DISTRICT_MIN,
+        own_revenue_share_district_max: OWN_REVENUE_SHARE_DISTRICT_MAX,
+        efficiency_ratio_best: EFFICIENCY_RATIO_BEST,
+        efficiency_ratio_worst: EFFICIENCY_RATIO_WORST,
+        rm_intensity_max: RM_INTENSITY_MAX,
+        uifw_ratio_worst: UIFW_RATIO_WORST,
+        infra_ratio_worst: INFRA_RATIO_WORST,
+        infra_ratio_mid: INFRA_RATIO_MID,
+        infra_ratio_best: INFRA_RATIO_BEST,
+        liquidity_months_min: LIQUIDITY_MONTHS_MIN,
+        liquidity_months_max: LIQUIDITY_MONTHS_MAX,
+    };
+
+    /// Checks every normalization range is non-empty and ordered (min below
+    /// max, worst/mid/best ascending), shares stay within [0, 1] and the
+    /// single-anchor metrics have a positive anchor. Returns every violation.
+    pub fn validate(&self) -> Result<(), Vec<ScoringConfigError>> {
+        let mut errors = Vec::new();
+        let mut fail = |field: &str, message: String| {
+            errors.push(ScoringConfigError { field: field.to_string(), message });
+        };
+
+        let ascending = [
+            ("debt_ratio", "debt_ratio_min", self.debt_ratio_min, "debt_ratio_max", self.debt_ratio_max),
+            (
+                "own_revenue_share",
+                "own_revenue_share_min",
+                self.own_revenue_share_min,
+                "own_revenue_share_max",
+                self.own_revenue_share_max,
+            ),
+            (
+                "own_revenue_share_metro",
+                "own_revenue_share_metro_min",
+                self.own_revenue_share_metro_min,
+                "own_revenue_share_metro_max",
+                self.own_revenue_share_metro_max,
+            ),
+            (
+                "own_revenue_share_district",
+                "own_revenue_share_district_min",
+                self.own_revenue_share_district_min,
+                "own_revenue_share_district_max",
+                self.own_revenue_share_district_max,
+            ),
+            (
+                "efficiency_ratio",
+                "efficiency_ratio_best",
+                self.efficiency_ratio_best,
+                "efficiency_ratio_worst",
+                self.efficiency_ratio_worst,
+            ),
+            ("infra_ratio", "infra_ratio_worst", self.infra_ratio_worst, "infra_ratio_mid", self.infra_ratio_mid),
+            ("infra_ratio", "infra_ratio_mid", self.infra_ratio_mid, "infra_ratio_best", self.infra_ratio_best),
+            (
+                "liquidity_months",
+                "liquidity_months_min",
+                self.liquidity_months_min,
+                "liquidity_months_max",
+                self.liquidity_months_max,
+            ),
+        ];
+        for (field, low_name, low, high_name, high) in ascending {
+            if low >= high {
+                fail(field, format!("{low_name} ({low}) must be less than {high_name} ({high})"));
+            }
+        }
+
+        let non_negative = [
+            ("debt_ratio_min", self.debt_ratio_min),
+            ("own_revenue_share_min", self.own_revenue_share_min),
+            ("own_revenue_share_metro_min", self.own_revenue_share_metro_min),
+            ("own_revenue_share_district_min", self.own_revenue_share_district_min),
+            ("efficiency_ratio_best", self.efficiency_ratio_best),
+            ("infra_ratio_worst", self.infra_ratio_worst),
+            ("liquidity_months_min", self.liquidity_months_min),
+        ];
+        for (field, value) in non_negative {
+            if value < Decimal::ZERO {
+                fail(field, format!("must not be negative, got {value}"));
+            }
+        }
+        for (field, value) in [
+            ("own_revenue_share_max", self.own_revenue_share_max),
+            ("own_revenue_share_metro_max", self.own_revenue_share_metro_max),
+            ("own_revenue_share_district_max", self.own_revenue_share_district_max),
+        ] {
+            if value > Decimal::ONE {
+                fail(field, format!("a share cannot exceed 1, got {value}"));
+            }
+        }
+        for (field, value) in [("rm_intensity_max", self.rm_intensity_max), ("uifw_ratio_worst", self.uifw_ratio_worst)] {
+            if value <= Decimal::ZERO {
+                fail(field, format!("must be greater than 0, got {value}"));
+            }
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Loads and validates thresholds from a JSON file, e.g.
+    /// `{ "debt_ratio_max": "1.5" }` (decimals as strings, as in
+    /// `ScoringConfig`). Fields left out keep their default, so a file only
+    /// needs the anchors being recalibrated.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read scoring thresholds file {}: {e}", path.display()))?;
+        let thresholds: ScoringThresholds = serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid scoring thresholds file {}: {e}", path.display()))?;
+        thresholds.validate().map_err(|errors| {
+            format!("Invalid scoring thresholds file {}: {}", path.display(), describe_errors(&errors))
+        })?;
+        log::info!("Scoring thresholds loaded from {}", path.display());
+        Ok(thresholds)
+    }
+
+    /// FNV-1a over every anchor as `name=value`, sorted by name, with trailing
+    /// zeros dropped ("1.50" and "1.5" are the same anchor). Stable across
+    /// builds, unlike `std::hash`.
+    fn fingerprint(&self) -> u32 {
+        let serde_json::Value::Object(fields) = serde_json::to_value(self).unwrap_or_default() else {
+            return 0;
+        };
+        let mut anchors: Vec<String> = fields
+            .iter()
+            .map(|(name, value)| {
+                let text = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+                let value = text.parse::<Decimal>().map(|d| d.normalize().to_string()).unwrap_or(text);
+                format!("{name}={value}")
+            })
+            .collect();
+        anchors.sort();
+        anchors.join(";").bytes().fold(0x811c_9dc5, |hash, byte| (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193))
+    }
+
+    /// (min, max) own-revenue share anchors for the peer group. An unknown
+    /// group gets the national band.
+    fn own_revenue_band(&self, peer_group: Option<PeerGroup>) -> (Decimal, Decimal) {
+        match peer_group {
+            Some(PeerGroup::Metro) => (self.own_revenue_share_metro_min, self.own_revenue_share_metro_max),
+            Some(PeerGroup::District) => (self.own_revenue_share_district_min, self.own_revenue_share_district_max),
+            Some(PeerGroup::Local) | None => (self.own_revenue_share_min, self.own_revenue_share_max),
+        }
+    }
+}
+
+impl Default for ScoringThresholds {
+    fn default() -> Self {
+        ScoringThresholds::DEFAULT
+    }
+}
+
+fn describe_errors(errors: &[ScoringConfigError]) -> String {
+    errors.iter().map(|e| format!("{}: {}", e.field, e.message)).collect::<Vec<_>>().join("; ")
+}
+
+static THRESHOLDS: OnceLock<ScoringThresholds> = OnceLock::new();
+
+/// Installs the thresholds `calculate_financial_score` and
+/// `explain_financial_score` apply for the rest of the process. Called once at
+/// startup: scoring runs deep inside the services and the cache warmer, so the
+/// anchors are process-wide, like the metrics registry. Invalid thresholds
+/// and a second call are refused.
+pub fn set_thresholds(thresholds: ScoringThresholds) -> Result<(), String> {
+    thresholds.validate().map_err(|errors| describe_errors(&errors))?;
+    THRESHOLDS.set(thresholds).map_err(|_| "Scoring thresholds are already set".to_string())
+}
+
+/// The thresholds in force: those installed at startup, else the defaults.
+pub fn thresholds() -> &'static ScoringThresholds {
+    THRESHOLDS.get().unwrap_or(&ScoringThresholds::DEFAULT)
+}
+
+// Overridden anchors are stamped `SCORE_VERSION + SCORE_VERSION_STEP × n`,
+// with n a fingerprint of the anchors in 1..=THRESHOLDS_FINGERPRINTS; the
+// largest stamp still fits the i32 score_version column.
+const SCORE_VERSION_STEP: i32 = 100;
+const THRESHOLDS_FINGERPRINTS: u32 = 10_000_000;
+// Added to the stamp of rows scored under `MissingPillarPolicy::RequireRawPillars`,
+// past every fingerprinted stamp; the sum of both still fits the column.
+const REQUIRE_RAW_PILLARS_OFFSET: i32 = SCORE_VERSION_STEP * (THRESHOLDS_FINGERPRINTS as i32 + 1);
+const _: () = assert!(SCORE_VERSION < SCORE_VERSION_STEP);
+const _: () = assert!(
+    (REQUIRE_RAW_PILLARS_OFFSET as i64) * 2 + (SCORE_VERSION as i64) <= i32::MAX as i64,
+    "stamps must fit the i32 score_version column"
+);
+
+/// The score version of rows scored with `thresholds` under the missing-pillar
+/// policy in force (see `score_version_with`).
+pub fn score_version_for(thresholds: &ScoringThresholds) -> i32 {
+    score_version_with(thresholds, missing_pillar_policy())
+}
+
+/// The score version of rows scored with `thresholds` and `policy`. The
+/// default anchors give `SCORE_VERSION`; any other set gives a version derived
+/// from the anchors themselves, so installing, changing or dropping a
+/// SCORING_THRESHOLDS_FILE makes score healing re-derive the rows scored under
+/// the previous anchors, as a version bump does. `RequireRawPillars` shifts
+/// the stamp again, so switching MISSING_PILLAR_POLICY heals the same way.
+/// `version % 100` is always the formula version.
+pub fn score_version_with(thresholds: &ScoringThresholds, policy: MissingPillarPolicy) -> i32 {
+    let policy_offset = match policy {
+        MissingPillarPolicy::Renormalize => 0,
+        MissingPillarPolicy::RequireRawPillars => REQUIRE_RAW_PILLARS_OFFSET,
+    };
+    if *thresholds == ScoringThresholds::DEFAULT {
+        return SCORE_VERSION + policy_offset;
+    }
+    let n = thresholds.fingerprint() % THRESHOLDS_FINGERPRINTS + 1;
+    SCORE_VERSION + SCORE_VERSION_STEP * n as i32 + policy_offset
+}
+
+/// The score version stamped on rows scored with the thresholds in force.
+pub fn score_version() -> i32 {
+    score_version_for(thresholds())
+}
+
+/// Every tunable weight and normalization anchor of the scoring model. The
+/// `Default` is the model `calculate_financial_score` applies today; an operator
+/// can dry-run a proposed replacement through `validate` before adopting it.
+/// `thresholds` takes the SCORING_THRESHOLDS_FILE format, so anchors left out
+/// keep their default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScoringConfig {
+    pub weight_financial_health: Decimal,
+    pub weight_infrastructure: Decimal,
+    pub weight_efficiency: Decimal,
+    pub weight_accountability: Decimal,
+    pub weight_liquidity: Decimal,
+    pub infra_rm_weight: Decimal,
+    pub acc_uifw_weight: Decimal,
+    pub thresholds: ScoringThresholds,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        ScoringConfig::with_thresholds(&ScoringThresholds::DEFAULT)
+    }
+}
+
+/// One rule a `ScoringConfig` breaks: the offending field and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScoringConfigError {
+    pub field: String,
+    pub message: String,
+}
+
+impl ScoringConfig {
+    /// The built-in weights with `thresholds` as the anchors: the model
+    /// scored while those thresholds are in force.
+    pub fn with_thresholds(thresholds: &ScoringThresholds) -> Self {
+        ScoringConfig {
+            weight_financial_health: WEIGHT_FIN_HEALTH,
+            weight_infrastructure: WEIGHT_INFRA,
+            weight_efficiency: WEIGHT_EFFICIENCY,
+            weight_accountability: WEIGHT_ACCOUNTABILITY,
+            weight_liquidity: WEIGHT_LIQUIDITY,
+            infra_rm_weight: INFRA_RM_WEIGHT,
+            acc_uifw_weight: ACC_UIFW_WEIGHT,
+            thresholds: thresholds.clone(),
+        }
+    }
+
+    /// Checks the config is usable: pillar weights in [0, 1] summing to 1,
+    /// blend weights in [0, 1], and every normalization range non-empty and
+    /// ordered (min below max, worst/mid/best ascending). Returns every
+    /// violation, not just the first, so an operator can fix them in one go.
+    pub fn validate(&self) -> Result<(), Vec<ScoringConfigError>> {
+        let mut errors = Vec::new();
+        let mut fail = |field: &str, message: String| {
+            errors.push(ScoringConfigError { field: field.to_string(), message });
+        };
+
+        let weights = [
+            ("weight_financial_health", self.weight_financial_health),
+            ("weight_infrastructure", self.weight_infrastructure),
+            ("weight_efficiency", self.weight_efficiency),
+            ("weight_accountability", self.weight_accountability),
+            ("weight_liquidity", self.weight_liquidity),
+            ("infra_rm_weight", self.infra_rm_weight),
+            ("acc_uifw_weight", self.acc_uifw_weight),
+        ];
+        for (field, weight) in weights {
+            if weight < Decimal::ZERO || weight > Decimal::ONE {
+                fail(field, format!("must be between 0 and 1, got {weight}"));
+            }
+        }
+        let pillar_sum: Decimal = weights[..5].iter().map(|(_, w)| *w).sum();
+        if pillar_sum != Decimal::ONE {
+            fail("weights", format!("pillar weights must sum to 1, got {pillar_sum}"));
+        }
+
+        if let Err(threshold_errors) = self.thresholds.validate() {
+            errors.extend(threshold_errors);
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// The scoring model as data, for `GET /api/scoring/methodology`: the weights
+/// and anchors of a `ScoringConfig` plus the fixed parts of the model (blend
+/// weights, audit ladder, grade cut-offs), so explanations shown to users are
+/// read from the same values the computation uses. Decimals are exact numbers.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ScoringMethodology {
+    pub score_version: i32,
+    pub weights: PillarWeights,
+    /// Share of the pillar weight that must be scored for an overall score.
+    #[serde(serialize_with = "crate::utils::serialize_decimal_exact")]
+    pub min_scored_weight: Decimal,
+    pub blend_weights: BlendWeights,
+    pub thresholds: NormalizationAnchors,
+    pub audit: AuditMethodology,
+    /// Inclusive lower bound of each grade, highest first; below the last is an F.
+    pub grade_cutoffs: Vec<GradeCutoff>,
+}
+
+/// Nominal pillar weights, summing to 1.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct PillarWeights {
+    #[serde(serialize_with = "crate::utils::serialize_decimal_exact")]
+    pub financial_health: Decimal,
+    #[serde(serialize_with = "crate::utils::serialize_decimal_exact")]
+    pub infrastructure: Decimal,
+    #[serde(serialize_with = "crate::utils::serialize_decimal_exact")]
+    pub efficiency: Decimal,
+    #[serde(serialize_with = "crate::utils::serialize_decimal_exact")]
+    pub accountability: Decimal,
+    #[serde(serialize_with = "crate::utils::serialize_decimal_exact")]
+    pub liquidity: Decimal,
+}
+
+/// Weight of each secondary metric within its pillar; the primary metric
+/// carries the rest. R&M and UIFW only count when reported.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct BlendWeights {
+    #[serde(serialize_with = "crate::utils::serialize_decimal_exact")]
+    pub financial_health_debt_ratio: Decimal,
+    #[serde(serialize_with = "crate::utils::serialize_decimal_exact")]
+    pub infrastructure_rm_intensity: Decimal,
+    #[serde(serialize_with = "crate::utils::serialize_decimal_exact")]
+    pub accountability_uifw: Decimal,
+}
+
+/// Where each metric scores 0 and 100 (linear in between, clamped outside),
+/// in the same terms as the `score_audit` components.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct NormalizationAnchors {
+    pub debt_ratio: NormalizationRange,
+    pub own_revenue_share: NormalizationRange,
+    pub own_revenue_share_metro: NormalizationRange,
+    pub own_revenue_share_district: NormalizationRange,
+    pub efficiency_ratio: NormalizationRange,
+    pub capex_share: NormalizationRange,
+    pub rm_intensity: NormalizationRange,
+    pub uifw_ratio: NormalizationRange,
+    pub cash_cover_months: NormalizationRange,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct NormalizationRange {
+    #[serde(serialize_with = "crate::utils::serialize_decimal_exact")]
+    pub score_0_at: Decimal,
+    /// Only for piecewise-linear metrics (capex share).
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "crate::utils::serialize_option_decimal_exact"
+    )]
+    pub score_50_at: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_decimal_exact")]
+    pub score_100_at: Decimal,
+}
+
+impl NormalizationRange {
+    fn linear(score_0_at: Decimal, score_100_at: Decimal) -> Self {
+        NormalizationRange { score_0_at, score_50_at: None, score_100_at }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct AuditMethodology {
+    /// Base sub-score per opinion; `null` for outcomes that are not scored.
+    pub outcome_scores: Vec<AuditOutcomeScore>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct AuditOutcomeScore {
+    #[schema(value_type = String)]
+    pub outcome: &'static str,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_exact")]
+    pub score: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct GradeCutoff {
+    pub grade: Grade,
+    #[serde(serialize_with = "crate::utils::serialize_decimal_exact")]
+    pub min_score: Decimal,
+}
+
+/// Describes the model scored with `config`.
+pub fn methodology(config: &ScoringConfig) -> ScoringMethodology {
+    let anchors = &config.thresholds;
+    let outcome_scores = [
+        AuditOutcome::Clean,
+        AuditOutcome::FinanciallyUnqualified,
+        AuditOutcome::Qualified,
+        AuditOutcome::Adverse,
+        AuditOutcome::Disclaimer,
+        AuditOutcome::Outstanding,
+    ]
+    .iter()
+    .map(|outcome| AuditOutcomeScore { outcome: outcome.key(), score: outcome.base_score() })
+    .collect();
+    ScoringMethodology {
+        score_version: score_version_for(&config.thresholds),
+        weights: PillarWeights {
+            financial_health: config.weight_financial_health,
+            infrastructure: config.weight_infrastructure,
+            efficiency: config.weight_efficiency,
+            accountability: config.weight_accountability,
+            liquidity: config.weight_liquidity,
+        },
+        min_scored_weight: MIN_SCORED_WEIGHT,
+        blend_weights: BlendWeights {
+            financial_health_debt_ratio: FH_DEBT_RATIO_WEIGHT,
+            infrastructure_rm_intensity: config.infra_rm_weight,
+            accountability_uifw: config.acc_uifw_weight,
+        },
+        thresholds: NormalizationAnchors {
+            debt_ratio: NormalizationRange::linear(anchors.debt_ratio_max, anchors.debt_ratio_min),
+            own_revenue_share: NormalizationRange::linear(anchors.own_revenue_share_min, anchors.own_revenue_share_max),
+            own_revenue_share_metro: NormalizationRange::linear(
+                anchors.own_revenue_share_metro_min,
+                anchors.own_revenue_share_metro_max,
+            ),
+            own_revenue_share_district: NormalizationRange::linear(
+                anchors.own_revenue_share_district_min,
+                anchors.own_revenue_share_district_max,
+            ),
+            efficiency_ratio: NormalizationRange::linear(anchors.efficiency_ratio_worst, anchors.efficiency_ratio_best),
+            capex_share: NormalizationRange {
+                score_0_at: anchors.infra_ratio_worst,
+                score_50_at: Some(anchors.infra_ratio_mid),
+                score_100_at: anchors.infra_ratio_best,
+            },
+            rm_intensity: NormalizationRange::linear(Decimal::ZERO, anchors.rm_intensity_max),
+            uifw_ratio: NormalizationRange::linear(anchors.uifw_ratio_worst, Decimal::ZERO),
+            cash_cover_months: NormalizationRange::linear(anchors.liquidity_months_min, anchors.liquidity_months_max),
+        },
+        audit: AuditMethodology { outcome_scores },
+        grade_cutoffs: GRADE_CUTOFFS.iter().map(|(grade, min)| GradeCutoff { grade: *grade, min_score: *min }).collect(),
+    }
+}
+
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct ScoringInput {
     pub revenue: Option<Decimal>,
@@ -60,29 +627,166 @@ pub struct ScoringInput {
     pub debt: Option<Decimal>,
     pub audit_outcome: Option<String>,
     pub population: Option<u32>,
+    /// Municipal category from `municipalities.classification` (A/B/C or
+    /// Metro/Local/District); selects the peer-group normalization band.
+    pub classification: Option<String>,
     /// Operational grants received (part of revenue); basis for own-revenue share.
     pub transfers_operational: Option<Decimal>,
     /// Unauthorised/irregular/fruitless & wasteful expenditure; None = not reported.
     pub uifw_expenditure: Option<Decimal>,
     /// Repairs & maintenance spend; None = not reported.
     pub repairs_maintenance: Option<Decimal>,
+    /// Cash and cash equivalents at year end; None = not reported.
+    pub cash_balance: Option<Decimal>,
     /// When the data-confidence layer graded the raw figures `unreliable`,
     /// pillars derived from them are suppressed (None) rather than computed
     /// from artifacts — e.g. negative debt must not earn a perfect debt score.
     pub data_unreliable: bool,
+    /// Fixed anchors (the default) or rank within a year's distribution.
+    pub normalization: Normalization,
+}
+
+impl ScoringInput {
+    /// The input a stored row was scored from: its raw figures and confidence
+    /// grade, plus the municipality-level `population` and `classification`
+    /// the row does not carry. Scoring it reproduces the row's stored scores
+    /// under the current formula, as score healing and `rescore_all` do.
+    pub fn from_db_record(record: &FinancialDataDb, population: Option<f32>, classification: Option<&str>) -> Self {
+        ScoringInput {
+            revenue: record.revenue,
+            operational_expenditure: record.operational_expenditure,
+            capital_expenditure: record.capital_expenditure,
+            debt: record.debt,
+            audit_outcome: record.audit_outcome.clone(),
+            population: population_count(population),
+            classification: classification.map(str::to_string),
+            transfers_operational: record.transfers_operational,
+            uifw_expenditure: record.uifw_expenditure,
+            repairs_maintenance: record.repairs_maintenance,
+            cash_balance: record.cash_balance,
+            data_unreliable: record.data_confidence.as_deref() == Some(CONFIDENCE_UNRELIABLE),
+            // Fixed anchors; callers scoring as stored set the mode in force
+            // (`services::normalization`).
+            normalization: Normalization::Linear,
+        }
+    }
+
+    /// Revenue less operational transfers — what the municipality raises
+    /// itself. None unless both are reported and the difference is positive.
+    pub fn own_revenue(&self) -> Option<Decimal> {
+        own_revenue(self.revenue, self.transfers_operational)
+    }
+}
+
+/// Peer group a municipality is normalized against, from its category under
+/// the Municipal Structures Act.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PeerGroup {
+    Metro,    // Category A
+    Local,    // Category B (B1-B4)
+    District, // Category C (C1, C2)
+}
+
+impl PeerGroup {
+    /// Accepts the bare category letter, the sub-category (e.g. "B4") or the
+    /// descriptive label. `None` for anything else.
+    pub fn from_classification(classification: &str) -> Option<Self> {
+        let class = classification.trim().to_ascii_uppercase();
+        match class.as_str() {
+            "A" | "METRO" | "METROPOLITAN" => Some(PeerGroup::Metro),
+            "B" | "B1" | "B2" | "B3" | "B4" | "LOCAL" => Some(PeerGroup::Local),
+            "C" | "C1" | "C2" | "DISTRICT" => Some(PeerGroup::District),
+            _ => None,
+        }
+    }
 }
 
 /// Per-pillar scores. A pillar is `None` when its inputs were missing or invalid,
 /// which is distinct from an earned score of 0. `overall_score` is `Some` only
-/// when every pillar could be computed, so "no data" never masquerades as
-/// "worst in the country".
-#[derive(Debug, Clone, PartialEq)]
+/// when every core pillar could be computed (Liquidity is optional), so "no
+/// data" never masquerades as "worst in the country".
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
 pub struct ScoreBreakdown {
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
     pub overall_score: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
     pub financial_health_score: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
     pub infrastructure_score: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
     pub efficiency_score: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
     pub accountability_score: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub liquidity_score: Option<Decimal>,
+    /// Letter grade of `overall_score`; `None` exactly when it is.
+    pub grade: Option<Grade>,
+}
+
+/// Per-pillar difference between two breakdowns (`a - b`); `None` where
+/// either side lacks the score.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ScoreDeltas {
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub overall_score: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub financial_health_score: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub infrastructure_score: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub efficiency_score: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub accountability_score: Option<Decimal>,
+    #[serde(serialize_with = "crate::utils::serialize_option_decimal_as_f64")]
+    pub liquidity_score: Option<Decimal>,
+}
+
+impl ScoreBreakdown {
+    /// `self - other`, pillar by pillar.
+    pub fn deltas(&self, other: &ScoreBreakdown) -> ScoreDeltas {
+        let minus = |a: Option<Decimal>, b: Option<Decimal>| Some(a? - b?);
+        ScoreDeltas {
+            overall_score: minus(self.overall_score, other.overall_score),
+            financial_health_score: minus(self.financial_health_score, other.financial_health_score),
+            infrastructure_score: minus(self.infrastructure_score, other.infrastructure_score),
+            efficiency_score: minus(self.efficiency_score, other.efficiency_score),
+            accountability_score: minus(self.accountability_score, other.accountability_score),
+            liquidity_score: minus(self.liquidity_score, other.liquidity_score),
+        }
+    }
+}
+
+/// Compact A-F badge for an overall score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, ToSchema)]
+pub enum Grade {
+    A,
+    B,
+    C,
+    D,
+    F,
+}
+
+impl std::fmt::Display for Grade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let letter = match self {
+            Grade::A => "A",
+            Grade::B => "B",
+            Grade::C => "C",
+            Grade::D => "D",
+            Grade::F => "F",
+        };
+        f.write_str(letter)
+    }
+}
+
+/// Maps a 0-100 overall score to its grade: A >= 80, B >= 65, C >= 50,
+/// D >= 35, F below. Lower bounds are inclusive, so 80.00 is an A and 79.99 a B.
+pub fn grade_for_score(score: Decimal) -> Grade {
+    GRADE_CUTOFFS
+        .iter()
+        .find(|(_, min)| score >= *min)
+        .map_or(Grade::F, |(grade, _)| *grade)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -92,10 +796,36 @@ pub enum AuditOutcome {
     Qualified,
     Adverse,
     Disclaimer,
-    Outstanding,     // Financial statements never submitted for audit — an earned worst outcome
+    Outstanding,     // No opinion issued yet (statements outstanding / audit not finalised) — not scored
     Unknown(String), // Unrecognized label — treated as missing data, not as a bad outcome
 }
 
+impl AuditOutcome {
+    /// The audit sub-score for this opinion, per prd.md. None when there is no opinion to score.
+    fn base_score(&self) -> Option<Decimal> {
+        match self {
+            AuditOutcome::Clean => Some(dec!(100.0)),
+            AuditOutcome::FinanciallyUnqualified => Some(dec!(75.0)),
+            AuditOutcome::Qualified => Some(dec!(50.0)),
+            AuditOutcome::Adverse | AuditOutcome::Disclaimer => Some(dec!(25.0)),
+            AuditOutcome::Outstanding | AuditOutcome::Unknown(_) => None,
+        }
+    }
+
+    /// Stable snake_case name used in the methodology document.
+    fn key(&self) -> &'static str {
+        match self {
+            AuditOutcome::Clean => "clean",
+            AuditOutcome::FinanciallyUnqualified => "financially_unqualified",
+            AuditOutcome::Qualified => "qualified",
+            AuditOutcome::Adverse => "adverse",
+            AuditOutcome::Disclaimer => "disclaimer",
+            AuditOutcome::Outstanding => "outstanding",
+            AuditOutcome::Unknown(_) => "unknown",
+        }
+    }
+}
+
 impl From<&str> for AuditOutcome {
     fn from(s: &str) -> Self {
         // The Treasury API label wording varies by year; match the variants observed
@@ -112,7 +842,12 @@ impl From<&str> for AuditOutcome {
             "disclaimer" | "disclaimer of opinion" | "disclaimer with findings" => {
                 AuditOutcome::Disclaimer
             }
-            "outstanding" | "financial statements not submitted" => AuditOutcome::Outstanding,
+            "outstanding"
+            | "audit outstanding"
+            | "financial statements not submitted"
+            | "not yet released"
+            | "audit not finalised"
+            | "audit not finalized" => AuditOutcome::Outstanding,
             _ => AuditOutcome::Unknown(s.to_string()),
         }
     }
@@ -139,6 +874,343 @@ fn round_score(score: Decimal) -> Decimal {
     score.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
 }
 
+/// `municipalities.population` (a `real`) as the head count `ScoringInput`
+/// takes; see [`head_count`]. A rejected value is logged and treated as
+/// missing. Zero passes through: `per_capita` already ignores it.
+pub fn population_count(population: Option<f32>) -> Option<u32> {
+    let population = population?;
+    let count = head_count(f64::from(population));
+    if count.is_none() {
+        log::warn!("Ignoring population {}: not a head count", population);
+    }
+    count
+}
+
+/// Amount per resident, rounded to cents. `None` when the amount is missing or
+/// the population is missing or zero — a per-capita figure is meaningless then.
+pub fn per_capita(amount_opt: Option<Decimal>, population_opt: Option<u32>) -> Option<Decimal> {
+    let population = population_opt.filter(|p| *p > 0)?;
+    let per_capita = amount_opt? / Decimal::from(population);
+    Some(per_capita.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero))
+}
+
+/// Revenue minus operational expenditure; negative is a deficit. `None` when
+/// either figure is missing.
+pub fn operating_surplus(revenue_opt: Option<Decimal>, operational_expenditure_opt: Option<Decimal>) -> Option<Decimal> {
+    Some(revenue_opt? - operational_expenditure_opt?)
+}
+
+// --- Ratios ---
+// Each returns `None` when an input is missing or the denominator is not
+// positive. Shared by the pillar functions and `explain_financial_score`, so
+// the audit shows exactly the ratios that were scored.
+//
+// `Decimal` arithmetic panics past ~7.9e28. No real budget comes near that,
+// but a corrupt upstream figure over a tiny denominator can, so the ratios
+// saturate at `Decimal::MAX`/`MIN` instead. An overflowing ratio then scores
+// like the extreme ratio it is, keeping scores monotonic in the ratio.
+
+fn positive(value_opt: Option<Decimal>) -> Option<Decimal> {
+    value_opt.filter(|v| *v > Decimal::ZERO)
+}
+
+/// `numerator / denominator` for a denominator known to be positive,
+/// saturating where the quotient can't be represented, including a
+/// denominator so small it rounded to zero.
+fn saturating_ratio(numerator: Decimal, denominator: Decimal) -> Decimal {
+    if numerator.is_zero() {
+        return Decimal::ZERO;
+    }
+    numerator
+        .checked_div(denominator)
+        .unwrap_or(if numerator.is_sign_negative() { Decimal::MIN } else { Decimal::MAX })
+}
+
+/// 1 - transfers / revenue, clamped to [0, 1].
+fn own_revenue_share(revenue_opt: Option<Decimal>, transfers_opt: Option<Decimal>) -> Option<Decimal> {
+    let revenue = positive(revenue_opt)?;
+    let own_revenue = revenue.saturating_sub(transfers_opt?);
+    Some(saturating_ratio(own_revenue, revenue).clamp(Decimal::ZERO, Decimal::ONE))
+}
+
+/// Revenue less operational transfers; None when either is missing or the
+/// difference is not positive.
+fn own_revenue(revenue_opt: Option<Decimal>, transfers_opt: Option<Decimal>) -> Option<Decimal> {
+    let own_revenue = positive(revenue_opt)?.saturating_sub(transfers_opt?);
+    (own_revenue > Decimal::ZERO).then_some(own_revenue)
+}
+
+/// Denominator for the debt ratio: own revenue when transfers are reported —
+/// grants cannot service debt, so a grant-funded budget must not make a
+/// municipality look lightly indebted — otherwise total revenue.
+fn debt_ratio_basis(revenue_opt: Option<Decimal>, transfers_opt: Option<Decimal>) -> Option<Decimal> {
+    own_revenue(revenue_opt, transfers_opt).or_else(|| positive(revenue_opt))
+}
+
+fn debt_to_revenue(debt_opt: Option<Decimal>, revenue_opt: Option<Decimal>) -> Option<Decimal> {
+    Some(saturating_ratio(debt_opt?, positive(revenue_opt)?))
+}
+
+fn opex_to_revenue(operational_expenditure_opt: Option<Decimal>, revenue_opt: Option<Decimal>) -> Option<Decimal> {
+    Some(saturating_ratio(operational_expenditure_opt?, positive(revenue_opt)?))
+}
+
+/// CapEx / (OpEx + CapEx); 0 when total spend is zero or negative.
+fn capex_share(operational_expenditure_opt: Option<Decimal>, capex_opt: Option<Decimal>) -> Option<Decimal> {
+    let (opex, capex) = (operational_expenditure_opt?, capex_opt?);
+    let total_expenditure = opex.saturating_add(capex);
+    if total_expenditure <= Decimal::ZERO {
+        return Some(Decimal::ZERO);
+    }
+    Some(saturating_ratio(capex.max(Decimal::ZERO), total_expenditure))
+}
+
+/// R&M / OpEx; negative R&M is invalid.
+fn rm_intensity(rm_opt: Option<Decimal>, operational_expenditure_opt: Option<Decimal>) -> Option<Decimal> {
+    let rm = rm_opt.filter(|v| *v >= Decimal::ZERO)?;
+    Some(saturating_ratio(rm, positive(operational_expenditure_opt)?))
+}
+
+/// UIFW / OpEx; negative UIFW is invalid.
+fn uifw_to_opex(uifw_opt: Option<Decimal>, operational_expenditure_opt: Option<Decimal>) -> Option<Decimal> {
+    let uifw = uifw_opt.filter(|v| *v >= Decimal::ZERO)?;
+    Some(saturating_ratio(uifw, positive(operational_expenditure_opt)?))
+}
+
+/// Year-end cash over average monthly OpEx. Negative cash is a real figure.
+/// A monthly OpEx too small to represent rounds to zero, so the cover
+/// saturates like any other overflow.
+fn cash_cover_months(cash_opt: Option<Decimal>, operational_expenditure_opt: Option<Decimal>) -> Option<Decimal> {
+    let monthly_opex = positive(operational_expenditure_opt)? / dec!(12);
+    Some(saturating_ratio(cash_opt?, monthly_opex))
+}
+
+// --- Percentile normalization ---
+
+/// How a `ScoringInput`'s metrics become sub-scores.
+///
+/// Linear mode scores each metric between the fixed anchors of
+/// `ScoringThresholds`: a score means the same thing every year, but the
+/// anchors go stale as the economy moves and need annual calibration.
+/// Percentile mode scores it by rank within a year's national distribution
+/// instead: it never goes stale and always spreads municipalities across the
+/// scale, but a score then says where a municipality stands among its peers,
+/// not how sound it is — a year in which every municipality deteriorates
+/// leaves the scores unchanged — and it is not comparable across years.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Normalization {
+    /// Linear between the `ScoringThresholds` anchors. Stored scores use
+    /// this unless SCORING_NORMALIZATION=percentile.
+    #[default]
+    Linear,
+    /// By rank within the distribution, for every metric with at least
+    /// `PERCENTILE_MIN_SAMPLE` values; thinner metrics stay linear. Shared,
+    /// as every municipality of a year is ranked against the same one.
+    Percentile(Arc<MetricDistribution>),
+}
+
+impl Normalization {
+    /// Name as reported in a `ScoreAudit`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Normalization::Linear => "linear",
+            Normalization::Percentile(_) => "percentile",
+        }
+    }
+}
+
+/// One metric's values across a year's municipalities, sorted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Distribution {
+    values: Vec<Decimal>,
+}
+
+impl Distribution {
+    pub fn new(values: impl IntoIterator<Item = Decimal>) -> Self {
+        let mut values: Vec<Decimal> = values.into_iter().collect();
+        values.sort();
+        Distribution { values }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Mid-rank percentile of `value` in [0, 1]: the share of the sample
+    /// below it plus half the share equal to it, so ties rank alike.
+    fn percentile_rank(&self, value: Decimal) -> Decimal {
+        let below = self.values.partition_point(|v| *v < value);
+        let at_or_below = self.values.partition_point(|v| *v <= value);
+        let rank = Decimal::from(below) + Decimal::from(at_or_below - below) / dec!(2);
+        rank / Decimal::from(self.values.len())
+    }
+
+    /// The sample value at percentile `p` (nearest rank).
+    fn value_at(&self, p: Decimal) -> Decimal {
+        let n = Decimal::from(self.values.len());
+        let rank = (p * n).ceil().to_usize().unwrap_or(0);
+        self.values[rank.clamp(1, self.values.len()) - 1]
+    }
+
+    /// The sub-score of `value`; `None` when the sample is too thin to rank
+    /// against.
+    fn score(&self, value: Decimal, direction: Direction) -> Option<Decimal> {
+        if self.values.len() < PERCENTILE_MIN_SAMPLE {
+            return None;
+        }
+        let rank = match direction {
+            Direction::HigherIsBetter => self.percentile_rank(value),
+            Direction::LowerIsBetter => Decimal::ONE - self.percentile_rank(value),
+        };
+        let normalized = ((rank - PERCENTILE_SCORE_0_AT) / (PERCENTILE_SCORE_100_AT - PERCENTILE_SCORE_0_AT))
+            .clamp(Decimal::ZERO, Decimal::ONE);
+        Some(clamp_score(normalized * dec!(100.0)))
+    }
+
+    /// The values scoring 0 and 100, for the score audit. Approximate: the
+    /// sample values nearest those percentiles.
+    fn anchors(&self, direction: Direction) -> Option<(Decimal, Decimal)> {
+        if self.values.len() < PERCENTILE_MIN_SAMPLE {
+            return None;
+        }
+        let (low, high) = (self.value_at(PERCENTILE_SCORE_0_AT), self.value_at(PERCENTILE_SCORE_100_AT));
+        Some(match direction {
+            Direction::HigherIsBetter => (low, high),
+            Direction::LowerIsBetter => (high, low),
+        })
+    }
+}
+
+/// The distribution of every normalized metric over one year. Own-revenue
+/// share is split by peer group like its linear bands: a municipality is
+/// ranked against its own kind, and an unknown class against locals.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricDistribution {
+    pub own_revenue_share: Distribution,
+    pub own_revenue_share_metro: Distribution,
+    pub own_revenue_share_district: Distribution,
+    pub debt_ratio: Distribution,
+    pub capex_share: Distribution,
+    pub rm_intensity: Distribution,
+    pub opex_ratio: Distribution,
+    pub uifw_ratio: Distribution,
+    pub cash_cover_months: Distribution,
+}
+
+impl MetricDistribution {
+    /// Builds the distributions from a year's inputs, each metric derived as
+    /// the pillar functions derive it. Inputs graded unreliable are left out,
+    /// as their raw-derived pillars are.
+    pub fn from_inputs<'a>(inputs: impl IntoIterator<Item = &'a ScoringInput>) -> Self {
+        let (mut own_local, mut own_metro, mut own_district) = (Vec::new(), Vec::new(), Vec::new());
+        let (mut debt, mut capex, mut rm, mut opex_ratio, mut uifw, mut cash) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for input in inputs.into_iter().filter(|input| !input.data_unreliable) {
+            let opex = input.operational_expenditure;
+            if let Some(share) = own_revenue_share(input.revenue, input.transfers_operational) {
+                match input.classification.as_deref().and_then(PeerGroup::from_classification) {
+                    Some(PeerGroup::Metro) => own_metro.push(share),
+                    Some(PeerGroup::District) => own_district.push(share),
+                    Some(PeerGroup::Local) | None => own_local.push(share),
+                }
+            }
+            debt.extend(debt_to_revenue(input.debt, debt_ratio_basis(input.revenue, input.transfers_operational)));
+            capex.extend(capex_share(opex, input.capital_expenditure));
+            rm.extend(rm_intensity(input.repairs_maintenance, opex));
+            opex_ratio.extend(opex_to_revenue(opex, input.revenue));
+            uifw.extend(uifw_to_opex(input.uifw_expenditure, opex));
+            cash.extend(cash_cover_months(input.cash_balance, opex));
+        }
+        MetricDistribution {
+            own_revenue_share: Distribution::new(own_local),
+            own_revenue_share_metro: Distribution::new(own_metro),
+            own_revenue_share_district: Distribution::new(own_district),
+            debt_ratio: Distribution::new(debt),
+            capex_share: Distribution::new(capex),
+            rm_intensity: Distribution::new(rm),
+            opex_ratio: Distribution::new(opex_ratio),
+            uifw_ratio: Distribution::new(uifw),
+            cash_cover_months: Distribution::new(cash),
+        }
+    }
+
+    fn get(&self, metric: Metric) -> &Distribution {
+        match metric {
+            Metric::OwnRevenueShare(Some(PeerGroup::Metro)) => &self.own_revenue_share_metro,
+            Metric::OwnRevenueShare(Some(PeerGroup::District)) => &self.own_revenue_share_district,
+            Metric::OwnRevenueShare(Some(PeerGroup::Local) | None) => &self.own_revenue_share,
+            Metric::DebtRatio => &self.debt_ratio,
+            Metric::CapexShare => &self.capex_share,
+            Metric::RmIntensity => &self.rm_intensity,
+            Metric::OpexRatio => &self.opex_ratio,
+            Metric::UifwRatio => &self.uifw_ratio,
+            Metric::CashCoverMonths => &self.cash_cover_months,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    HigherIsBetter,
+    LowerIsBetter,
+}
+
+/// A normalized metric; own-revenue share carries the peer group it is
+/// ranked within.
+#[derive(Debug, Clone, Copy)]
+enum Metric {
+    OwnRevenueShare(Option<PeerGroup>),
+    DebtRatio,
+    CapexShare,
+    RmIntensity,
+    OpexRatio,
+    UifwRatio,
+    CashCoverMonths,
+}
+
+impl Metric {
+    fn direction(self) -> Direction {
+        match self {
+            Metric::DebtRatio | Metric::OpexRatio | Metric::UifwRatio => Direction::LowerIsBetter,
+            _ => Direction::HigherIsBetter,
+        }
+    }
+}
+
+/// What the pillar functions normalize against: the fixed anchors, and in
+/// percentile mode the year's distribution, which wins for every metric
+/// with a large enough sample.
+#[derive(Debug, Clone, Copy)]
+struct Normalizer<'a> {
+    thresholds: &'a ScoringThresholds,
+    distribution: Option<&'a MetricDistribution>,
+}
+
+impl<'a> Normalizer<'a> {
+    fn new(thresholds: &'a ScoringThresholds, normalization: &'a Normalization) -> Self {
+        let distribution = match normalization {
+            Normalization::Linear => None,
+            Normalization::Percentile(distribution) => Some(distribution.as_ref()),
+        };
+        Normalizer { thresholds, distribution }
+    }
+
+    /// The percentile sub-score of `value`, when ranking applies to `metric`.
+    fn percentile_score(&self, metric: Metric, value: Decimal) -> Option<Decimal> {
+        self.distribution?.get(metric).score(value, metric.direction())
+    }
+
+    /// (score_0_at, score_100_at) for `metric`: the distribution's when
+    /// ranking applies, else `linear`.
+    fn anchors(&self, metric: Metric, linear: (Decimal, Decimal)) -> (Decimal, Decimal) {
+        self.distribution.and_then(|d| d.get(metric).anchors(metric.direction())).unwrap_or(linear)
+    }
+}
+
 // --- Pillar Score Calculation Functions ---
 
 /// Calculates the Own-Revenue sub-score (0-100): how much of the municipality's
@@ -149,24 +1221,23 @@ fn round_score(score: Decimal) -> Decimal {
 /// # Arguments
 /// * `revenue_opt` - Total municipal revenue (> 0).
 /// * `transfers_opt` - Operational grants received (item 2200), part of revenue.
+/// * `peer_group` - Selects the normalization band; `None` = national band.
 ///
 /// # Returns
-/// * `Some(score)` - linear from OWN_REVENUE_SHARE_MIN (0) to _MAX (100).
+/// * `Some(score)` - linear from the band's min (0) to its max (100).
 /// * `None` - If revenue or transfers is missing, or revenue is zero/negative.
 fn calculate_own_revenue_subscore(
     revenue_opt: Option<Decimal>,
     transfers_opt: Option<Decimal>,
+    peer_group: Option<PeerGroup>,
+    norm: Normalizer<'_>,
 ) -> Option<Decimal> {
-    let revenue = match revenue_opt {
-        Some(r) if r > Decimal::ZERO => Some(r),
-        _ => None,
-    }?;
-    let transfers = transfers_opt?;
-
-    let share = ((revenue - transfers) / revenue).clamp(Decimal::ZERO, Decimal::ONE);
-
-    let range = OWN_REVENUE_SHARE_MAX - OWN_REVENUE_SHARE_MIN;
-    let normalized = ((share - OWN_REVENUE_SHARE_MIN) / range).clamp(Decimal::ZERO, Decimal::ONE);
+    let share = own_revenue_share(revenue_opt, transfers_opt)?;
+    if let Some(score) = norm.percentile_score(Metric::OwnRevenueShare(peer_group), share) {
+        return Some(score);
+    }
+    let (band_min, band_max) = norm.thresholds.own_revenue_band(peer_group);
+    let normalized = ((share - band_min) / (band_max - band_min)).clamp(Decimal::ZERO, Decimal::ONE);
     Some(clamp_score(normalized * dec!(100.0)))
 }
 
@@ -177,14 +1248,15 @@ fn calculate_own_revenue_subscore(
 fn calculate_rm_subscore(
     rm_opt: Option<Decimal>,
     operational_expenditure_opt: Option<Decimal>,
+    norm: Normalizer<'_>,
 ) -> Option<Decimal> {
-    let rm = rm_opt.filter(|v| *v >= Decimal::ZERO)?;
-    let opex = match operational_expenditure_opt {
-        Some(o) if o > Decimal::ZERO => Some(o),
-        _ => None,
-    }?;
-    let intensity = (rm / opex).clamp(Decimal::ZERO, RM_INTENSITY_MAX);
-    Some(clamp_score(intensity / RM_INTENSITY_MAX * dec!(100.0)))
+    let intensity = rm_intensity(rm_opt, operational_expenditure_opt)?;
+    if let Some(score) = norm.percentile_score(Metric::RmIntensity, intensity) {
+        return Some(score);
+    }
+    let max = norm.thresholds.rm_intensity_max;
+    let intensity = intensity.clamp(Decimal::ZERO, max);
+    Some(clamp_score(intensity / max * dec!(100.0)))
 }
 
 /// UIFW sub-score (0-100): unauthorised/irregular/fruitless & wasteful spend
@@ -194,44 +1266,52 @@ fn calculate_rm_subscore(
 fn calculate_uifw_subscore(
     uifw_opt: Option<Decimal>,
     operational_expenditure_opt: Option<Decimal>,
+    norm: Normalizer<'_>,
 ) -> Option<Decimal> {
-    let uifw = uifw_opt.filter(|v| *v >= Decimal::ZERO)?;
-    let opex = match operational_expenditure_opt {
-        Some(o) if o > Decimal::ZERO => Some(o),
-        _ => None,
-    }?;
-    let ratio = (uifw / opex).clamp(Decimal::ZERO, UIFW_RATIO_WORST);
-    Some(clamp_score((Decimal::ONE - ratio / UIFW_RATIO_WORST) * dec!(100.0)))
+    let ratio = uifw_to_opex(uifw_opt, operational_expenditure_opt)?;
+    if let Some(score) = norm.percentile_score(Metric::UifwRatio, ratio) {
+        return Some(score);
+    }
+    let worst = norm.thresholds.uifw_ratio_worst;
+    let ratio = ratio.clamp(Decimal::ZERO, worst);
+    Some(clamp_score((Decimal::ONE - ratio / worst) * dec!(100.0)))
 }
 
 /// Calculates the Debt Ratio sub-score (0-100).
-/// Measures total debt relative to total revenue. Lower debt ratio yields a higher score.
+/// Measures total debt relative to revenue. Lower debt ratio yields a higher score.
 /// The score is normalized linearly between DEBT_RATIO_MIN (score 100) and DEBT_RATIO_MAX (score 0).
+/// The same anchors apply to either basis, so a self-funded municipality
+/// (no transfers) scores identically against own or total revenue.
 ///
 /// # Arguments
 /// * `debt_opt` - Total municipal debt.
-/// * `revenue_opt` - Total municipal revenue (> 0).
+/// * `revenue_opt` - The revenue basis (> 0): own revenue when known, else
+///   total revenue (see `debt_ratio_basis`).
 ///
 /// # Returns
 /// * `Some(score)` - Score between 0 and 100 if inputs are valid.
 /// * `None` - If debt or revenue is missing, or revenue is zero/negative.
-fn calculate_debt_ratio_subscore(debt_opt: Option<Decimal>, revenue_opt: Option<Decimal>) -> Option<Decimal> {
-    let debt = debt_opt?;
-    let revenue = match revenue_opt {
-        Some(r) if r > Decimal::ZERO => Some(r),
-        _ => None, // Return None if revenue is None or zero/negative
-    }?;
-
-    let debt_ratio = debt / revenue;
+fn calculate_debt_ratio_subscore(
+    debt_opt: Option<Decimal>,
+    revenue_opt: Option<Decimal>,
+    norm: Normalizer<'_>,
+) -> Option<Decimal> {
+    let debt_ratio = debt_to_revenue(debt_opt, revenue_opt)?;
+    if let Some(score) = norm.percentile_score(Metric::DebtRatio, debt_ratio) {
+        return Some(score);
+    }
+    let (min, max) = (norm.thresholds.debt_ratio_min, norm.thresholds.debt_ratio_max);
 
     // Normalize score linearly between MIN and MAX thresholds (inverted)
-    let range = DEBT_RATIO_MAX - DEBT_RATIO_MIN;
+    let range = max - min;
     if range <= Decimal::ZERO { // Avoid division by zero/negative range
-        return Some(if debt_ratio <= DEBT_RATIO_MIN { dec!(100.0) } else { Decimal::ZERO });
+        return Some(if debt_ratio <= min { dec!(100.0) } else { Decimal::ZERO });
     }
 
-    // Calculate normalized position within the range
-    let normalized_position = ((debt_ratio - DEBT_RATIO_MIN) / range)
+    // Calculate normalized position within the range. Clamping the ratio
+    // first keeps an extreme one from overflowing the arithmetic.
+    let debt_ratio = debt_ratio.clamp(min, max);
+    let normalized_position = ((debt_ratio - min) / range)
         .clamp(Decimal::ZERO, dec!(1.0));
 
     // Invert the score: higher position in range means lower score
@@ -251,16 +1331,14 @@ fn calculate_fin_health_score(
     revenue_opt: Option<Decimal>,
     debt_opt: Option<Decimal>,
     transfers_opt: Option<Decimal>,
+    peer_group: Option<PeerGroup>,
+    norm: Normalizer<'_>,
 ) -> Option<Decimal> {
-    // Weights for sub-scores within Financial Health (must sum to 1.0)
-    const WEIGHT_OWN_REVENUE: Decimal = dec!(0.5);
-    const WEIGHT_DEBT_RATIO: Decimal = dec!(0.5);
-
-    let own_revenue_score = calculate_own_revenue_subscore(revenue_opt, transfers_opt)?;
-    let debt_ratio_score = calculate_debt_ratio_subscore(debt_opt, revenue_opt)?;
+    let own_revenue_score = calculate_own_revenue_subscore(revenue_opt, transfers_opt, peer_group, norm)?;
+    let debt_ratio_score = calculate_debt_ratio_subscore(debt_opt, debt_ratio_basis(revenue_opt, transfers_opt), norm)?;
 
     let weighted_score =
-        (own_revenue_score * WEIGHT_OWN_REVENUE) + (debt_ratio_score * WEIGHT_DEBT_RATIO);
+        (own_revenue_score * FH_OWN_REVENUE_WEIGHT) + (debt_ratio_score * FH_DEBT_RATIO_WEIGHT);
     // No final clamp needed here as weighted average of 0-100 scores is also 0-100.
     Some(weighted_score)
 }
@@ -277,9 +1355,10 @@ fn calculate_infra_score(
     operational_expenditure_opt: Option<Decimal>,
     capex_opt: Option<Decimal>,
     rm_opt: Option<Decimal>,
+    norm: Normalizer<'_>,
 ) -> Option<Decimal> {
-    let capex_score = calculate_capex_subscore(operational_expenditure_opt, capex_opt)?;
-    match calculate_rm_subscore(rm_opt, operational_expenditure_opt) {
+    let capex_score = calculate_capex_subscore(operational_expenditure_opt, capex_opt, norm)?;
+    match calculate_rm_subscore(rm_opt, operational_expenditure_opt, norm) {
         Some(rm_score) => {
             Some(capex_score * (Decimal::ONE - INFRA_RM_WEIGHT) + rm_score * INFRA_RM_WEIGHT)
         }
@@ -292,43 +1371,38 @@ fn calculate_infra_score(
 fn calculate_capex_subscore(
     operational_expenditure_opt: Option<Decimal>,
     capex_opt: Option<Decimal>,
+    norm: Normalizer<'_>,
 ) -> Option<Decimal> {
-    let opex = operational_expenditure_opt?;
-    let capex = capex_opt?;
-
-    let total_expenditure = opex + capex;
-
-    if total_expenditure <= Decimal::ZERO {
-        return Some(Decimal::ZERO); // Avoid division by zero/negative, score 0
+    // Zero/negative total spend yields ratio 0, which scores 0.
+    let capex_ratio = capex_share(operational_expenditure_opt, capex_opt)?;
+    if let Some(score) = norm.percentile_score(Metric::CapexShare, capex_ratio) {
+        return Some(score);
     }
-
-    // Ensure capex used is non-negative (already filtered implicitly by Option check)
-    let valid_capex = capex.max(Decimal::ZERO);
-
-    let capex_ratio = valid_capex / total_expenditure;
+    let thresholds = norm.thresholds;
+    let (worst, mid, best) = (thresholds.infra_ratio_worst, thresholds.infra_ratio_mid, thresholds.infra_ratio_best);
 
     // Normalize the score based on thresholds
-    let score = if capex_ratio <= INFRA_RATIO_WORST {
+    let score = if capex_ratio <= worst {
         dec!(0.0)
-    } else if capex_ratio < INFRA_RATIO_MID {
+    } else if capex_ratio < mid {
         // Linear scale from 0 (at WORST) up to 50 (at MID)
         // Calculate slope and apply formula: slope * (value - start_value)
-        let range = INFRA_RATIO_MID - INFRA_RATIO_WORST;
+        let range = mid - worst;
         if range > Decimal::ZERO {
-             (dec!(50.0) / range) * (capex_ratio - INFRA_RATIO_WORST)
+             (dec!(50.0) / range) * (capex_ratio - worst)
         } else {
              dec!(0.0) // Avoid division by zero if WORST == MID
         }
-    } else if capex_ratio < INFRA_RATIO_BEST {
+    } else if capex_ratio < best {
         // Linear scale from 50 (at MID) up to 100 (at BEST)
         let score_mid_point = dec!(50.0);
-        let range = INFRA_RATIO_BEST - INFRA_RATIO_MID;
+        let range = best - mid;
         if range > Decimal::ZERO {
-            score_mid_point + (dec!(100.0) - score_mid_point) * (capex_ratio - INFRA_RATIO_MID) / range
+            score_mid_point + (dec!(100.0) - score_mid_point) * (capex_ratio - mid) / range
         } else {
             score_mid_point // Avoid division by zero if MID == BEST
         }
-    } else { // capex_ratio >= INFRA_RATIO_BEST
+    } else { // capex_ratio >= best
         dec!(100.0)
     };
 
@@ -350,25 +1424,55 @@ fn calculate_capex_subscore(
 fn calculate_efficiency_score(
     operational_expenditure_opt: Option<Decimal>,
     revenue_opt: Option<Decimal>,
+    norm: Normalizer<'_>,
 ) -> Option<Decimal> {
-    let opex = operational_expenditure_opt?;
-    let revenue = match revenue_opt {
-        Some(r) if r > Decimal::ZERO => Some(r),
-        _ => None, // Return None if revenue is None or zero/negative
-    }?;
-
-    let opex_ratio = opex / revenue;
+    // A zero or negative revenue (reversals outweighing postings) has no
+    // meaningful cost ratio; no score rather than a misleading one.
+    let revenue = positive(revenue_opt)?;
+    let opex_ratio = opex_to_revenue(operational_expenditure_opt, Some(revenue))?;
+    if let Some(score) = norm.percentile_score(Metric::OpexRatio, opex_ratio) {
+        return Some(score);
+    }
+    let (best, worst) = (norm.thresholds.efficiency_ratio_best, norm.thresholds.efficiency_ratio_worst);
 
-    let range = EFFICIENCY_RATIO_WORST - EFFICIENCY_RATIO_BEST;
+    let range = worst - best;
     if range <= Decimal::ZERO {
-        return Some(if opex_ratio <= EFFICIENCY_RATIO_BEST { dec!(100.0) } else { Decimal::ZERO });
+        return Some(if opex_ratio <= best { dec!(100.0) } else { Decimal::ZERO });
+    }
+
+    // Clamped first so an extreme ratio can't overflow the arithmetic.
+    let opex_ratio = opex_ratio.clamp(best, worst);
+    let normalized_position = ((opex_ratio - best) / range)
+        .clamp(Decimal::ZERO, dec!(1.0));
+    let score = (dec!(1.0) - normalized_position) * dec!(100.0);
+
+    Some(clamp_score(score))
+}
+
+/// Calculates Liquidity Score (0-100): cash coverage, i.e. year-end cash and
+/// equivalents divided by average monthly operating expenditure. Linear from
+/// 0 at LIQUIDITY_MONTHS_MIN (1 month) to 100 at LIQUIDITY_MONTHS_MAX (3).
+///
+/// # Returns
+/// * `Some(score)` - Score between 0 and 100 if inputs are valid.
+/// * `None` - If cash or opex is missing, or opex is zero/negative. Negative
+///   cash (an overdraft) is a real figure and scores 0.
+fn calculate_liquidity_score(
+    cash_opt: Option<Decimal>,
+    operational_expenditure_opt: Option<Decimal>,
+    norm: Normalizer<'_>,
+) -> Option<Decimal> {
+    let months = cash_cover_months(cash_opt, operational_expenditure_opt)?;
+    if let Some(score) = norm.percentile_score(Metric::CashCoverMonths, months) {
+        return Some(score);
     }
+    let (min, max) = (norm.thresholds.liquidity_months_min, norm.thresholds.liquidity_months_max);
+    // Clamped first so an extreme cover can't overflow the arithmetic.
+    let months = months.clamp(min, max);
 
-    let normalized_position = ((opex_ratio - EFFICIENCY_RATIO_BEST) / range)
-        .clamp(Decimal::ZERO, dec!(1.0));
-    let score = (dec!(1.0) - normalized_position) * dec!(100.0);
-
-    Some(clamp_score(score))
+    let range = max - min;
+    let normalized = ((months - min) / range).clamp(Decimal::ZERO, Decimal::ONE);
+    Some(clamp_score(normalized * dec!(100.0)))
 }
 
 /// Calculates Accountability Score.
@@ -377,14 +1481,15 @@ fn calculate_efficiency_score(
 ///
 /// # Returns
 /// * `Some(score)` - audit sub-score, blended with UIFW when available.
-/// * `None` - If the audit outcome is missing or the label is unrecognized.
+/// * `None` - If the audit outcome is missing, outstanding, or unrecognized.
 fn calculate_accountability_score(
     outcome_str_opt: Option<&str>,
     uifw_opt: Option<Decimal>,
     operational_expenditure_opt: Option<Decimal>,
+    norm: Normalizer<'_>,
 ) -> Option<Decimal> {
     let audit_score = calculate_audit_subscore(outcome_str_opt)?;
-    match calculate_uifw_subscore(uifw_opt, operational_expenditure_opt) {
+    match calculate_uifw_subscore(uifw_opt, operational_expenditure_opt, norm) {
         Some(uifw_score) => {
             Some(audit_score * (Decimal::ONE - ACC_UIFW_WEIGHT) + uifw_score * ACC_UIFW_WEIGHT)
         }
@@ -396,63 +1501,107 @@ fn calculate_accountability_score(
 /// Audit-outcome sub-score, mapped per prd.md.
 ///
 /// # Returns
-/// * `Some(score)` - 0, 25, 50, 75, or 100 for a recognized outcome. "Outstanding"
-///   (statements never submitted) earns an explicit 0.
-/// * `None` - If the outcome is missing or the label is unrecognized. An unknown
-///   label means *we* can't interpret it — that must not be scored as if the
-///   municipality failed its audit.
+/// * `Some(score)` - 25, 50, 75, or 100 for an issued opinion.
+/// * `None` - If the outcome is missing, outstanding, or the label is
+///   unrecognized. "Outstanding" means the AG has not released an opinion yet
+///   (often just timing at the reporting cut-off), and an unknown label means
+///   *we* can't interpret it — neither may be scored as a failed audit.
 fn calculate_audit_subscore(outcome_str_opt: Option<&str>) -> Option<Decimal> {
-    match AuditOutcome::from(outcome_str_opt?) {
-        AuditOutcome::Clean => Some(dec!(100.0)),
-        AuditOutcome::FinanciallyUnqualified => Some(dec!(75.0)),
-        AuditOutcome::Qualified => Some(dec!(50.0)),
-        AuditOutcome::Adverse => Some(dec!(25.0)),
-        AuditOutcome::Disclaimer => Some(dec!(25.0)), // Group Adverse and Disclaimer
-        AuditOutcome::Outstanding => Some(dec!(0.0)),
-        AuditOutcome::Unknown(label) => {
-            warn!("Unrecognized audit outcome label {label:?}; treating as missing data");
-            None
-        }
+    let outcome = AuditOutcome::from(outcome_str_opt?);
+    match &outcome {
+        AuditOutcome::Outstanding => debug!("Audit outcome outstanding; no opinion to score yet"),
+        AuditOutcome::Unknown(label) => warn!("Unrecognized audit outcome label {label:?}; treating as missing data"),
+        _ => {}
     }
+    outcome.base_score()
 }
 
 // --- Main Scoring Function ---
 
-/// Calculates the overall financial score and its per-pillar breakdown (v2 —
-/// see `SCORE_VERSION`).
+/// How the overall score treats a pillar that could not be computed. A missing
+/// pillar is never counted as 0; the policies differ in when an overall score
+/// is produced at all.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingPillarPolicy {
+    /// Drop every missing pillar's weight and renormalize over the rest:
+    /// `overall = Σ score·w / Σ w` across scored pillars. NULL when the scored
+    /// weight is below `MIN_SCORED_WEIGHT`.
+    #[default]
+    Renormalize,
+    /// The pre-v6 rule: NULL unless Financial Health, Infrastructure and
+    /// Efficiency are all scored; only Accountability and Liquidity may drop out.
+    RequireRawPillars,
+}
+
+static MISSING_PILLAR_POLICY: OnceLock<MissingPillarPolicy> = OnceLock::new();
+
+/// Installs the `MissingPillarPolicy` every score computed by this process
+/// applies (MISSING_PILLAR_POLICY). Called once at startup, like
+/// `set_thresholds`; a second call is refused.
+pub fn set_missing_pillar_policy(policy: MissingPillarPolicy) -> Result<(), String> {
+    MISSING_PILLAR_POLICY.set(policy).map_err(|_| "Missing-pillar policy is already set".to_string())
+}
+
+/// The policy in force: the one installed at startup, else the default
+/// `MissingPillarPolicy::Renormalize`.
+pub fn missing_pillar_policy() -> MissingPillarPolicy {
+    MISSING_PILLAR_POLICY.get().copied().unwrap_or_default()
+}
+
+/// Calculates the overall financial score and its per-pillar breakdown under
+/// the missing-pillar policy (see `missing_pillar_policy`) and the thresholds
+/// (see `thresholds`) in force.
+pub fn calculate_financial_score(input: &ScoringInput) -> ScoreBreakdown {
+    calculate_financial_score_with(input, missing_pillar_policy(), thresholds())
+}
+
+/// Calculates the overall financial score and its per-pillar breakdown.
 ///
-/// Each pillar is `None` when its inputs are missing or invalid; the overall
-/// score is `Some` only when **all four** pillars could be computed. Partial
-/// data therefore yields partial pillar scores but never a misleading overall
-/// number — a NULL overall renders as "no data" (grey) on the map.
+/// Each pillar is `None` when its inputs are missing or invalid. A missing
+/// pillar's weight is redistributed proportionally across the scored ones,
+/// never counted as 0 — a municipality without capex data is not scored as if
+/// it built nothing. When too little of the rubric is scored (see `policy`),
+/// the overall is `None` and renders as "no data" (grey) on the map.
 ///
-/// When the data-confidence layer graded the figures `unreliable`, the three
-/// pillars derived from them (Financial Health, Infrastructure, Efficiency)
+/// When the data-confidence layer graded the figures `unreliable`, the pillars
+/// derived from them (Financial Health, Infrastructure, Efficiency, Liquidity)
 /// are suppressed: artifacts like negative debt must not earn perfect
 /// sub-scores. The audit pillar still stands — it is the AG's own statement.
 ///
-/// Weights:
-/// - Financial Health (Own-Revenue share, Debt Ratio): 30%
-/// - Infrastructure Investment (Capex Ratio + R&M intensity): 25%
-/// - Operating Efficiency (OpEx Ratio): 25%
-/// - Accountability (Audit Outcome + UIFW intensity): 20%
-pub fn calculate_financial_score(input: &ScoringInput) -> ScoreBreakdown {
+/// Weights (without cash data in brackets):
+/// - Financial Health (Own-Revenue share vs peer group, Debt Ratio): 24% (30%)
+/// - Infrastructure Investment (Capex Ratio + R&M intensity): 20% (25%)
+/// - Operating Efficiency (OpEx Ratio): 20% (25%)
+/// - Accountability (Audit Outcome + UIFW intensity): 16% (20%)
+/// - Liquidity (cash coverage in months): 20% (—)
+///
+/// Each metric is normalized between the anchors in `thresholds`.
+pub fn calculate_financial_score_with(
+    input: &ScoringInput,
+    policy: MissingPillarPolicy,
+    thresholds: &ScoringThresholds,
+) -> ScoreBreakdown {
     debug!("Calculating financial score with input: {:?}", input);
 
-    let (fin_health_score, infra_score, efficiency_score) = if input.data_unreliable {
+    let norm = Normalizer::new(thresholds, &input.normalization);
+    let peer_group = input.classification.as_deref().and_then(PeerGroup::from_classification);
+    let (fin_health_score, infra_score, efficiency_score, liquidity_score) = if input.data_unreliable {
         debug!("Raw figures graded unreliable — suppressing raw-derived pillars");
-        (None, None, None)
+        (None, None, None, None)
     } else {
         (
-            calculate_fin_health_score(input.revenue, input.debt, input.transfers_operational)
+            calculate_fin_health_score(input.revenue, input.debt, input.transfers_operational, peer_group, norm)
                 .map(round_score),
             calculate_infra_score(
                 input.operational_expenditure,
                 input.capital_expenditure,
                 input.repairs_maintenance,
+                norm,
             )
             .map(round_score),
-            calculate_efficiency_score(input.operational_expenditure, input.revenue)
+            calculate_efficiency_score(input.operational_expenditure, input.revenue, norm)
+                .map(round_score),
+            calculate_liquidity_score(input.cash_balance, input.operational_expenditure, norm)
                 .map(round_score),
         )
     };
@@ -460,25 +1609,38 @@ pub fn calculate_financial_score(input: &ScoringInput) -> ScoreBreakdown {
         input.audit_outcome.as_deref(),
         input.uifw_expenditure,
         input.operational_expenditure,
+        norm,
     )
     .map(round_score);
 
-    // Overall requires every pillar; a missing pillar must not silently count as 0.
-    let overall_score = match (fin_health_score, infra_score, efficiency_score, accountability_score) {
-        (Some(fh), Some(infra), Some(eff), Some(acc)) => Some(round_score(clamp_score(
-            fh * WEIGHT_FIN_HEALTH
-                + infra * WEIGHT_INFRA
-                + eff * WEIGHT_EFFICIENCY
-                + acc * WEIGHT_ACCOUNTABILITY,
-        ))),
-        _ => {
-            debug!(
-                "Overall score unavailable (pillars: FH={:?}, Infra={:?}, Eff={:?}, Acc={:?})",
-                fin_health_score, infra_score, efficiency_score, accountability_score
-            );
-            None
+    // A missing pillar must not silently count as 0: it drops out of the
+    // weighting and the scored pillars are renormalized.
+    let scored = [
+        (fin_health_score, WEIGHT_FIN_HEALTH),
+        (infra_score, WEIGHT_INFRA),
+        (efficiency_score, WEIGHT_EFFICIENCY),
+        (accountability_score, WEIGHT_ACCOUNTABILITY),
+        (liquidity_score, WEIGHT_LIQUIDITY),
+    ];
+    let (sum, scored_weight) = scored
+        .iter()
+        .filter_map(|(score, weight)| score.map(|s| (s * weight, *weight)))
+        .fold((Decimal::ZERO, Decimal::ZERO), |(sum, total), (part, w)| (sum + part, total + w));
+    let enough_scored = match policy {
+        MissingPillarPolicy::Renormalize => scored_weight >= MIN_SCORED_WEIGHT,
+        MissingPillarPolicy::RequireRawPillars => {
+            fin_health_score.is_some() && infra_score.is_some() && efficiency_score.is_some()
         }
     };
+    let overall_score = if enough_scored && !scored_weight.is_zero() {
+        Some(round_score(clamp_score(sum / scored_weight)))
+    } else {
+        debug!(
+            "Overall score unavailable under {:?} (pillars: FH={:?}, Infra={:?}, Eff={:?}, Acc={:?}, Liq={:?})",
+            policy, fin_health_score, infra_score, efficiency_score, accountability_score, liquidity_score
+        );
+        None
+    };
 
     ScoreBreakdown {
         overall_score,
@@ -486,6 +1648,286 @@ pub fn calculate_financial_score(input: &ScoringInput) -> ScoreBreakdown {
         infrastructure_score: infra_score,
         efficiency_score,
         accountability_score,
+        liquidity_score,
+        grade: overall_score.map(grade_for_score),
+    }
+}
+
+// --- Score audit ---
+
+/// One metric inside a pillar: the ratio that was normalized, the anchors it
+/// was normalized between, and its weight within the pillar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ComponentAudit {
+    #[schema(value_type = String)]
+    pub metric: Cow<'static, str>,
+    /// The ratio (or months of cash cover) that was normalized; null for the
+    /// audit outcome, which is scored by label.
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal"
+    )]
+    pub value: Option<Decimal>,
+    /// Values scoring 0 and 100, linear between — except the capex share,
+    /// which is piecewise with 50 at 0.10. Under percentile normalization,
+    /// the sample values nearest the 10th and 90th percentiles, and the score
+    /// follows rank between them.
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal"
+    )]
+    pub score_0_at: Option<Decimal>,
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal"
+    )]
+    pub score_100_at: Option<Decimal>,
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal"
+    )]
+    pub sub_score: Option<Decimal>,
+    /// Weight within the pillar; 0 for an unreported optional metric.
+    #[serde(
+        serialize_with = "crate::utils::serialize_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_decimal"
+    )]
+    pub weight: Decimal,
+}
+
+/// One pillar: its score (as stored) is the weighted sum of its components,
+/// rounded to 2 dp; its contribution to the overall is score × effective weight.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PillarAudit {
+    #[schema(value_type = String)]
+    pub pillar: Cow<'static, str>,
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal"
+    )]
+    pub score: Option<Decimal>,
+    /// Nominal weight in the rubric.
+    #[serde(
+        serialize_with = "crate::utils::serialize_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_decimal"
+    )]
+    pub weight: Decimal,
+    /// Weight actually applied after renormalizing over the pillars present;
+    /// null when the pillar (or the overall) was not scored.
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal"
+    )]
+    pub effective_weight: Option<Decimal>,
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal"
+    )]
+    pub contribution: Option<Decimal>,
+    pub components: Vec<ComponentAudit>,
+}
+
+/// Everything that produced one overall score, so it can be reproduced by
+/// hand: overall = round(Σ pillar contributions). The raw figures are the
+/// financial year's own fields and are not repeated here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ScoreAudit {
+    pub score_version: i32,
+    /// "linear" or "percentile" (see `Normalization`).
+    #[schema(value_type = String)]
+    pub normalization: Cow<'static, str>,
+    /// Selects the own-revenue band; null = national band.
+    pub peer_group: Option<PeerGroup>,
+    /// When true, the raw-derived pillars were suppressed; their components
+    /// are still shown for reference.
+    pub data_unreliable: bool,
+    pub pillars: Vec<PillarAudit>,
+    #[serde(
+        serialize_with = "crate::utils::serialize_option_decimal_as_f64",
+        deserialize_with = "crate::utils::deserialize_option_decimal"
+    )]
+    pub overall_score: Option<Decimal>,
+    /// The weighted sum written out, e.g. "50.00 × 0.30 + … = 62.50"; null
+    /// without an overall score.
+    pub arithmetic: Option<String>,
+}
+
+/// Scores `input` like `calculate_financial_score` and returns the full
+/// derivation. Sizeable, so only built on request (`?audit=true`).
+pub fn explain_financial_score(input: &ScoringInput) -> ScoreAudit {
+    explain_financial_score_with(input, thresholds())
+}
+
+/// `explain_financial_score` against explicit thresholds.
+pub fn explain_financial_score_with(input: &ScoringInput, thresholds: &ScoringThresholds) -> ScoreAudit {
+    let breakdown = calculate_financial_score_with(input, missing_pillar_policy(), thresholds);
+    let norm = Normalizer::new(thresholds, &input.normalization);
+    let peer_group = input.classification.as_deref().and_then(PeerGroup::from_classification);
+    let (own_revenue_min, own_revenue_max) =
+        norm.anchors(Metric::OwnRevenueShare(peer_group), thresholds.own_revenue_band(peer_group));
+    let (debt_0_at, debt_100_at) =
+        norm.anchors(Metric::DebtRatio, (thresholds.debt_ratio_max, thresholds.debt_ratio_min));
+    let (capex_0_at, capex_100_at) =
+        norm.anchors(Metric::CapexShare, (thresholds.infra_ratio_worst, thresholds.infra_ratio_best));
+    let (rm_0_at, rm_100_at) = norm.anchors(Metric::RmIntensity, (Decimal::ZERO, thresholds.rm_intensity_max));
+    let (opex_0_at, opex_100_at) =
+        norm.anchors(Metric::OpexRatio, (thresholds.efficiency_ratio_worst, thresholds.efficiency_ratio_best));
+    let (uifw_0_at, uifw_100_at) = norm.anchors(Metric::UifwRatio, (thresholds.uifw_ratio_worst, Decimal::ZERO));
+    let (cash_0_at, cash_100_at) =
+        norm.anchors(Metric::CashCoverMonths, (thresholds.liquidity_months_min, thresholds.liquidity_months_max));
+    let opex = input.operational_expenditure;
+    let debt_basis = debt_ratio_basis(input.revenue, input.transfers_operational);
+
+    // An optional metric's weight is 0 when unreported; the primary metric
+    // then carries the whole pillar.
+    let blend = |optional_score: Option<Decimal>, optional_weight: Decimal| {
+        if optional_score.is_some() { (Decimal::ONE - optional_weight, optional_weight) } else { (Decimal::ONE, Decimal::ZERO) }
+    };
+    let rm_sub = calculate_rm_subscore(input.repairs_maintenance, opex, norm);
+    let (capex_weight, rm_weight) = blend(rm_sub, INFRA_RM_WEIGHT);
+    let uifw_sub = calculate_uifw_subscore(input.uifw_expenditure, opex, norm);
+    let (audit_weight, uifw_weight) = blend(uifw_sub, ACC_UIFW_WEIGHT);
+
+    let component = |metric, value, score_0_at, score_100_at, sub_score, weight| ComponentAudit {
+        metric: Cow::Borrowed(metric),
+        value,
+        score_0_at,
+        score_100_at,
+        sub_score,
+        weight,
+    };
+    let pillars = [
+        (
+            "financial_health",
+            breakdown.financial_health_score,
+            WEIGHT_FIN_HEALTH,
+            vec![
+                component(
+                    "own_revenue_share",
+                    own_revenue_share(input.revenue, input.transfers_operational),
+                    Some(own_revenue_min),
+                    Some(own_revenue_max),
+                    calculate_own_revenue_subscore(input.revenue, input.transfers_operational, peer_group, norm),
+                    FH_OWN_REVENUE_WEIGHT,
+                ),
+                component(
+                    if input.own_revenue().is_some() { "debt_to_own_revenue" } else { "debt_to_revenue" },
+                    debt_to_revenue(input.debt, debt_basis),
+                    Some(debt_0_at),
+                    Some(debt_100_at),
+                    calculate_debt_ratio_subscore(input.debt, debt_basis, norm),
+                    FH_DEBT_RATIO_WEIGHT,
+                ),
+            ],
+        ),
+        (
+            "infrastructure",
+            breakdown.infrastructure_score,
+            WEIGHT_INFRA,
+            vec![
+                component(
+                    "capex_share",
+                    capex_share(opex, input.capital_expenditure),
+                    Some(capex_0_at),
+                    Some(capex_100_at),
+                    calculate_capex_subscore(opex, input.capital_expenditure, norm),
+                    capex_weight,
+                ),
+                component(
+                    "repairs_maintenance_to_opex",
+                    rm_intensity(input.repairs_maintenance, opex),
+                    Some(rm_0_at),
+                    Some(rm_100_at),
+                    rm_sub,
+                    rm_weight,
+                ),
+            ],
+        ),
+        (
+            "efficiency",
+            breakdown.efficiency_score,
+            WEIGHT_EFFICIENCY,
+            vec![component(
+                "opex_to_revenue",
+                opex_to_revenue(opex, input.revenue),
+                Some(opex_0_at),
+                Some(opex_100_at),
+                calculate_efficiency_score(opex, input.revenue, norm),
+                Decimal::ONE,
+            )],
+        ),
+        (
+            "accountability",
+            breakdown.accountability_score,
+            WEIGHT_ACCOUNTABILITY,
+            vec![
+                component(
+                    "audit_outcome",
+                    None,
+                    None,
+                    None,
+                    calculate_audit_subscore(input.audit_outcome.as_deref()),
+                    audit_weight,
+                ),
+                component(
+                    "uifw_to_opex",
+                    uifw_to_opex(input.uifw_expenditure, opex),
+                    Some(uifw_0_at),
+                    Some(uifw_100_at),
+                    uifw_sub,
+                    uifw_weight,
+                ),
+            ],
+        ),
+        (
+            "liquidity",
+            breakdown.liquidity_score,
+            WEIGHT_LIQUIDITY,
+            vec![component(
+                "cash_cover_months",
+                cash_cover_months(input.cash_balance, opex),
+                Some(cash_0_at),
+                Some(cash_100_at),
+                calculate_liquidity_score(input.cash_balance, opex, norm),
+                Decimal::ONE,
+            )],
+        ),
+    ];
+
+    // Same renormalization as `calculate_financial_score`: only the pillars
+    // that were scored share the weight.
+    let scored_weight: Decimal = pillars.iter().filter(|p| p.1.is_some()).map(|p| p.2).sum();
+    let pillars: Vec<PillarAudit> = pillars
+        .into_iter()
+        .map(|(pillar, score, weight, components)| {
+            let effective_weight = breakdown.overall_score.and(score).map(|_| weight / scored_weight);
+            PillarAudit {
+                pillar: Cow::Borrowed(pillar),
+                score,
+                weight,
+                effective_weight,
+                contribution: score.zip(effective_weight).map(|(s, w)| s * w),
+                components,
+            }
+        })
+        .collect();
+
+    let arithmetic = breakdown.overall_score.map(|overall| {
+        let terms: Vec<String> = pillars
+            .iter()
+            .filter_map(|p| Some(format!("{:.2} × {}", p.score?, p.effective_weight?.normalize())))
+            .collect();
+        format!("{} = {:.2}", terms.join(" + "), overall)
+    });
+
+    ScoreAudit {
+        score_version: score_version_for(thresholds),
+        normalization: Cow::Borrowed(input.normalization.label()),
+        peer_group,
+        data_unreliable: input.data_unreliable,
+        pillars,
+        overall_score: breakdown.overall_score,
+        arithmetic,
     }
 }
 
@@ -493,11 +1935,14 @@ pub fn calculate_financial_score(input: &ScoringInput) -> ScoreBreakdown {
 mod tests {
     use super::*;
 
+    const DEFAULTS: &ScoringThresholds = &ScoringThresholds::DEFAULT;
+    const T: Normalizer<'static> = Normalizer { thresholds: DEFAULTS, distribution: None };
+
     fn full_input() -> ScoringInput {
         // Chosen so every pillar lands exactly on 100:
         // own-revenue share = 1.0 (no transfers), debt ratio = 0.1 (best),
         // opex ratio = 0.85 (best), capex ratio = 5.1M/17M = 0.30 (best),
-        // clean audit, zero UIFW, R&M at the 8% norm.
+        // clean audit, zero UIFW, R&M at the 8% norm, 3 months' cash cover.
         ScoringInput {
             revenue: Some(dec!(14_000_000)),
             operational_expenditure: Some(dec!(11_900_000)),
@@ -505,10 +1950,13 @@ mod tests {
             debt: Some(dec!(1_400_000)),
             audit_outcome: Some("Unqualified opinion with no findings".to_string()),
             population: Some(1000),
+            classification: None,
             transfers_operational: Some(dec!(0)),
             uifw_expenditure: Some(dec!(0)),
             repairs_maintenance: Some(dec!(952_000)), // 8% of opex
+            cash_balance: Some(dec!(2_975_000)),      // 3 months of opex
             data_unreliable: false,
+            normalization: Normalization::Linear,
         }
     }
 
@@ -518,22 +1966,67 @@ mod tests {
     fn own_revenue_share_anchors() {
         let revenue = Some(dec!(1_000_000));
         // fully self-funded -> 100
-        assert_eq!(calculate_own_revenue_subscore(revenue, Some(dec!(0))), Some(dec!(100.0)));
+        assert_eq!(calculate_own_revenue_subscore(revenue, Some(dec!(0)), None, T), Some(dec!(100.0)));
         // share 0.75 -> 100 (top anchor)
-        assert_eq!(calculate_own_revenue_subscore(revenue, Some(dec!(250_000))), Some(dec!(100.0)));
+        assert_eq!(calculate_own_revenue_subscore(revenue, Some(dec!(250_000)), None, T), Some(dec!(100.0)));
         // share 0.50 -> midpoint 50
-        assert_eq!(calculate_own_revenue_subscore(revenue, Some(dec!(500_000))), Some(dec!(50.0)));
+        assert_eq!(calculate_own_revenue_subscore(revenue, Some(dec!(500_000)), None, T), Some(dec!(50.0)));
         // share 0.25 -> 0 (bottom anchor)
-        assert_eq!(calculate_own_revenue_subscore(revenue, Some(dec!(750_000))), Some(dec!(0.0)));
+        assert_eq!(calculate_own_revenue_subscore(revenue, Some(dec!(750_000)), None, T), Some(dec!(0.0)));
         // fully grant-dependent -> 0
-        assert_eq!(calculate_own_revenue_subscore(revenue, Some(dec!(1_000_000))), Some(dec!(0.0)));
+        assert_eq!(calculate_own_revenue_subscore(revenue, Some(dec!(1_000_000)), None, T), Some(dec!(0.0)));
     }
 
     #[test]
     fn own_revenue_missing_or_invalid_inputs() {
-        assert_eq!(calculate_own_revenue_subscore(None, Some(dec!(1))), None);
-        assert_eq!(calculate_own_revenue_subscore(Some(dec!(1_000)), None), None);
-        assert_eq!(calculate_own_revenue_subscore(Some(Decimal::ZERO), Some(dec!(0))), None);
+        assert_eq!(calculate_own_revenue_subscore(None, Some(dec!(1)), None, T), None);
+        assert_eq!(calculate_own_revenue_subscore(Some(dec!(1_000)), None, None, T), None);
+        assert_eq!(calculate_own_revenue_subscore(Some(Decimal::ZERO), Some(dec!(0)), None, T), None);
+    }
+
+    #[test]
+    fn own_revenue_band_follows_peer_group() {
+        // Identical books — own-revenue share 0.70 — scored against each peer group.
+        let (revenue, transfers) = (Some(dec!(1_000_000)), Some(dec!(300_000)));
+        let metro = calculate_own_revenue_subscore(revenue, transfers, Some(PeerGroup::Metro), T);
+        let local = calculate_own_revenue_subscore(revenue, transfers, Some(PeerGroup::Local), T);
+        let district = calculate_own_revenue_subscore(revenue, transfers, Some(PeerGroup::District), T);
+        // Metro band 0.60-0.90: a third of the way up.
+        assert_eq!(metro.map(round_score), Some(dec!(33.33)));
+        // National band 0.25-0.75: near the top.
+        assert_eq!(local, Some(dec!(90.0)));
+        // District band 0.05-0.40: well above the bar.
+        assert_eq!(district, Some(dec!(100.0)));
+        // Unknown class falls back to the national band.
+        assert_eq!(calculate_own_revenue_subscore(revenue, transfers, None, T), local);
+    }
+
+    #[test]
+    fn metro_and_rural_local_with_same_figures_score_differently() {
+        let mut metro = full_input();
+        metro.transfers_operational = Some(dec!(4_200_000)); // share 0.70
+        metro.classification = Some("A".to_string());
+        let mut rural = metro.clone();
+        rural.classification = Some("B4".to_string());
+
+        let metro_score = calculate_financial_score(&metro);
+        let rural_score = calculate_financial_score(&rural);
+        // FH = (own-revenue + debt) / 2; debt 1.4M / own revenue 9.8M -> 95.24
+        assert_eq!(metro_score.financial_health_score, Some(dec!(64.29)));
+        assert_eq!(rural_score.financial_health_score, Some(dec!(92.62)));
+        assert!(metro_score.overall_score < rural_score.overall_score);
+    }
+
+    #[test]
+    fn peer_group_from_classification_labels() {
+        assert_eq!(PeerGroup::from_classification("A"), Some(PeerGroup::Metro));
+        assert_eq!(PeerGroup::from_classification("Metro"), Some(PeerGroup::Metro));
+        assert_eq!(PeerGroup::from_classification("b4"), Some(PeerGroup::Local));
+        assert_eq!(PeerGroup::from_classification("Local"), Some(PeerGroup::Local));
+        assert_eq!(PeerGroup::from_classification("C2"), Some(PeerGroup::District));
+        assert_eq!(PeerGroup::from_classification(" District "), Some(PeerGroup::District));
+        assert_eq!(PeerGroup::from_classification("B9"), None);
+        assert_eq!(PeerGroup::from_classification(""), None);
     }
 
     // --- Debt Ratio sub-score ---
@@ -542,37 +2035,59 @@ mod tests {
     fn debt_ratio_bounds_and_midpoint() {
         let revenue = Some(dec!(1_000_000));
         // ratio 0.1 -> 100
-        assert_eq!(calculate_debt_ratio_subscore(Some(dec!(100_000)), revenue), Some(dec!(100.0)));
+        assert_eq!(calculate_debt_ratio_subscore(Some(dec!(100_000)), revenue, T), Some(dec!(100.0)));
         // ratio 0.55 -> midpoint 50
-        assert_eq!(calculate_debt_ratio_subscore(Some(dec!(550_000)), revenue), Some(dec!(50.0)));
+        assert_eq!(calculate_debt_ratio_subscore(Some(dec!(550_000)), revenue, T), Some(dec!(50.0)));
         // ratio 1.0 -> 0
-        assert_eq!(calculate_debt_ratio_subscore(Some(dec!(1_000_000)), revenue), Some(dec!(0.0)));
+        assert_eq!(calculate_debt_ratio_subscore(Some(dec!(1_000_000)), revenue, T), Some(dec!(0.0)));
         // beyond worst clamps to 0
-        assert_eq!(calculate_debt_ratio_subscore(Some(dec!(2_000_000)), revenue), Some(dec!(0.0)));
+        assert_eq!(calculate_debt_ratio_subscore(Some(dec!(2_000_000)), revenue, T), Some(dec!(0.0)));
     }
 
     #[test]
     fn debt_ratio_missing_or_invalid_inputs() {
-        assert_eq!(calculate_debt_ratio_subscore(None, Some(dec!(1))), None);
-        assert_eq!(calculate_debt_ratio_subscore(Some(dec!(1)), None), None);
-        assert_eq!(calculate_debt_ratio_subscore(Some(dec!(1)), Some(Decimal::ZERO)), None);
+        assert_eq!(calculate_debt_ratio_subscore(None, Some(dec!(1)), T), None);
+        assert_eq!(calculate_debt_ratio_subscore(Some(dec!(1)), None, T), None);
+        assert_eq!(calculate_debt_ratio_subscore(Some(dec!(1)), Some(Decimal::ZERO), T), None);
     }
 
     // --- Financial Health pillar (v2: own-revenue + debt) ---
 
+    #[test]
+    fn debt_ratio_prefers_own_revenue_and_falls_back_to_total() {
+        let (revenue, debt) = (Some(dec!(1_000_000)), Some(dec!(325_000)));
+        // Half the budget is grants: debt / own revenue 500k = 0.65 -> 38.89,
+        // where against total revenue (0.325) it would read 75.
+        assert_eq!(debt_ratio_basis(revenue, Some(dec!(500_000))), Some(dec!(500_000)));
+        assert_eq!(
+            calculate_fin_health_score(revenue, debt, Some(dec!(500_000)), None, T).map(round_score),
+            Some(dec!(44.44)) // (own-revenue share 0.5 -> 50 + 38.89) / 2
+        );
+        // Transfers unknown, or swallowing all revenue: total revenue it is.
+        assert_eq!(debt_ratio_basis(revenue, None), revenue);
+        assert_eq!(debt_ratio_basis(revenue, Some(dec!(1_000_000))), revenue);
+        assert_eq!(
+            calculate_debt_ratio_subscore(debt, debt_ratio_basis(revenue, None), T),
+            Some(dec!(75.0))
+        );
+        let input = ScoringInput { revenue, transfers_operational: Some(dec!(500_000)), ..Default::default() };
+        assert_eq!(input.own_revenue(), Some(dec!(500_000)));
+        assert_eq!(ScoringInput { revenue, ..Default::default() }.own_revenue(), None);
+    }
+
     #[test]
     fn fin_health_averages_subscores() {
         // own-revenue share 1.0 -> 100; debt ratio 0.55 -> 50; average = 75
         assert_eq!(
-            calculate_fin_health_score(Some(dec!(1_000_000)), Some(dec!(550_000)), Some(dec!(0))),
+            calculate_fin_health_score(Some(dec!(1_000_000)), Some(dec!(550_000)), Some(dec!(0)), None, T),
             Some(dec!(75.0))
         );
     }
 
     #[test]
     fn fin_health_requires_both_subscores() {
-        assert_eq!(calculate_fin_health_score(Some(dec!(1)), None, Some(dec!(0))), None);
-        assert_eq!(calculate_fin_health_score(Some(dec!(1)), Some(dec!(1)), None), None);
+        assert_eq!(calculate_fin_health_score(Some(dec!(1)), None, Some(dec!(0)), None, T), None);
+        assert_eq!(calculate_fin_health_score(Some(dec!(1)), Some(dec!(1)), None, None, T), None);
     }
 
     // --- Infrastructure pillar (v2: capex + optional R&M) ---
@@ -581,22 +2096,22 @@ mod tests {
     fn infra_capex_piecewise_points_without_rm() {
         // ratio 0.10 -> 50 (capex 1000 of total 10_000)
         assert_eq!(
-            calculate_infra_score(Some(dec!(9_000)), Some(dec!(1_000)), None),
+            calculate_infra_score(Some(dec!(9_000)), Some(dec!(1_000)), None, T),
             Some(dec!(50.0))
         );
         // ratio 0.20 -> 75 (halfway between MID 0.10=50 and BEST 0.30=100)
         assert_eq!(
-            calculate_infra_score(Some(dec!(8_000)), Some(dec!(2_000)), None),
+            calculate_infra_score(Some(dec!(8_000)), Some(dec!(2_000)), None, T),
             Some(dec!(75.0))
         );
         // ratio 0.30 -> 100
         assert_eq!(
-            calculate_infra_score(Some(dec!(7_000)), Some(dec!(3_000)), None),
+            calculate_infra_score(Some(dec!(7_000)), Some(dec!(3_000)), None, T),
             Some(dec!(100.0))
         );
         // ratio 0 -> 0
         assert_eq!(
-            calculate_infra_score(Some(dec!(10_000)), Some(dec!(0)), None),
+            calculate_infra_score(Some(dec!(10_000)), Some(dec!(0)), None, T),
             Some(dec!(0.0))
         );
     }
@@ -605,22 +2120,22 @@ mod tests {
     fn infra_blends_rm_when_reported() {
         // capex ratio 0.30 -> 100; R&M 4% of opex -> 50; blend 0.7*100 + 0.3*50 = 85
         assert_eq!(
-            calculate_infra_score(Some(dec!(7_000)), Some(dec!(3_000)), Some(dec!(280))),
+            calculate_infra_score(Some(dec!(7_000)), Some(dec!(3_000)), Some(dec!(280)), T),
             Some(dec!(85.0))
         );
         // R&M at/above the 8% norm -> 100; blend stays 100
         assert_eq!(
-            calculate_infra_score(Some(dec!(7_000)), Some(dec!(3_000)), Some(dec!(560))),
+            calculate_infra_score(Some(dec!(7_000)), Some(dec!(3_000)), Some(dec!(560)), T),
             Some(dec!(100.0))
         );
     }
 
     #[test]
     fn infra_missing_inputs() {
-        assert_eq!(calculate_infra_score(None, Some(dec!(1)), None), None);
-        assert_eq!(calculate_infra_score(Some(dec!(1)), None, None), None);
+        assert_eq!(calculate_infra_score(None, Some(dec!(1)), None, T), None);
+        assert_eq!(calculate_infra_score(Some(dec!(1)), None, None, T), None);
         // zero total expenditure is an earned 0, not missing data
-        assert_eq!(calculate_infra_score(Some(dec!(0)), Some(dec!(0)), None), Some(dec!(0.0)));
+        assert_eq!(calculate_infra_score(Some(dec!(0)), Some(dec!(0)), None, T), Some(dec!(0.0)));
     }
 
     // --- Efficiency pillar ---
@@ -628,25 +2143,27 @@ mod tests {
     #[test]
     fn efficiency_linear_with_breakeven_at_50() {
         let revenue = Some(dec!(1_000_000));
-        assert_eq!(calculate_efficiency_score(Some(dec!(850_000)), revenue), Some(dec!(100.0)));
-        assert_eq!(calculate_efficiency_score(Some(dec!(1_000_000)), revenue), Some(dec!(50.0)));
-        assert_eq!(calculate_efficiency_score(Some(dec!(1_150_000)), revenue), Some(dec!(0.0)));
-        assert_eq!(calculate_efficiency_score(Some(dec!(500_000)), revenue), Some(dec!(100.0)));
-        assert_eq!(calculate_efficiency_score(Some(dec!(2_000_000)), revenue), Some(dec!(0.0)));
+        assert_eq!(calculate_efficiency_score(Some(dec!(850_000)), revenue, T), Some(dec!(100.0)));
+        assert_eq!(calculate_efficiency_score(Some(dec!(1_000_000)), revenue, T), Some(dec!(50.0)));
+        assert_eq!(calculate_efficiency_score(Some(dec!(1_150_000)), revenue, T), Some(dec!(0.0)));
+        assert_eq!(calculate_efficiency_score(Some(dec!(500_000)), revenue, T), Some(dec!(100.0)));
+        assert_eq!(calculate_efficiency_score(Some(dec!(2_000_000)), revenue, T), Some(dec!(0.0)));
     }
 
     #[test]
     fn efficiency_missing_or_invalid_inputs() {
-        assert_eq!(calculate_efficiency_score(None, Some(dec!(1))), None);
-        assert_eq!(calculate_efficiency_score(Some(dec!(1)), None), None);
-        assert_eq!(calculate_efficiency_score(Some(dec!(1)), Some(Decimal::ZERO)), None);
+        assert_eq!(calculate_efficiency_score(None, Some(dec!(1)), T), None);
+        assert_eq!(calculate_efficiency_score(Some(dec!(1)), None, T), None);
+        assert_eq!(calculate_efficiency_score(Some(dec!(1)), Some(Decimal::ZERO), T), None);
+        // Negative revenue over negative spend would be a "healthy" ratio.
+        assert_eq!(calculate_efficiency_score(Some(dec!(-400)), Some(dec!(-500)), T), None);
     }
 
     // --- Accountability pillar (v2: audit + optional UIFW) ---
 
     #[test]
     fn accountability_maps_real_world_labels_audit_only() {
-        let score = |s: &str| calculate_accountability_score(Some(s), None, None);
+        let score = |s: &str| calculate_accountability_score(Some(s), None, None, T);
         assert_eq!(score("Unqualified - No findings"), Some(dec!(100.0)));
         assert_eq!(score("Unqualified opinion with no findings"), Some(dec!(100.0)));
         assert_eq!(score("UNQUALIFIED OPINION WITH FINDINGS"), Some(dec!(75.0)));
@@ -654,8 +2171,16 @@ mod tests {
         assert_eq!(score("Qualified opinion"), Some(dec!(50.0)));
         assert_eq!(score("Adverse opinion"), Some(dec!(25.0)));
         assert_eq!(score("Disclaimer of opinion"), Some(dec!(25.0)));
-        assert_eq!(score("Outstanding"), Some(dec!(0.0)));
-        assert_eq!(score("Financial statements not submitted"), Some(dec!(0.0)));
+    }
+
+    #[test]
+    fn accountability_outstanding_is_none_not_zero() {
+        let score = |s: &str| calculate_accountability_score(Some(s), Some(dec!(0)), Some(dec!(1)), T);
+        assert_eq!(AuditOutcome::from("Not yet released"), AuditOutcome::Outstanding);
+        assert_eq!(AuditOutcome::from(" AUDIT NOT FINALISED "), AuditOutcome::Outstanding);
+        assert_eq!(score("Outstanding"), None);
+        assert_eq!(score("Financial statements not submitted"), None);
+        assert_eq!(score("Not yet released"), None);
     }
 
     #[test]
@@ -663,30 +2188,26 @@ mod tests {
         let opex = Some(dec!(1_000_000));
         // clean audit + zero UIFW -> 100
         assert_eq!(
-            calculate_accountability_score(Some("Unqualified - No findings"), Some(dec!(0)), opex),
+            calculate_accountability_score(Some("Unqualified - No findings"), Some(dec!(0)), opex, T),
             Some(dec!(100.0))
         );
         // clean audit + UIFW at 5% of opex (sub-score 50): 0.7*100 + 0.3*50 = 85
         assert_eq!(
-            calculate_accountability_score(
-                Some("Unqualified - No findings"),
-                Some(dec!(50_000)),
-                opex
-            ),
+            calculate_accountability_score(Some("Unqualified - No findings"), Some(dec!(50_000)), opex, T),
             Some(dec!(85.0))
         );
         // qualified audit + UIFW >= 10% of opex (sub-score 0): 0.7*50 = 35
         assert_eq!(
-            calculate_accountability_score(Some("Qualified"), Some(dec!(200_000)), opex),
+            calculate_accountability_score(Some("Qualified"), Some(dec!(200_000)), opex, T),
             Some(dec!(35.0))
         );
     }
 
     #[test]
     fn accountability_unknown_or_missing_is_none() {
-        assert_eq!(calculate_accountability_score(None, Some(dec!(0)), Some(dec!(1))), None);
+        assert_eq!(calculate_accountability_score(None, Some(dec!(0)), Some(dec!(1)), T), None);
         assert_eq!(
-            calculate_accountability_score(Some("Some future label"), Some(dec!(0)), Some(dec!(1))),
+            calculate_accountability_score(Some("Some future label"), Some(dec!(0)), Some(dec!(1)), T),
             None
         );
     }
@@ -700,16 +2221,18 @@ mod tests {
         assert_eq!(breakdown.infrastructure_score, Some(dec!(100.0)));
         assert_eq!(breakdown.efficiency_score, Some(dec!(100.0)));
         assert_eq!(breakdown.accountability_score, Some(dec!(100.0)));
+        assert_eq!(breakdown.liquidity_score, Some(dec!(100.0)));
         assert_eq!(breakdown.overall_score, Some(dec!(100.0)));
     }
 
     #[test]
     fn overall_weighted_mix() {
-        // FH: own-revenue share 0.5 -> 50, debt ratio 0.55 -> 50 => 50
+        // FH: own-revenue share 0.5 -> 50, debt 3.85M / own revenue 3.5M -> 0 => 25
         // Infra: capex 3M of 10M total -> ratio 0.30 => 100 (no R&M reported)
         // Eff: opex 7M / rev 7M -> ratio 1.0 => 50
         // Acc: Qualified, no UIFW reported => 50
-        // Overall = 50*0.30 + 100*0.25 + 50*0.25 + 50*0.20 = 62.5
+        // No cash reported: liquidity drops out, core weights renormalize to
+        // the v2 weights. Overall = 25*0.30 + 100*0.25 + 50*0.25 + 50*0.20 = 55
         let input = ScoringInput {
             revenue: Some(dec!(7_000_000)),
             operational_expenditure: Some(dec!(7_000_000)),
@@ -721,22 +2244,78 @@ mod tests {
             ..Default::default()
         };
         let breakdown = calculate_financial_score(&input);
-        assert_eq!(breakdown.overall_score, Some(dec!(62.5)));
+        assert_eq!(breakdown.overall_score, Some(dec!(55.00)));
     }
 
     #[test]
-    fn overall_none_when_any_pillar_missing() {
+    fn overall_renormalizes_a_missing_pillar_instead_of_zeroing_it() {
+        // No capex reported knocks out Infrastructure only; 2 months' cash
+        // gives Liquidity 50, everything else 100.
         let mut input = full_input();
-        input.debt = None; // knocks out Financial Health only
+        input.capital_expenditure = None;
+        input.cash_balance = Some(dec!(1_983_333.33));
         let breakdown = calculate_financial_score(&input);
+        assert_eq!(breakdown.infrastructure_score, None);
+        // Counting the missing pillar as 0 would give
+        // 100*0.24 + 0*0.20 + 100*0.20 + 100*0.16 + 50*0.20 = 70.
+        let zero_default = dec!(70);
+        // Renormalized: (24 + 20 + 16 + 10) / 0.80 = 87.5.
+        assert_eq!(breakdown.overall_score, Some(dec!(87.50)));
+        assert!(breakdown.overall_score.unwrap() > zero_default);
+        let audit = explain_financial_score(&input);
+        assert_eq!(audit.overall_score, breakdown.overall_score);
+        assert_reproducible(&audit);
+    }
+
+    #[test]
+    fn require_raw_pillars_policy_keeps_the_strict_rule() {
+        let mut input = full_input();
+        input.debt = None; // knocks out Financial Health only
+        let breakdown = calculate_financial_score_with(&input, MissingPillarPolicy::RequireRawPillars, DEFAULTS);
         assert_eq!(breakdown.financial_health_score, None);
         assert_eq!(breakdown.infrastructure_score, Some(dec!(100.0)));
-        assert_eq!(breakdown.efficiency_score, Some(dec!(100.0)));
-        assert_eq!(breakdown.accountability_score, Some(dec!(100.0)));
         assert_eq!(
             breakdown.overall_score, None,
-            "partial data must not produce an overall score"
+            "partial raw data must not produce an overall score under the strict policy"
         );
+        // Accountability may still drop out under the strict policy.
+        let mut input = full_input();
+        input.audit_outcome = None;
+        let breakdown = calculate_financial_score_with(&input, MissingPillarPolicy::RequireRawPillars, DEFAULTS);
+        assert_eq!(breakdown.overall_score, Some(dec!(100.0)));
+    }
+
+    #[test]
+    fn overall_needs_half_the_rubric_scored() {
+        // Only Efficiency (0.20) and Accountability (0.16) scored: 0.36 < 0.5.
+        let input = ScoringInput {
+            revenue: Some(dec!(1_000_000)),
+            operational_expenditure: Some(dec!(850_000)),
+            audit_outcome: Some("Qualified".to_string()),
+            ..Default::default()
+        };
+        let breakdown = calculate_financial_score(&input);
+        assert_eq!(breakdown.efficiency_score, Some(dec!(100.0)));
+        assert_eq!(breakdown.accountability_score, Some(dec!(50.0)));
+        assert_eq!(breakdown.overall_score, None);
+    }
+
+    #[test]
+    fn overall_renormalizes_without_accountability() {
+        // Liquidity 50 (2 months' cash), the rest at 100. With no audit opinion
+        // the 16% accountability weight drops out:
+        // (100*0.24 + 100*0.20 + 100*0.20 + 50*0.20) / 0.84 = 88.10
+        let mut input = full_input();
+        input.cash_balance = Some(dec!(1_983_333.33));
+        for outcome in [Some("Outstanding"), None, Some("§§ garbage ??")] {
+            input.audit_outcome = outcome.map(str::to_string);
+            let breakdown = calculate_financial_score(&input);
+            assert_eq!(breakdown.accountability_score, None, "{outcome:?}");
+            assert_eq!(breakdown.overall_score, Some(dec!(88.10)), "{outcome:?}");
+            let audit = explain_financial_score(&input);
+            assert_eq!(audit.overall_score, breakdown.overall_score);
+            assert_reproducible(&audit);
+        }
     }
 
     #[test]
@@ -749,6 +2328,7 @@ mod tests {
         assert_eq!(breakdown.infrastructure_score, None);
         assert_eq!(breakdown.efficiency_score, None);
         assert_eq!(breakdown.accountability_score, None);
+        assert_eq!(breakdown.liquidity_score, None);
     }
 
     #[test]
@@ -762,6 +2342,700 @@ mod tests {
         assert_eq!(breakdown.efficiency_score, None);
         // The audit pillar is the AG's statement, independent of the books.
         assert_eq!(breakdown.accountability_score, Some(dec!(100.0)));
+        assert_eq!(breakdown.liquidity_score, None);
         assert_eq!(breakdown.overall_score, None);
     }
+
+    // --- Liquidity (v3) ---
+
+    #[test]
+    fn liquidity_linear_between_one_and_three_months() {
+        let opex = Some(dec!(1_200_000)); // 100k per month
+        assert_eq!(calculate_liquidity_score(Some(dec!(100_000)), opex, T), Some(dec!(0)));
+        assert_eq!(calculate_liquidity_score(Some(dec!(200_000)), opex, T), Some(dec!(50)));
+        assert_eq!(calculate_liquidity_score(Some(dec!(300_000)), opex, T), Some(dec!(100)));
+        assert_eq!(calculate_liquidity_score(Some(dec!(900_000)), opex, T), Some(dec!(100)));
+        // An overdraft is a real (bad) figure, not missing data.
+        assert_eq!(calculate_liquidity_score(Some(dec!(-50_000)), opex, T), Some(dec!(0)));
+    }
+
+    #[test]
+    fn liquidity_missing_or_invalid_inputs() {
+        assert_eq!(calculate_liquidity_score(None, Some(dec!(1_200_000)), T), None);
+        assert_eq!(calculate_liquidity_score(Some(dec!(100_000)), None, T), None);
+        assert_eq!(calculate_liquidity_score(Some(dec!(100_000)), Some(dec!(0)), T), None);
+    }
+
+    #[test]
+    fn overall_includes_liquidity_when_reported() {
+        // Every core pillar at 100, cash cover of 2 months -> liquidity 50.
+        // Overall = 100*0.80 + 50*0.20 = 90
+        let mut input = full_input();
+        input.cash_balance = Some(dec!(1_983_333.33));
+        let breakdown = calculate_financial_score(&input);
+        assert_eq!(breakdown.liquidity_score, Some(dec!(50.00)));
+        assert_eq!(breakdown.overall_score, Some(dec!(90.00)));
+
+        // Without cash data the core pillars alone still produce a score.
+        input.cash_balance = None;
+        let breakdown = calculate_financial_score(&input);
+        assert_eq!(breakdown.liquidity_score, None);
+        assert_eq!(breakdown.overall_score, Some(dec!(100.0)));
+    }
+
+    // --- ScoringConfig validation ---
+
+    #[test]
+    fn default_scoring_config_is_valid() {
+        assert_eq!(ScoringConfig::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn scoring_config_reports_every_violation() {
+        let config = ScoringConfig {
+            weight_accountability: dec!(0.26), // pillar weights now sum to 1.10
+            thresholds: ScoringThresholds {
+                debt_ratio_min: dec!(1.0),
+                debt_ratio_max: dec!(0.5),
+                infra_ratio_mid: dec!(0.40), // above infra_ratio_best
+                uifw_ratio_worst: dec!(0),
+                ..ScoringThresholds::DEFAULT
+            },
+            ..ScoringConfig::default()
+        };
+        let errors = config.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["weights", "debt_ratio", "infra_ratio", "uifw_ratio_worst"]);
+        assert!(errors[0].message.contains("1.10"), "{:?}", errors[0]);
+    }
+
+    // --- Scoring thresholds ---
+
+    #[test]
+    fn custom_thresholds_change_the_score() {
+        // Debt ratio 0.55: halfway between the default anchors (0.1, 1.0).
+        let input = ScoringInput { debt: Some(dec!(7_700_000)), ..full_input() };
+        let default = calculate_financial_score_with(&input, MissingPillarPolicy::default(), DEFAULTS);
+        assert_eq!(default.financial_health_score, Some(dec!(75.0)));
+
+        let lenient = ScoringThresholds { debt_ratio_max: dec!(1.9), ..ScoringThresholds::DEFAULT };
+        let breakdown = calculate_financial_score_with(&input, MissingPillarPolicy::default(), &lenient);
+        assert_eq!(breakdown.financial_health_score, Some(dec!(87.5)));
+        assert!(breakdown.overall_score > default.overall_score);
+
+        let audit = explain_financial_score_with(&input, &lenient);
+        assert_eq!(audit.pillars[0].components[1].score_0_at, Some(dec!(1.9)));
+        assert_eq!(audit.overall_score, breakdown.overall_score);
+    }
+
+    #[test]
+    fn overridden_thresholds_are_stamped_with_their_own_score_version() {
+        assert_eq!(score_version_for(DEFAULTS), SCORE_VERSION);
+
+        let lenient = ScoringThresholds { debt_ratio_max: dec!(1.9), ..ScoringThresholds::DEFAULT };
+        let version = score_version_for(&lenient);
+        assert_ne!(version, SCORE_VERSION);
+        assert_eq!(version % 100, SCORE_VERSION);
+        // The same anchors, however written, get the same version...
+        let rewritten = ScoringThresholds { debt_ratio_max: dec!(1.90), ..ScoringThresholds::DEFAULT };
+        assert_eq!(score_version_for(&rewritten), version);
+        // ...and different anchors a different one.
+        let stricter = ScoringThresholds { debt_ratio_max: dec!(0.9), ..ScoringThresholds::DEFAULT };
+        assert_ne!(score_version_for(&stricter), version);
+
+        let input = ScoringInput { debt: Some(dec!(7_700_000)), ..full_input() };
+        assert_eq!(explain_financial_score_with(&input, &lenient).score_version, version);
+        assert_eq!(methodology(&ScoringConfig::with_thresholds(&lenient)).score_version, version);
+    }
+
+    #[test]
+    fn requiring_raw_pillars_is_stamped_with_its_own_score_version() {
+        let lenient = ScoringThresholds { debt_ratio_max: dec!(1.9), ..ScoringThresholds::DEFAULT };
+        let stamps = [
+            score_version_with(DEFAULTS, MissingPillarPolicy::Renormalize),
+            score_version_with(DEFAULTS, MissingPillarPolicy::RequireRawPillars),
+            score_version_with(&lenient, MissingPillarPolicy::Renormalize),
+            score_version_with(&lenient, MissingPillarPolicy::RequireRawPillars),
+        ];
+        assert_eq!(stamps[0], SCORE_VERSION);
+        for (i, stamp) in stamps.iter().enumerate() {
+            assert_eq!(stamp % 100, SCORE_VERSION);
+            assert!(!stamps[..i].contains(stamp), "{stamps:?}");
+        }
+    }
+
+    #[test]
+    fn thresholds_reject_inverted_or_nonsensical_ranges() {
+        assert_eq!(ScoringThresholds::DEFAULT.validate(), Ok(()));
+        assert_eq!(ScoringConfig::with_thresholds(DEFAULTS), ScoringConfig::default());
+        assert_eq!(ScoringConfig::default().thresholds, ScoringThresholds::DEFAULT);
+
+        let thresholds = ScoringThresholds {
+            liquidity_months_min: dec!(3),
+            liquidity_months_max: dec!(3),
+            own_revenue_share_max: dec!(1.5),
+            rm_intensity_max: dec!(-0.08),
+            ..ScoringThresholds::DEFAULT
+        };
+        let errors = thresholds.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["liquidity_months", "own_revenue_share_max", "rm_intensity_max"]);
+        assert!(set_thresholds(thresholds).is_err());
+    }
+
+    #[test]
+    fn thresholds_file_overrides_only_the_fields_it_names() {
+        let path = std::env::temp_dir().join(format!("thresholds-test-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{ "debt_ratio_max": "1.5", "liquidity_months_max": "6" }"#).unwrap();
+        let thresholds = ScoringThresholds::from_file(&path).unwrap();
+        assert_eq!(thresholds.debt_ratio_max, dec!(1.5));
+        assert_eq!(thresholds.liquidity_months_max, dec!(6));
+        assert_eq!(thresholds.debt_ratio_min, ScoringThresholds::DEFAULT.debt_ratio_min);
+
+        // Plain JSON numbers work as well as strings, and keep every digit.
+        std::fs::write(&path, r#"{ "debt_ratio_max": 1.55, "liquidity_months_max": 6 }"#).unwrap();
+        let thresholds = ScoringThresholds::from_file(&path).unwrap();
+        assert_eq!(thresholds.debt_ratio_max, dec!(1.55));
+        assert_eq!(thresholds.liquidity_months_max, dec!(6));
+
+        std::fs::write(&path, r#"{ "debt_ratio_min": "2" }"#).unwrap();
+        let err = ScoringThresholds::from_file(&path).unwrap_err();
+        assert!(err.contains("debt_ratio_min (2) must be less than debt_ratio_max"), "{err}");
+
+        std::fs::write(&path, r#"{ "debt_ratio_maximum": "2" }"#).unwrap();
+        assert!(ScoringThresholds::from_file(&path).is_err(), "unknown fields are rejected");
+        std::fs::remove_file(&path).ok();
+    }
+
+    // --- Percentile normalization ---
+
+    /// 40 municipalities alike but for their debt ratios: 0.05, 0.10, …, 2.00.
+    fn year_of_debt_ratios() -> Vec<ScoringInput> {
+        (1..=40)
+            .map(|i| ScoringInput { debt: Some(dec!(14_000_000) * Decimal::from(i) / dec!(20)), ..full_input() })
+            .collect()
+    }
+
+    #[test]
+    fn percentile_rank_splits_ties() {
+        let distribution = Distribution::new([dec!(3), dec!(2), dec!(1), dec!(2)]);
+        assert_eq!(distribution.percentile_rank(dec!(2)), dec!(0.5));
+        assert_eq!(distribution.percentile_rank(dec!(0)), Decimal::ZERO);
+        assert_eq!(distribution.percentile_rank(dec!(3)), dec!(0.875));
+        assert_eq!(distribution.score(dec!(2), Direction::HigherIsBetter), None, "too few values to rank against");
+    }
+
+    #[test]
+    fn both_modes_score_the_same_input() {
+        let year = year_of_debt_ratios();
+        let distribution = Arc::new(MetricDistribution::from_inputs(&year));
+        assert_eq!(distribution.debt_ratio.len(), 40);
+        assert!(distribution.own_revenue_share_metro.is_empty());
+
+        // Debt ratio 0.55: halfway between the fixed anchors, but better than
+        // 29 of the other 39 municipalities.
+        let linear = year[10].clone();
+        let percentile =
+            ScoringInput { normalization: Normalization::Percentile(distribution.clone()), ..linear.clone() };
+        let norm = Normalizer { thresholds: DEFAULTS, distribution: Some(distribution.as_ref()) };
+        assert_eq!(calculate_debt_ratio_subscore(linear.debt, linear.revenue, T), Some(dec!(50.0)));
+        // Mid-rank 10.5/40 = 0.2625, inverted to 0.7375, then scaled from the
+        // 10th-90th percentile band: (0.7375 - 0.10) / 0.80 = 0.796875.
+        assert_eq!(calculate_debt_ratio_subscore(linear.debt, linear.revenue, norm), Some(dec!(79.6875)));
+
+        assert_eq!(calculate_financial_score(&linear).financial_health_score, Some(dec!(75.0)));
+        // Every municipality shares the same own-revenue share, so each ranks
+        // at the median (50) on it: 0.5 * 50 + 0.5 * 79.6875.
+        assert_eq!(calculate_financial_score(&percentile).financial_health_score, Some(dec!(64.84)));
+
+        // The extremes saturate.
+        let best = ScoringInput { normalization: percentile.normalization.clone(), ..year[0].clone() };
+        let worst = ScoringInput { normalization: percentile.normalization.clone(), ..year[39].clone() };
+        assert_eq!(calculate_debt_ratio_subscore(best.debt, best.revenue, norm), Some(dec!(100.0)));
+        assert_eq!(calculate_debt_ratio_subscore(worst.debt, worst.revenue, norm), Some(dec!(0.0)));
+
+        let audit = explain_financial_score(&percentile);
+        assert_eq!(audit.normalization, "percentile");
+        let debt = &audit.pillars[0].components[1];
+        assert_eq!((debt.score_0_at, debt.score_100_at), (Some(dec!(1.8)), Some(dec!(0.2))));
+        assert_eq!(explain_financial_score(&linear).normalization, "linear");
+    }
+
+    #[test]
+    fn percentile_mode_keeps_fixed_anchors_for_thin_samples() {
+        let distribution = Arc::new(MetricDistribution::from_inputs(&year_of_debt_ratios()));
+        let metro = ScoringInput { classification: Some("A".to_string()), ..full_input() };
+        let ranked = ScoringInput { normalization: Normalization::Percentile(distribution), ..metro.clone() };
+        // No metro in the sample: own-revenue share stays on the metro band.
+        let audit = explain_financial_score(&ranked);
+        let own_revenue = &audit.pillars[0].components[0];
+        assert_eq!((own_revenue.score_0_at, own_revenue.score_100_at), (Some(dec!(0.60)), Some(dec!(0.90))));
+        assert_eq!(own_revenue.sub_score, explain_financial_score(&metro).pillars[0].components[0].sub_score);
+    }
+
+    // --- Per-capita figures ---
+
+    #[test]
+    fn per_capita_divides_by_population() {
+        assert_eq!(per_capita(Some(dec!(1_500_000)), Some(1_200)), Some(dec!(1250.00)));
+        assert_eq!(per_capita(Some(dec!(100)), Some(3)), Some(dec!(33.33)));
+    }
+
+    #[test]
+    fn per_capita_missing_or_zero_population_is_none() {
+        assert_eq!(per_capita(Some(dec!(1_000)), None), None);
+        assert_eq!(per_capita(Some(dec!(1_000)), Some(0)), None);
+        assert_eq!(per_capita(None, Some(1_000)), None);
+    }
+
+    #[test]
+    fn population_count_rejects_what_is_not_a_head_count() {
+        assert_eq!(population_count(Some(433_688.0)), Some(433_688));
+        assert_eq!(population_count(Some(1_200.6)), Some(1_201));
+        assert_eq!(population_count(Some(0.0)), Some(0));
+        assert_eq!(population_count(Some(f32::NAN)), None);
+        assert_eq!(population_count(Some(f32::INFINITY)), None);
+        assert_eq!(population_count(Some(-1.0)), None);
+        assert_eq!(population_count(Some(5e9)), None);
+        assert_eq!(population_count(None), None);
+        // A rejected population leaves per-capita figures empty, never garbage.
+        assert_eq!(per_capita(Some(dec!(1_000)), population_count(Some(f32::NAN))), None);
+    }
+
+    #[test]
+    fn operating_surplus_is_revenue_less_opex() {
+        assert_eq!(operating_surplus(Some(dec!(1_000_000)), Some(dec!(400_000))), Some(dec!(600_000)));
+        assert_eq!(operating_surplus(Some(dec!(400_000)), Some(dec!(1_000_000))), Some(dec!(-600_000)));
+        assert_eq!(operating_surplus(None, Some(dec!(1))), None);
+        assert_eq!(operating_surplus(Some(dec!(1)), None), None);
+    }
+
+    // --- Score audit ---
+
+    /// Recomputes pillars and the overall from the serialized audit alone,
+    /// as a reader of the API response would.
+    fn assert_reproducible(audit: &ScoreAudit) {
+        let json = serde_json::to_value(audit).unwrap();
+        let mut overall = 0.0;
+        for pillar in json["pillars"].as_array().unwrap() {
+            let Some(score) = pillar["score"].as_f64() else { continue };
+            let from_components: f64 = pillar["components"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|c| c["sub_score"].as_f64().unwrap_or(0.0) * c["weight"].as_f64().unwrap())
+                .sum();
+            assert!((from_components - score).abs() <= 0.005, "{}: {from_components} vs {score}", pillar["pillar"]);
+            if let Some(weight) = pillar["effective_weight"].as_f64() {
+                overall += score * weight;
+            }
+        }
+        let reported = json["overall_score"].as_f64().unwrap();
+        assert!((overall - reported).abs() <= 0.005, "recomputed {overall} vs reported {reported}");
+    }
+
+    #[test]
+    fn audit_reproduces_overall_with_all_pillars() {
+        let input = ScoringInput {
+            revenue: Some(dec!(9_000_000)),
+            operational_expenditure: Some(dec!(8_100_000)),
+            capital_expenditure: Some(dec!(1_300_000)),
+            debt: Some(dec!(2_700_000)),
+            audit_outcome: Some("Unqualified - Emphasis of Matter items".to_string()),
+            classification: Some("B3".to_string()),
+            transfers_operational: Some(dec!(3_100_000)),
+            uifw_expenditure: Some(dec!(210_000)),
+            repairs_maintenance: Some(dec!(330_000)),
+            cash_balance: Some(dec!(1_150_000)),
+            ..Default::default()
+        };
+        let audit = explain_financial_score(&input);
+        assert_eq!(audit.overall_score, calculate_financial_score(&input).overall_score);
+        assert_eq!(audit.peer_group, Some(PeerGroup::Local));
+        // With every pillar present the effective weights are the nominal ones.
+        assert!(audit.pillars.iter().all(|p| p.effective_weight == Some(p.weight)));
+        assert_reproducible(&audit);
+    }
+
+    #[test]
+    fn audit_shows_renormalized_weights_without_liquidity() {
+        let mut input = full_input();
+        input.cash_balance = None;
+        input.repairs_maintenance = None;
+        input.transfers_operational = Some(dec!(5_000_000));
+        let audit = explain_financial_score(&input);
+        let liquidity = audit.pillars.iter().find(|p| p.pillar == "liquidity").unwrap();
+        assert_eq!(liquidity.effective_weight, None);
+        let fin_health = audit.pillars.iter().find(|p| p.pillar == "financial_health").unwrap();
+        assert_eq!(fin_health.effective_weight, Some(dec!(0.30)));
+        // Unreported R&M hands its weight to the capex share.
+        let infra = audit.pillars.iter().find(|p| p.pillar == "infrastructure").unwrap();
+        assert_eq!(infra.components[0].weight, Decimal::ONE);
+        assert_eq!(infra.components[1].weight, Decimal::ZERO);
+        assert!(audit.arithmetic.as_deref().unwrap().ends_with(&format!("= {:.2}", audit.overall_score.unwrap())));
+        assert_reproducible(&audit);
+    }
+
+    #[test]
+    fn audit_of_unscored_year_has_no_arithmetic() {
+        let mut input = full_input();
+        input.data_unreliable = true;
+        let audit = explain_financial_score(&input);
+        assert!(audit.data_unreliable);
+        assert_eq!(audit.overall_score, None);
+        assert_eq!(audit.arithmetic, None);
+        assert!(audit.pillars.iter().all(|p| p.contribution.is_none()));
+    }
+
+    // --- Letter grade ---
+
+    #[test]
+    fn grade_boundaries_are_inclusive_lower_bounds() {
+        assert_eq!(grade_for_score(dec!(100)), Grade::A);
+        assert_eq!(grade_for_score(dec!(80.0)), Grade::A);
+        assert_eq!(grade_for_score(dec!(79.99)), Grade::B);
+        assert_eq!(grade_for_score(dec!(65.0)), Grade::B);
+        assert_eq!(grade_for_score(dec!(64.99)), Grade::C);
+        assert_eq!(grade_for_score(dec!(50.0)), Grade::C);
+        assert_eq!(grade_for_score(dec!(49.99)), Grade::D);
+        assert_eq!(grade_for_score(dec!(35.0)), Grade::D);
+        assert_eq!(grade_for_score(dec!(34.99)), Grade::F);
+        assert_eq!(grade_for_score(dec!(0)), Grade::F);
+    }
+
+    #[test]
+    fn grade_displays_as_letter_and_follows_overall() {
+        assert_eq!(Grade::A.to_string(), "A");
+        assert_eq!(Grade::F.to_string(), "F");
+        assert_eq!(calculate_financial_score(&full_input()).grade, Some(Grade::A));
+        assert_eq!(calculate_financial_score(&ScoringInput::default()).grade, None);
+    }
+
+    // --- Methodology ---
+
+    #[test]
+    fn methodology_matches_the_scoring_it_describes() {
+        let doc = methodology(&ScoringConfig::default());
+        assert_eq!(doc.score_version, SCORE_VERSION);
+        assert_eq!(doc.weights.financial_health, WEIGHT_FIN_HEALTH);
+        assert_eq!(doc.blend_weights.financial_health_debt_ratio, Decimal::ONE - FH_OWN_REVENUE_WEIGHT);
+        for entry in &doc.audit.outcome_scores {
+            let label = match entry.outcome {
+                "clean" => "Unqualified - No findings",
+                "financially_unqualified" => "Financially unqualified opinion",
+                "qualified" => "Qualified",
+                "adverse" => "Adverse",
+                "disclaimer" => "Disclaimer",
+                "outstanding" => "Outstanding",
+                other => panic!("unexpected outcome {other}"),
+            };
+            assert_eq!(entry.score, calculate_audit_subscore(Some(label)), "{label}");
+        }
+        for cutoff in &doc.grade_cutoffs {
+            assert_eq!(grade_for_score(cutoff.min_score), cutoff.grade);
+        }
+
+        let json = serde_json::to_value(&doc).unwrap();
+        assert_eq!(json["weights"]["financial_health"].to_string(), "0.24");
+        assert_eq!(json["thresholds"]["debt_ratio"].to_string(), r#"{"score_0_at":1.0,"score_100_at":0.1}"#);
+        assert_eq!(json["thresholds"]["capex_share"]["score_50_at"].to_string(), "0.10");
+        assert_eq!(json["grade_cutoffs"][0].to_string(), r#"{"grade":"A","min_score":80}"#);
+        assert_eq!(json["audit"]["outcome_scores"][5], serde_json::json!({ "outcome": "outstanding", "score": null }));
+    }
+
+    #[test]
+    fn deltas_subtract_pillar_by_pillar_and_need_both_sides() {
+        let a = calculate_financial_score(&full_input());
+        let b = ScoreBreakdown { liquidity_score: None, ..a.clone() };
+        let deltas = a.deltas(&b);
+        assert_eq!(deltas.overall_score, Some(Decimal::ZERO));
+        assert_eq!(deltas.liquidity_score, None);
+
+        let lower = ScoreBreakdown { overall_score: a.overall_score.map(|s| s - dec!(12.5)), ..a.clone() };
+        assert_eq!(a.deltas(&lower).overall_score, Some(dec!(12.5)));
+        assert_eq!(lower.deltas(&a).overall_score, Some(dec!(-12.5)));
+    }
+
+    #[test]
+    fn breakdown_serializes_scores_as_numbers_and_grade_as_letter() {
+        let json = serde_json::to_value(calculate_financial_score(&full_input())).unwrap();
+        assert!(json["overall_score"].is_f64());
+        assert_eq!(json["grade"], "A");
+    }
+
+    // --- Whole-pipeline scenarios ---
+    // Every pillar is hand-computed in the comments, then the full breakdown
+    // is pinned. A weight or anchor change must update these on purpose.
+
+    #[test]
+    fn scenario_healthy_metro() {
+        // FH: own share 42.5/50 = 0.85 in the metro band 0.60-0.90 -> 83.33;
+        //     debt 12.75bn / own revenue 42.5bn = 0.30 -> 77.78; mean 80.56
+        // Infra: capex 8/54 = 0.1481 -> 50 + 50*0.0481/0.2 = 62.04;
+        //     R&M 7% of opex -> 87.5; 62.04*0.7 + 87.5*0.3 = 69.68
+        // Eff: opex/revenue 0.92 -> (1 - 0.07/0.30)*100 = 76.67
+        // Acc: clean -> 100; UIFW 1% of opex -> 90; 100*0.7 + 90*0.3 = 97
+        // Liq: 9.2bn over 46bn/12 = 2.4 months -> 70
+        // Overall: 80.56*0.24 + 69.68*0.20 + 76.67*0.20 + 97*0.16 + 70*0.20 = 78.1244
+        let input = ScoringInput {
+            revenue: Some(dec!(50_000_000_000)),
+            operational_expenditure: Some(dec!(46_000_000_000)),
+            capital_expenditure: Some(dec!(8_000_000_000)),
+            debt: Some(dec!(12_750_000_000)),
+            audit_outcome: Some("Unqualified - No findings".to_string()),
+            population: Some(4_800_000),
+            classification: Some("A".to_string()),
+            transfers_operational: Some(dec!(7_500_000_000)),
+            uifw_expenditure: Some(dec!(460_000_000)),
+            repairs_maintenance: Some(dec!(3_220_000_000)),
+            cash_balance: Some(dec!(9_200_000_000)),
+            ..Default::default()
+        };
+        assert_eq!(
+            calculate_financial_score(&input),
+            ScoreBreakdown {
+                overall_score: Some(dec!(78.12)),
+                financial_health_score: Some(dec!(80.56)),
+                infrastructure_score: Some(dec!(69.68)),
+                efficiency_score: Some(dec!(76.67)),
+                accountability_score: Some(dec!(97.00)),
+                liquidity_score: Some(dec!(70.00)),
+                grade: Some(Grade::B),
+            }
+        );
+    }
+
+    #[test]
+    fn scenario_indebted_rural_local() {
+        // FH: own share 0.30 in the national band 0.25-0.75 -> 10;
+        //     debt 90M / own revenue 60M = 1.5 -> 0; mean 5
+        // Infra: capex 10/225 = 0.0444 -> 500*0.0444 = 22.22; no R&M reported
+        // Eff: opex/revenue 1.075 -> (1 - 0.225/0.30)*100 = 25
+        // Acc: qualified -> 50; UIFW 15% of opex (past the 10% floor) -> 0;
+        //     50*0.7 = 35
+        // Liq: overdraft -> 0
+        // Overall: 5*0.24 + 22.22*0.20 + 25*0.20 + 35*0.16 + 0*0.20 = 16.244
+        let input = ScoringInput {
+            revenue: Some(dec!(200_000_000)),
+            operational_expenditure: Some(dec!(215_000_000)),
+            capital_expenditure: Some(dec!(10_000_000)),
+            debt: Some(dec!(90_000_000)),
+            audit_outcome: Some("Qualified".to_string()),
+            population: Some(60_000),
+            classification: Some("B4".to_string()),
+            transfers_operational: Some(dec!(140_000_000)),
+            uifw_expenditure: Some(dec!(32_250_000)),
+            cash_balance: Some(dec!(-5_000_000)),
+            ..Default::default()
+        };
+        assert_eq!(
+            calculate_financial_score(&input),
+            ScoreBreakdown {
+                overall_score: Some(dec!(16.24)),
+                financial_health_score: Some(dec!(5.00)),
+                infrastructure_score: Some(dec!(22.22)),
+                efficiency_score: Some(dec!(25.00)),
+                accountability_score: Some(dec!(35.00)),
+                liquidity_score: Some(dec!(0)),
+                grade: Some(Grade::F),
+            }
+        );
+    }
+
+    #[test]
+    fn scenario_missing_data_scores_nothing_until_half_the_rubric_is_covered() {
+        // Only revenue/opex and an outstanding audit: Eff 0.9 -> 83.33 alone
+        // is 20% of the rubric, so no overall.
+        let mut input = ScoringInput {
+            revenue: Some(dec!(100_000_000)),
+            operational_expenditure: Some(dec!(90_000_000)),
+            audit_outcome: Some("Audit outstanding".to_string()),
+            ..Default::default()
+        };
+        let unscored = ScoreBreakdown {
+            overall_score: None,
+            financial_health_score: None,
+            infrastructure_score: None,
+            efficiency_score: Some(dec!(83.33)),
+            accountability_score: None,
+            liquidity_score: None,
+            grade: None,
+        };
+        assert_eq!(calculate_financial_score(&input), unscored);
+
+        // Adding debt and transfers scores FH: own share 0.8 -> 100; debt
+        // 30M / own revenue 80M = 0.375 -> 69.44; mean 84.72. Still only 44%.
+        input.debt = Some(dec!(30_000_000));
+        input.transfers_operational = Some(dec!(20_000_000));
+        assert_eq!(
+            calculate_financial_score(&input),
+            ScoreBreakdown { financial_health_score: Some(dec!(84.72)), ..unscored.clone() }
+        );
+
+        // Capex at exactly the 0.10 midpoint -> Infra 50; 64% is enough.
+        // Overall: (84.72*0.24 + 50*0.20 + 83.33*0.20) / 0.64 = 73.435625
+        input.capital_expenditure = Some(dec!(10_000_000));
+        assert_eq!(
+            calculate_financial_score(&input),
+            ScoreBreakdown {
+                overall_score: Some(dec!(73.44)),
+                financial_health_score: Some(dec!(84.72)),
+                infrastructure_score: Some(dec!(50.00)),
+                grade: Some(Grade::B),
+                ..unscored
+            }
+        );
+    }
+
+    #[test]
+    fn scenario_zero_revenue_drops_revenue_pillars_without_dividing_by_zero() {
+        // FH and Eff need revenue > 0 and drop out.
+        // Infra: capex 5/55 = 0.0909 -> 45.45; R&M reported as 0 -> 0;
+        //     45.45*0.7 = 31.82
+        // Acc: clean, zero UIFW -> 100. Liq: 10M over 50M/12 = 2.4 months -> 70
+        // Overall: (31.82*0.20 + 100*0.16 + 70*0.20) / 0.56 = 64.9357
+        let input = ScoringInput {
+            revenue: Some(Decimal::ZERO),
+            operational_expenditure: Some(dec!(50_000_000)),
+            capital_expenditure: Some(dec!(5_000_000)),
+            debt: Some(dec!(10_000_000)),
+            audit_outcome: Some("Unqualified opinion with no findings".to_string()),
+            transfers_operational: Some(Decimal::ZERO),
+            uifw_expenditure: Some(Decimal::ZERO),
+            repairs_maintenance: Some(Decimal::ZERO),
+            cash_balance: Some(dec!(10_000_000)),
+            ..Default::default()
+        };
+        assert_eq!(
+            calculate_financial_score(&input),
+            ScoreBreakdown {
+                overall_score: Some(dec!(64.94)),
+                financial_health_score: None,
+                infrastructure_score: Some(dec!(31.82)),
+                efficiency_score: None,
+                accountability_score: Some(dec!(100.00)),
+                liquidity_score: Some(dec!(70.00)),
+                grade: Some(Grade::C),
+            }
+        );
+    }
+
+    #[test]
+    fn zero_denominators_never_divide() {
+        let zero = Some(Decimal::ZERO);
+        let amount = Some(dec!(1_000_000));
+        // Revenue is the denominator.
+        assert_eq!(calculate_own_revenue_subscore(zero, zero, None, T), None);
+        assert_eq!(calculate_debt_ratio_subscore(amount, zero, T), None);
+        assert_eq!(calculate_efficiency_score(amount, zero, T), None);
+        // Own revenue of zero (all transfers) falls back to total revenue.
+        assert_eq!(debt_ratio_basis(amount, amount), amount);
+        // Opex (or total spend) is the denominator.
+        assert_eq!(calculate_capex_subscore(zero, zero, T), Some(Decimal::ZERO));
+        assert_eq!(calculate_rm_subscore(amount, zero, T), None);
+        assert_eq!(calculate_uifw_subscore(amount, zero, T), None);
+        assert_eq!(calculate_liquidity_score(amount, zero, T), None);
+        // Population is the denominator.
+        assert_eq!(per_capita(amount, Some(0)), None);
+        // A negative denominator is as invalid as zero.
+        let negative = Some(dec!(-1));
+        assert_eq!(calculate_efficiency_score(amount, negative, T), None);
+        assert_eq!(calculate_liquidity_score(amount, negative, T), None);
+    }
+
+    #[test]
+    fn overflowing_ratios_saturate_and_score_like_the_extreme_ratio() {
+        let tiny = Some(Decimal::new(1, 28));
+        let max = Some(Decimal::MAX);
+        let two = Some(dec!(2));
+        // Quotients past Decimal::MAX.
+        assert_eq!(debt_to_revenue(max, Some(dec!(0.5))), max);
+        assert_eq!(opex_to_revenue(max, tiny), max);
+        assert_eq!(opex_to_revenue(Some(Decimal::MIN), tiny), Some(Decimal::MIN));
+        // Each overflow scores what the largest representable ratio does.
+        assert_eq!(calculate_debt_ratio_subscore(max, tiny, T), Some(Decimal::ZERO));
+        assert_eq!(calculate_efficiency_score(max, Some(dec!(0.5)), T), Some(Decimal::ZERO));
+        assert_eq!(calculate_rm_subscore(max, tiny, T), calculate_rm_subscore(max, two, T));
+        assert_eq!(calculate_uifw_subscore(max, tiny, T), Some(Decimal::ZERO));
+        // A monthly OpEx that rounds to zero saturates the cover too.
+        assert_eq!(calculate_liquidity_score(max, tiny, T), Some(dec!(100)));
+        assert_eq!(calculate_liquidity_score(Some(Decimal::ZERO), tiny, T), Some(Decimal::ZERO));
+        // Sums and differences past Decimal::MAX.
+        assert_eq!(own_revenue_share(max, Some(Decimal::MIN)), Some(Decimal::ONE));
+        assert_eq!(own_revenue(max, Some(Decimal::MIN)), max);
+        assert_eq!(capex_share(max, max), Some(Decimal::ONE));
+        assert_eq!(calculate_infra_score(max, max, None, T), calculate_infra_score(Some(dec!(0)), two, None, T));
+    }
+
+    #[test]
+    fn representable_extreme_ratios_score_at_the_bounds() {
+        let max = Some(Decimal::MAX);
+        // Ratios near Decimal::MAX are clamped before being normalized.
+        assert_eq!(calculate_debt_ratio_subscore(max, Some(dec!(2)), T), Some(Decimal::ZERO));
+        assert_eq!(calculate_efficiency_score(max, Some(dec!(2)), T), Some(Decimal::ZERO));
+        assert_eq!(calculate_liquidity_score(max, Some(dec!(24)), T), Some(dec!(100)));
+        assert_eq!(calculate_liquidity_score(Some(Decimal::MIN), Some(dec!(12)), T), Some(Decimal::ZERO));
+        assert_eq!(calculate_efficiency_score(Some(Decimal::MIN), Some(dec!(2)), T), Some(dec!(100)));
+
+        // And the whole pipeline survives adversarial figures.
+        let breakdown = calculate_financial_score(&ScoringInput {
+            revenue: Some(Decimal::new(1, 28)),
+            operational_expenditure: max,
+            capital_expenditure: max,
+            debt: max,
+            transfers_operational: Some(Decimal::MIN),
+            uifw_expenditure: max,
+            repairs_maintenance: max,
+            cash_balance: Some(Decimal::MIN),
+            population: Some(1),
+            ..full_input()
+        });
+        assert!(breakdown.financial_health_score.is_some());
+        assert_eq!(breakdown.efficiency_score, Some(Decimal::ZERO));
+        assert!(breakdown.infrastructure_score.is_some());
+        assert_eq!(breakdown.liquidity_score, Some(Decimal::ZERO));
+        assert!(breakdown.accountability_score.is_some());
+        assert!(breakdown.overall_score.is_some());
+    }
+
+    #[test]
+    fn db_record_scores_like_the_input_it_was_stored_from() {
+        let input = full_input();
+        let now = chrono::Utc::now();
+        let mut record = FinancialDataDb {
+            id: uuid::Uuid::new_v4(),
+            municipality_id: "CPT".to_string(),
+            year: 2023,
+            revenue: input.revenue,
+            operational_expenditure: input.operational_expenditure,
+            capital_expenditure: input.capital_expenditure,
+            debt: input.debt,
+            audit_outcome: input.audit_outcome.clone(),
+            transfers_operational: input.transfers_operational,
+            uifw_expenditure: input.uifw_expenditure,
+            repairs_maintenance: input.repairs_maintenance,
+            cash_balance: input.cash_balance,
+            operating_surplus: None,
+            amount_type: Some("AUDA".to_string()),
+            overall_score: None,
+            financial_health_score: None,
+            infrastructure_score: None,
+            efficiency_score: None,
+            accountability_score: None,
+            liquidity_score: None,
+            data_confidence: Some("ok".to_string()),
+            confidence_notes: None,
+            score_version: None,
+            created_at: now,
+            updated_at: now,
+        };
+        let rebuilt = ScoringInput::from_db_record(&record, Some(1000.0), None);
+        assert!(!rebuilt.data_unreliable);
+        assert_eq!(calculate_financial_score(&rebuilt), calculate_financial_score(&input));
+
+        record.data_confidence = Some(CONFIDENCE_UNRELIABLE.to_string());
+        let rebuilt = ScoringInput::from_db_record(&record, Some(1000.0), Some("A"));
+        assert!(rebuilt.data_unreliable);
+        assert_eq!(rebuilt.classification.as_deref(), Some("A"));
+    }
 }
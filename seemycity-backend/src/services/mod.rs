@@ -0,0 +1,4 @@
+// src/services/mod.rs
+
+pub mod municipality;
+pub mod normalization;
@@ -0,0 +1,1150 @@
+// src/services/municipality.rs
+
+//! The fetch-score-upsert pipeline behind the detail endpoints and the cache
+//! warmer: refresh a municipality's cached financial years from the Treasury
+//! API, score them, persist them, and assemble the detail payload. No HTTP
+//! concerns (admission, embargo, request parsing) live here.
+
+use chrono::{Duration, Utc};
+use crate::api::muni_money::audit::get_audit_outcome;
+use crate::api::muni_money::demographics::get_population;
+use crate::api::muni_money::client::MunicipalMoneyClient;
+use crate::api::muni_money::cubes::{CAPEX_CUBE, CFLOW_CUBE, FINPOS_CUBE, INCEXP_CUBE, REPMAINT_CUBE, UIFW_CUBE};
+use crate::api::muni_money::financials::{
+    capital_expenditure_from_facts, cash_balance_from_facts, checked_total, debt_from_facts, get_capital_expenditure,
+    get_cash_balance, get_expenditure_breakdown, get_repairs_maintenance, get_revenue_and_expenditure,
+    get_revenue_breakdown, get_total_debt, get_uifw_total, incexp_figures_from_facts, least_authoritative_amount_type,
+    total_from_facts, BreakdownKind, IncexpBreakdown, IncexpFigures, SourcedAmount, DEFAULT_AMOUNT_TYPES,
+};
+use crate::api::muni_money::item_codes::ItemCodeSets;
+use crate::api::muni_money::types::{AmountType, ApiClientError, FinancialItemFact};
+use crate::confidence::{evaluate as evaluate_confidence, ConfidenceInput, CONFIDENCE_UNRELIABLE};
+use crate::db::financials::{
+    get_all_financial_rows, get_all_financial_years_db, get_available_years, is_record_stale,
+    update_recomputed_records, update_scores_batch, upsert_complete_financial_record,
+    upsert_financial_records_batch,
+};
+use crate::db::geo::get_geometry_for_municipality;
+use crate::db::raw_facts::{get_raw_fact_years, get_raw_facts, replace_raw_facts};
+use crate::db::municipalities::{
+    claim_population_lookup, get_all_municipality_scoring_profiles, get_municipality_base_info_db,
+    store_population_if_missing,
+};
+use crate::errors::AppError;
+use crate::metrics::metrics;
+use crate::services::normalization::{normalization_for_year, normalizations_for_years, YearNormalizations};
+use crate::models::{BreakdownItem, FinancialBreakdown, FinancialDataDb, FinancialYearData, MunicipalityDetail};
+use crate::scoring::{
+    calculate_financial_score, explain_financial_score, operating_surplus, population_count, Normalization,
+    score_version, ScoreBreakdown, ScoringInput,
+};
+use rust_decimal::Decimal;
+use sqlx::PgPool as DbPool;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// How many financial years to walk back from `Config::current_financial_year`
+/// looking for usable data: some municipalities publish later than the usual
+/// reporting lag.
+const YEAR_FALLBACK_DEPTH: i32 = 3;
+
+/// How many years `refresh_financial_years` fetches at once. Each year is
+/// seven cube calls, so this stays small; the client's
+/// `MUNI_MONEY_MAX_CONCURRENCY` still caps the requests in flight.
+pub const MULTI_YEAR_CONCURRENCY: usize = 4;
+
+/// After a refresh round where every upstream call failed at the transport level,
+/// skip the Treasury API for this long and serve cached data only. Prevents a
+/// degraded upstream from stalling every cold request on timeouts.
+const UPSTREAM_COOLDOWN_SECS: u64 = 300;
+
+/// How long a population lookup that found nothing (or failed) is remembered
+/// before the demographics cube is asked again. StatsSA estimates are yearly,
+/// so a day between attempts loses nothing.
+const POPULATION_RECHECK_HOURS: i64 = 24;
+
+/// `cache` label of cached financial_data rows in
+/// `seemycity_cache_{hits,misses}_total`: a hit is a fresh row for a year the
+/// pipeline looked at, a miss one it had to refetch (or could not).
+const FINANCIALS_CACHE_METRIC: &str = "financials";
+
+/// Circuit breaker for the Treasury API. Shared across workers.
+#[derive(Default)]
+pub struct UpstreamHealth {
+    down_until: std::sync::RwLock<Option<std::time::Instant>>,
+}
+
+impl UpstreamHealth {
+    /// False while the breaker is open: upstream fetches are being skipped
+    /// and cached data served instead.
+    pub fn is_up(&self) -> bool {
+        match self.down_until.read() {
+            Ok(guard) => guard.is_none_or(|t| std::time::Instant::now() >= t),
+            Err(_) => true,
+        }
+    }
+
+    fn mark_down(&self) {
+        if let Ok(mut guard) = self.down_until.write() {
+            *guard = Some(std::time::Instant::now() + std::time::Duration::from_secs(UPSTREAM_COOLDOWN_SECS));
+        }
+        log::warn!(
+            "Treasury API unreachable — skipping upstream fetches for {}s, serving cached data only",
+            UPSTREAM_COOLDOWN_SECS
+        );
+    }
+}
+
+/// The detail payload for one municipality: base info, financials refreshed
+/// through `ensure_financials_fresh` (fetching, scoring and upserting whatever
+/// is missing or expired), boundary and last-updated time. `years`, unless
+/// empty, restricts `financials` to those years (refreshing them together);
+/// `audit` attaches each year's `score_audit`. All-NULL negative-cache rows
+/// are left out; embargo filtering is the caller's job. `newest_year` and
+/// `cache_ttl` are as for `ensure_financials_fresh`.
+#[allow(clippy::too_many_arguments)] // shared resources plus the request's options
+pub async fn build_and_store_detail(
+    pool: &DbPool,
+    api_client: &MunicipalMoneyClient,
+    upstream_health: &UpstreamHealth,
+    muni_id: &str,
+    years: &[i32],
+    audit: bool,
+    newest_year: i32,
+    cache_ttl: Duration,
+) -> Result<MunicipalityDetail, AppError> {
+    // Fetch base municipality info
+    let base_info = get_municipality_base_info_db(pool, muni_id).await?.ok_or_else(|| {
+        log::warn!("Municipality base info not found for ID: {}", muni_id);
+        AppError::NotFound(format!("Municipality with ID {} not found", muni_id))
+    })?;
+    let muni_code = base_info.id.clone();
+    let population_opt =
+        resolve_population(pool, api_client, upstream_health, &muni_code, base_info.population, newest_year).await;
+    let classification = base_info.classification.clone();
+
+    let mut rows = ensure_financials_fresh(
+        pool,
+        api_client,
+        upstream_health,
+        &muni_code,
+        population_opt,
+        classification.as_deref(),
+        years,
+        newest_year,
+        cache_ttl,
+    )
+    .await?;
+
+    // All-NULL rows are cache internals, not user data; newest year first.
+    rows.sort_by_key(|r| std::cmp::Reverse(r.year));
+    let normalizations = if audit {
+        let row_years: Vec<i32> = rows.iter().map(|r| r.year).collect();
+        normalizations_for_years(pool, &row_years).await
+    } else {
+        YearNormalizations::default()
+    };
+    let financials: Vec<FinancialYearData> = rows
+        .iter()
+        .filter(|r| r.has_any_data())
+        .filter(|r| years.is_empty() || years.contains(&r.year))
+        .map(|r| {
+            let mut year_data = FinancialYearData::from(r).with_per_capita(population_opt);
+            if audit {
+                let input = ScoringInput {
+                    normalization: normalizations.get(r.year),
+                    ..ScoringInput::from_db_record(r, population_opt, classification.as_deref())
+                };
+                year_data.score_audit = Some(explain_financial_score(&input));
+            }
+            year_data
+        })
+        .collect();
+
+    // A missing boundary degrades to geometry: null rather than failing the request.
+    let geometry = get_geometry_for_municipality(pool, &muni_code).await?;
+    // Dates the last real fetch: negative-cache rows hold no figures, and
+    // healing and recomputes keep each row's updated_at. Taken from the
+    // refreshed rows, so a just-fetched year counts.
+    let last_updated = rows.iter().filter(|r| r.has_any_data()).map(|r| r.updated_at).max();
+
+    Ok(MunicipalityDetail {
+        id: base_info.id,
+        name: base_info.name,
+        province: base_info.province,
+        population: population_opt,
+        classification: base_info.classification,
+        website: base_info.website,
+        address: base_info.address,
+        phone: base_info.phone,
+        district_id: base_info.district_id,
+        district_name: base_info.district_name,
+        has_financial_data: !financials.is_empty(),
+        financials,
+        geometry,
+        last_updated,
+    })
+}
+
+/// The year a breakdown defaults to: the municipality's newest cached year.
+/// Resolved before `build_breakdown` so the caller can check it (e.g. against
+/// the embargo) before anything is fetched.
+pub async fn default_breakdown_year(pool: &DbPool, muni_id: &str) -> Result<i32, AppError> {
+    let base_info = get_municipality_base_info_db(pool, muni_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Municipality with ID {} not found", muni_id)))?;
+    get_available_years(pool, &base_info.id).await?.last().map(|y| y.year).ok_or_else(|| {
+        AppError::NotFound(format!("Municipality {} has no cached financial year yet; pass ?year=", base_info.id))
+    })
+}
+
+/// The line items behind one year's revenue or operational expenditure,
+/// fetched live from the Treasury API's individual facts (nothing is cached).
+/// Fails with `ServiceUnavailable` while the upstream circuit breaker is open,
+/// since there is no cached copy to fall back on.
+pub async fn build_breakdown(
+    pool: &DbPool,
+    api_client: &MunicipalMoneyClient,
+    upstream_health: &UpstreamHealth,
+    muni_id: &str,
+    year: i32,
+    kind: BreakdownKind,
+) -> Result<FinancialBreakdown, AppError> {
+    let base_info = get_municipality_base_info_db(pool, muni_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Municipality with ID {} not found", muni_id)))?;
+    if !upstream_health.is_up() {
+        return Err(AppError::ServiceUnavailable(
+            "The upstream data source is unavailable; try again shortly.".to_string(),
+        ));
+    }
+
+    let IncexpBreakdown { amount_type, lines } = match kind {
+        BreakdownKind::Revenue => get_revenue_breakdown(api_client, &base_info.id, year, &DEFAULT_AMOUNT_TYPES).await?,
+        BreakdownKind::Expenditure => {
+            get_expenditure_breakdown(api_client, &base_info.id, year, &DEFAULT_AMOUNT_TYPES).await?
+        }
+    };
+    let total: Option<Decimal> = (!lines.is_empty()).then(|| lines.iter().map(|l| l.amount).sum());
+    let items = lines
+        .into_iter()
+        .map(|line| BreakdownItem {
+            share: total
+                .filter(|t| !t.is_zero())
+                .map(|t| (line.amount / t * Decimal::ONE_HUNDRED).round_dp(2)),
+            item_code: line.item_code,
+            item_label: line.item_label,
+            amount: line.amount,
+        })
+        .collect();
+
+    Ok(FinancialBreakdown {
+        municipality_id: base_info.id,
+        year,
+        kind: kind.as_str().to_string(),
+        amount_type: amount_type.map(|t| t.as_code().to_string()),
+        total,
+        items,
+    })
+}
+
+/// The population to score a municipality with: the seeded one, else
+/// `fetch_missing_population`.
+async fn resolve_population(
+    pool: &DbPool,
+    api_client: &MunicipalMoneyClient,
+    upstream_health: &UpstreamHealth,
+    muni_code: &str,
+    seeded: Option<f32>,
+    newest_year: i32,
+) -> Option<f32> {
+    match seeded {
+        Some(population) => Some(population),
+        None => fetch_missing_population(pool, api_client, upstream_health, muni_code, newest_year).await,
+    }
+}
+
+/// A population for a municipality whose seeded one is missing: the newest
+/// demographics-cube figure within `YEAR_FALLBACK_DEPTH` years of
+/// `newest_year`, stored back so later requests and the cache warmer read it
+/// from the DB. Best effort: upstream or DB failures are logged and leave the
+/// population missing, as does an open circuit breaker.
+///
+/// Each lookup is claimed first (`claim_population_lookup`), so one that
+/// finds nothing, fails or times out is not repeated for
+/// `POPULATION_RECHECK_HOURS`, and the whole walk is bounded by the client's
+/// metric deadline.
+async fn fetch_missing_population(
+    pool: &DbPool,
+    api_client: &MunicipalMoneyClient,
+    upstream_health: &UpstreamHealth,
+    muni_code: &str,
+    newest_year: i32,
+) -> Option<f32> {
+    if !upstream_health.is_up() {
+        return None;
+    }
+    match claim_population_lookup(pool, muni_code, Utc::now() - Duration::hours(POPULATION_RECHECK_HOURS)).await {
+        Ok(true) => {}
+        Ok(false) => return None,
+        Err(e) => {
+            log::warn!("Failed to claim the population lookup for {}: {}", muni_code, e);
+            return None;
+        }
+    }
+
+    let lookup = async {
+        for year in ((newest_year - YEAR_FALLBACK_DEPTH + 1)..=newest_year).rev() {
+            if let Some(population) = get_population(api_client, muni_code, year).await? {
+                return Ok(Some((year, population)));
+            }
+        }
+        Ok::<_, ApiClientError>(None)
+    };
+    match api_client.within_metric_deadline(lookup).await {
+        Ok(Some((year, population))) => {
+            log::info!("Population for {} taken from the demographics cube ({}): {}", muni_code, year, population);
+            if let Err(e) = store_population_if_missing(pool, muni_code, population).await {
+                log::warn!("Failed to store fetched population for {}: {}", muni_code, e);
+            }
+            Some(population as f32)
+        }
+        Ok(None) => {
+            log::info!(
+                "No population for {} in the demographics cube; not asking again for {}h",
+                muni_code,
+                POPULATION_RECHECK_HOURS
+            );
+            None
+        }
+        Err(e) => {
+            log::warn!("Failed to fetch population for {} ({}): {}", muni_code, newest_year, e);
+            None
+        }
+    }
+}
+
+/// Brings a municipality's financial-year rows up to date and returns them:
+/// walks candidate years newest-first from `newest_year`
+/// (`Config::current_financial_year`) until one yields usable data, refreshing missing/expired
+/// rows from the Treasury API; then re-derives scores for every cached row so
+/// formula changes propagate without upstream calls.
+///
+/// Fresh cached rows are trusted as-is — including all-NULL negative-cache
+/// rows. When the upstream circuit breaker is open, cached (even stale) data
+/// is returned immediately. Used by `build_and_store_detail` (and so the
+/// detail endpoints) and the background cache warmer.
+///
+/// `requested_years` that are missing or expired are refreshed first, all
+/// together (`refresh_financial_years`), so a client asking for older years
+/// gets real figures rather than a cache miss, and asking for several costs
+/// one round of upstream calls rather than one per year.
+/// `cache_ttl` is how long a cached row counts as fresh (`Config::cache_ttl`).
+#[allow(clippy::too_many_arguments)] // shared resources plus the municipality's scoring inputs
+pub async fn ensure_financials_fresh(
+    pool: &DbPool,
+    api_client: &MunicipalMoneyClient,
+    upstream_health: &UpstreamHealth,
+    muni_code: &str,
+    population_opt: Option<f32>,
+    classification: Option<&str>,
+    requested_years: &[i32],
+    newest_year: i32,
+    cache_ttl: Duration,
+) -> Result<Vec<FinancialDataDb>, AppError> {
+    let mut rows = get_all_financial_years_db(pool, muni_code).await?;
+    let now = Utc::now();
+    let walk_years = (newest_year - YEAR_FALLBACK_DEPTH + 1)..=newest_year;
+
+    let expired: Vec<i32> = requested_years
+        .iter()
+        .copied()
+        .filter(|&year| {
+            let cached_fresh = rows.iter().any(|r| r.year == year && !is_record_stale(r, cache_ttl, now));
+            metrics().record_cache(FINANCIALS_CACHE_METRIC, cached_fresh);
+            !cached_fresh
+        })
+        .collect();
+    if !expired.is_empty() && upstream_health.is_up() {
+        let refreshed =
+            refresh_financial_years(pool, api_client, muni_code, &expired, population_opt, classification, &rows).await;
+        if refreshed.is_empty() {
+            upstream_health.mark_down();
+        }
+        for row in refreshed {
+            rows.retain(|r| r.year != row.year);
+            rows.push(row);
+        }
+    }
+
+    // Walk until a year yields a *scorable* row (all four pillars), not merely
+    // any data: the newest financial year often publishes figures months before
+    // its audit opinion, and stopping there would leave the municipality
+    // unscored while a complete prior year sits one step further back.
+    for year in walk_years.rev() {
+        let cached_fresh_has_score = rows
+            .iter()
+            .find(|r| r.year == year)
+            .filter(|r| !is_record_stale(r, cache_ttl, now))
+            .map(|r| r.overall_score.is_some());
+        // Requested years were counted above.
+        if !requested_years.contains(&year) {
+            metrics().record_cache(FINANCIALS_CACHE_METRIC, cached_fresh_has_score.is_some());
+        }
+
+        let has_score = match cached_fresh_has_score {
+            Some(has_score) => has_score,
+            None if !upstream_health.is_up() => {
+                // Circuit open: serve whatever is cached (stale included) rather
+                // than stalling the request on a known-degraded upstream.
+                log::debug!("Muni: {muni_code}, upstream cooling down; skipping refresh of {year}");
+                break;
+            }
+            None => {
+                let prior = rows.iter().find(|r| r.year == year).cloned();
+                match refresh_financial_year(pool, api_client, muni_code, year, population_opt, classification, prior.as_ref())
+                    .await
+                {
+                    Some(refreshed) => {
+                        let has_score = refreshed.overall_score.is_some();
+                        rows.retain(|r| r.year != year);
+                        rows.push(refreshed);
+                        has_score
+                    }
+                    None => {
+                        // Every upstream call failed at the transport level. Not
+                        // the same as "no data" — nothing is persisted, and we
+                        // stop trying older years against a dead upstream.
+                        upstream_health.mark_down();
+                        break;
+                    }
+                }
+            }
+        };
+
+        if has_score {
+            break;
+        }
+    }
+
+    // Scores are pure derivations of the stored raw metrics, so recompute them for
+    // every cached row and heal any that disagree with the current formula. This
+    // propagates scoring-rubric changes to historical years (and to the map, which
+    // reads persisted scores) lazily, without any Treasury API calls.
+    // Only the score columns are written, so updated_at keeps dating the fetch.
+    let row_years: Vec<i32> = rows.iter().map(|r| r.year).collect();
+    let normalizations = normalizations_for_years(pool, &row_years).await;
+    let mut healed = Vec::new();
+    for row in rows.iter_mut() {
+        if !row.has_any_data() {
+            continue;
+        }
+        let stored_version = row.score_version;
+        if !rescore_row(row, population_opt, classification, normalizations.get(row.year)) {
+            continue;
+        }
+        log::info!(
+            "Muni: {}, healing year {} (score_version {:?} -> {})",
+            muni_code, row.year, stored_version, score_version()
+        );
+        healed.push(row.clone());
+    }
+    if let Err(e) = update_scores_batch(pool, &healed).await {
+        log::error!("Muni: {}, failed to persist healed scores: {}", muni_code, e);
+    }
+
+    Ok(rows)
+}
+
+/// Recomputes a stored row's scores, operating surplus and (when never
+/// graded) confidence from its raw figures under the current formula and
+/// `normalization` (see `services::normalization`), and stamps the score
+/// version of the thresholds in force (`scoring::score_version`). Returns
+/// false, leaving the row untouched, when it is already up to date.
+fn rescore_row(
+    row: &mut FinancialDataDb,
+    population_opt: Option<f32>,
+    classification: Option<&str>,
+    normalization: Normalization,
+) -> bool {
+    // Confidence backfill from stored values for rows never evaluated.
+    // A grade set at fetch time (which may reflect the revenue checksum)
+    // is kept as-is.
+    let (confidence, confidence_notes) = if row.data_confidence.is_none() {
+        let grade = evaluate_confidence(&ConfidenceInput {
+            revenue: row.revenue,
+            operational_expenditure: row.operational_expenditure,
+            capital_expenditure: row.capital_expenditure,
+            debt: row.debt,
+            population: population_count(population_opt),
+            revenue_checksum: None,
+        });
+        (Some(grade.grade.to_string()), grade.notes)
+    } else {
+        (row.data_confidence.clone(), row.confidence_notes.clone())
+    };
+    let input = ScoringInput {
+        data_unreliable: confidence.as_deref() == Some(CONFIDENCE_UNRELIABLE),
+        normalization,
+        ..ScoringInput::from_db_record(row, population_opt, classification)
+    };
+    let breakdown = calculate_financial_score(&input);
+    let surplus = operating_surplus(row.revenue, row.operational_expenditure);
+    let up_to_date = row.score_version == Some(score_version())
+        && row.operating_surplus == surplus
+        && row.overall_score == breakdown.overall_score
+        && row.financial_health_score == breakdown.financial_health_score
+        && row.infrastructure_score == breakdown.infrastructure_score
+        && row.efficiency_score == breakdown.efficiency_score
+        && row.accountability_score == breakdown.accountability_score
+        && row.liquidity_score == breakdown.liquidity_score
+        && row.data_confidence == confidence;
+    if up_to_date {
+        return false;
+    }
+    row.overall_score = breakdown.overall_score;
+    row.financial_health_score = breakdown.financial_health_score;
+    row.infrastructure_score = breakdown.infrastructure_score;
+    row.efficiency_score = breakdown.efficiency_score;
+    row.accountability_score = breakdown.accountability_score;
+    row.liquidity_score = breakdown.liquidity_score;
+    row.operating_surplus = surplus;
+    row.data_confidence = confidence;
+    row.confidence_notes = confidence_notes;
+    row.score_version = Some(score_version());
+    true
+}
+
+/// Score healing for every stored row at once, rather than lazily as each
+/// municipality is next loaded: rescores every municipality-year from its
+/// stored figures (see `rescore_row`) and writes back the rows that changed,
+/// so a change to the scoring logic reaches all existing data. Two queries to
+/// load the rows and profiles and one score-only update; the figures and
+/// their `updated_at` are left alone (re-deriving the figures is
+/// `recompute_all_from_raw`). Returns how many rows were rewritten.
+pub async fn rescore_all(pool: &DbPool) -> Result<usize, AppError> {
+    let profiles: HashMap<String, (Option<f32>, Option<String>)> = get_all_municipality_scoring_profiles(pool)
+        .await?
+        .into_iter()
+        .map(|(muni_code, population, classification)| (muni_code, (population, classification)))
+        .collect();
+    let rows = get_all_financial_rows(pool).await?;
+    log::info!("Rescoring {} stored financial rows for {} municipalities", rows.len(), profiles.len());
+    let row_years: Vec<i32> = rows.iter().map(|r| r.year).collect();
+    let normalizations = normalizations_for_years(pool, &row_years).await;
+    let rescored: Vec<FinancialDataDb> = rows
+        .into_iter()
+        .filter(|r| r.has_any_data())
+        .filter_map(|mut row| {
+            let (population, classification) =
+                profiles.get(&row.municipality_id).map(|(p, c)| (*p, c.as_deref())).unwrap_or_default();
+            let normalization = normalizations.get(row.year);
+            rescore_row(&mut row, population, classification, normalization).then_some(row)
+        })
+        .collect();
+    let written = update_scores_batch(pool, &rescored).await?;
+    log::info!("Rescored {} rows", written);
+    Ok(written as usize)
+}
+
+/// Warmer progress is logged every this many municipalities.
+const WARMER_PROGRESS_EVERY: usize = 25;
+
+/// Warms the score cache for every municipality so the map is fully colored
+/// and interactive requests are served from the DB without depending on
+/// detail-page traffic. `concurrency` municipalities are refreshed at once,
+/// each by its own worker task; upstream requests stay bounded by the
+/// client's concurrency cap. Fresh rows are skipped by the cache logic, so
+/// repeat runs are cheap. A failing municipality is logged and skipped; the
+/// run aborts early if the Treasury API circuit breaker opens. `newest_year`
+/// and `cache_ttl` are as for `ensure_financials_fresh`.
+pub async fn warm_all_municipalities(
+    pool: &DbPool,
+    api_client: &MunicipalMoneyClient,
+    upstream_health: &Arc<UpstreamHealth>,
+    newest_year: i32,
+    cache_ttl: Duration,
+    concurrency: usize,
+) {
+    let munis = match get_all_municipality_scoring_profiles(pool).await {
+        Ok(m) => Arc::new(m),
+        Err(e) => {
+            log::error!("Cache warmer: failed to list municipalities: {e}");
+            return;
+        }
+    };
+
+    log::info!("Cache warmer: checking {} municipalities, {} at a time", munis.len(), concurrency);
+    let started = std::time::Instant::now();
+    // Workers pull the next index until the list (or the upstream) runs out.
+    let next = Arc::new(AtomicUsize::new(0));
+    let (done, scored, no_data) = (Arc::<AtomicUsize>::default(), Arc::<AtomicUsize>::default(), Arc::<AtomicUsize>::default());
+    let mut workers = tokio::task::JoinSet::new();
+    for _ in 0..concurrency.max(1) {
+        let (pool, api_client, upstream_health) = (pool.clone(), api_client.clone(), upstream_health.clone());
+        let (munis, next, done, scored, no_data) =
+            (munis.clone(), next.clone(), done.clone(), scored.clone(), no_data.clone());
+        workers.spawn(async move {
+            while let Some((id, population, classification)) = munis.get(next.fetch_add(1, Ordering::SeqCst)) {
+                if !upstream_health.is_up() {
+                    log::warn!("Cache warmer: upstream circuit open, aborting run early");
+                    break;
+                }
+                let population =
+                    resolve_population(&pool, &api_client, &upstream_health, id, *population, newest_year).await;
+                match ensure_financials_fresh(
+                    &pool,
+                    &api_client,
+                    &upstream_health,
+                    id,
+                    population,
+                    classification.as_deref(),
+                    &[],
+                    newest_year,
+                    cache_ttl,
+                )
+                .await
+                {
+                    Ok(rows) if rows.iter().any(|r| r.overall_score.is_some()) => {
+                        scored.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Ok(_) => {
+                        no_data.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Err(e) => log::error!("Cache warmer: {id} failed: {e}"),
+                }
+                let done = done.fetch_add(1, Ordering::SeqCst) + 1;
+                if done % WARMER_PROGRESS_EVERY == 0 {
+                    log::info!("Cache warmer: {done}/{} municipalities checked", munis.len());
+                }
+            }
+        });
+    }
+    while let Some(joined) = workers.join_next().await {
+        if let Err(e) = joined {
+            log::error!("Cache warmer: worker failed: {e}");
+        }
+    }
+    log::info!(
+        "Cache warmer: done in {:.0?} — {} municipalities scored, {} without data (of {})",
+        started.elapsed(),
+        scored.load(Ordering::SeqCst),
+        no_data.load(Ordering::SeqCst),
+        munis.len()
+    );
+}
+
+/// Raw figures for one municipality-year, as summed from cube cells (fetched
+/// or stored), before confidence grading and scoring.
+#[derive(Debug, Clone, Default)]
+struct YearFigures {
+    revenue: Option<Decimal>,
+    operational_expenditure: Option<Decimal>,
+    transfers_operational: Option<Decimal>,
+    revenue_checksum: Option<Decimal>,
+    capital_expenditure: Option<Decimal>,
+    debt: Option<Decimal>,
+    cash_balance: Option<Decimal>,
+    uifw_expenditure: Option<Decimal>,
+    repairs_maintenance: Option<Decimal>,
+    audit_outcome: Option<String>,
+    amount_type: Option<String>,
+}
+
+/// Grades and scores `figures` into a complete row (not yet persisted). Both
+/// the fetch path and `recompute_from_raw` score through here, so negative
+/// totals are screened (`checked_total`) the same way whichever summed them:
+/// logged, and replaced by zero when `clamp_negative_totals` is set.
+fn score_year_figures(
+    muni_code: &str,
+    year: i32,
+    figures: YearFigures,
+    population: Option<f32>,
+    classification: Option<&str>,
+    clamp_negative_totals: bool,
+    normalization: Normalization,
+) -> FinancialDataDb {
+    let YearFigures {
+        revenue,
+        operational_expenditure,
+        transfers_operational,
+        revenue_checksum,
+        capital_expenditure,
+        debt,
+        cash_balance,
+        uifw_expenditure,
+        repairs_maintenance,
+        audit_outcome,
+        amount_type,
+    } = figures;
+    let checked = |total, what| checked_total(total, what, muni_code, year, clamp_negative_totals);
+    let revenue = checked(revenue, "revenue");
+    let operational_expenditure = checked(operational_expenditure, "expenditure");
+    let transfers_operational = checked(transfers_operational, "operational transfers");
+    let capital_expenditure = checked(capital_expenditure, "capital expenditure");
+    let debt = checked(debt, "debt");
+    let population = population_count(population);
+
+    // Confidence first: an `unreliable` grade suppresses raw-derived pillars.
+    let grade = evaluate_confidence(&ConfidenceInput {
+        revenue,
+        operational_expenditure,
+        capital_expenditure,
+        debt,
+        population,
+        revenue_checksum,
+    });
+
+    let scoring_input = ScoringInput {
+        revenue,
+        operational_expenditure,
+        capital_expenditure,
+        debt,
+        audit_outcome: audit_outcome.clone(),
+        population,
+        classification: classification.map(str::to_string),
+        transfers_operational,
+        uifw_expenditure,
+        repairs_maintenance,
+        cash_balance,
+        data_unreliable: grade.grade == CONFIDENCE_UNRELIABLE,
+        normalization,
+    };
+    let ScoreBreakdown {
+        overall_score,
+        financial_health_score,
+        infrastructure_score,
+        efficiency_score,
+        accountability_score,
+        liquidity_score,
+        ..
+    } = calculate_financial_score(&scoring_input);
+
+    let now = Utc::now();
+    FinancialDataDb {
+        id: Uuid::new_v4(), // in-memory only; an existing DB row keeps its own id
+        municipality_id: muni_code.to_string(),
+        year,
+        revenue,
+        operational_expenditure,
+        capital_expenditure,
+        debt,
+        audit_outcome,
+        transfers_operational,
+        uifw_expenditure,
+        repairs_maintenance,
+        cash_balance,
+        operating_surplus: operating_surplus(revenue, operational_expenditure),
+        amount_type,
+        overall_score,
+        financial_health_score,
+        infrastructure_score,
+        efficiency_score,
+        accountability_score,
+        liquidity_score,
+        data_confidence: Some(grade.grade.to_string()),
+        confidence_notes: grade.notes,
+        score_version: Some(score_version()),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+/// Fetches every metric for one municipality-year from the Treasury API
+/// (concurrently), recomputes scores, evaluates data confidence, and upserts
+/// the result — NULLs included, so the row doubles as a negative-cache marker.
+/// Individual fetch or upsert failures degrade to NULL fields rather than
+/// failing the request. The cells of every cube that answered are stored in
+/// `raw_financial_facts` for `recompute_from_raw`.
+///
+/// Returns `None` when **every** upstream call failed at the transport level:
+/// that means the Treasury API is unreachable, which must not be cached as
+/// "this year has no data".
+///
+/// When the fetch succeeds but yields **no data at all** while `prior` holds
+/// real data, the prior row is kept untouched: the Treasury API has been
+/// observed returning empty-but-successful responses while degraded, and
+/// stale-but-real beats fresh-but-empty.
+async fn refresh_financial_year(
+    pool: &DbPool,
+    api_client: &MunicipalMoneyClient,
+    muni_code: &str,
+    year: i32,
+    population: Option<f32>,
+    classification: Option<&str>,
+    prior: Option<&FinancialDataDb>,
+) -> Option<FinancialDataDb> {
+    let normalization = normalization_for_year(pool, year).await;
+    match fetch_financial_year(api_client, muni_code, year, population, classification, prior, normalization).await? {
+        FetchedYear::KeptPrior(row) => Some(row),
+        FetchedYear::Fetched { row, facts } => {
+            if let Err(e) = upsert_complete_financial_record(pool, &row).await {
+                // Serve the fetched data anyway; the cache simply retries next request.
+                log::error!("Muni: {muni_code}, Failed to upsert data for {year}: {e}");
+            }
+            store_fetched_facts(pool, muni_code, year, &facts).await;
+            Some(row)
+        }
+    }
+}
+
+/// What a refresh fetched for one municipality-year, before anything is
+/// persisted.
+enum FetchedYear {
+    /// The upstream returned nothing while real data is cached; keep the
+    /// cached row and store nothing.
+    KeptPrior(FinancialDataDb),
+    /// A newly scored row, plus the cells of every cube that answered (even
+    /// with nothing), by cube. Cubes that failed are left out so their
+    /// stored facts survive.
+    Fetched { row: FinancialDataDb, facts: Vec<(&'static str, Vec<FinancialItemFact>)> },
+}
+
+/// The Treasury API half of `refresh_financial_year`: fetches and scores one
+/// municipality-year without touching the database. `None` and the kept prior
+/// row are as described there.
+async fn fetch_financial_year(
+    api_client: &MunicipalMoneyClient,
+    muni_code: &str,
+    year: i32,
+    population: Option<f32>,
+    classification: Option<&str>,
+    prior: Option<&FinancialDataDb>,
+    normalization: Normalization,
+) -> Option<FetchedYear> {
+    log::info!("Muni: {}, refreshing financial data for {} from Treasury API", muni_code, year);
+
+    // Revenue/opex/transfers share one incexp cube fetch; capex, debt, audit,
+    // UIFW, repairs & maintenance, and cash each have their own cube. Seven
+    // concurrent upstream calls in total, each bounded by the metric deadline
+    // so one hung cube fails alone instead of holding up the other six.
+    let amount_types = &DEFAULT_AMOUNT_TYPES;
+    let (incexp_res, capex_res, debt_res, audit_res, uifw_res, rm_res, cash_res) = tokio::join!(
+        api_client.within_metric_deadline(get_revenue_and_expenditure(api_client, muni_code, year, amount_types)),
+        api_client.within_metric_deadline(get_capital_expenditure(api_client, muni_code, year, amount_types)),
+        api_client.within_metric_deadline(get_total_debt(api_client, muni_code, year, amount_types)),
+        api_client.within_metric_deadline(get_audit_outcome(api_client, muni_code, year)),
+        api_client.within_metric_deadline(get_uifw_total(api_client, muni_code, year)),
+        api_client.within_metric_deadline(get_repairs_maintenance(api_client, muni_code, year)),
+        api_client.within_metric_deadline(get_cash_balance(api_client, muni_code, year, amount_types)),
+    );
+
+    // Reachability judged on the four core cubes; UIFW/R&M/cash are enrichments.
+    if incexp_res.is_err() && capex_res.is_err() && debt_res.is_err() && audit_res.is_err() {
+        log::error!("Muni: {muni_code}, all Treasury API calls failed for {year}; upstream unreachable");
+        return None;
+    }
+
+    // Each result comes with whether its cube answered: a cube that failed
+    // keeps its previously stored facts, one that answered (even with
+    // nothing) replaces them.
+    let (incexp, incexp_ok) = match incexp_res {
+        Ok(figures) => (figures, true),
+        Err(e) => {
+            log::error!("Muni: {muni_code}, Failed Revenue/Expenditure fetch for {year}: {e}");
+            (IncexpFigures::default(), false)
+        }
+    };
+    let IncexpFigures {
+        revenue,
+        operational_expenditure,
+        transfers_operational,
+        revenue_checksum,
+        amount_type: incexp_amount_type,
+        facts: incexp_facts,
+    } = incexp;
+    let sourced = |res: Result<SourcedAmount, ApiClientError>, what: &str| match res {
+        Ok(sourced) => (sourced, true),
+        Err(e) => {
+            log::error!("Muni: {muni_code}, Failed {what} fetch for {year}: {e}");
+            (SourcedAmount::default(), false)
+        }
+    };
+    let (capex, capex_ok) = sourced(capex_res, "Capex");
+    let (debt, debt_ok) = sourced(debt_res, "Debt");
+    let (cash, cash_ok) = sourced(cash_res, "Cash");
+    let (uifw, uifw_ok) = sourced(uifw_res, "UIFW");
+    let (rm, rm_ok) = sourced(rm_res, "R&M");
+    // Budgeted (ORGB/ADJB) inputs mark the whole row as budgeted.
+    let amount_type = least_authoritative_amount_type(
+        &[incexp_amount_type, capex.amount_type, debt.amount_type, cash.amount_type],
+        &DEFAULT_AMOUNT_TYPES,
+    )
+    .map(|t| t.as_code().to_string());
+    let audit_outcome = audit_res
+        .map_err(|e| log::error!("Muni: {muni_code}, Failed Audit fetch for {year}: {e}"))
+        .ok()
+        .flatten();
+
+    let figures = YearFigures {
+        revenue,
+        operational_expenditure,
+        transfers_operational,
+        revenue_checksum,
+        capital_expenditure: capex.amount,
+        debt: debt.amount,
+        cash_balance: cash.amount,
+        uifw_expenditure: uifw.amount,
+        repairs_maintenance: rm.amount,
+        audit_outcome,
+        amount_type,
+    };
+
+    // Empty-but-successful responses during upstream degradation must not
+    // erase real cached data (observed 2026-07-07: 9 municipalities were
+    // wrongly negative-cached this way, including eThekwini).
+    let fetched_nothing = figures.revenue.is_none()
+        && figures.operational_expenditure.is_none()
+        && figures.capital_expenditure.is_none()
+        && figures.debt.is_none()
+        && figures.audit_outcome.is_none();
+    if fetched_nothing
+        && let Some(prior_row) = prior.filter(|p| p.has_any_data())
+    {
+        log::warn!(
+            "Muni: {muni_code}, year {year}: upstream returned no data but real data is cached — keeping the cached row (possible upstream degradation)"
+        );
+        return Some(FetchedYear::KeptPrior(prior_row.clone()));
+    }
+
+    let row = score_year_figures(
+        muni_code,
+        year,
+        figures,
+        population,
+        classification,
+        api_client.clamps_negative_totals(),
+        normalization,
+    );
+    let facts = [
+        (INCEXP_CUBE, incexp_facts, incexp_ok),
+        (CAPEX_CUBE, capex.facts, capex_ok),
+        (FINPOS_CUBE, debt.facts, debt_ok),
+        (CFLOW_CUBE, cash.facts, cash_ok),
+        (UIFW_CUBE, uifw.facts, uifw_ok),
+        (REPMAINT_CUBE, rm.facts, rm_ok),
+    ]
+    .into_iter()
+    .filter(|(_, _, ok)| *ok)
+    .map(|(cube, facts, _)| (cube, facts))
+    .collect();
+    Some(FetchedYear::Fetched { row, facts })
+}
+
+/// Stores the cells a refresh fetched, one cube at a time. Failures are
+/// logged; the scored row does not depend on them.
+async fn store_fetched_facts(
+    pool: &DbPool,
+    muni_code: &str,
+    year: i32,
+    facts: &[(&'static str, Vec<FinancialItemFact>)],
+) {
+    for (cube, facts) in facts {
+        if let Err(e) = replace_raw_facts(pool, muni_code, year, cube, facts).await {
+            log::error!("Muni: {muni_code}, Failed to store raw {cube} facts for {year}: {e}");
+        }
+    }
+}
+
+/// `refresh_financial_year` for several years of one municipality at once:
+/// up to `MULTI_YEAR_CONCURRENCY` years are fetched and scored concurrently
+/// (each still under the client's own in-flight cap), then every new row is
+/// written with a single `upsert_financial_records_batch`. `cached` supplies
+/// each year's prior row, as `prior` does for the single-year refresh.
+///
+/// A year whose fetch fails, or whose task panics, is logged and left out of
+/// the result without affecting the others; an empty result for a non-empty
+/// `years` therefore means the upstream was unreachable for all of them. A
+/// failed batch upsert is logged and the fetched rows are returned anyway,
+/// as a failed single upsert is.
+pub async fn refresh_financial_years(
+    pool: &DbPool,
+    api_client: &MunicipalMoneyClient,
+    muni_code: &str,
+    years: &[i32],
+    population: Option<f32>,
+    classification: Option<&str>,
+    cached: &[FinancialDataDb],
+) -> Vec<FinancialDataDb> {
+    let limiter = Arc::new(tokio::sync::Semaphore::new(MULTI_YEAR_CONCURRENCY));
+    let normalizations = normalizations_for_years(pool, years).await;
+    let mut tasks = tokio::task::JoinSet::new();
+    for &year in years {
+        let (api_client, limiter) = (api_client.clone(), limiter.clone());
+        let (muni_code, classification) = (muni_code.to_string(), classification.map(str::to_string));
+        let prior = cached.iter().find(|r| r.year == year).cloned();
+        let normalization = normalizations.get(year);
+        tasks.spawn(crate::request_id::inherit(async move {
+            let _permit = limiter.acquire_owned().await.expect("year limiter is never closed");
+            let fetched = fetch_financial_year(
+                &api_client,
+                &muni_code,
+                year,
+                population,
+                classification.as_deref(),
+                prior.as_ref(),
+                normalization,
+            )
+            .await;
+            (year, fetched)
+        }));
+    }
+
+    let mut refreshed = Vec::new();
+    let mut fetched_facts = Vec::new();
+    let mut new_rows = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((_, Some(FetchedYear::KeptPrior(row)))) => refreshed.push(row),
+            Ok((year, Some(FetchedYear::Fetched { row, facts }))) => {
+                fetched_facts.push((year, facts));
+                new_rows.push(row);
+            }
+            Ok((year, None)) => log::warn!("Muni: {muni_code}, refresh of {year} failed; other years unaffected"),
+            Err(e) => log::error!("Muni: {muni_code}, a year refresh task failed: {e}"),
+        }
+    }
+
+    if let Err(e) = upsert_financial_records_batch(pool, &new_rows).await {
+        // Serve the fetched data anyway; the cache simply retries next request.
+        log::error!("Muni: {muni_code}, Failed to batch upsert {} refreshed years: {e}", new_rows.len());
+    }
+    for (year, facts) in &fetched_facts {
+        store_fetched_facts(pool, muni_code, *year, facts).await;
+    }
+    refreshed.extend(new_rows);
+    refreshed.sort_by_key(|r| r.year);
+    refreshed
+}
+
+/// Re-derives one municipality-year from its stored raw facts — re-summing
+/// each cube with the current item-code selections, then re-grading and
+/// re-scoring — and upserts the result, without any Treasury API call.
+/// `item_codes` and `clamp_negative_totals` should be the API client's, so the
+/// figures come out as a fresh fetch would give them.
+///
+/// Figures from cubes with no stored facts, and the audit outcome (not a cube
+/// sum), are carried over from the cached row, and so is its `updated_at`: a
+/// recompute is not a fetch, so it leaves the cache TTL and `Last-Modified`
+/// alone. Returns `None` when nothing is stored for the year.
+pub async fn recompute_from_raw(
+    pool: &DbPool,
+    item_codes: &ItemCodeSets,
+    clamp_negative_totals: bool,
+    muni_code: &str,
+    year: i32,
+    population: Option<f32>,
+    classification: Option<&str>,
+) -> Result<Option<FinancialDataDb>, AppError> {
+    let stored = get_raw_facts(pool, muni_code, year).await?;
+    if stored.is_empty() {
+        return Ok(None);
+    }
+    let prior = get_all_financial_years_db(pool, muni_code).await?.into_iter().find(|r| r.year == year);
+    let cells = |cube: &str| -> Option<Vec<FinancialItemFact>> {
+        let cells: Vec<FinancialItemFact> =
+            stored.iter().filter(|f| f.cube == cube).map(|f| f.to_item_fact(muni_code)).collect();
+        (!cells.is_empty()).then_some(cells)
+    };
+    let amount_type_of = |cells: &[FinancialItemFact]| {
+        cells.iter().find_map(|c| AmountType::try_from(c.amount_type_code.as_deref()?).ok())
+    };
+    let prior_value = |f: fn(&FinancialDataDb) -> Option<Decimal>| prior.as_ref().and_then(f);
+
+    let mut figures = YearFigures {
+        revenue: prior_value(|r| r.revenue),
+        operational_expenditure: prior_value(|r| r.operational_expenditure),
+        transfers_operational: prior_value(|r| r.transfers_operational),
+        revenue_checksum: None,
+        capital_expenditure: prior_value(|r| r.capital_expenditure),
+        debt: prior_value(|r| r.debt),
+        cash_balance: prior_value(|r| r.cash_balance),
+        uifw_expenditure: prior_value(|r| r.uifw_expenditure),
+        repairs_maintenance: prior_value(|r| r.repairs_maintenance),
+        audit_outcome: prior.as_ref().and_then(|r| r.audit_outcome.clone()),
+        amount_type: None,
+    };
+    // Amount types of the cubes the row's label is derived from; a cube
+    // without stored facts contributes nothing, as when it was fetched empty.
+    let mut amount_types = Vec::new();
+    if let Some(cells) = cells(INCEXP_CUBE) {
+        let incexp = incexp_figures_from_facts(&cells, amount_type_of(&cells), item_codes);
+        figures.revenue = incexp.revenue;
+        figures.operational_expenditure = incexp.operational_expenditure;
+        figures.transfers_operational = incexp.transfers_operational;
+        figures.revenue_checksum = incexp.revenue_checksum;
+        amount_types.push(incexp.amount_type);
+    }
+    if let Some(cells) = cells(CAPEX_CUBE) {
+        figures.capital_expenditure = capital_expenditure_from_facts(&cells);
+        amount_types.push(figures.capital_expenditure.and(amount_type_of(&cells)));
+    }
+    if let Some(cells) = cells(FINPOS_CUBE) {
+        figures.debt = debt_from_facts(&cells, item_codes);
+        amount_types.push(figures.debt.and(amount_type_of(&cells)));
+    }
+    if let Some(cells) = cells(CFLOW_CUBE) {
+        figures.cash_balance = cash_balance_from_facts(&cells, item_codes);
+        amount_types.push(figures.cash_balance.and(amount_type_of(&cells)));
+    }
+    if let Some(cells) = cells(UIFW_CUBE) {
+        figures.uifw_expenditure = total_from_facts(&cells, "UIFW");
+    }
+    if let Some(cells) = cells(REPMAINT_CUBE) {
+        figures.repairs_maintenance = total_from_facts(&cells, "repairs & maintenance");
+    }
+    figures.amount_type = least_authoritative_amount_type(&amount_types, &DEFAULT_AMOUNT_TYPES)
+        .map(|t| t.as_code().to_string())
+        .or_else(|| prior.as_ref().and_then(|r| r.amount_type.clone()));
+
+    let normalization = normalization_for_year(pool, year).await;
+    let mut row =
+        score_year_figures(muni_code, year, figures, population, classification, clamp_negative_totals, normalization);
+    match prior {
+        // Same fetch, re-derived: the row keeps its id and its age.
+        Some(prior) => {
+            row.id = prior.id;
+            row.created_at = prior.created_at;
+            row.updated_at = prior.updated_at;
+            update_recomputed_records(pool, std::slice::from_ref(&row)).await?;
+        }
+        None => upsert_complete_financial_record(pool, &row).await?,
+    }
+    log::info!(
+        "Muni: {}, recomputed {} from stored facts: overall {:?}",
+        muni_code, year, row.overall_score
+    );
+    Ok(Some(row))
+}
+
+/// `recompute_from_raw` for every municipality-year with stored facts, one at
+/// a time (database work only). A failing year is logged and skipped. Returns
+/// how many rows were rewritten.
+pub async fn recompute_all_from_raw(
+    pool: &DbPool,
+    item_codes: &ItemCodeSets,
+    clamp_negative_totals: bool,
+) -> Result<usize, AppError> {
+    let years = get_raw_fact_years(pool).await?;
+    log::info!("Recomputing {} municipality-years from stored facts", years.len());
+    let mut recomputed = 0;
+    for (muni_code, year, population, classification) in years {
+        let recomputed_year = recompute_from_raw(
+            pool,
+            item_codes,
+            clamp_negative_totals,
+            &muni_code,
+            year,
+            population,
+            classification.as_deref(),
+        );
+        match recomputed_year.await {
+            Ok(Some(_)) => recomputed += 1,
+            Ok(None) => {}
+            Err(e) => log::error!("Muni: {muni_code}, recompute of {year} from stored facts failed: {e}"),
+        }
+    }
+    Ok(recomputed)
+}
@@ -0,0 +1,125 @@
+// src/services/normalization.rs
+
+//! How stored scores are normalized: the fixed anchors (the default), or with
+//! SCORING_NORMALIZATION=percentile, rank within each year's national
+//! distribution (`scoring::Normalization`). The choice is made once at
+//! startup and applies everywhere a row is scored: fetches, recomputes, score
+//! healing, `rescore_all`, score audits and comparisons.
+//!
+//! A year's distribution is built from its stored figures and kept for
+//! [`DISTRIBUTION_TTL_SECS`], so a batch of rows costs one query per year. As
+//! other municipalities' figures land, the distribution moves and score
+//! healing rewrites the rows whose rank changed.
+
+use crate::db::financials::get_metric_inputs;
+use crate::errors::AppError;
+use crate::scoring::{MetricDistribution, Normalization, ScoringInput};
+use sqlx::PgPool;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::{Duration, Instant};
+
+/// How long a year's distribution is reused before it is rebuilt from the
+/// stored figures. An hour keeps score healing from rewriting rows on every
+/// load while new figures still reach the ranks the same day.
+pub const DISTRIBUTION_TTL_SECS: u64 = 3600;
+
+static PERCENTILE: AtomicBool = AtomicBool::new(false);
+
+/// Each year's distribution with the time it was built.
+type DistributionCache = HashMap<i32, (Instant, Arc<MetricDistribution>)>;
+
+static DISTRIBUTIONS: LazyLock<RwLock<DistributionCache>> = LazyLock::new(Default::default);
+
+/// Switches every score computed by this process to percentile normalization.
+/// Called once at startup when SCORING_NORMALIZATION=percentile.
+pub fn enable_percentile_normalization() {
+    PERCENTILE.store(true, Ordering::Relaxed);
+}
+
+/// Whether scores are normalized by percentile rank.
+pub fn percentile_normalization_enabled() -> bool {
+    PERCENTILE.load(Ordering::Relaxed)
+}
+
+/// The distribution of every normalized metric over the stored rows for
+/// `year`, leaving out `excluded_ids` and rows graded unreliable.
+pub async fn metric_distribution(
+    pool: &PgPool,
+    year: i32,
+    excluded_ids: &[String],
+) -> Result<MetricDistribution, AppError> {
+    let inputs: Vec<ScoringInput> = get_metric_inputs(pool, year, excluded_ids)
+        .await?
+        .into_iter()
+        .map(|row| ScoringInput {
+            revenue: row.revenue,
+            operational_expenditure: row.operational_expenditure,
+            capital_expenditure: row.capital_expenditure,
+            debt: row.debt,
+            classification: row.classification,
+            transfers_operational: row.transfers_operational,
+            uifw_expenditure: row.uifw_expenditure,
+            repairs_maintenance: row.repairs_maintenance,
+            cash_balance: row.cash_balance,
+            ..ScoringInput::default()
+        })
+        .collect();
+    Ok(MetricDistribution::from_inputs(&inputs))
+}
+
+/// The normalization for each of `years`, as [`normalization_for_year`] gives
+/// it; a year not asked for gets the fixed anchors.
+#[derive(Debug, Clone, Default)]
+pub struct YearNormalizations(HashMap<i32, Normalization>);
+
+impl YearNormalizations {
+    pub fn get(&self, year: i32) -> Normalization {
+        self.0.get(&year).cloned().unwrap_or_default()
+    }
+}
+
+/// The normalization to score rows of `year` with. Linear mode needs no
+/// query. In percentile mode the year's cached distribution is used, rebuilt
+/// when older than [`DISTRIBUTION_TTL_SECS`]; if it can't be loaded, the
+/// fixed anchors stand in and the failure is logged.
+pub async fn normalization_for_year(pool: &PgPool, year: i32) -> Normalization {
+    if !percentile_normalization_enabled() {
+        return Normalization::Linear;
+    }
+    let cached = DISTRIBUTIONS.read().ok().and_then(|cache| {
+        cache
+            .get(&year)
+            .filter(|(built, _)| built.elapsed() < Duration::from_secs(DISTRIBUTION_TTL_SECS))
+            .map(|(_, distribution)| distribution.clone())
+    });
+    if let Some(distribution) = cached {
+        return Normalization::Percentile(distribution);
+    }
+    match metric_distribution(pool, year, &[]).await {
+        Ok(distribution) => {
+            let distribution = Arc::new(distribution);
+            if let Ok(mut cache) = DISTRIBUTIONS.write() {
+                cache.insert(year, (Instant::now(), distribution.clone()));
+            }
+            Normalization::Percentile(distribution)
+        }
+        Err(e) => {
+            log::error!("Metric distribution for {} failed to load, scoring with fixed anchors: {}", year, e);
+            Normalization::Linear
+        }
+    }
+}
+
+/// [`normalization_for_year`] for each distinct year in `years`.
+pub async fn normalizations_for_years(pool: &PgPool, years: &[i32]) -> YearNormalizations {
+    let mut normalizations = HashMap::new();
+    for &year in years {
+        if let Entry::Vacant(slot) = normalizations.entry(year) {
+            slot.insert(normalization_for_year(pool, year).await);
+        }
+    }
+    YearNormalizations(normalizations)
+}
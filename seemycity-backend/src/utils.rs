@@ -1,5 +1,5 @@
 // src/utils.rs
-use serde::{Serializer};
+use serde::{Deserialize, Deserializer, Serializer};
 use sqlx::types::Decimal;
 use std::str::FromStr;
 
@@ -25,15 +25,229 @@ where
     }
 }
 
+/// Serializes Decimal into f64 for JSON compatibility (see the Option variant).
+pub fn serialize_decimal_as_f64<S>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_option_decimal_as_f64(&Some(*value), serializer)
+}
+
+/// Serializes Option<Decimal> as a JSON number carrying every digit of the
+/// decimal (e.g. `123456789012.34`), with no round trip through f64. Relies
+/// on serde_json's `arbitrary_precision` feature; use for rand amounts, where
+/// the f64 serializer above can drop cents on large totals.
+pub fn serialize_option_decimal_exact<S>(value: &Option<Decimal>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(decimal_val) => {
+            let number = serde_json::Number::from_str(&decimal_val.to_string()).map_err(serde::ser::Error::custom)?;
+            serializer.serialize_some(&number)
+        }
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Serializes Decimal as an exact JSON number (see the Option variant).
+pub fn serialize_decimal_exact<S>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_option_decimal_exact(&Some(*value), serializer)
+}
+
 /// Serializes Option<f32> into Option<f64> for JSON compatibility.
+/// NaN and infinities have no JSON form and are emitted as null.
 pub fn serialize_option_f32_as_f64<S>(value: &Option<f32>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
     match value {
-        Some(f32_val) => serializer.serialize_some(&(f32_val.to_string().parse::<f64>().unwrap_or(f64::NAN))), // Convert f32 to f64
-        None => serializer.serialize_none(),
+        Some(f32_val) if f32_val.is_finite() => serializer.serialize_some(&(*f32_val as f64)),
+        _ => serializer.serialize_none(),
     }
 }
 
-// You could add other utility functions here as needed.
\ No newline at end of file
+/// Reads back a number written by either Decimal serializer above: the f64
+/// form (`serialize_option_decimal_as_f64`) or the exact one
+/// (`serialize_option_decimal_exact`). The number is parsed from its JSON
+/// text, so an exact amount keeps every digit; `null` is None.
+pub fn deserialize_option_decimal<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(number) = Option::<serde_json::Number>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let text = number.to_string();
+    Decimal::from_str(&text)
+        .or_else(|_| Decimal::from_scientific(&text))
+        .map(Some)
+        .map_err(|e| serde::de::Error::custom(format!("{text} is not a representable decimal: {e}")))
+}
+
+/// `deserialize_option_decimal` for a required number: reads back
+/// `serialize_decimal_as_f64` or `serialize_decimal_exact`.
+pub fn deserialize_decimal<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_option_decimal(deserializer)?.ok_or_else(|| serde::de::Error::custom("expected a number, got null"))
+}
+
+/// Reads back `serialize_option_f32_as_f64`: a JSON number narrowed to f32,
+/// or null.
+pub fn deserialize_option_f64_as_f32<'de, D>(deserializer: D) -> Result<Option<f32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<f64>::deserialize(deserializer)?.map(|v| v as f32))
+}
+
+/// Rounds a population figure to a whole head count. `None` for NaN,
+/// infinities, negatives and anything past `u32::MAX`, where a bare `as u32`
+/// would saturate to 0 or `u32::MAX` and pass for a real figure.
+pub fn head_count(population: f64) -> Option<u32> {
+    let rounded = population.round();
+    // `as i64` saturates, so a finite value out of range still fails the
+    // `u32::try_from`; only NaN and infinities have to be ruled out first.
+    rounded.is_finite().then(|| u32::try_from(rounded as i64).ok()).flatten()
+}
+
+// You could add other utility functions here as needed.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[derive(serde::Serialize)]
+    struct Amount {
+        #[serde(serialize_with = "serialize_option_decimal_exact")]
+        value: Option<Decimal>,
+    }
+
+    #[test]
+    fn exact_serializer_keeps_every_digit() {
+        for (value, json) in [
+            (Some(dec!(123456789012.34)), r#"{"value":123456789012.34}"#),
+            (Some(dec!(98765432109876543.21)), r#"{"value":98765432109876543.21}"#),
+            (Some(dec!(-0.05)), r#"{"value":-0.05}"#),
+            (None, r#"{"value":null}"#),
+        ] {
+            assert_eq!(serde_json::to_string(&Amount { value }).unwrap(), json);
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    struct Population {
+        #[serde(serialize_with = "serialize_option_f32_as_f64")]
+        value: Option<f32>,
+    }
+
+    #[test]
+    fn f32_serializer_emits_finite_numbers_or_null() {
+        for (value, json) in [
+            (Some(4_000_000.0), r#"{"value":4000000.0}"#),
+            (Some(16_777_216.0), r#"{"value":16777216.0}"#),
+            (None, r#"{"value":null}"#),
+            (Some(f32::NAN), r#"{"value":null}"#),
+            (Some(f32::INFINITY), r#"{"value":null}"#),
+            (Some(f32::NEG_INFINITY), r#"{"value":null}"#),
+        ] {
+            assert_eq!(serde_json::to_string(&Population { value }).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn exact_amounts_read_back_unchanged() {
+        let amount = dec!(98765432109876543.21);
+        let json = serde_json::to_value(Amount { value: Some(amount) }).unwrap();
+        let read_back: Decimal = json["value"].to_string().parse().unwrap();
+        assert_eq!(read_back, amount);
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Scored {
+        #[serde(serialize_with = "serialize_option_decimal_as_f64", deserialize_with = "deserialize_option_decimal")]
+        score: Option<Decimal>,
+        #[serde(serialize_with = "serialize_option_decimal_exact", deserialize_with = "deserialize_option_decimal")]
+        amount: Option<Decimal>,
+        #[serde(serialize_with = "serialize_option_f32_as_f64", deserialize_with = "deserialize_option_f64_as_f32")]
+        population: Option<f32>,
+    }
+
+    #[test]
+    fn both_decimal_forms_and_f32_read_back() {
+        for value in [
+            Scored { score: Some(dec!(63.33)), amount: Some(dec!(98765432109876543.21)), population: Some(433_688.0) },
+            Scored { score: Some(dec!(0.0000001)), amount: Some(dec!(-0.05)), population: None },
+            Scored { score: None, amount: None, population: Some(1.5) },
+        ] {
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(serde_json::from_str::<Scored>(&json).unwrap(), value, "{json}");
+        }
+        assert!(serde_json::from_str::<Scored>(r#"{"score":"high","amount":null,"population":null}"#).is_err());
+    }
+
+    #[test]
+    fn detail_payload_round_trips_through_json() {
+        use crate::models::{FinancialYearData, MunicipalityDetail};
+        let year = FinancialYearData {
+            year: 2024,
+            revenue: Some(dec!(61843219876.54)),
+            operational_expenditure: Some(dec!(58671234567.89)),
+            debt: Some(dec!(0)),
+            operating_surplus: Some(dec!(3171985308.65)),
+            overall_score: Some(dec!(71.25)),
+            liquidity_score: Some(dec!(33.33)),
+            revenue_per_capita: Some(dec!(12957.123456)),
+            grade: Some("B".to_string()),
+            score_audit: Some(crate::scoring::explain_financial_score(&crate::scoring::ScoringInput {
+                revenue: Some(dec!(61843219876.54)),
+                operational_expenditure: Some(dec!(58671234567.89)),
+                debt: Some(dec!(0)),
+                classification: Some("A".to_string()),
+                ..Default::default()
+            })),
+            ..FinancialYearData::default()
+        };
+        let detail = MunicipalityDetail {
+            id: "CPT".to_string(),
+            name: "City of Cape Town".to_string(),
+            province: "Western Cape".to_string(),
+            population: Some(4_772_846.0),
+            classification: Some("A".to_string()),
+            website: None,
+            address: None,
+            phone: None,
+            district_id: None,
+            district_name: None,
+            financials: vec![year],
+            has_financial_data: true,
+            geometry: Some(geojson::Geometry::new(geojson::Value::Point(vec![18.4241, -33.9249]))),
+            last_updated: Some(chrono::Utc::now()),
+        };
+
+        let json = serde_json::to_string(&detail).unwrap();
+        let read_back: MunicipalityDetail = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&read_back).unwrap(), json);
+        let year = &read_back.financials[0];
+        assert_eq!(year.revenue, Some(dec!(61843219876.54)));
+        assert_eq!(year.revenue_per_capita, Some(dec!(12957.123456)));
+        assert_eq!(year.overall_score, Some(dec!(71.25)));
+        assert_eq!(year.capital_expenditure, None);
+        assert_eq!(read_back.population, Some(4_772_846.0));
+
+        let audit = year.score_audit.as_ref().expect("the audit survives the round trip");
+        assert_eq!(audit.peer_group, Some(crate::scoring::PeerGroup::Metro));
+        assert_eq!(audit.pillars.len(), 5);
+
+        // Fields absent from stored JSON read as None, except the year.
+        let sparse: FinancialYearData = serde_json::from_str(r#"{"year":2023,"revenue":1000.5}"#).unwrap();
+        assert_eq!((sparse.revenue, sparse.overall_score), (Some(dec!(1000.5)), None));
+        assert!(serde_json::from_str::<FinancialYearData>(r#"{"revenue":1000.5}"#).is_err());
+    }
+}
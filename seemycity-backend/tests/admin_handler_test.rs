@@ -0,0 +1,197 @@
+// tests/admin_handler_test.rs
+
+// Admin endpoints: auth guard, the dry-run scoring-config validator, map
+// cache invalidation and job polling.
+// No database or upstream is involved.
+use actix_web::{http::StatusCode, test, web, App};
+use seemycity_backend::handlers::admin::{get_job_handler, invalidate_map_cache_handler, validate_scoring_config_handler};
+use seemycity_backend::handlers::municipalities::{MapPayload, MapResponseCache};
+use seemycity_backend::jobs::JobRegistry;
+use seemycity_backend::scoring::ScoringConfig;
+use seemycity_backend::Config;
+
+const TOKEN: &str = "test-admin-token";
+
+async fn call(config: Config, token: Option<&str>, body: serde_json::Value) -> (StatusCode, serde_json::Value) {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(config))
+            .route("/api/admin/validate-config", web::post().to(validate_scoring_config_handler)),
+    )
+    .await;
+    let mut req = test::TestRequest::post().uri("/api/admin/validate-config").set_json(body);
+    if let Some(token) = token {
+        req = req.insert_header(("Authorization", format!("Bearer {token}")));
+    }
+    let resp = test::call_service(&app, req.to_request()).await;
+    let status = resp.status();
+    (status, test::read_body_json(resp).await)
+}
+
+fn admin_config() -> Config {
+    Config { admin_token: Some(TOKEN.to_string()), ..Config::default() }
+}
+
+// The default config with `overrides` applied; a `thresholds` object is
+// merged into the default anchors rather than replacing them.
+fn config_json(overrides: serde_json::Value) -> serde_json::Value {
+    let mut body = serde_json::to_value(ScoringConfig::default()).unwrap();
+    for (key, value) in overrides.as_object().unwrap() {
+        match value.as_object() {
+            Some(anchors) if key == "thresholds" => {
+                for (anchor, value) in anchors {
+                    body["thresholds"][anchor] = value.clone();
+                }
+            }
+            _ => body[key] = value.clone(),
+        }
+    }
+    body
+}
+
+#[actix_web::test]
+async fn valid_config_is_accepted() {
+    let (status, body) = call(admin_config(), Some(TOKEN), config_json(serde_json::json!({}))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, serde_json::json!({ "valid": true }));
+}
+
+#[actix_web::test]
+async fn weight_sum_violation_is_reported() {
+    let overrides = serde_json::json!({ "weight_financial_health": "0.34" });
+    let (status, body) = call(admin_config(), Some(TOKEN), config_json(overrides)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["valid"], false);
+    assert_eq!(body["errors"][0]["field"], "weights");
+    assert!(body["errors"][0]["message"].as_str().unwrap().contains("1.10"));
+}
+
+#[actix_web::test]
+async fn threshold_ordering_violations_are_all_reported() {
+    let overrides = serde_json::json!({
+        "thresholds": { "efficiency_ratio_best": "1.20", "infra_ratio_worst": "0.10" },
+    });
+    let (_, body) = call(admin_config(), Some(TOKEN), config_json(overrides)).await;
+    assert_eq!(body["valid"], false);
+    let errors = body["errors"].as_array().unwrap();
+    let fields: Vec<&str> = errors.iter().map(|e| e["field"].as_str().unwrap()).collect();
+    assert_eq!(fields, ["efficiency_ratio", "infra_ratio"]);
+    assert!(errors[1]["message"].as_str().unwrap().contains("infra_ratio_worst (0.10)"));
+}
+
+#[actix_web::test]
+async fn numeric_decimals_are_read_exactly() {
+    // Every decimal sent as a JSON number rather than a string.
+    fn to_numbers(value: &mut serde_json::Value) {
+        if let Some(fields) = value.as_object_mut() {
+            fields.values_mut().for_each(to_numbers);
+        } else if let Some(number) = value.as_str().and_then(|s| s.parse::<serde_json::Number>().ok()) {
+            *value = serde_json::Value::Number(number);
+        }
+    }
+    let mut body = config_json(serde_json::json!({}));
+    to_numbers(&mut body);
+    let (status, response) = call(admin_config(), Some(TOKEN), body.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response, serde_json::json!({ "valid": true }));
+
+    body["weight_financial_health"] = serde_json::json!(0.34);
+    let (status, response) = call(admin_config(), Some(TOKEN), body).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["errors"][0]["field"], "weights");
+    assert!(response["errors"][0]["message"].as_str().unwrap().contains("1.10"), "{response}");
+}
+
+#[actix_web::test]
+async fn unknown_or_missing_fields_are_bad_requests() {
+    let mut body = config_json(serde_json::json!({ "weight_typo": "0.1" }));
+    let (status, _) = call(admin_config(), Some(TOKEN), body.clone()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    body.as_object_mut().unwrap().remove("weight_typo");
+    body["thresholds"]["debt_ratio_typo"] = serde_json::json!("0.1");
+    let (status, _) = call(admin_config(), Some(TOKEN), body.clone()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    body["thresholds"].as_object_mut().unwrap().remove("debt_ratio_typo");
+    body.as_object_mut().unwrap().remove("weight_liquidity");
+    let (status, _) = call(admin_config(), Some(TOKEN), body).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn anchors_left_out_keep_their_default() {
+    let mut body = config_json(serde_json::json!({}));
+    body["thresholds"] = serde_json::json!({ "debt_ratio_max": "1.5" });
+    let (status, response) = call(admin_config(), Some(TOKEN), body).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response, serde_json::json!({ "valid": true }));
+}
+
+#[actix_web::test]
+async fn requests_without_the_admin_token_are_rejected() {
+    let body = config_json(serde_json::json!({}));
+    let (status, _) = call(admin_config(), None, body.clone()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = call(admin_config(), Some("wrong"), body.clone()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    // No ADMIN_TOKEN configured: admin endpoints are off entirely.
+    let (status, _) = call(Config::default(), Some(TOKEN), body).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn invalidate_map_cache_reports_evicted_entries() {
+    let cache = web::Data::new(MapResponseCache::new(std::time::Duration::from_secs(60)));
+    cache.get_or_build("full".to_string(), || async { Ok(MapPayload::new("{}".to_string(), None)) }).await.unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(admin_config()))
+            .app_data(cache.clone())
+            .route("/api/admin/invalidate-map-cache", web::post().to(invalidate_map_cache_handler)),
+    )
+    .await;
+
+    let req = test::TestRequest::post().uri("/api/admin/invalidate-map-cache");
+    let resp = test::call_service(&app, req.to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    for evicted in [1, 0] {
+        let req = test::TestRequest::post()
+            .uri("/api/admin/invalidate-map-cache")
+            .insert_header(("Authorization", format!("Bearer {TOKEN}")));
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req.to_request()).await;
+        assert_eq!(body, serde_json::json!({ "evicted": evicted }));
+    }
+}
+
+#[actix_web::test]
+async fn jobs_are_reported_by_id_to_admins_only() {
+    let jobs = web::Data::new(JobRegistry::default());
+    let job = jobs.create("CPT").unwrap();
+    jobs.mark_running(&job.id);
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(admin_config()))
+            .app_data(jobs.clone())
+            .route("/api/admin/jobs/{job_id}", web::get().to(get_job_handler)),
+    )
+    .await;
+    let uri = format!("/api/admin/jobs/{}", job.id);
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::get().uri(&uri).insert_header(("Authorization", format!("Bearer {TOKEN}")));
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req.to_request()).await;
+    assert_eq!(body["id"], job.id.as_str());
+    assert_eq!(body["municipality_id"], "CPT");
+    assert_eq!(body["status"], "running");
+    assert_eq!(body["error"], serde_json::Value::Null);
+
+    let req = test::TestRequest::get()
+        .uri("/api/admin/jobs/no-such-job")
+        .insert_header(("Authorization", format!("Bearer {TOKEN}")));
+    let resp = test::call_service(&app, req.to_request()).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,28 @@
+// tests/cache_warmer_test.rs
+
+// Cache-warmer behaviour that needs no database: the pool points at a closed
+// port, so listing municipalities fails and the run must give up cleanly.
+mod common;
+
+use common::MockUpstream;
+use seemycity_backend::services::municipality::{warm_all_municipalities, UpstreamHealth};
+use seemycity_backend::MunicipalMoneyClient;
+use sqlx::postgres::PgPoolOptions;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[actix_web::test]
+async fn warmer_gives_up_without_upstream_calls_when_the_db_is_down() {
+    let upstream = MockUpstream::start().await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+    let pool = PgPoolOptions::new()
+        .acquire_timeout(Duration::from_millis(500))
+        .connect_lazy("postgres://nobody@127.0.0.1:1/none")
+        .unwrap();
+
+    let health = Arc::new(UpstreamHealth::default());
+
+    let run = warm_all_municipalities(&pool, &client, &health, 2024, chrono::Duration::days(7), 4);
+    tokio::time::timeout(Duration::from_secs(5), run).await.expect("warmer run should end");
+    assert_eq!(upstream.hits(), 0);
+}
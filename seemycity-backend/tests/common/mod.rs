@@ -0,0 +1,236 @@
+// tests/common/mod.rs
+
+// Shared helpers for integration tests. Not every test file uses every helper.
+#![allow(dead_code)]
+
+use actix_web::http::header::HeaderMap;
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use rust_decimal_macros::dec;
+use seemycity_backend::embargo::Embargo;
+use seemycity_backend::handlers::municipalities::DetailAdmission;
+use seemycity_backend::models::MapMunicipalityProperties;
+use seemycity_backend::services::municipality::UpstreamHealth;
+use seemycity_backend::{Config, MunicipalMoneyClient};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A local stand-in for the Municipal Money API. Answers every request with an
+/// empty cube response (or a canned body, see `start_with_body` and
+/// `start_with_pages`) and counts the hits, so tests can assert how many
+/// upstream calls a code path makes (including none at all). Also records the
+/// peak number of requests in flight at once and every request URL (decoded).
+pub struct MockUpstream {
+    pub base_url: String,
+    hits: Arc<AtomicUsize>,
+    max_in_flight: Arc<AtomicUsize>,
+    requests: Arc<Mutex<Vec<String>>>,
+    headers: Arc<Mutex<Vec<HeaderMap>>>,
+}
+
+/// What failing responses carry unless a test sets its own body.
+const MOCK_FAILURE_BODY: &str = "mock failure";
+
+impl MockUpstream {
+    pub async fn start() -> Self {
+        Self::spawn(0, 503, MOCK_FAILURE_BODY, Duration::ZERO, None, None, vec![empty_cube()])
+    }
+
+    /// Like `start`, but every successful response is `body` instead of an
+    /// empty cube, for exercising response parsing.
+    pub async fn start_with_body(body: serde_json::Value) -> Self {
+        Self::spawn(0, 503, MOCK_FAILURE_BODY, Duration::ZERO, None, None, vec![body])
+    }
+
+    /// Like `start_with_body`, but answers with `pages[n - 1]` for a request
+    /// carrying `page=n` (no `page` parameter means page 1), for exercising
+    /// paginated aggregate responses. Pages past the end are empty cubes.
+    pub async fn start_with_pages(pages: Vec<serde_json::Value>) -> Self {
+        Self::spawn(0, 503, MOCK_FAILURE_BODY, Duration::ZERO, None, None, pages)
+    }
+
+    /// Like `start`, but the first `failures` requests are answered with
+    /// `status` (e.g. 503 for a transient outage, 400 for a client error).
+    pub async fn start_failing(failures: usize, status: u16) -> Self {
+        Self::spawn(failures, status, MOCK_FAILURE_BODY, Duration::ZERO, None, None, vec![empty_cube()])
+    }
+
+    /// Like `start_failing`, but the failures carry `body` (e.g. a JSON error
+    /// document) instead of the plain-text "mock failure".
+    pub async fn start_failing_with_body(failures: usize, status: u16, body: &'static str) -> Self {
+        Self::spawn(failures, status, body, Duration::ZERO, None, None, vec![empty_cube()])
+    }
+
+    /// Like `start`, but every response is held for `delay`, so concurrent
+    /// requests overlap and `max_in_flight` is meaningful.
+    pub async fn start_delayed(delay: Duration) -> Self {
+        Self::spawn(0, 503, MOCK_FAILURE_BODY, delay, None, None, vec![empty_cube()])
+    }
+
+    /// Like `start_with_body`, but requests whose path contains `stalled_path`
+    /// (e.g. one cube's `/cubes/capital_v2/`) are held for `delay`, for
+    /// exercising a single hung upstream call among several.
+    pub async fn start_stalling(body: serde_json::Value, stalled_path: &'static str, delay: Duration) -> Self {
+        Self::spawn(0, 503, MOCK_FAILURE_BODY, delay, Some(stalled_path), None, vec![body])
+    }
+
+    /// Like `start_with_body`, but every request whose decoded URL contains
+    /// `failing_url` (e.g. one year's cut, `financial_year_end.year:2010|`) is
+    /// answered with `status`, however often it is retried.
+    pub async fn start_failing_url(body: serde_json::Value, failing_url: &'static str, status: u16) -> Self {
+        Self::spawn(0, status, MOCK_FAILURE_BODY, Duration::ZERO, None, Some(failing_url), vec![body])
+    }
+
+    // `delay` holds every response, or only those for `stalled_path` when set.
+    // The first `failures` requests fail, as do all matching `failing_url`.
+    fn spawn(
+        failures: usize,
+        status: u16,
+        failure_body: &'static str,
+        delay: Duration,
+        stalled_path: Option<&'static str>,
+        failing_url: Option<&'static str>,
+        pages: Vec<serde_json::Value>,
+    ) -> Self {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let headers = Arc::new(Mutex::new(Vec::new()));
+        let (server_hits, server_max, server_requests) = (hits.clone(), max_in_flight.clone(), requests.clone());
+        let server_headers = headers.clone();
+        let server = HttpServer::new(move || {
+            let (hits, in_flight, max_in_flight) = (server_hits.clone(), in_flight.clone(), server_max.clone());
+            let (requests, headers) = (server_requests.clone(), server_headers.clone());
+            let pages = pages.clone();
+            App::new().default_service(web::to(move |req: HttpRequest| {
+                let url = decoded_url(&req);
+                let failing = failing_url.is_some_and(|pattern| url.contains(pattern));
+                requests.lock().unwrap().push(url);
+                headers.lock().unwrap().push(req.headers().clone());
+                let seen = hits.fetch_add(1, Ordering::SeqCst);
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                let in_flight = in_flight.clone();
+                let delay = match stalled_path {
+                    Some(path) if !req.path().contains(path) => Duration::ZERO,
+                    _ => delay,
+                };
+                let body = requested_page(&req)
+                    .and_then(|page| pages.get(page - 1).cloned())
+                    .unwrap_or_else(empty_cube);
+                async move {
+                    actix_web::rt::time::sleep(delay).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    if seen < failures || failing {
+                        let status = actix_web::http::StatusCode::from_u16(status).unwrap();
+                        return HttpResponse::build(status).body(failure_body);
+                    }
+                    HttpResponse::Ok().json(body)
+                }
+            }))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .expect("Failed to bind mock upstream");
+        let addr = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+        MockUpstream { base_url: format!("http://{addr}"), hits, max_in_flight, requests, headers }
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::SeqCst)
+    }
+
+    /// Path and percent-decoded query of every request received, in order.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The `name` header of every request received, in order (`None` where a
+    /// request did not send it).
+    pub fn header_values(&self, name: &str) -> Vec<Option<String>> {
+        self.headers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|headers| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string))
+            .collect()
+    }
+
+    /// Most requests the mock was serving simultaneously.
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+// Empty in both shapes: an `/aggregate` response and a `/facts` one.
+fn empty_cube() -> serde_json::Value {
+    serde_json::json!({ "total_cell_count": 0, "cells": [], "total_fact_count": 0, "data": [] })
+}
+
+// The 1-based `page` query parameter, defaulting to 1 when absent.
+fn requested_page(req: &HttpRequest) -> Option<usize> {
+    let pairs = web::Query::<Vec<(String, String)>>::from_query(req.query_string()).ok()?;
+    match pairs.iter().find(|(k, _)| k == "page") {
+        Some((_, v)) => v.parse().ok().filter(|p| *p >= 1),
+        None => Some(1),
+    }
+}
+
+// "/path?k=v&..." with the query percent-decoded, so tests can compare against
+// the URL as written in the client code.
+fn decoded_url(req: &HttpRequest) -> String {
+    let pairs = web::Query::<Vec<(String, String)>>::from_query(req.query_string())
+        .map(|q| q.into_inner())
+        .unwrap_or_default();
+    let query: Vec<String> = pairs.iter().map(|(k, v)| format!("{k}={v}")).collect();
+    format!("{}?{}", req.path(), query.join("&"))
+}
+
+/// A pool that never connects (nothing listens on port 1), so a test using it
+/// only passes if the handler answers before any query.
+pub fn unreachable_pool() -> PgPool {
+    PgPoolOptions::new()
+        .connect_lazy("postgres://nobody@127.0.0.1:1/none")
+        .unwrap()
+}
+
+/// App state for a handler test without a database, for `App::configure`:
+/// `config`, an [`unreachable_pool`], a client pointed at `upstream`, upstream
+/// health, a four-slot detail admission and `embargo`. Add the route under
+/// test after it.
+pub fn offline_app_state(
+    upstream: &MockUpstream,
+    config: Config,
+    embargo: Embargo,
+) -> impl FnOnce(&mut web::ServiceConfig) {
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+    move |cfg| {
+        cfg.app_data(web::Data::new(config))
+            .app_data(web::Data::new(unreachable_pool()))
+            .app_data(web::Data::new(client))
+            .app_data(web::Data::new(UpstreamHealth::default()))
+            .app_data(web::Data::new(DetailAdmission::new(4)))
+            .app_data(web::Data::new(embargo));
+    }
+}
+
+/// Map feature properties for `id` with every field set, including the
+/// map-only ones. Override fields with struct update syntax.
+pub fn map_properties(id: &str) -> MapMunicipalityProperties {
+    MapMunicipalityProperties {
+        id: id.to_string(),
+        name: format!("Municipality {id}"),
+        province: "Western Cape".to_string(),
+        population: Some(4_772_846.0),
+        classification: Some("A".to_string()),
+        latest_score: Some(dec!(71.25)),
+        last_updated: Some(chrono::Utc::now()),
+        centroid: Some([18.4241, -33.9249]),
+        bbox: Some([18.3074, -34.3583, 19.0046, -33.4713]),
+        area_km2: Some(2444.97),
+    }
+}
@@ -0,0 +1,311 @@
+// tests/detail_handler_test.rs
+
+// Detail-endpoint behaviour that needs no database: the pool is lazy and never
+// connects, so these tests only pass if the handler returns before any query.
+mod common;
+
+use actix_web::{http::StatusCode, test, web, App};
+use common::{offline_app_state, MockUpstream};
+use seemycity_backend::embargo::{Embargo, EmbargoEntry};
+use seemycity_backend::handlers::admin::refresh_municipality_handler;
+use seemycity_backend::handlers::municipalities::{
+    batch_municipality_detail_handler, compare_municipalities_handler, export_municipality_csv_handler,
+    get_municipality_benchmarks_handler, get_municipality_breakdown_handler, get_municipality_detail_handler,
+    get_municipality_geometry_handler, get_municipality_percentile_handler, get_municipality_summary_handler,
+    get_municipality_years_handler, DetailAdmission, MapResponseCache, BATCH_DETAIL_MAX_IDS,
+};
+use seemycity_backend::jobs::JobRegistry;
+use seemycity_backend::services::municipality::UpstreamHealth;
+use seemycity_backend::{Config, MunicipalMoneyClient};
+use sqlx::postgres::PgPoolOptions;
+use std::time::Duration;
+
+#[actix_web::test]
+async fn far_future_year_is_rejected_without_upstream_calls() {
+    let upstream = MockUpstream::start().await;
+    let config = Config { latest_data_year: 2026, ..Config::default() };
+
+    let app = test::init_service(
+        App::new()
+            .configure(offline_app_state(&upstream, config, Embargo::default()))
+            .route("/api/municipalities/{id}", web::get().to(get_municipality_detail_handler)),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/api/municipalities/CPT?year=2030").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let message = body["error"].as_str().unwrap();
+    assert!(message.contains("2030"), "unexpected message: {message}");
+    assert!(message.contains("2026"), "unexpected message: {message}");
+    assert_eq!(upstream.hits(), 0, "a future year must not reach the Treasury API");
+}
+
+#[actix_web::test]
+async fn invalid_years_lists_are_rejected_without_upstream_calls() {
+    let upstream = MockUpstream::start().await;
+    let config = Config { latest_data_year: 2026, ..Config::default() };
+    let embargo = Embargo::new(vec![EmbargoEntry { id: "CPT".to_string(), years: vec![2021], reason: None }]);
+
+    let app = test::init_service(
+        App::new()
+            .configure(offline_app_state(&upstream, config, embargo))
+            .route("/api/municipalities/{id}", web::get().to(get_municipality_detail_handler)),
+    )
+    .await;
+
+    for (uri, status, fragment) in [
+        ("/api/municipalities/JHB?years=2022,2030", StatusCode::BAD_REQUEST, "2030"),
+        ("/api/municipalities/JHB?year=1990", StatusCode::BAD_REQUEST, "earliest available year is 2009"),
+        ("/api/municipalities/JHB?years=2008,2022", StatusCode::BAD_REQUEST, "2008"),
+        ("/api/municipalities/JHB?years=2022,twenty", StatusCode::BAD_REQUEST, "twenty"),
+        ("/api/municipalities/JHB?years=", StatusCode::BAD_REQUEST, "years"),
+        ("/api/municipalities/JHB?year=2022&years=2021,2022", StatusCode::BAD_REQUEST, "not both"),
+        ("/api/municipalities/JHB?years=2015,2016,2017,2018,2019,2020,2021", StatusCode::BAD_REQUEST, "At most"),
+        ("/api/municipalities/JHB?years=2021,2021,2021,2021,2021,2021,2021", StatusCode::BAD_REQUEST, "At most"),
+        ("/api/municipalities/CPT?years=2020,2021", StatusCode::NOT_FOUND, "withheld"),
+    ] {
+        let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+        assert_eq!(resp.status(), status, "{uri}");
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let message = body["error"].as_str().unwrap();
+        assert!(message.contains(fragment), "unexpected message for {uri}: {message}");
+    }
+    assert_eq!(upstream.hits(), 0, "a rejected request must not reach the Treasury API");
+}
+
+#[actix_web::test]
+async fn malformed_municipality_id_is_rejected_without_upstream_calls() {
+    let upstream = MockUpstream::start().await;
+
+    let app = test::init_service(
+        App::new()
+            .configure(offline_app_state(&upstream, Config::default(), Embargo::default()))
+            .route("/api/municipalities/{id}", web::get().to(get_municipality_detail_handler)),
+    )
+    .await;
+
+    for uri in ["/api/municipalities/WC-011", "/api/municipalities/CPT%20", "/api/municipalities/ABCDEFGHIJKLMNOP"] {
+        let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{uri}");
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert!(body["error"].as_str().unwrap().contains("Invalid municipality id"), "{uri}");
+    }
+    assert_eq!(upstream.hits(), 0, "a malformed id must not reach the Treasury API");
+}
+
+#[actix_web::test]
+async fn requests_beyond_the_admission_limit_get_503() {
+    // A listener that never answers: admitted requests stall on the DB until
+    // the pool's acquire timeout, holding their slot the whole time.
+    let blackhole = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let pool = PgPoolOptions::new()
+        .acquire_timeout(Duration::from_millis(500))
+        .connect_lazy(&format!("postgres://nobody@{}/none", blackhole.local_addr().unwrap()))
+        .unwrap();
+    let upstream = MockUpstream::start().await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(Config::default()))
+            .app_data(web::Data::new(pool))
+            .app_data(web::Data::new(client))
+            .app_data(web::Data::new(UpstreamHealth::default()))
+            .app_data(web::Data::new(DetailAdmission::new(2)))
+            .app_data(web::Data::new(Embargo::default()))
+            .route("/api/municipalities/{id}", web::get().to(get_municipality_detail_handler)),
+    )
+    .await;
+
+    let call = |id: &str| {
+        let req = test::TestRequest::get().uri(&format!("/api/municipalities/{id}")).to_request();
+        test::call_service(&app, req)
+    };
+    let responses = tokio::join!(call("A"), call("B"), call("C"), call("D"), call("E"));
+    let responses = [responses.0, responses.1, responses.2, responses.3, responses.4];
+
+    let shed: Vec<_> = responses
+        .iter()
+        .filter(|r| r.status() == StatusCode::SERVICE_UNAVAILABLE)
+        .collect();
+    assert_eq!(shed.len(), 3, "two admitted, the rest shed");
+    for response in shed {
+        assert_eq!(response.headers().get("Retry-After").unwrap(), "5");
+    }
+    // Slots are released once admitted requests finish.
+    let again = call("F").await;
+    assert_ne!(again.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[actix_web::test]
+async fn embargoed_municipality_and_year_are_withheld_without_db_access() {
+    let upstream = MockUpstream::start().await;
+    let embargo = Embargo::new(vec![
+        EmbargoEntry { id: "BUF".to_string(), years: vec![], reason: None },
+        EmbargoEntry { id: "CPT".to_string(), years: vec![2023], reason: None },
+    ]);
+
+    let app = test::init_service(
+        App::new()
+            .configure(offline_app_state(&upstream, Config::default(), embargo))
+            .route("/api/municipalities/{id}", web::get().to(get_municipality_detail_handler)),
+    )
+    .await;
+
+    for uri in ["/api/municipalities/BUF", "/api/municipalities/buf?year=2022", "/api/municipalities/CPT?year=2023"] {
+        let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND, "{uri}");
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let message = body["error"].as_str().unwrap();
+        assert!(message.contains("withheld"), "unexpected message for {uri}: {message}");
+    }
+    assert_eq!(upstream.hits(), 0, "withheld data must not reach the Treasury API");
+}
+
+#[actix_web::test]
+async fn comparison_with_an_embargoed_side_is_withheld_without_db_access() {
+    let upstream = MockUpstream::start().await;
+    let embargo = Embargo::new(vec![
+        EmbargoEntry { id: "BUF".to_string(), years: vec![], reason: None },
+        EmbargoEntry { id: "CPT".to_string(), years: vec![2023], reason: None },
+    ]);
+
+    let app = test::init_service(
+        App::new()
+            .configure(offline_app_state(&upstream, Config::default(), embargo))
+            .route("/api/municipalities/compare", web::get().to(compare_municipalities_handler)),
+    )
+    .await;
+
+    for uri in [
+        "/api/municipalities/compare?a=JHB&b=buf",
+        "/api/municipalities/compare?a=CPT&b=JHB&year=2023",
+    ] {
+        let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND, "{uri}");
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert!(body["error"].as_str().unwrap().contains("withheld"), "{uri}: {body}");
+    }
+    assert_eq!(upstream.hits(), 0);
+}
+
+#[actix_web::test]
+async fn comparison_rejects_malformed_or_repeated_ids_without_db_access() {
+    let upstream = MockUpstream::start().await;
+    let app = test::init_service(
+        App::new()
+            .configure(offline_app_state(&upstream, Config::default(), Embargo::default()))
+            .route("/api/municipalities/compare", web::get().to(compare_municipalities_handler)),
+    )
+    .await;
+
+    for (uri, fragment) in [
+        ("/api/municipalities/compare?a=CPT&b=CPT", "CPT twice"),
+        ("/api/municipalities/compare?a=cpt&b=CPT&year=2023", "CPT twice"),
+        ("/api/municipalities/compare?a=CPT&b=WC-011", "Invalid municipality id"),
+    ] {
+        let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{uri}");
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert!(body["error"].as_str().unwrap().contains(fragment), "{uri}: {body}");
+    }
+    assert_eq!(upstream.hits(), 0);
+}
+
+#[actix_web::test]
+async fn batch_rejects_oversized_requests_and_reports_withheld_ids_per_id() {
+    let upstream = MockUpstream::start().await;
+    let embargo = Embargo::new(vec![
+        EmbargoEntry { id: "BUF".to_string(), years: vec![], reason: None },
+        EmbargoEntry { id: "CPT".to_string(), years: vec![2023], reason: None },
+    ]);
+    let config = Config { latest_data_year: 2026, ..Config::default() };
+
+    let app = test::init_service(
+        App::new()
+            .configure(offline_app_state(&upstream, config, embargo))
+            .route("/api/municipalities/batch", web::post().to(batch_municipality_detail_handler)),
+    )
+    .await;
+    let batch = |body: serde_json::Value| {
+        test::TestRequest::post().uri("/api/municipalities/batch").set_json(body).to_request()
+    };
+
+    let too_many: Vec<String> = (0..=BATCH_DETAIL_MAX_IDS).map(|i| format!("M{i}")).collect();
+    let resp = test::call_service(&app, batch(serde_json::json!({ "ids": too_many }))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = test::call_service(&app, batch(serde_json::json!({ "ids": ["CPT"], "year": 2030 }))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // Duplicates collapse; each withheld id is its own error entry.
+    let body: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        batch(serde_json::json!({ "ids": ["BUF", "CPT", "BUF"], "year": 2023 })),
+    )
+    .await;
+    assert_eq!(body["municipalities"], serde_json::json!({}));
+    let errors = body["errors"].as_object().unwrap();
+    assert_eq!(errors.len(), 2, "{body}");
+    for id in ["BUF", "CPT"] {
+        assert_eq!(errors[id]["code"], "NOT_FOUND", "{id}");
+        assert!(errors[id]["error"].as_str().unwrap().contains("withheld"), "{id}");
+    }
+    assert_eq!(upstream.hits(), 0);
+}
+
+#[actix_web::test]
+async fn unknown_breakdown_type_is_rejected_without_upstream_calls() {
+    let upstream = MockUpstream::start().await;
+
+    let app = test::init_service(
+        App::new()
+            .configure(offline_app_state(&upstream, Config::default(), Embargo::default()))
+            .route("/api/municipalities/{id}/breakdown", web::get().to(get_municipality_breakdown_handler)),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/api/municipalities/CPT/breakdown?type=debt").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["error"].as_str().unwrap().contains("'debt'"));
+    assert_eq!(upstream.hits(), 0);
+}
+
+#[actix_web::test]
+async fn malformed_ids_are_rejected_on_every_id_route_without_db_access() {
+    let upstream = MockUpstream::start().await;
+    let config = Config { admin_token: Some("test-admin-token".to_string()), ..Config::default() };
+
+    let app = test::init_service(
+        App::new()
+            .configure(offline_app_state(&upstream, config, Embargo::default()))
+            .app_data(web::Data::new(MapResponseCache::default()))
+            .app_data(web::Data::new(JobRegistry::default()))
+            .route("/api/municipalities/{id}/summary", web::get().to(get_municipality_summary_handler))
+            .route("/api/municipalities/{id}/geometry", web::get().to(get_municipality_geometry_handler))
+            .route("/api/municipalities/{id}/years", web::get().to(get_municipality_years_handler))
+            .route("/api/municipalities/{id}/export.csv", web::get().to(export_municipality_csv_handler))
+            .route("/api/municipalities/{id}/breakdown", web::get().to(get_municipality_breakdown_handler))
+            .route("/api/municipalities/{id}/percentile", web::get().to(get_municipality_percentile_handler))
+            .route("/api/municipalities/{id}/benchmarks", web::get().to(get_municipality_benchmarks_handler))
+            .route("/api/admin/municipalities/{id}/refresh", web::post().to(refresh_municipality_handler)),
+    )
+    .await;
+
+    for route in ["summary", "geometry", "years", "export.csv", "breakdown?type=revenue", "percentile", "benchmarks"] {
+        let uri = format!("/api/municipalities/WC-011/{route}");
+        let resp = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{uri}");
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert!(body["error"].as_str().unwrap().contains("Invalid municipality id"), "{uri}");
+    }
+    let req = test::TestRequest::post()
+        .uri("/api/admin/municipalities/WC-011/refresh")
+        .insert_header(("Authorization", "Bearer test-admin-token"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(upstream.hits(), 0);
+}
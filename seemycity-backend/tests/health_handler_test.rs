@@ -0,0 +1,52 @@
+// tests/health_handler_test.rs
+
+// Health probes against an unreachable database (lazy pool pointing at a
+// closed port) and a mock Treasury API.
+mod common;
+
+use actix_web::{test, web, App};
+use common::{unreachable_pool, MockUpstream};
+use seemycity_backend::handlers::health::{health_handler, readiness_handler};
+use seemycity_backend::MunicipalMoneyClient;
+
+#[actix_web::test]
+async fn health_is_503_when_the_database_is_down() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(unreachable_pool()))
+            .route("/health", web::get().to(health_handler)),
+    )
+    .await;
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri("/health").to_request()).await;
+    assert_eq!(resp.status(), 503);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body, serde_json::json!({ "status": "unavailable", "database": "down" }));
+}
+
+#[actix_web::test]
+async fn readiness_reports_each_dependency_separately() {
+    let upstream = MockUpstream::start().await;
+    let failing = MockUpstream::start_failing(usize::MAX, 503).await;
+
+    for (base_url, expected_upstream) in [(&upstream.base_url, "up"), (&failing.base_url, "down")] {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(unreachable_pool()))
+                .app_data(web::Data::new(MunicipalMoneyClient::with_base_url(base_url).unwrap()))
+                .route("/health/ready", web::get().to(readiness_handler)),
+        )
+        .await;
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri("/health/ready").to_request()).await;
+        assert_eq!(resp.status(), 503);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body,
+            serde_json::json!({ "status": "unavailable", "database": "down", "upstream": expected_upstream })
+        );
+    }
+    assert_eq!(upstream.hits(), 1);
+    assert!(upstream.requests()[0].starts_with("/cubes"), "{:?}", upstream.requests());
+    assert_eq!(failing.hits(), 1, "the probe is never retried");
+}
@@ -0,0 +1,76 @@
+// tests/list_handler_test.rs
+
+// List-endpoint query validation that needs no database: the pool is lazy and
+// never connects, so these tests only pass if the handler rejects the request
+// before any query.
+mod common;
+
+use actix_web::{http::StatusCode, test, web, App};
+use common::unreachable_pool;
+use seemycity_backend::embargo::Embargo;
+use seemycity_backend::handlers::municipalities::{get_municipalities_list_handler, MapResponseCache};
+
+async fn status_for(uri: &str) -> (StatusCode, serde_json::Value) {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(unreachable_pool()))
+            .app_data(web::Data::new(MapResponseCache::default()))
+            .app_data(web::Data::new(Embargo::default()))
+            .service(get_municipalities_list_handler),
+    )
+    .await;
+    let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+    let status = resp.status();
+    (status, test::read_body_json(resp).await)
+}
+
+#[actix_web::test]
+async fn negative_offset_is_rejected() {
+    let (status, body) = status_for("/api/municipalities?offset=-1").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().contains("offset"));
+}
+
+#[actix_web::test]
+async fn non_positive_limit_is_rejected() {
+    let (status, body) = status_for("/api/municipalities?limit=0&offset=10").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().contains("limit"));
+}
+
+#[actix_web::test]
+async fn invalid_score_bounds_are_rejected() {
+    for uri in [
+        "/api/municipalities?min_score=60&max_score=40",
+        "/api/municipalities?min_score=-1",
+        "/api/municipalities?max_score=100.5",
+        "/api/municipalities?min_score=NaN",
+    ] {
+        let (status, body) = status_for(uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+        assert!(body["error"].as_str().unwrap().contains("score"), "{uri}: {body}");
+    }
+}
+
+#[actix_web::test]
+async fn out_of_range_simplify_tolerance_is_rejected() {
+    for uri in ["/api/municipalities?simplify=-0.001", "/api/municipalities?simplify=1"] {
+        let (status, body) = status_for(uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+        assert!(body["error"].as_str().unwrap().contains("simplify"), "{uri}: {body}");
+    }
+}
+
+#[actix_web::test]
+async fn incomplete_or_invalid_bbox_is_rejected() {
+    for uri in [
+        "/api/municipalities?min_lon=18&min_lat=-34&max_lon=19",
+        "/api/municipalities?min_lon=18&min_lat=-34&max_lon=190&max_lat=-33",
+        "/api/municipalities?min_lon=18&min_lat=-34&max_lon=19&max_lat=-95",
+        "/api/municipalities?min_lon=19&min_lat=-34&max_lon=18&max_lat=-33",
+    ] {
+        let (status, _) = status_for(uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+    }
+}
+
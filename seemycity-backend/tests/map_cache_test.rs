@@ -0,0 +1,121 @@
+// tests/map_cache_test.rs
+
+// MapResponseCache on its own: keying, expiry, single-flight rebuilds and
+// invalidation. No database involved; `build` stands in for the map query.
+use seemycity_backend::errors::AppError;
+use seemycity_backend::handlers::municipalities::{MapPayload, MapResponseCache};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+async fn build_counted(builds: &AtomicUsize, body: &str) -> Result<MapPayload, AppError> {
+    builds.fetch_add(1, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    Ok(MapPayload::new(body.to_string(), None))
+}
+
+#[actix_web::test]
+async fn concurrent_misses_for_one_key_build_once() {
+    let cache = MapResponseCache::new(Duration::from_secs(60));
+    let builds = AtomicUsize::new(0);
+
+    let request = || cache.get_or_build("full".to_string(), || build_counted(&builds, "map"));
+    let (a, b, c, d, e, f) = tokio::join!(request(), request(), request(), request(), request(), request());
+
+    assert!([a, b, c, d, e, f].iter().all(|payload| payload.as_ref().is_ok_and(|p| p.body == "map")));
+    assert_eq!(builds.load(Ordering::SeqCst), 1);
+}
+
+#[actix_web::test]
+async fn keys_are_cached_separately_and_expire() {
+    let cache = MapResponseCache::new(Duration::from_millis(200));
+    let builds = AtomicUsize::new(0);
+
+    for (key, body) in [("a", "A"), ("b", "B"), ("a", "A"), ("b", "B")] {
+        let got = cache.get_or_build(key.to_string(), || build_counted(&builds, body)).await;
+        assert_eq!(got.unwrap().body, body);
+    }
+    assert_eq!(builds.load(Ordering::SeqCst), 2, "second round is served from memory");
+
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    cache.get_or_build("a".to_string(), || build_counted(&builds, "A")).await.unwrap();
+    assert_eq!(builds.load(Ordering::SeqCst), 3, "an expired entry is rebuilt");
+}
+
+#[actix_web::test]
+async fn errors_are_not_cached_and_zero_ttl_disables_caching() {
+    let cache = MapResponseCache::new(Duration::from_secs(60));
+    let failed = cache
+        .get_or_build("k".to_string(), || async { Err(AppError::InternalError("db down".to_string())) })
+        .await;
+    assert!(failed.is_err());
+    let builds = AtomicUsize::new(0);
+    cache.get_or_build("k".to_string(), || build_counted(&builds, "ok")).await.unwrap();
+    assert_eq!(builds.load(Ordering::SeqCst), 1);
+
+    let disabled = MapResponseCache::new(Duration::ZERO);
+    for _ in 0..2 {
+        disabled.get_or_build("k".to_string(), || build_counted(&builds, "ok")).await.unwrap();
+    }
+    assert_eq!(builds.load(Ordering::SeqCst), 3);
+}
+
+#[actix_web::test]
+async fn a_request_arriving_while_a_waiter_rebuilds_joins_that_build() {
+    let cache = MapResponseCache::new(Duration::from_secs(60));
+    let (builds, building, most_at_once) = (AtomicUsize::new(0), AtomicUsize::new(0), AtomicUsize::new(0));
+    let build = |ok: bool| {
+        let (builds, building, most_at_once) = (&builds, &building, &most_at_once);
+        move || async move {
+            builds.fetch_add(1, Ordering::SeqCst);
+            most_at_once.fetch_max(building.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            building.fetch_sub(1, Ordering::SeqCst);
+            if ok {
+                Ok(MapPayload::new("map".to_string(), None))
+            } else {
+                Err(AppError::InternalError("db down".to_string()))
+            }
+        }
+    };
+
+    // The first build fails at 50ms; the waiter behind it rebuilds until
+    // 100ms, and a request arriving at 70ms must wait for that rebuild
+    // rather than start its own.
+    let first = cache.get_or_build("k".to_string(), build(false));
+    let waiter = async {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        cache.get_or_build("k".to_string(), build(true)).await
+    };
+    let latecomer = async {
+        tokio::time::sleep(Duration::from_millis(70)).await;
+        cache.get_or_build("k".to_string(), build(true)).await
+    };
+    let (first, waiter, latecomer) = tokio::join!(first, waiter, latecomer);
+
+    assert!(first.is_err());
+    assert_eq!(waiter.unwrap().body, "map");
+    assert_eq!(latecomer.unwrap().body, "map");
+    assert_eq!(builds.load(Ordering::SeqCst), 2);
+    assert_eq!(most_at_once.load(Ordering::SeqCst), 1);
+}
+
+#[actix_web::test]
+async fn invalidate_drops_every_entry() {
+    let cache = MapResponseCache::new(Duration::from_secs(60));
+    let builds = AtomicUsize::new(0);
+    for key in ["a", "b"] {
+        cache.get_or_build(key.to_string(), || build_counted(&builds, key)).await.unwrap();
+    }
+
+    assert_eq!(cache.invalidate(), 2);
+    cache.get_or_build("a".to_string(), || build_counted(&builds, "a")).await.unwrap();
+    assert_eq!(builds.load(Ordering::SeqCst), 3);
+}
+
+#[actix_web::test]
+async fn payload_etag_follows_the_body() {
+    let a = MapPayload::new("{\"features\":[]}".to_string(), None);
+    let b = MapPayload::new("{\"features\":[{}]}".to_string(), None);
+    assert_eq!(a.etag, MapPayload::new(a.body.clone(), None).etag);
+    assert_ne!(a.etag, b.etag);
+}
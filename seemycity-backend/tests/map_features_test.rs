@@ -0,0 +1,49 @@
+// tests/map_features_test.rs
+
+// Building the map's FeatureCollection from features already in hand: the
+// streamed body and the collection's extent, no database needed.
+mod common;
+
+use common::map_properties;
+use seemycity_backend::handlers::feature_stream::FeatureCollectionBody;
+use seemycity_backend::models::{features_extent, MapFeature, MapFeatureCollection, MapMunicipalityProperties};
+
+fn feature(id: &str, bbox: Option<[f64; 4]>) -> MapFeature {
+    MapFeature {
+        feature_type: "Feature".to_string(),
+        geometry: Some(geojson::Geometry::new(geojson::Value::Point(vec![18.4, -33.9]))),
+        properties: MapMunicipalityProperties { bbox, ..map_properties(id) },
+    }
+}
+
+#[actix_web::test]
+async fn streamed_body_matches_the_serialized_collection() {
+    let box_of = Some([18.3, -34.4, 19.0, -33.5]);
+    for features in [
+        vec![],
+        vec![feature("CPT", box_of)],
+        vec![feature("CPT", box_of), feature("WC011", None), feature("WC012", box_of)],
+    ] {
+        let expected = serde_json::to_string(&MapFeatureCollection {
+            collection_type: "FeatureCollection".to_string(),
+            bbox: None,
+            features: features.clone(),
+            total: None,
+        })
+        .unwrap();
+        let body = actix_web::body::to_bytes(FeatureCollectionBody::new(features)).await.unwrap();
+        assert_eq!(std::str::from_utf8(&body).unwrap(), expected);
+    }
+}
+
+#[test]
+fn extent_encloses_every_feature_bbox() {
+    let features = [
+        feature("TST1", Some([18.3, -34.4, 19.0, -33.5])),
+        feature("TST2", None),
+        feature("TST3", Some([27.9, -26.5, 28.3, -25.9])),
+    ];
+    assert_eq!(features_extent(&features), Some([18.3, -34.4, 28.3, -25.9]));
+    assert_eq!(features_extent(&features[1..2]), None);
+    assert_eq!(features_extent(&[]), None);
+}
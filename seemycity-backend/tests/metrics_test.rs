@@ -0,0 +1,66 @@
+// tests/metrics_test.rs
+
+// The /metrics endpoint and the request, upstream-error and cache counters it
+// exposes. The registry is process-wide, so assertions name series that only
+// this file's tests produce.
+mod common;
+
+use actix_web::{middleware::from_fn, test, web, App, HttpResponse};
+use common::MockUpstream;
+use seemycity_backend::handlers::metrics::metrics_handler;
+use seemycity_backend::metrics::track_requests;
+use seemycity_backend::api::muni_money::types::AmountType;
+use seemycity_backend::MunicipalMoneyClient;
+use std::time::Duration;
+
+async fn scrape() -> String {
+    let app = test::init_service(App::new().route("/metrics", web::get().to(metrics_handler))).await;
+    let resp = test::call_service(&app, test::TestRequest::get().uri("/metrics").to_request()).await;
+    assert!(resp.status().is_success());
+    let content_type = resp.headers().get("content-type").unwrap().to_str().unwrap().to_string();
+    assert!(content_type.starts_with("text/plain; version=0.0.4"), "{content_type}");
+    String::from_utf8(test::read_body(resp).await.to_vec()).unwrap()
+}
+
+#[actix_web::test]
+async fn requests_are_counted_under_their_route_pattern() {
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(track_requests))
+            .route("/metrics-test/{id}", web::get().to(|| async { HttpResponse::Ok().finish() })),
+    )
+    .await;
+    for id in ["A", "B"] {
+        let uri = format!("/metrics-test/{id}");
+        test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+    }
+    test::call_service(&app, test::TestRequest::get().uri("/metrics-test-nowhere").to_request()).await;
+
+    let text = scrape().await;
+    assert!(
+        text.contains("seemycity_http_requests_total{method=\"GET\",route=\"/metrics-test/{id}\",status=\"200\"} 2"),
+        "{text}"
+    );
+    assert!(
+        text.contains("seemycity_http_request_duration_seconds_count{method=\"GET\",route=\"/metrics-test/{id}\"} 2"),
+        "{text}"
+    );
+    assert!(text.contains("route=\"unmatched\",status=\"404\"}"), "{text}");
+}
+
+#[actix_web::test]
+async fn upstream_errors_are_counted_by_cube_and_status() {
+    let upstream = MockUpstream::start_failing(usize::MAX, 418).await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url)
+        .unwrap()
+        .with_retry_policy(0, Duration::ZERO);
+
+    assert!(client.fetch_incexp_aggregate("TST", 2024, &[AmountType::Audited]).await.is_err());
+    assert!(client.fetch_incexp_aggregate("TST", 2024, &[AmountType::Audited]).await.is_err());
+    assert!(client.fetch_audit_opinion_facts("TST", 2024).await.is_err());
+
+    let text = scrape().await;
+    assert!(text.contains("seemycity_upstream_errors_total{cube=\"incexp_v2\",status=\"418\"} 2"), "{text}");
+    assert!(text.contains("seemycity_upstream_errors_total{cube=\"audit_opinions\",status=\"418\"} 1"), "{text}");
+    assert!(text.contains("# TYPE seemycity_cache_hits_total counter"), "{text}");
+}
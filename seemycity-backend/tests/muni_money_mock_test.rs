@@ -0,0 +1,634 @@
+// tests/muni_money_mock_test.rs
+
+// MunicipalMoneyClient behaviour against a local mock upstream (no network).
+mod common;
+
+use common::MockUpstream;
+use seemycity_backend::api::muni_money::audit::{
+    get_audit_outcome, get_audit_outcome_history, AUDIT_HISTORY_CONCURRENCY,
+};
+use seemycity_backend::api::muni_money::demographics::get_population;
+use seemycity_backend::api::muni_money::financials::{
+    get_capital_expenditure, get_expenditure_breakdown, get_grants_and_transfers, get_revenue_and_expenditure,
+    get_revenue_breakdown, get_total_debt, IncexpFigures, DEFAULT_AMOUNT_TYPES,
+};
+use seemycity_backend::api::muni_money::types::AmountType;
+use seemycity_backend::api::ApiClientError;
+use actix_web::http::StatusCode;
+use actix_web::ResponseError;
+use seemycity_backend::confidence::{evaluate, ConfidenceInput, CONFIDENCE_UNRELIABLE};
+use seemycity_backend::config::MuniMoneySettings;
+use seemycity_backend::errors::AppError;
+use seemycity_backend::scoring::{calculate_financial_score, per_capita, ScoringInput};
+use seemycity_backend::MunicipalMoneyClient;
+use rust_decimal::Decimal;
+use std::time::Duration;
+
+// The four core figures for one municipality-year: incexp, finpos, capital, audit.
+async fn fetch_core_figures(client: &MunicipalMoneyClient) {
+    let (incexp, capex, debt, audit) = tokio::join!(
+        get_revenue_and_expenditure(client, "TST", 2024, &DEFAULT_AMOUNT_TYPES),
+        get_capital_expenditure(client, "TST", 2024, &DEFAULT_AMOUNT_TYPES),
+        get_total_debt(client, "TST", 2024, &DEFAULT_AMOUNT_TYPES),
+        get_audit_outcome(client, "TST", 2024),
+    );
+    assert!(incexp.is_ok() && capex.is_ok() && debt.is_ok() && audit.is_ok());
+}
+
+#[actix_web::test]
+async fn batched_amount_types_make_one_request_per_cube() {
+    let upstream = MockUpstream::start().await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url)
+        .unwrap()
+        .with_batched_amount_types(true);
+
+    fetch_core_figures(&client).await;
+    assert_eq!(upstream.hits(), 4, "incexp + finpos + capital + audit");
+}
+
+#[actix_web::test]
+async fn unbatched_amount_types_walk_the_fallback_chain() {
+    // Baseline for the batched test: with no facts anywhere, each of the three
+    // amount-typed cubes is asked once per amount type.
+    let upstream = MockUpstream::start().await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    fetch_core_figures(&client).await;
+    assert_eq!(upstream.hits(), 3 * DEFAULT_AMOUNT_TYPES.len() + 1);
+}
+
+#[actix_web::test]
+async fn transient_5xx_is_retried_until_success() {
+    let upstream = MockUpstream::start_failing(2, 503).await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url)
+        .unwrap()
+        .with_retry_policy(3, Duration::from_millis(1));
+
+    let result = get_audit_outcome(&client, "TST", 2024).await;
+    assert!(result.is_ok(), "expected success after retries: {result:?}");
+    assert_eq!(upstream.hits(), 3, "two failures, then the successful attempt");
+}
+
+#[actix_web::test]
+async fn retries_give_up_after_max_attempts() {
+    let upstream = MockUpstream::start_failing(usize::MAX, 502).await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url)
+        .unwrap()
+        .with_retry_policy(2, Duration::from_millis(1));
+
+    let result = get_audit_outcome(&client, "TST", 2024).await;
+    assert!(matches!(result, Err(ApiClientError::ApiError { status: 502, .. })));
+    assert_eq!(upstream.hits(), 3, "one attempt plus two retries");
+}
+
+#[actix_web::test]
+async fn client_errors_are_not_retried() {
+    let upstream = MockUpstream::start_failing(usize::MAX, 400).await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url)
+        .unwrap()
+        .with_retry_policy(3, Duration::from_millis(1));
+
+    let result = get_audit_outcome(&client, "TST", 2024).await;
+    assert!(matches!(result, Err(ApiClientError::ApiError { status: 400, .. })));
+    assert_eq!(upstream.hits(), 1);
+}
+
+#[actix_web::test]
+async fn max_concurrency_is_shared_across_clones() {
+    let upstream = MockUpstream::start_delayed(Duration::from_millis(100)).await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url)
+        .unwrap()
+        .with_max_concurrency(2);
+    let clone = client.clone();
+
+    let (a, b, c, d, e, f) = tokio::join!(
+        get_audit_outcome(&client, "TST", 2022),
+        get_audit_outcome(&client, "TST", 2023),
+        get_audit_outcome(&client, "TST", 2024),
+        get_audit_outcome(&clone, "TST", 2022),
+        get_audit_outcome(&clone, "TST", 2023),
+        get_audit_outcome(&clone, "TST", 2024),
+    );
+    assert!([a, b, c, d, e, f].iter().all(|r| r.is_ok()));
+    assert_eq!(upstream.hits(), 6);
+    assert_eq!(upstream.max_in_flight(), 2, "clones must share one limit");
+}
+
+#[actix_web::test]
+async fn each_cube_requests_its_aggregate_url() {
+    let upstream = MockUpstream::start().await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    client.fetch_incexp_aggregate("TST", 2024, &[AmountType::Audited]).await.unwrap();
+    client.fetch_finpos_aggregate("TST", 2024, &[AmountType::OriginalBudget]).await.unwrap();
+    client.fetch_capital_aggregate("TST", 2024, &[AmountType::AdjustedBudget]).await.unwrap();
+    client.fetch_cflow_aggregate("TST", 2024, &[AmountType::Audited]).await.unwrap();
+    client.fetch_repmaint_aggregate("TST", 2024, &[AmountType::Audited]).await.unwrap();
+    client.fetch_uifw_aggregate("TST", 2024).await.unwrap();
+    client.fetch_household_services_aggregate("TST", 2024).await.unwrap();
+
+    let typed = "demarcation.code|demarcation.label|item.code|item.label|amount_type.code";
+    let untyped = "demarcation.code|demarcation.label|item.code|item.label";
+    let period_cut = |amount_type: &str| {
+        format!("amount_type.code:{amount_type}|financial_period.period:2024|demarcation.code:\"TST\"")
+    };
+    let year_cut = "financial_year_end.year:2024|demarcation.code:\"TST\"";
+    // Every aggregate is ordered by its own drilldown.
+    let url = |cube: &str, drilldown: &str, cut: &str| {
+        let order: Vec<String> = drilldown.split('|').map(|d| format!("{d}:asc")).collect();
+        format!(
+            "/cubes/{cube}/aggregate?drilldown={drilldown}&cut={cut}&aggregates=amount.sum&order={}",
+            order.join("|")
+        )
+    };
+    assert_eq!(
+        upstream.requests(),
+        [
+            url("incexp_v2", typed, &period_cut("AUDA")),
+            url("financial_position_v2", typed, &period_cut("ORGB")),
+            url("capital_v2", typed, &period_cut("ADJB")),
+            url("cflow_v2", typed, &period_cut("AUDA")),
+            url("repmaint_v2", untyped, &period_cut("AUDA")),
+            url("uifwexp", untyped, year_cut),
+            url("households", untyped, year_cut),
+        ]
+    );
+}
+
+#[actix_web::test]
+async fn from_settings_uses_the_configured_base_url_and_timeout() {
+    let upstream = MockUpstream::start_delayed(Duration::from_millis(1500)).await;
+    let settings = MuniMoneySettings {
+        base_url: upstream.base_url.clone(),
+        timeout_secs: 1,
+        ..MuniMoneySettings::default()
+    };
+    let client = MunicipalMoneyClient::from_settings(&settings)
+        .unwrap()
+        .with_retry_policy(0, Duration::from_millis(1));
+    assert_eq!(client.base_url(), upstream.base_url);
+
+    let result = get_audit_outcome(&client, "TST", 2024).await;
+    assert!(
+        matches!(&result, Err(ApiClientError::RequestError(e)) if e.is_timeout()),
+        "expected a timeout: {result:?}"
+    );
+    assert_eq!(upstream.hits(), 1);
+    // Surfaced to clients as a gateway timeout rather than our own failure.
+    assert_eq!(AppError::from(result.unwrap_err()).status_code(), StatusCode::GATEWAY_TIMEOUT);
+}
+
+#[actix_web::test]
+async fn each_cube_is_sent_with_its_own_timeout() {
+    let upstream = MockUpstream::start_delayed(Duration::from_millis(600)).await;
+    let settings = MuniMoneySettings {
+        base_url: upstream.base_url.clone(),
+        cube_timeouts: vec![("incexp_v2".to_string(), 3)],
+        ..MuniMoneySettings::default()
+    };
+    let client = MunicipalMoneyClient::from_settings(&settings)
+        .unwrap()
+        .with_cube_timeout("audit_opinions", Duration::from_millis(200))
+        .with_retry_policy(0, Duration::from_millis(1));
+    assert_eq!(client.cube_timeout("incexp_v2"), Duration::from_secs(3));
+    assert_eq!(client.cube_timeout("capital_v2"), Duration::from_secs(10), "uncovered cubes keep the default");
+
+    let (audit, incexp) = tokio::join!(
+        get_audit_outcome(&client, "TST", 2024),
+        client.fetch_incexp_aggregate("TST", 2024, &[AmountType::Audited]),
+    );
+    assert!(matches!(&audit, Err(ApiClientError::RequestError(e)) if e.is_timeout()), "{audit:?}");
+    assert!(incexp.is_ok(), "{incexp:?}");
+
+    // Built-in cube timeouts never exceed a lower default.
+    let short =
+        MunicipalMoneyClient::from_settings(&MuniMoneySettings { timeout_secs: 1, ..MuniMoneySettings::default() })
+            .unwrap();
+    assert_eq!(short.cube_timeout("audit_opinions"), Duration::from_secs(1));
+}
+
+#[actix_web::test]
+async fn a_metric_past_its_deadline_is_abandoned_alone() {
+    // The capital cube hangs far past the deadline; incexp answers at once.
+    let upstream = MockUpstream::start_stalling(
+        serde_json::json!({ "total_cell_count": 1, "cells": [incexp_cell("0200", 100.0)] }),
+        "/cubes/capital_v2/",
+        Duration::from_secs(30),
+    )
+    .await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url)
+        .unwrap()
+        .with_metric_deadline(Duration::from_millis(300));
+
+    let started = std::time::Instant::now();
+    let (capex, figures) = tokio::join!(
+        client.within_metric_deadline(get_capital_expenditure(&client, "TST", 2024, &DEFAULT_AMOUNT_TYPES)),
+        client.within_metric_deadline(get_revenue_and_expenditure(&client, "TST", 2024, &DEFAULT_AMOUNT_TYPES)),
+    );
+    assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+    assert!(
+        matches!(capex, Err(ApiClientError::DeadlineExceeded(d)) if d == Duration::from_millis(300)),
+        "{capex:?}"
+    );
+    assert_eq!(figures.unwrap().revenue, Some(100.into()));
+    assert_eq!(AppError::from(capex.unwrap_err()).status_code(), StatusCode::GATEWAY_TIMEOUT);
+}
+
+#[actix_web::test]
+async fn every_request_identifies_the_client() {
+    let upstream = MockUpstream::start().await;
+    let settings = MuniMoneySettings {
+        base_url: upstream.base_url.clone(),
+        user_agent: "seemycity-test/0.1".to_string(),
+        contact: Some("ops@seemycity.example".to_string()),
+        ..MuniMoneySettings::default()
+    };
+    let client = MunicipalMoneyClient::from_settings(&settings).unwrap();
+    fetch_core_figures(&client).await;
+    client.fetch_uifw_aggregate("TST", 2024).await.unwrap();
+
+    let hits = upstream.hits();
+    assert!(hits >= 5, "{hits}");
+    assert_eq!(upstream.header_values("user-agent"), vec![Some("seemycity-test/0.1".to_string()); hits]);
+    assert_eq!(upstream.header_values("from"), vec![Some("ops@seemycity.example".to_string()); hits]);
+
+    // Without configuration the crate version is sent and no contact.
+    let bare = MockUpstream::start().await;
+    get_audit_outcome(&MunicipalMoneyClient::with_base_url(&bare.base_url).unwrap(), "TST", 2024).await.unwrap();
+    assert_eq!(bare.header_values("user-agent"), [Some(format!("seemycity/{}", env!("CARGO_PKG_VERSION")))]);
+    assert_eq!(bare.header_values("from"), [None]);
+}
+
+#[actix_web::test]
+async fn unreachable_upstream_is_a_bad_gateway() {
+    // Nothing listens on port 1, so the connection is refused outright.
+    let client = MunicipalMoneyClient::with_base_url("http://127.0.0.1:1")
+        .unwrap()
+        .with_retry_policy(0, Duration::from_millis(1));
+
+    let error = get_audit_outcome(&client, "TST", 2024).await.unwrap_err();
+    assert_eq!(AppError::from(error).status_code(), StatusCode::BAD_GATEWAY);
+}
+
+#[actix_web::test]
+async fn incexp_aggregate_builds_its_query_and_parses_cells() {
+    let upstream = MockUpstream::start_with_body(serde_json::json!({
+        "total_cell_count": 2,
+        "cells": [
+            {
+                "demarcation.code": "TST",
+                "demarcation.label": "Test Municipality",
+                "item.code": "0200",
+                "item.label": "Service Charges - Electricity Revenue",
+                "amount_type.code": "AUDA",
+                "amount.sum": 1234567890.12
+            },
+            {
+                "demarcation.code": "TST",
+                "demarcation.label": "Test Municipality",
+                "item.code": "4600",
+                "item.label": "Employee Related Costs",
+                "amount.sum": null
+            }
+        ]
+    }))
+    .await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    let response = client.fetch_incexp_aggregate("TST", 2024, &[AmountType::Audited]).await.unwrap();
+
+    assert_eq!(
+        upstream.requests(),
+        ["/cubes/incexp_v2/aggregate?drilldown=demarcation.code|demarcation.label|item.code|item.label|amount_type.code\
+          &cut=amount_type.code:AUDA|financial_period.period:2024|demarcation.code:\"TST\"&aggregates=amount.sum\
+          &order=demarcation.code:asc|demarcation.label:asc|item.code:asc|item.label:asc|amount_type.code:asc"]
+    );
+    assert_eq!(response.total_cell_count, 2);
+    let [revenue, costs] = response.cells.as_slice() else { panic!("expected two cells: {response:?}") };
+    assert_eq!(revenue.demarcation_code, "TST");
+    assert_eq!(revenue.item_code, "0200");
+    assert_eq!(revenue.amount_type_code.as_deref(), Some("AUDA"));
+    assert_eq!(revenue.amount, Some(1234567890.12));
+    assert_eq!(costs.item_label, "Employee Related Costs");
+    assert_eq!(costs.amount_type_code, None);
+    assert_eq!(costs.amount, None);
+}
+
+#[actix_web::test]
+async fn incexp_aggregate_with_no_cells_is_an_empty_response() {
+    let upstream = MockUpstream::start().await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    let response = client.fetch_incexp_aggregate("TST", 2024, &[AmountType::Audited]).await.unwrap();
+    assert_eq!(response.total_cell_count, 0);
+    assert!(response.cells.is_empty());
+}
+
+#[actix_web::test]
+async fn incexp_aggregate_server_error_surfaces_the_status() {
+    let upstream = MockUpstream::start_failing(usize::MAX, 500).await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url)
+        .unwrap()
+        .with_retry_policy(0, Duration::from_millis(1));
+
+    let result = client.fetch_incexp_aggregate("TST", 2024, &[AmountType::Audited]).await;
+    assert!(
+        matches!(&result, Err(ApiClientError::ApiError { status: 500, message: None, body: Some(body) }) if body == "mock failure"),
+        "{result:?}"
+    );
+    assert_eq!(upstream.hits(), 1);
+}
+
+#[actix_web::test]
+async fn population_sum_is_rounded_to_a_head_count_for_per_capita_figures() {
+    let upstream = MockUpstream::start_with_body(serde_json::json!({
+        "total_cell_count": 1,
+        "cells": [{ "demarcation.code": "TST", "total_population.sum": 4772845.6 }]
+    }))
+    .await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    let population = get_population(&client, "TST", 2024).await.unwrap();
+    assert_eq!(population, Some(4_772_846));
+    let [request] = upstream.requests().try_into().unwrap();
+    assert!(request.contains("aggregates=total_population.sum"), "{request}");
+    assert!(request.contains("demarcation.code:\"TST\""), "{request}");
+    assert_eq!(per_capita(Some(Decimal::from(9_545_692)), population), Some(Decimal::from(2)));
+
+    // No estimate for the year: no population, so no per-capita figure.
+    let empty = MockUpstream::start().await;
+    let client = MunicipalMoneyClient::with_base_url(&empty.base_url).unwrap();
+    let population = get_population(&client, "TST", 2024).await.unwrap();
+    assert_eq!(population, None);
+    assert_eq!(per_capita(Some(Decimal::from(9_545_692)), population), None);
+}
+
+#[actix_web::test]
+async fn json_error_bodies_surface_their_message() {
+    let upstream = MockUpstream::start_failing_with_body(
+        usize::MAX,
+        400,
+        r#"{"message": "Invalid cut: unknown dimension 'financial_year_end.yr'"}"#,
+    )
+    .await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    let error = client.fetch_incexp_aggregate("TST", 2024, &[AmountType::Audited]).await.unwrap_err();
+    assert!(
+        matches!(&error, ApiClientError::ApiError { status: 400, message: Some(message), body: Some(_) }
+            if message == "Invalid cut: unknown dimension 'financial_year_end.yr'"),
+        "{error:?}"
+    );
+    // The message, not the raw JSON, is what the server-side error log shows.
+    assert_eq!(
+        AppError::from(error).to_string(),
+        "API Client Error: API request failed with status 400: Invalid cut: unknown dimension 'financial_year_end.yr'"
+    );
+}
+
+#[actix_web::test]
+async fn incexp_aggregate_malformed_body_is_a_decode_error() {
+    let upstream = MockUpstream::start_with_body(serde_json::json!({ "total_cell_count": 1, "cells": "nope" })).await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    let result = client.fetch_incexp_aggregate("TST", 2024, &[AmountType::Audited]).await;
+    assert!(matches!(&result, Err(ApiClientError::RequestError(e)) if e.is_decode()), "{result:?}");
+}
+
+fn incexp_cell(item_code: &str, amount: f64) -> serde_json::Value {
+    serde_json::json!({
+        "demarcation.code": "TST",
+        "demarcation.label": "Test Municipality",
+        "item.code": item_code,
+        "item.label": format!("Item {item_code}"),
+        "amount_type.code": "AUDA",
+        "amount.sum": amount
+    })
+}
+
+#[actix_web::test]
+async fn paginated_aggregate_collects_every_page_before_summing() {
+    let upstream = MockUpstream::start_with_pages(vec![
+        serde_json::json!({
+            "total_cell_count": 5,
+            "cells": [incexp_cell("0200", 100.0), incexp_cell("0300", 200.0), incexp_cell("3100", 50.0)]
+        }),
+        serde_json::json!({
+            "total_cell_count": 5,
+            "cells": [incexp_cell("1000", 400.0), incexp_cell("4100", 25.0)]
+        }),
+    ])
+    .await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    let response = client.fetch_incexp_aggregate("TST", 2024, &[AmountType::Audited]).await.unwrap();
+    assert_eq!(response.cells.len(), 5);
+    let requests = upstream.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(!requests[0].contains("page="), "{requests:?}");
+    assert!(requests[1].ends_with("&page=2&pagesize=3"), "{requests:?}");
+    // Both pages are asked for in the same order, or cells could shift between them.
+    assert!(requests.iter().all(|r| r.contains("&order=demarcation.code:asc|")), "{requests:?}");
+
+    let figures = get_revenue_and_expenditure(&client, "TST", 2024, &DEFAULT_AMOUNT_TYPES).await.unwrap();
+    assert_eq!(figures.revenue, Some(700.into()));
+    assert_eq!(figures.operational_expenditure, Some(75.into()));
+}
+
+#[actix_web::test]
+async fn paginated_aggregate_stops_at_an_empty_page() {
+    // Claims more cells than it ever serves; the client must not loop forever.
+    let upstream = MockUpstream::start_with_pages(vec![serde_json::json!({
+        "total_cell_count": 10,
+        "cells": [incexp_cell("0200", 100.0)]
+    })])
+    .await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    let response = client.fetch_incexp_aggregate("TST", 2024, &[AmountType::Audited]).await.unwrap();
+    assert_eq!(response.cells.len(), 1);
+    assert_eq!(upstream.hits(), 2);
+}
+
+fn incexp_fact_row(item_code: &str, amount: f64) -> serde_json::Value {
+    serde_json::json!({
+        "demarcation.code": "TST",
+        "demarcation.label": "Test Municipality",
+        "item.code": item_code,
+        "item.label": format!("Item {item_code}"),
+        "amount_type.code": "AUDA",
+        "amount": amount
+    })
+}
+
+#[actix_web::test]
+async fn facts_fetch_follows_pages_and_breakdown_groups_by_item() {
+    // Two facts per item (one per function), split over two pages.
+    let upstream = MockUpstream::start_with_pages(vec![
+        serde_json::json!({
+            "total_fact_count": 5,
+            "page": 1,
+            "data": [incexp_fact_row("0200", 100.0), incexp_fact_row("0200", 50.0), incexp_fact_row("2900", 450.0)]
+        }),
+        serde_json::json!({
+            "total_fact_count": 5,
+            "page": 2,
+            "data": [incexp_fact_row("1000", 300.0), incexp_fact_row("3100", 80.0)]
+        }),
+    ])
+    .await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    let response = client.fetch_incexp_facts("TST", 2024, &[AmountType::Audited]).await.unwrap();
+    assert_eq!(response.cells.len(), 5);
+    assert_eq!(response.cells[0].amount, Some(100.0));
+    let requests = upstream.requests();
+    assert_eq!(
+        requests[0],
+        "/cubes/incexp_v2/facts?fields=demarcation.code,demarcation.label,item.code,item.label,amount_type.code,amount\
+         &cut=amount_type.code:AUDA|financial_period.period:2024|demarcation.code:\"TST\"\
+         &order=item.code:asc|amount_type.code:asc|amount:asc&pagesize=10000"
+    );
+    assert!(
+        requests[1].ends_with("&order=item.code:asc|amount_type.code:asc|amount:asc&pagesize=10000&page=2"),
+        "{requests:?}"
+    );
+
+    let revenue = get_revenue_breakdown(&client, "TST", 2024, &DEFAULT_AMOUNT_TYPES).await.unwrap();
+    assert_eq!(revenue.amount_type, Some(AmountType::Audited));
+    let lines: Vec<(&str, Decimal)> = revenue.lines.iter().map(|l| (l.item_code.as_str(), l.amount)).collect();
+    assert_eq!(lines, vec![("1000", Decimal::from(300)), ("0200", Decimal::from(150))]);
+
+    let spend = get_expenditure_breakdown(&client, "TST", 2024, &DEFAULT_AMOUNT_TYPES).await.unwrap();
+    assert_eq!(spend.lines.len(), 1);
+    assert_eq!(spend.lines[0].item_label, "Item 3100");
+}
+
+#[actix_web::test]
+async fn audit_history_fetches_each_year_oldest_first() {
+    let upstream = MockUpstream::start_with_body(serde_json::json!({
+        "total_fact_count": 1,
+        "data": [{
+            "demarcation.code": "TST",
+            "demarcation.label": "Test Municipality",
+            "opinion.code": "unqualified",
+            "opinion.label": "Unqualified - Emphasis of Matter items"
+        }]
+    }))
+    .await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    let history = get_audit_outcome_history(&client, "TST", 2021, 2023).await.unwrap();
+    let years: Vec<i32> = history.iter().map(|(y, _)| *y).collect();
+    assert_eq!(years, [2021, 2022, 2023]);
+    assert!(history.iter().all(|(_, label)| label == "Unqualified - Emphasis of Matter items"));
+    assert_eq!(upstream.hits(), 3);
+    // Years are fetched concurrently, so requests arrive in any order.
+    let requests = upstream.requests();
+    assert!(requests.iter().all(|r| r.contains("/cubes/audit_opinions/facts?")), "{requests:?}");
+    for year in 2021..=2023 {
+        let cut = format!("financial_year_end.year:{year}");
+        assert_eq!(requests.iter().filter(|r| r.contains(&cut)).count(), 1, "{requests:?}");
+    }
+}
+
+#[actix_web::test]
+async fn audit_history_fetches_years_concurrently_up_to_the_limit() {
+    let upstream = MockUpstream::start_delayed(Duration::from_millis(100)).await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    assert_eq!(get_audit_outcome_history(&client, "TST", 2015, 2023).await.unwrap(), []);
+    assert_eq!(upstream.hits(), 9);
+    assert_eq!(upstream.max_in_flight(), AUDIT_HISTORY_CONCURRENCY);
+}
+
+#[actix_web::test]
+async fn audit_opinion_cut_quotes_the_code() {
+    let upstream = MockUpstream::start().await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    client.fetch_audit_opinion_facts("WC011", 2023).await.unwrap();
+    let requests = upstream.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(
+        requests[0].contains("cut=financial_year_end.year:2023|demarcation.code:\"WC011\""),
+        "{}",
+        requests[0]
+    );
+}
+
+#[actix_web::test]
+async fn audit_history_skips_empty_years_and_rejects_inverted_ranges() {
+    let upstream = MockUpstream::start().await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    assert_eq!(get_audit_outcome_history(&client, "TST", 2021, 2022).await.unwrap(), []);
+    let inverted = get_audit_outcome_history(&client, "TST", 2023, 2021).await;
+    assert!(matches!(inverted, Err(ApiClientError::InvalidParameters(_))), "{inverted:?}");
+    assert_eq!(upstream.hits(), 2);
+}
+
+#[actix_web::test]
+async fn grants_and_transfers_read_only_the_operational_transfers_item() {
+    let upstream = MockUpstream::start_with_body(serde_json::json!({
+        "total_cell_count": 3,
+        "cells": [incexp_cell("0200", 600.0), incexp_cell("2200", 250.0), incexp_cell("4600", 90.0)]
+    }))
+    .await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    let transfers = get_grants_and_transfers(&client, "TST", 2024).await.unwrap();
+    assert_eq!(transfers, Some(250.into()));
+    assert!(upstream.requests()[0].contains("/incexp_v2/"), "{:?}", upstream.requests());
+}
+
+#[actix_web::test]
+async fn negative_revenue_never_reaches_a_score() {
+    // Reversals booked against revenue outweigh its postings: the return sums
+    // to -500 against 400 of operating spend.
+    let upstream = MockUpstream::start_with_body(serde_json::json!({
+        "total_cell_count": 2,
+        "cells": [incexp_cell("0200", -500.0), incexp_cell("3100", 400.0)]
+    }))
+    .await;
+    let scored = |figures: &IncexpFigures| {
+        let grade = evaluate(&ConfidenceInput {
+            revenue: figures.revenue,
+            operational_expenditure: figures.operational_expenditure,
+            ..ConfidenceInput::default()
+        });
+        let breakdown = calculate_financial_score(&ScoringInput {
+            revenue: figures.revenue,
+            operational_expenditure: figures.operational_expenditure,
+            // Enough for Financial Health to score against any positive revenue.
+            debt: Some(Decimal::from(100)),
+            transfers_operational: Some(Decimal::ZERO),
+            audit_outcome: Some("Unqualified opinion with no findings".to_string()),
+            data_unreliable: grade.grade == CONFIDENCE_UNRELIABLE,
+            ..ScoringInput::default()
+        });
+        (grade, breakdown)
+    };
+
+    // Kept as reported: the row is graded unreliable and every raw-derived
+    // pillar is withheld.
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+    let kept = get_revenue_and_expenditure(&client, "TST", 2024, &DEFAULT_AMOUNT_TYPES).await.unwrap();
+    assert_eq!(kept.revenue, Some(Decimal::from(-500)));
+    let (grade, breakdown) = scored(&kept);
+    assert_eq!(grade.grade, CONFIDENCE_UNRELIABLE);
+    assert!(grade.notes.unwrap().contains("negative revenue"));
+    assert_eq!(breakdown.efficiency_score, None);
+    assert_eq!(breakdown.financial_health_score, None);
+
+    // Clamping happens where the row is scored, not in the fetcher, so a
+    // recompute from the stored facts is screened the same way.
+    let client = client.with_clamped_negative_totals(true);
+    let fetched = get_revenue_and_expenditure(&client, "TST", 2024, &DEFAULT_AMOUNT_TYPES).await.unwrap();
+    assert_eq!(fetched.revenue, Some(Decimal::from(-500)));
+
+    // Zero revenue, as clamping stores it, is no divisor either.
+    let (_, breakdown) = scored(&IncexpFigures { revenue: Some(Decimal::ZERO), ..fetched });
+    assert_eq!(breakdown.efficiency_score, None);
+    assert_eq!(breakdown.financial_health_score, None);
+}
@@ -0,0 +1,34 @@
+// tests/nearest_handler_test.rs
+
+// Nearest-endpoint query validation that needs no database: the pool is lazy
+// and never connects, so these tests only pass if the handler rejects the
+// request before any query.
+mod common;
+
+use actix_web::{http::StatusCode, test, web, App};
+use common::unreachable_pool;
+use seemycity_backend::embargo::Embargo;
+use seemycity_backend::handlers::municipalities::get_nearest_municipalities_handler;
+
+async fn status_for(uri: &str) -> StatusCode {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(unreachable_pool()))
+            .app_data(web::Data::new(Embargo::default()))
+            .route("/api/municipalities/nearest", web::get().to(get_nearest_municipalities_handler)),
+    )
+    .await;
+    test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await.status()
+}
+
+#[actix_web::test]
+async fn invalid_points_and_counts_are_rejected() {
+    for uri in [
+        "/api/municipalities/nearest?lat=-34",
+        "/api/municipalities/nearest?lon=181&lat=-34",
+        "/api/municipalities/nearest?lon=18&lat=-91",
+        "/api/municipalities/nearest?lon=18&lat=-34&n=0",
+    ] {
+        assert_eq!(status_for(uri).await, StatusCode::BAD_REQUEST, "{uri}");
+    }
+}
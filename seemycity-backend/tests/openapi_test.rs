@@ -0,0 +1,308 @@
+// tests/openapi_test.rs
+
+// The derived OpenAPI document must describe what the API actually
+// serializes: every key a fully-populated model emits has to be in its schema,
+// with a matching JSON type (Decimals as numbers, geometry as an object).
+mod common;
+
+use actix_web::http::header;
+use actix_web::test as atest;
+use actix_web::{body::to_bytes, web, App, ResponseError};
+use common::map_properties;
+use rust_decimal_macros::dec;
+use seemycity_backend::errors::AppError;
+use seemycity_backend::handlers::docs::{swagger_redirect_handler, swagger_ui};
+use seemycity_backend::models::{
+    FinancialYearData, MapFeature, MapFeatureCollection, MunicipalityBenchmarks, MunicipalityDetail,
+    MunicipalityListPage, PillarBenchmark, PillarStatistics, ScoreStatistics,
+};
+use seemycity_backend::openapi::ApiDoc;
+use seemycity_backend::scoring::{explain_financial_score, methodology, ScoringConfig, ScoringInput};
+use serde_json::Value;
+use utoipa::OpenApi;
+
+fn openapi_spec() -> Value {
+    serde_json::to_value(ApiDoc::openapi()).unwrap()
+}
+
+fn resolve<'a>(spec: &'a Value, schema: &'a Value) -> &'a Value {
+    match schema.get("$ref").and_then(Value::as_str) {
+        Some(reference) => {
+            let name = reference.trim_start_matches("#/components/schemas/");
+            spec["components"]["schemas"].get(name).unwrap_or_else(|| panic!("dangling $ref {reference}"))
+        }
+        None => schema,
+    }
+}
+
+/// Checks `value` against `schema`, recursing into objects and arrays.
+fn check(spec: &Value, schema: &Value, value: &Value, path: &str) {
+    let schema = resolve(spec, schema);
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        if value.is_null() {
+            assert_eq!(schema["nullable"], true, "{path} is null but not nullable");
+            return;
+        }
+        // A nullable reference (`Option<Grade>`) wraps the one part.
+        if let [part] = parts.as_slice() {
+            return check(spec, part, value, path);
+        }
+        // Merge the parts' properties so flattened structs check as one object.
+        let mut properties = serde_json::Map::new();
+        for part in parts {
+            let part = resolve(spec, part);
+            assert_eq!(part["type"], "object", "{path}: allOf part is not an object");
+            properties.extend(part["properties"].as_object().unwrap().clone());
+        }
+        let merged = serde_json::json!({ "type": "object", "properties": properties });
+        return check(spec, &merged, value, path);
+    }
+
+    // `{}` (e.g. GeoJSON coordinates) accepts anything.
+    let Some(expected) = schema["type"].as_str() else { return };
+    match value {
+        Value::Null => assert_eq!(schema["nullable"], true, "{path} is null but not nullable"),
+        Value::Bool(_) => assert_eq!(expected, "boolean", "{path}"),
+        Value::Number(n) => match expected {
+            "number" => {}
+            "integer" => assert!(n.is_i64() || n.is_u64(), "{path} = {n} is not an integer"),
+            other => panic!("{path} serializes as a number but is described as {other}"),
+        },
+        Value::String(_) => assert_eq!(expected, "string", "{path}"),
+        Value::Array(items) => {
+            assert_eq!(expected, "array", "{path}");
+            for (i, item) in items.iter().enumerate() {
+                check(spec, &schema["items"], item, &format!("{path}[{i}]"));
+            }
+        }
+        Value::Object(fields) => {
+            assert_eq!(expected, "object", "{path}");
+            for (key, field) in fields {
+                let field_path = format!("{path}.{key}");
+                if let Some(property) = schema["properties"].get(key) {
+                    check(spec, property, field, &field_path);
+                } else if let Some(additional) = schema.get("additionalProperties") {
+                    check(spec, additional, field, &field_path);
+                } else {
+                    panic!("{field_path} is serialized but missing from the spec");
+                }
+            }
+        }
+    }
+}
+
+fn check_schema(name: &str, value: &Value) {
+    let spec = openapi_spec();
+    let schema = spec["components"]["schemas"][name].clone();
+    assert!(!schema.is_null(), "no schema {name}");
+    check(&spec, &schema, value, name);
+}
+
+fn full_year() -> FinancialYearData {
+    let input = ScoringInput {
+        revenue: Some(dec!(1000000)),
+        operational_expenditure: Some(dec!(900000)),
+        capital_expenditure: Some(dec!(150000)),
+        debt: Some(dec!(300000)),
+        audit_outcome: Some("Unqualified - No findings".to_string()),
+        population: Some(50000),
+        classification: Some("B2".to_string()),
+        transfers_operational: Some(dec!(200000)),
+        uifw_expenditure: Some(dec!(10000)),
+        repairs_maintenance: Some(dec!(60000)),
+        cash_balance: Some(dec!(120000)),
+        ..Default::default()
+    };
+    let audit = explain_financial_score(&input);
+    FinancialYearData {
+        year: 2023,
+        revenue: input.revenue,
+        operational_expenditure: input.operational_expenditure,
+        capital_expenditure: input.capital_expenditure,
+        debt: input.debt,
+        audit_outcome: input.audit_outcome.clone(),
+        transfers_operational: input.transfers_operational,
+        uifw_expenditure: input.uifw_expenditure,
+        repairs_maintenance: input.repairs_maintenance,
+        cash_balance: input.cash_balance,
+        operating_surplus: Some(dec!(100000)),
+        amount_type: Some("AUDA".to_string()),
+        overall_score: Some(dec!(71.25)),
+        financial_health_score: Some(dec!(70.00)),
+        infrastructure_score: Some(dec!(80.00)),
+        efficiency_score: Some(dec!(60.00)),
+        accountability_score: Some(dec!(100.00)),
+        liquidity_score: Some(dec!(50.00)),
+        grade: Some("B".to_string()),
+        data_confidence: Some("ok".to_string()),
+        confidence_notes: Some("".to_string()),
+        revenue_per_capita: Some(dec!(20)),
+        expenditure_per_capita: Some(dec!(18)),
+        debt_per_capita: Some(dec!(6)),
+        score_audit: Some(audit),
+    }
+}
+
+fn polygon() -> geojson::Geometry {
+    geojson::Geometry::new(geojson::Value::Polygon(vec![vec![
+        vec![18.0, -34.0],
+        vec![18.1, -34.0],
+        vec![18.1, -33.9],
+        vec![18.0, -34.0],
+    ]]))
+}
+
+// A type named by path (`crate::scoring::Grade`) becomes a `$ref` to
+// "crate.scoring.Grade", which no component is registered under.
+#[test]
+fn every_ref_names_a_registered_schema() {
+    fn walk(spec: &Value, node: &Value) {
+        match node {
+            Value::Object(map) => {
+                if map.contains_key("$ref") {
+                    resolve(spec, node);
+                }
+                map.values().for_each(|v| walk(spec, v));
+            }
+            Value::Array(items) => items.iter().for_each(|v| walk(spec, v)),
+            _ => {}
+        }
+    }
+    let spec = openapi_spec();
+    walk(&spec, &spec);
+}
+
+#[test]
+fn financial_year_schema_covers_every_serialized_field() {
+    let year = serde_json::to_value(full_year()).unwrap();
+    assert!(year["revenue"].is_number(), "Decimals serialize as numbers");
+    check_schema("FinancialYearData", &year);
+}
+
+#[test]
+fn municipality_detail_schema_matches_and_geometry_is_an_object() {
+    let detail = MunicipalityDetail {
+        id: "CPT".to_string(),
+        name: "City of Cape Town".to_string(),
+        province: "Western Cape".to_string(),
+        population: Some(4_772_846.0),
+        classification: Some("A".to_string()),
+        website: Some("https://www.capetown.gov.za".to_string()),
+        address: Some("12 Hertzog Boulevard".to_string()),
+        phone: Some("0860 103 089".to_string()),
+        district_id: None,
+        district_name: None,
+        financials: vec![full_year()],
+        has_financial_data: true,
+        geometry: Some(polygon()),
+        last_updated: Some(chrono::Utc::now()),
+    };
+    let value = serde_json::to_value(detail).unwrap();
+    assert!(value["geometry"].is_object());
+    check_schema("MunicipalityDetail", &value);
+}
+
+#[test]
+fn map_feature_collection_schema_matches() {
+    let collection = MapFeatureCollection {
+        collection_type: "FeatureCollection".to_string(),
+        bbox: Some([18.3074, -34.3583, 19.0046, -33.4713]),
+        features: vec![
+            MapFeature { feature_type: "Feature".to_string(), geometry: Some(polygon()), properties: map_properties("CPT") },
+            MapFeature { feature_type: "Feature".to_string(), geometry: None, properties: map_properties("CPT") },
+        ],
+        total: Some(2),
+    };
+    check_schema("MapFeatureCollection", &serde_json::to_value(collection).unwrap());
+}
+
+#[test]
+fn list_page_schema_matches() {
+    for limit in [Some(2), None] {
+        let page = MunicipalityListPage { items: vec![map_properties("CPT")], total: 3, limit, offset: 2, has_more: false };
+        check_schema("MunicipalityListPage", &serde_json::to_value(page).unwrap());
+    }
+}
+
+#[test]
+fn statistics_schema_matches_populated_and_empty_pillars() {
+    let populated = PillarStatistics {
+        count: 3,
+        min: Some(dec!(12.5)),
+        max: Some(dec!(88)),
+        average: Some(dec!(51.17)),
+        median: Some(dec!(53)),
+    };
+    let statistics = ScoreStatistics { year: Some(2023), overall: populated, ..ScoreStatistics::default() };
+    let value = serde_json::to_value(statistics).unwrap();
+    assert!(value["liquidity"]["median"].is_null());
+    check_schema("ScoreStatistics", &value);
+}
+
+#[actix_web::test]
+async fn error_body_matches_the_error_schema() {
+    let response = AppError::NotFound("No municipality XYZ".to_string()).error_response();
+    let body: Value = serde_json::from_slice(&to_bytes(response.into_body()).await.unwrap()).unwrap();
+    check_schema("ErrorResponse", &body);
+    let codes = &openapi_spec()["components"]["schemas"]["ErrorResponse"]["properties"]["code"]["enum"];
+    assert!(codes.as_array().unwrap().contains(&body["code"]));
+}
+
+#[actix_web::test]
+async fn spec_and_swagger_ui_are_served() {
+    let app = atest::init_service(
+        App::new()
+            .route("/swagger", web::get().to(swagger_redirect_handler))
+            .service(swagger_ui()),
+    )
+    .await;
+
+    let spec: Value = atest::call_and_read_body_json(
+        &app,
+        atest::TestRequest::get().uri("/api-docs/openapi.json").to_request(),
+    )
+    .await;
+    assert_eq!(spec["openapi"], "3.0.3");
+    assert!(spec["paths"]["/api/municipalities/{id}"]["get"].is_object());
+
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/swagger").to_request()).await;
+    assert_eq!(resp.headers().get(header::LOCATION).unwrap(), "/swagger/");
+    let resp = atest::call_service(&app, atest::TestRequest::get().uri("/swagger/").to_request()).await;
+    assert!(resp.status().is_success());
+    let html = String::from_utf8(atest::read_body(resp).await.to_vec()).unwrap();
+    assert!(!html.contains("unpkg.com"), "the UI assets are served locally");
+
+    // The UI is pointed at the document by its initializer script.
+    let script = atest::call_and_read_body(
+        &app,
+        atest::TestRequest::get().uri("/swagger/swagger-initializer.js").to_request(),
+    )
+    .await;
+    assert!(String::from_utf8(script.to_vec()).unwrap().contains("/api-docs/openapi.json"));
+}
+
+#[test]
+fn benchmarks_schema_matches() {
+    let scored = PillarBenchmark {
+        score: Some(dec!(61.5)),
+        provincial_average: Some(dec!(58.25)),
+        national_average: Some(dec!(49.1)),
+    };
+    let benchmarks = MunicipalityBenchmarks {
+        municipality_id: "CPT".to_string(),
+        province: "Western Cape".to_string(),
+        year: 2024,
+        overall: scored.clone(),
+        financial_health: scored.clone(),
+        infrastructure: scored.clone(),
+        efficiency: scored,
+        accountability: PillarBenchmark::default(),
+        liquidity: PillarBenchmark { score: None, provincial_average: None, national_average: Some(dec!(40)) },
+    };
+    check_schema("MunicipalityBenchmarks", &serde_json::to_value(benchmarks).unwrap());
+}
+
+#[test]
+fn scoring_methodology_schema_matches() {
+    check_schema("ScoringMethodology", &serde_json::to_value(methodology(&ScoringConfig::default())).unwrap());
+}
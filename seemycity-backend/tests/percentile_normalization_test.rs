@@ -0,0 +1,82 @@
+// tests/percentile_normalization_test.rs
+
+// SCORING_NORMALIZATION=percentile end to end, against a real Postgres
+// database. Percentile mode is process-wide, so it is switched on in this
+// binary only, away from the linear-mode tests in db_handlers_integration_test.
+// Requires DATABASE_URL; run with: cargo test -- --ignored percentile_
+mod common;
+
+use common::MockUpstream;
+use seemycity_backend::db::financials::get_all_financial_years_db;
+use seemycity_backend::scoring::{calculate_financial_score, Normalization, ScoringInput};
+use seemycity_backend::services::municipality::{build_and_store_detail, UpstreamHealth};
+use seemycity_backend::services::normalization::{enable_percentile_normalization, metric_distribution};
+use seemycity_backend::{Config, MunicipalMoneyClient};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+#[actix_web::test]
+#[ignore] // Run with: cargo test -- --ignored percentile_
+async fn percentile_mode_scores_stored_rows_by_rank_within_the_year() {
+    dotenvy::dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for DB tests");
+    let pool = PgPool::connect(&url).await.expect("Failed to connect to test database");
+    // A year no other test uses, so these are the only rows ranked.
+    const YEAR: i32 = 1899;
+    // 24 municipalities with OpEx ratios 0.52 to 0.98: all but the last two
+    // beat the linear anchor (0.85) and would score 100 there.
+    for i in 1..=24 {
+        let id = format!("TPCT{i:02}");
+        sqlx::query("DELETE FROM financial_data WHERE municipality_id = $1").bind(&id).execute(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO municipalities (id, name, province, population) VALUES ($1, $1, 'Test Province', 50000)
+             ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO financial_data (id, municipality_id, year, revenue, operational_expenditure, data_confidence, score_version)
+             VALUES ($1, $2, $3, 1000, $4, 'ok', 1)",
+        )
+        .bind(uuid::Uuid::new_v4())
+        .bind(&id)
+        .bind(YEAR)
+        .bind(rust_decimal::Decimal::from(500 + i * 20))
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    enable_percentile_normalization();
+    let upstream = MockUpstream::start().await;
+    let client = MunicipalMoneyClient::with_base_url(&upstream.base_url).unwrap();
+
+    // Loading the middle municipality heals its stale row under percentile mode.
+    let detail = build_and_store_detail(
+        &pool,
+        &client,
+        &UpstreamHealth::default(),
+        "TPCT12",
+        &[YEAR],
+        true,
+        Config::default().current_financial_year(),
+        chrono::Duration::days(7),
+    )
+    .await
+    .unwrap();
+
+    let stored = get_all_financial_years_db(&pool, "TPCT12").await.unwrap();
+    let row = stored.iter().find(|r| r.year == YEAR).expect("row is still stored");
+    let distribution = metric_distribution(&pool, YEAR, &[]).await.unwrap();
+    let expected = calculate_financial_score(&ScoringInput {
+        normalization: Normalization::Percentile(Arc::new(distribution)),
+        ..ScoringInput::from_db_record(row, Some(50_000.0), None)
+    });
+    assert_eq!(row.efficiency_score, expected.efficiency_score);
+    let linear = calculate_financial_score(&ScoringInput::from_db_record(row, Some(50_000.0), None));
+    assert!(row.efficiency_score < linear.efficiency_score, "{:?} vs {:?}", row.efficiency_score, linear.efficiency_score);
+
+    let audit = detail.financials[0].score_audit.as_ref().expect("audit was asked for");
+    assert_eq!(audit.normalization, "percentile");
+}
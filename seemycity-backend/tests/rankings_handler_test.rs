@@ -0,0 +1,26 @@
+// tests/rankings_handler_test.rs
+
+// Rankings query validation that needs no database: the pool is lazy and never
+// connects, so these tests only pass if the request is rejected before any query.
+mod common;
+
+use actix_web::{http::StatusCode, test, web, App};
+use common::unreachable_pool;
+use seemycity_backend::embargo::Embargo;
+use seemycity_backend::handlers::rankings::get_rankings_handler;
+
+#[actix_web::test]
+async fn invalid_limit_and_order_are_rejected() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(unreachable_pool()))
+            .app_data(web::Data::new(Embargo::default()))
+            .route("/api/rankings", web::get().to(get_rankings_handler)),
+    )
+    .await;
+
+    for uri in ["/api/rankings?year=2023&limit=0", "/api/rankings?year=2023&order=sideways"] {
+        let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{uri}");
+    }
+}
@@ -0,0 +1,55 @@
+// tests/request_id_test.rs
+
+// The X-Request-Id middleware: ids are reused or generated, visible to the
+// handler (and so to its log lines), and echoed on the response.
+use actix_web::{middleware::from_fn, test, web, App, HttpResponse};
+use seemycity_backend::request_id::{assign_request_id, current};
+
+async fn echo_current_id() -> HttpResponse {
+    HttpResponse::Ok().body(current().unwrap_or_default())
+}
+
+async fn call(header: Option<&str>) -> (String, String) {
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(assign_request_id))
+            .route("/echo", web::get().to(echo_current_id)),
+    )
+    .await;
+    let mut req = test::TestRequest::get().uri("/echo");
+    if let Some(id) = header {
+        req = req.insert_header(("X-Request-Id", id));
+    }
+    let resp = test::call_service(&app, req.to_request()).await;
+    assert!(resp.status().is_success());
+    let returned = resp.headers().get("x-request-id").expect("id is echoed").to_str().unwrap().to_string();
+    let seen = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    (returned, seen)
+}
+
+#[actix_web::test]
+async fn caller_supplied_id_is_reused() {
+    let (returned, seen) = call(Some("lb-7f3a.42")).await;
+    assert_eq!(returned, "lb-7f3a.42");
+    assert_eq!(seen, "lb-7f3a.42");
+}
+
+#[actix_web::test]
+async fn missing_id_is_generated() {
+    let (returned, seen) = call(None).await;
+    assert!(uuid::Uuid::parse_str(&returned).is_ok(), "{returned}");
+    assert_eq!(seen, returned);
+}
+
+#[actix_web::test]
+async fn unsafe_id_is_replaced() {
+    let (returned, seen) = call(Some("abc] ERROR forged")).await;
+    assert_ne!(returned, "abc] ERROR forged");
+    assert!(uuid::Uuid::parse_str(&returned).is_ok(), "{returned}");
+    assert_eq!(seen, returned);
+}
+
+#[actix_web::test]
+async fn no_id_outside_a_request() {
+    assert_eq!(current(), None);
+}
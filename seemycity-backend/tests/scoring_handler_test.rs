@@ -0,0 +1,50 @@
+// tests/scoring_handler_test.rs
+
+// GET /api/scoring/methodology: the document is built from the scoring config
+// the app was given, so a changed weight or anchor shows up in it.
+// No database or upstream is involved.
+use actix_web::{http::StatusCode, test, web, App};
+use rust_decimal_macros::dec;
+use seemycity_backend::handlers::scoring::get_scoring_methodology_handler;
+use seemycity_backend::scoring::{ScoringConfig, ScoringThresholds};
+use serde_json::Value;
+
+async fn call(scoring_config: ScoringConfig) -> (StatusCode, Value) {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(scoring_config))
+            .route("/api/scoring/methodology", web::get().to(get_scoring_methodology_handler)),
+    )
+    .await;
+    let resp = test::call_service(&app, test::TestRequest::get().uri("/api/scoring/methodology").to_request()).await;
+    let status = resp.status();
+    (status, test::read_body_json(resp).await)
+}
+
+#[actix_web::test]
+async fn default_methodology_describes_the_current_model() {
+    let (status, body) = call(ScoringConfig::default()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["score_version"], seemycity_backend::scoring::SCORE_VERSION);
+    assert_eq!(body["weights"]["financial_health"].to_string(), "0.24");
+    assert_eq!(body["thresholds"]["efficiency_ratio"]["score_100_at"].to_string(), "0.85");
+    let clean = &body["audit"]["outcome_scores"][0];
+    assert_eq!((clean["outcome"].as_str(), clean["score"].as_f64()), (Some("clean"), Some(100.0)));
+}
+
+#[actix_web::test]
+async fn changed_weights_and_anchors_are_reflected() {
+    let scoring_config = ScoringConfig {
+        weight_financial_health: dec!(0.30),
+        weight_liquidity: dec!(0.14),
+        thresholds: ScoringThresholds { debt_ratio_max: dec!(1.5), ..ScoringThresholds::DEFAULT },
+        ..ScoringConfig::default()
+    };
+    assert_eq!(scoring_config.validate(), Ok(()));
+
+    let (status, body) = call(scoring_config).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["weights"]["financial_health"].to_string(), "0.30");
+    assert_eq!(body["weights"]["liquidity"].to_string(), "0.14");
+    assert_eq!(body["thresholds"]["debt_ratio"]["score_0_at"].to_string(), "1.5");
+}
@@ -0,0 +1,27 @@
+// tests/search_handler_test.rs
+
+// Search behaviour that needs no database: the pool is lazy and never
+// connects, so a blank query must be answered without one.
+mod common;
+
+use actix_web::{test, web, App};
+use common::unreachable_pool;
+use seemycity_backend::embargo::Embargo;
+use seemycity_backend::handlers::municipalities::search_municipalities_handler;
+
+#[actix_web::test]
+async fn blank_query_returns_an_empty_list() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(unreachable_pool()))
+            .app_data(web::Data::new(Embargo::default()))
+            .route("/api/municipalities/search", web::get().to(search_municipalities_handler)),
+    )
+    .await;
+
+    for uri in ["/api/municipalities/search", "/api/municipalities/search?q=", "/api/municipalities/search?q=%20%20"] {
+        let body: serde_json::Value =
+            test::call_and_read_body_json(&app, test::TestRequest::get().uri(uri).to_request()).await;
+        assert_eq!(body, serde_json::json!([]), "{uri}");
+    }
+}